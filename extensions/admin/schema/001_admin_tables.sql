@@ -97,6 +97,9 @@ CREATE TABLE IF NOT EXISTS admin_invoices (
 CREATE INDEX IF NOT EXISTS idx_admin_invoices_status ON admin_invoices(status);
 CREATE INDEX IF NOT EXISTS idx_admin_invoices_type ON admin_invoices(type);
 CREATE INDEX IF NOT EXISTS idx_admin_invoices_date ON admin_invoices(invoice_date DESC);
+-- Invoice numbers are never reused, not even by deleted invoices
+CREATE UNIQUE INDEX IF NOT EXISTS idx_admin_invoices_reference
+    ON admin_invoices(reference) WHERE reference <> '';
 
 -- Security Deposits
 CREATE TABLE IF NOT EXISTS admin_deposits (
@@ -10,7 +10,11 @@ BEGIN
     TRUNCATE admin_properties, admin_tenants, admin_owners, admin_contracts,
              admin_invoices, admin_deposits, admin_sepa_batches, admin_issues,
              admin_insurance, admin_alerts, admin_contacts, admin_leads, admin_lead_notes,
-             admin_audit_log CASCADE;
+             admin_audit_log, admin_payout_batches, admin_payout_transfers, admin_payout_items,
+             admin_saved_views, admin_custom_fields, admin_document_templates,
+             admin_contract_documents, admin_signature_requests, admin_signers,
+             admin_inspections, admin_deposit_deductions, admin_portal_accounts,
+             admin_issue_photos, admin_webhook_events, admin_webhook_deliveries CASCADE;
 
     -- =============================================
     -- Properties
@@ -25,6 +29,22 @@ BEGIN
     ('07b8c9d0-e1f2-4a3b-4c5d-6e7f80910213', 'Penthouse, 30 The Embankment', '30 The Embankment, Penthouse, London SE1 7TJ', 'EMBANKMENT 30 - BROWN', 'Let', 2800.00, CURRENT_DATE - INTERVAL '12 months', CURRENT_DATE + INTERVAL '12 months', ARRAY['Residential','Premium','Central'], 'embankment-30', NOW() - INTERVAL '14 months', NOW() - INTERVAL '1 day'),
     ('18c9d0e1-f2a3-4b4c-5d6e-7f8091021324', 'Unit 3, Northern Industrial Estate', 'Northern Industrial Estate, Unit 3, Leeds LS9 8AG', '', 'Available', 2400.00, NULL, NULL, ARRAY['Industrial'], 'northern-industrial-3', NOW() - INTERVAL '22 months', NOW() - INTERVAL '20 days');
 
+    UPDATE admin_properties p
+    SET bedrooms = v.bedrooms, bathrooms = v.bathrooms, sqm = v.sqm, floor = v.floor,
+        energy_rating = v.energy_rating, furnished = v.furnished,
+        latitude = v.latitude, longitude = v.longitude
+    FROM (VALUES
+        ('a1b2c3d4-e5f6-4a7b-8c9d-0e1f2a3b4c5d'::uuid, 2, 1, 68.00, '3', 'C', true, 51.5117, -0.1240),
+        ('b2c3d4e5-f6a7-4b8c-9d0e-1f2a3b4c5d6e'::uuid, 2, 2, 82.00, '2', 'B', true, 53.4794, -2.2484),
+        ('c3d4e5f6-a7b8-4c9d-0e1f-2a3b4c5d6e7f'::uuid, NULL, 1, 140.00, 'Ground', 'C', false, 52.4797, -1.9027),
+        ('d4e5f6a7-b8c9-4d0e-1f2a-3b4c5d6e7f80'::uuid, 4, 3, 210.00, '', 'D', false, 55.9533, -3.2050),
+        ('e5f6a7b8-c9d0-4e1f-2a3b-4c5d6e7f8091'::uuid, 0, 1, 32.00, '1', 'E', true, 51.4572, -2.6009),
+        ('f6a7b8c9-d0e1-4f2a-3b4c-5d6e7f809102'::uuid, 2, 1, 75.00, '4', 'C', false, 55.9519, -3.1961),
+        ('07b8c9d0-e1f2-4a3b-4c5d-6e7f80910213'::uuid, 3, 2, 145.00, '12', 'B', true, 51.5033, -0.1195),
+        ('18c9d0e1-f2a3-4b4c-5d6e-7f8091021324'::uuid, NULL, 1, 650.00, 'Ground', 'D', false, 53.7930, -1.5080)
+    ) AS v(id, bedrooms, bathrooms, sqm, floor, energy_rating, furnished, latitude, longitude)
+    WHERE p.id = v.id;
+
     -- =============================================
     -- Tenants
     -- =============================================
@@ -169,6 +189,48 @@ BEGIN
     ('ee000001-0000-4000-8000-000000000006', 'dd000001-0000-4000-8000-000000000005', 'Called to follow up but went to voicemail. Will try again next week.', 'James', NOW() - INTERVAL '20 days', NOW() - INTERVAL '20 days'),
     ('ee000001-0000-4000-8000-000000000007', 'dd000001-0000-4000-8000-000000000005', 'Client confirmed they signed with another agency. Marking as lost.', 'James', NOW() - INTERVAL '15 days', NOW() - INTERVAL '15 days');
 
+    -- =============================================
+    -- Document Templates
+    -- =============================================
+    INSERT INTO admin_document_templates (id, name, kind, format, body, created_at, updated_at) VALUES
+    ('ff000001-0000-4000-8000-000000000001', 'Assured Shorthold Tenancy', 'lease', 'markdown', $tpl$# Assured Shorthold Tenancy Agreement
+
+Reference: **{{contract.contract_ref}}** · Date: {{today}}
+
+## 1. Parties
+
+- Landlord: {{owner.name}}, {{owner.address}}
+- Tenant: {{tenant.name}}, {{tenant.email}}
+
+## 2. Property
+
+The landlord lets to the tenant the property known as {{property.property_name}}, {{property.address}}.
+
+## 3. Term
+
+The tenancy begins on {{contract.start_date}} and ends on {{contract.end_date}}.
+
+## 4. Rent
+
+The rent is {{contract.rent}} per month, payable in advance on the first day of each month.
+
+---
+
+Signed by the landlord: ______________________
+
+Signed by the tenant: ______________________
+$tpl$, NOW() - INTERVAL '60 days', NOW() - INTERVAL '60 days'),
+    ('ff000001-0000-4000-8000-000000000002', 'Rent Review Annex', 'annex', 'html', $tpl$<h1>Rent Review Annex</h1>
+<p>Annex to tenancy <strong>{{contract.contract_ref}}</strong> dated {{today}}.</p>
+<p>The parties agree that from the next rent period the rent for {{property.property_name}} is {{contract.rent}} per month.</p>
+<ol>
+  <li>All other terms of the tenancy remain unchanged.</li>
+  <li>This annex forms part of the tenancy agreement.</li>
+</ol>
+<hr>
+<p>{{owner.name}} &amp; {{tenant.name}}</p>
+$tpl$, NOW() - INTERVAL '30 days', NOW() - INTERVAL '30 days');
+
     -- =============================================
     -- Audit Log
     -- =============================================
@@ -0,0 +1,9 @@
+-- =============================================
+-- Owner Statements (liquidaciones)
+-- =============================================
+
+-- Management fee deducted from collected rent on each owner statement
+ALTER TABLE admin_owners
+    ADD COLUMN IF NOT EXISTS management_fee_pct NUMERIC(5,2) NOT NULL DEFAULT 10.00;
+
+COMMENT ON COLUMN admin_owners.management_fee_pct IS 'Management fee (% of collected rent) applied on owner statements';
@@ -0,0 +1,88 @@
+-- =============================================
+-- Owner Payouts (SEPA Credit Transfer batches)
+-- =============================================
+
+-- Payout batches: one pain.001 file per batch
+CREATE TABLE IF NOT EXISTS admin_payout_batches (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    batch_ref TEXT NOT NULL,
+    period_from DATE NOT NULL,
+    period_to DATE NOT NULL,
+    execution_date DATE NOT NULL,
+    status TEXT NOT NULL DEFAULT 'Draft',
+    debtor_name TEXT NOT NULL DEFAULT '',
+    debtor_iban TEXT NOT NULL DEFAULT '',
+    debtor_bic TEXT NOT NULL DEFAULT '',
+    currency TEXT NOT NULL DEFAULT 'EUR',
+    total_amount NUMERIC(12,2) NOT NULL DEFAULT 0,
+    num_transfers INTEGER NOT NULL DEFAULT 0,
+    approved_by TEXT NOT NULL DEFAULT '',
+    approved_at TIMESTAMPTZ,
+    exported_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_admin_payout_batches_ref ON admin_payout_batches(batch_ref);
+CREATE INDEX IF NOT EXISTS idx_admin_payout_batches_status ON admin_payout_batches(status);
+
+-- Individual credit transfers to owners
+CREATE TABLE IF NOT EXISTS admin_payout_transfers (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    batch_id UUID NOT NULL REFERENCES admin_payout_batches(id) ON DELETE CASCADE,
+    owner_id UUID NOT NULL,
+    owner_name TEXT NOT NULL DEFAULT '',
+    creditor_iban TEXT NOT NULL DEFAULT '',
+    amount NUMERIC(12,2) NOT NULL DEFAULT 0,
+    end_to_end_id TEXT NOT NULL DEFAULT '',
+    remittance_info TEXT NOT NULL DEFAULT '',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_admin_payout_transfers_batch ON admin_payout_transfers(batch_id);
+CREATE INDEX IF NOT EXISTS idx_admin_payout_transfers_owner ON admin_payout_transfers(owner_id);
+
+-- Amounts paid out per invoice: rent collected passed on to the owner for
+-- income invoices, the amount deducted for expenses. An invoice can be settled
+-- over several payouts as further payments come in. Only cancelling a batch
+-- removes them; batches are never trashed, and an invoice that was paid out
+-- cannot be purged.
+CREATE TABLE IF NOT EXISTS admin_payout_items (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    transfer_id UUID REFERENCES admin_payout_transfers(id) ON DELETE SET NULL,
+    invoice_id UUID NOT NULL REFERENCES admin_invoices(id) ON DELETE RESTRICT,
+    amount NUMERIC(12,2) NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_admin_payout_items_transfer ON admin_payout_items(transfer_id);
+CREATE INDEX IF NOT EXISTS idx_admin_payout_items_invoice ON admin_payout_items(invoice_id);
+
+-- Earlier installs linked each invoice to a single transfer
+DO $$
+BEGIN
+    IF EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_name = 'admin_invoices' AND column_name = 'payout_transfer_id'
+    ) THEN
+        INSERT INTO admin_payout_items (transfer_id, invoice_id, amount)
+        SELECT payout_transfer_id, id, CASE WHEN type = 'income' THEN paid ELSE amount END
+        FROM admin_invoices WHERE payout_transfer_id IS NOT NULL;
+        ALTER TABLE admin_invoices DROP COLUMN payout_transfer_id;
+    END IF;
+END $$;
+
+-- Earlier installs erased an invoice's payout history along with it
+DO $$
+BEGIN
+    IF EXISTS (
+        SELECT 1 FROM pg_constraint
+        WHERE conname = 'admin_payout_items_invoice_id_fkey' AND confdeltype = 'c'
+    ) THEN
+        ALTER TABLE admin_payout_items DROP CONSTRAINT admin_payout_items_invoice_id_fkey;
+        ALTER TABLE admin_payout_items ADD CONSTRAINT admin_payout_items_invoice_id_fkey
+            FOREIGN KEY (invoice_id) REFERENCES admin_invoices(id) ON DELETE RESTRICT;
+    END IF;
+END $$;
+
+COMMENT ON TABLE admin_payout_batches IS 'Owner payout batches exported as SEPA pain.001 credit transfers';
+COMMENT ON TABLE admin_payout_transfers IS 'Credit transfers to owners within a payout batch';
+COMMENT ON TABLE admin_payout_items IS 'Invoice amounts settled by owner payout transfers';
@@ -0,0 +1,10 @@
+-- =============================================
+-- Spanish Tax Reports (Modelo 115 / 180 / 347)
+-- =============================================
+
+-- Business tenants withhold IRPF on the rent they pay and declare it on
+-- Modelo 115 (quarterly) and Modelo 180 (annual summary).
+ALTER TABLE admin_tenants
+    ADD COLUMN IF NOT EXISTS irpf_withholding BOOLEAN NOT NULL DEFAULT false;
+
+COMMENT ON COLUMN admin_tenants.irpf_withholding IS 'Tenant is a withholding agent (retenedor) for IRPF on rent';
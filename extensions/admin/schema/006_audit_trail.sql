@@ -0,0 +1,14 @@
+-- =============================================
+-- Audit Trail (actor and revert tracking)
+-- =============================================
+
+ALTER TABLE admin_audit_log
+    ADD COLUMN IF NOT EXISTS actor_user_id TEXT,
+    ADD COLUMN IF NOT EXISTS actor_ip TEXT,
+    ADD COLUMN IF NOT EXISTS actor_user_agent TEXT,
+    ADD COLUMN IF NOT EXISTS reverted_by UUID REFERENCES admin_audit_log(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_admin_audit_log_entity ON admin_audit_log(entity_type, entity_id, created_at DESC);
+
+COMMENT ON COLUMN admin_audit_log.actor_user_id IS 'User id (token subject) that made the change';
+COMMENT ON COLUMN admin_audit_log.reverted_by IS 'Audit entry of the revert that undid this change';
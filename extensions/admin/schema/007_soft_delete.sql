@@ -0,0 +1,25 @@
+-- =============================================
+-- Soft Delete (trash with retention purge)
+-- =============================================
+
+-- Deleting a record sets deleted_at; lists and lookups hide it unless asked
+-- with ?include_deleted=true. The admin_trash_purge job removes rows that have
+-- been in the trash longer than the retention period.
+DO $$
+DECLARE
+    t TEXT;
+BEGIN
+    FOREACH t IN ARRAY ARRAY[
+        'admin_properties', 'admin_tenants', 'admin_owners', 'admin_contracts',
+        'admin_invoices', 'admin_deposits', 'admin_sepa_batches', 'admin_payout_batches',
+        'admin_issues', 'admin_insurance', 'admin_alerts', 'admin_contacts',
+        'admin_leads', 'admin_lead_notes'
+    ] LOOP
+        EXECUTE format('ALTER TABLE %I ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ', t);
+        EXECUTE format(
+            'CREATE INDEX IF NOT EXISTS %I ON %I (deleted_at) WHERE deleted_at IS NOT NULL',
+            'idx_' || t || '_deleted_at', t
+        );
+        EXECUTE format('COMMENT ON COLUMN %I.deleted_at IS %L', t, 'Moved to the trash at; NULL while live');
+    END LOOP;
+END $$;
@@ -0,0 +1,32 @@
+-- =============================================
+-- Row Versions (optimistic concurrency)
+-- =============================================
+
+-- Every UPDATE bumps revision, whoever makes it. The API exposes it as the
+-- ETag of a record and requires a matching If-Match on PUT and DELETE.
+CREATE OR REPLACE FUNCTION admin_bump_revision() RETURNS trigger AS $$
+BEGIN
+    NEW.revision := OLD.revision + 1;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DO $$
+DECLARE
+    t TEXT;
+BEGIN
+    FOREACH t IN ARRAY ARRAY[
+        'admin_properties', 'admin_tenants', 'admin_owners', 'admin_contracts',
+        'admin_invoices', 'admin_deposits', 'admin_sepa_batches', 'admin_payout_batches',
+        'admin_issues', 'admin_insurance', 'admin_alerts', 'admin_contacts',
+        'admin_leads', 'admin_lead_notes'
+    ] LOOP
+        EXECUTE format('ALTER TABLE %I ADD COLUMN IF NOT EXISTS revision INTEGER NOT NULL DEFAULT 1', t);
+        EXECUTE format('DROP TRIGGER IF EXISTS %I ON %I', t || '_revision', t);
+        EXECUTE format(
+            'CREATE TRIGGER %I BEFORE UPDATE ON %I FOR EACH ROW EXECUTE FUNCTION admin_bump_revision()',
+            t || '_revision', t
+        );
+        EXECUTE format('COMMENT ON COLUMN %I.revision IS %L', t, 'Incremented on every update; the record ETag');
+    END LOOP;
+END $$;
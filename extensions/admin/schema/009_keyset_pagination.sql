@@ -0,0 +1,12 @@
+-- =============================================
+-- Keyset Pagination
+-- =============================================
+
+-- Lists page by (sort column, id). These cover the default sorts of the
+-- largest tables so ?cursor= pages stay index scans.
+CREATE INDEX IF NOT EXISTS idx_admin_invoices_date_id ON admin_invoices(invoice_date DESC, id DESC);
+CREATE INDEX IF NOT EXISTS idx_admin_invoices_created_id ON admin_invoices(created_at DESC, id DESC);
+CREATE INDEX IF NOT EXISTS idx_admin_audit_created_id ON admin_audit_log(created_at DESC, id DESC);
+CREATE INDEX IF NOT EXISTS idx_admin_properties_created_id ON admin_properties(created_at DESC, id DESC);
+CREATE INDEX IF NOT EXISTS idx_admin_contracts_created_id ON admin_contracts(created_at DESC, id DESC);
+CREATE INDEX IF NOT EXISTS idx_admin_tenants_created_id ON admin_tenants(created_at DESC, id DESC);
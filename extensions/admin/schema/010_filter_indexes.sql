@@ -0,0 +1,8 @@
+-- =============================================
+-- Filter Indexes
+-- =============================================
+
+-- tags[contains] and tags[overlaps] filter with @> and &&, which only a GIN
+-- index can serve.
+CREATE INDEX IF NOT EXISTS idx_admin_properties_tags ON admin_properties USING GIN (tags);
+CREATE INDEX IF NOT EXISTS idx_admin_contracts_tags ON admin_contracts USING GIN (tags);
@@ -0,0 +1,65 @@
+-- =============================================
+-- Global Search
+-- =============================================
+
+CREATE EXTENSION IF NOT EXISTS unaccent;
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+-- unaccent() is only STABLE because its dictionary can change; pinning the
+-- dictionary makes it usable in index expressions.
+CREATE OR REPLACE FUNCTION admin_unaccent(TEXT) RETURNS TEXT
+    LANGUAGE sql IMMUTABLE PARALLEL SAFE STRICT
+    AS $$ SELECT public.unaccent('public.unaccent'::regdictionary, $1) $$;
+
+-- Accent-folding, language-neutral configuration: "Garcia" matches "García".
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_ts_config WHERE cfgname = 'admin_search') THEN
+        CREATE TEXT SEARCH CONFIGURATION admin_search (COPY = simple);
+        ALTER TEXT SEARCH CONFIGURATION admin_search
+            ALTER MAPPING FOR hword, hword_part, word WITH unaccent, simple;
+    END IF;
+END $$;
+
+-- The searchable text of a record, from its searchable columns.
+CREATE OR REPLACE FUNCTION admin_search_text(TEXT[]) RETURNS TEXT
+    LANGUAGE sql IMMUTABLE PARALLEL SAFE
+    AS $$ SELECT array_to_string($1, ' ') $$;
+
+-- The same text lower-cased and unaccented, for trigram (typo) matching.
+CREATE OR REPLACE FUNCTION admin_search_key(TEXT[]) RETURNS TEXT
+    LANGUAGE sql IMMUTABLE PARALLEL SAFE
+    AS $$ SELECT lower(admin_unaccent(array_to_string($1, ' '))) $$;
+
+-- One full-text and one trigram index per searched table. The column arrays
+-- must match the `fields` of the targets in services/search.rs exactly, or
+-- the planner will not use the indexes.
+CREATE INDEX IF NOT EXISTS idx_admin_properties_fts ON admin_properties
+    USING GIN (to_tsvector('admin_search', admin_search_text(ARRAY[property_name, address, contract_ref] || tags)));
+CREATE INDEX IF NOT EXISTS idx_admin_properties_trgm ON admin_properties
+    USING GIN (admin_search_key(ARRAY[property_name, address, contract_ref] || tags) gin_trgm_ops);
+
+CREATE INDEX IF NOT EXISTS idx_admin_tenants_fts ON admin_tenants
+    USING GIN (to_tsvector('admin_search', admin_search_text(ARRAY[name, tax_id, email, phone, property_name])));
+CREATE INDEX IF NOT EXISTS idx_admin_tenants_trgm ON admin_tenants
+    USING GIN (admin_search_key(ARRAY[name, tax_id, email, phone, property_name]) gin_trgm_ops);
+
+CREATE INDEX IF NOT EXISTS idx_admin_owners_fts ON admin_owners
+    USING GIN (to_tsvector('admin_search', admin_search_text(ARRAY[name, tax_id, email, phone, property_name])));
+CREATE INDEX IF NOT EXISTS idx_admin_owners_trgm ON admin_owners
+    USING GIN (admin_search_key(ARRAY[name, tax_id, email, phone, property_name]) gin_trgm_ops);
+
+CREATE INDEX IF NOT EXISTS idx_admin_contracts_fts ON admin_contracts
+    USING GIN (to_tsvector('admin_search', admin_search_text(ARRAY[contract_ref, property_name, address, tenant_name] || tags)));
+CREATE INDEX IF NOT EXISTS idx_admin_contracts_trgm ON admin_contracts
+    USING GIN (admin_search_key(ARRAY[contract_ref, property_name, address, tenant_name] || tags) gin_trgm_ops);
+
+CREATE INDEX IF NOT EXISTS idx_admin_invoices_fts ON admin_invoices
+    USING GIN (to_tsvector('admin_search', admin_search_text(ARRAY[reference, description, contract_ref, property_name, payer, payee])));
+CREATE INDEX IF NOT EXISTS idx_admin_invoices_trgm ON admin_invoices
+    USING GIN (admin_search_key(ARRAY[reference, description, contract_ref, property_name, payer, payee]) gin_trgm_ops);
+
+CREATE INDEX IF NOT EXISTS idx_admin_contacts_fts ON admin_contacts
+    USING GIN (to_tsvector('admin_search', admin_search_text(ARRAY[name, tax_id, email, phone, notes])));
+CREATE INDEX IF NOT EXISTS idx_admin_contacts_trgm ON admin_contacts
+    USING GIN (admin_search_key(ARRAY[name, tax_id, email, phone, notes]) gin_trgm_ops);
@@ -0,0 +1,21 @@
+-- =============================================
+-- Saved Views (per-user list presets)
+-- =============================================
+
+CREATE TABLE IF NOT EXISTS admin_saved_views (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    entity TEXT NOT NULL,
+    name TEXT NOT NULL,
+    owner_user_id TEXT NOT NULL,
+    shared BOOLEAN NOT NULL DEFAULT false,
+    query JSONB NOT NULL DEFAULT '{}',
+    columns TEXT[] NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (owner_user_id, entity, name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_admin_saved_views_entity ON admin_saved_views(entity, owner_user_id);
+
+COMMENT ON COLUMN admin_saved_views.query IS 'Stored list parameters: search, sort, order, per_page and filters';
+COMMENT ON COLUMN admin_saved_views.shared IS 'Visible to everyone in the agency, editable only by the owner';
@@ -0,0 +1,35 @@
+-- =============================================
+-- Custom Fields
+-- =============================================
+
+-- Agency-defined fields, e.g. the energy certificate rating of a property.
+CREATE TABLE IF NOT EXISTS admin_custom_fields (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    entity TEXT NOT NULL,
+    key TEXT NOT NULL CHECK (key ~ '^[a-z][a-z0-9_]{0,62}$'),
+    label TEXT NOT NULL,
+    field_type TEXT NOT NULL CHECK (field_type IN ('text', 'number', 'date', 'boolean', 'select')),
+    options TEXT[] NOT NULL DEFAULT '{}',
+    position INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (entity, key)
+);
+
+COMMENT ON COLUMN admin_custom_fields.options IS 'Allowed values of a select field';
+
+-- Values live on each record, keyed by the field key.
+DO $$
+DECLARE
+    t TEXT;
+BEGIN
+    FOREACH t IN ARRAY ARRAY[
+        'admin_properties', 'admin_tenants', 'admin_owners', 'admin_contracts',
+        'admin_invoices', 'admin_deposits', 'admin_sepa_batches', 'admin_payout_batches',
+        'admin_issues', 'admin_insurance', 'admin_alerts', 'admin_contacts',
+        'admin_leads', 'admin_lead_notes'
+    ] LOOP
+        EXECUTE format('ALTER TABLE %I ADD COLUMN IF NOT EXISTS custom_fields JSONB NOT NULL DEFAULT ''{}''', t);
+        EXECUTE format('COMMENT ON COLUMN %I.custom_fields IS %L', t, 'Values of the admin_custom_fields defined for the entity');
+    END LOOP;
+END $$;
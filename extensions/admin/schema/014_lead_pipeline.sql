@@ -0,0 +1,30 @@
+-- =============================================
+-- Lead Pipeline
+-- =============================================
+
+-- A converted lead points at the tenant and draft contract created from it.
+ALTER TABLE admin_leads ADD COLUMN IF NOT EXISTS converted_tenant_id UUID;
+ALTER TABLE admin_leads ADD COLUMN IF NOT EXISTS converted_contract_id UUID;
+ALTER TABLE admin_leads ADD COLUMN IF NOT EXISTS converted_at TIMESTAMPTZ;
+
+-- Contracts created from a lead carry its notes.
+ALTER TABLE admin_contracts ADD COLUMN IF NOT EXISTS notes TEXT NOT NULL DEFAULT '';
+
+-- How well a rent fits a budget, from 0 to 1: 1 inside the range, falling
+-- to 0 at 20% above the maximum or 50% below the minimum. A missing bound
+-- is open; NULL when the lead gave no budget at all.
+CREATE OR REPLACE FUNCTION admin_budget_fit(rent NUMERIC, budget_min NUMERIC, budget_max NUMERIC)
+    RETURNS FLOAT8
+    LANGUAGE sql IMMUTABLE PARALLEL SAFE
+    AS $$
+    SELECT CASE
+        WHEN budget_min IS NULL AND budget_max IS NULL THEN NULL
+        WHEN rent > budget_max
+            THEN GREATEST(0, 1 - (rent - budget_max) / NULLIF(budget_max * 0.2, 0))::float8
+        WHEN rent < budget_min
+            THEN GREATEST(0, 1 - (budget_min - rent) / NULLIF(budget_min * 0.5, 0))::float8
+        ELSE 1
+    END
+    $$;
+
+COMMENT ON COLUMN admin_leads.score IS 'Recomputed nightly from recency, budget fit and note activity';
@@ -0,0 +1,60 @@
+-- =============================================
+-- Property Attributes and Buildings
+-- =============================================
+
+ALTER TABLE admin_properties ADD COLUMN IF NOT EXISTS bedrooms INTEGER CHECK (bedrooms >= 0);
+ALTER TABLE admin_properties ADD COLUMN IF NOT EXISTS bathrooms INTEGER CHECK (bathrooms >= 0);
+ALTER TABLE admin_properties ADD COLUMN IF NOT EXISTS sqm NUMERIC(10,2) CHECK (sqm > 0);
+ALTER TABLE admin_properties ADD COLUMN IF NOT EXISTS floor TEXT NOT NULL DEFAULT '';
+ALTER TABLE admin_properties ADD COLUMN IF NOT EXISTS energy_rating TEXT
+    CHECK (energy_rating IN ('A', 'B', 'C', 'D', 'E', 'F', 'G'));
+ALTER TABLE admin_properties ADD COLUMN IF NOT EXISTS cadastral_ref TEXT NOT NULL DEFAULT ''
+    CHECK (cadastral_ref = '' OR cadastral_ref ~ '^[0-9A-Z]{20}$');
+ALTER TABLE admin_properties ADD COLUMN IF NOT EXISTS furnished BOOLEAN;
+ALTER TABLE admin_properties ADD COLUMN IF NOT EXISTS latitude DOUBLE PRECISION
+    CHECK (latitude BETWEEN -90 AND 90);
+ALTER TABLE admin_properties ADD COLUMN IF NOT EXISTS longitude DOUBLE PRECISION
+    CHECK (longitude BETWEEN -180 AND 180);
+
+-- A building groups units. Its community fees, insurance and issues apply to
+-- every unit, and units are let individually.
+ALTER TABLE admin_properties ADD COLUMN IF NOT EXISTS kind TEXT NOT NULL DEFAULT 'unit'
+    CHECK (kind IN ('building', 'unit'));
+ALTER TABLE admin_properties ADD COLUMN IF NOT EXISTS parent_id UUID
+    REFERENCES admin_properties(id) ON DELETE SET NULL;
+ALTER TABLE admin_properties ADD COLUMN IF NOT EXISTS community_fee NUMERIC(10,2) NOT NULL DEFAULT 0;
+ALTER TABLE admin_properties ADD COLUMN IF NOT EXISTS fee_share NUMERIC(7,4)
+    CHECK (fee_share > 0 AND fee_share <= 100);
+
+CREATE INDEX IF NOT EXISTS idx_admin_properties_parent ON admin_properties(parent_id);
+
+COMMENT ON COLUMN admin_properties.cadastral_ref IS '20-character cadastral reference';
+COMMENT ON COLUMN admin_properties.community_fee IS 'Monthly community fee; for a building, shared among its units';
+COMMENT ON COLUMN admin_properties.fee_share IS 'Percentage of the building community fee borne by the unit; by floor area when unset';
+
+-- Units belong to buildings, one level deep.
+CREATE OR REPLACE FUNCTION admin_check_property_parent() RETURNS trigger AS $$
+BEGIN
+    IF NEW.parent_id IS NOT NULL THEN
+        IF NEW.kind <> 'unit' THEN
+            RAISE EXCEPTION 'Only a unit can belong to a building' USING ERRCODE = 'check_violation';
+        END IF;
+        IF NOT EXISTS (
+            SELECT 1 FROM admin_properties WHERE id = NEW.parent_id AND kind = 'building'
+        ) THEN
+            RAISE EXCEPTION 'parent_id must be a building' USING ERRCODE = 'check_violation';
+        END IF;
+    END IF;
+    IF TG_OP = 'UPDATE' AND OLD.kind = 'building' AND NEW.kind <> 'building' AND EXISTS (
+        SELECT 1 FROM admin_properties WHERE parent_id = NEW.id
+    ) THEN
+        RAISE EXCEPTION 'A building with units cannot become a unit' USING ERRCODE = 'check_violation';
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS admin_properties_parent ON admin_properties;
+CREATE TRIGGER admin_properties_parent
+    BEFORE INSERT OR UPDATE OF parent_id, kind ON admin_properties
+    FOR EACH ROW EXECUTE FUNCTION admin_check_property_parent();
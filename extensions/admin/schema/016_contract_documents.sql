@@ -0,0 +1,35 @@
+-- =============================================
+-- Document Templates and Contract Documents
+-- =============================================
+
+-- Lease and annex templates with {{root.field}} merge fields
+CREATE TABLE IF NOT EXISTS admin_document_templates (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name TEXT NOT NULL UNIQUE,
+    kind TEXT NOT NULL DEFAULT 'lease' CHECK (kind IN ('lease', 'annex')),
+    format TEXT NOT NULL DEFAULT 'markdown' CHECK (format IN ('markdown', 'html')),
+    body TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Files attached to a contract, generated from a template or uploaded
+CREATE TABLE IF NOT EXISTS admin_contract_documents (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    contract_id UUID NOT NULL REFERENCES admin_contracts(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    type TEXT NOT NULL DEFAULT 'contract',
+    file_type TEXT NOT NULL DEFAULT 'application/pdf',
+    original_file TEXT NOT NULL DEFAULT '',
+    size INTEGER NOT NULL DEFAULT 0,
+    content BYTEA,
+    template_id UUID REFERENCES admin_document_templates(id) ON DELETE SET NULL,
+    document_date DATE NOT NULL DEFAULT CURRENT_DATE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_admin_contract_documents_contract
+    ON admin_contract_documents(contract_id, created_at DESC);
+
+COMMENT ON COLUMN admin_contract_documents.type IS 'lease or annex for generated documents';
+COMMENT ON COLUMN admin_contract_documents.original_file IS 'Download file name; empty when there is no file';
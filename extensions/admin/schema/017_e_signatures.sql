@@ -0,0 +1,51 @@
+-- =============================================
+-- Electronic Signatures
+-- =============================================
+
+-- Merged template blocks of generated documents; only these can be sent for
+-- signature
+ALTER TABLE admin_contract_documents ADD COLUMN IF NOT EXISTS blocks JSONB;
+ALTER TABLE admin_contract_documents ADD COLUMN IF NOT EXISTS sha256 TEXT NOT NULL DEFAULT '';
+
+CREATE TABLE IF NOT EXISTS admin_signature_requests (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    contract_id UUID NOT NULL REFERENCES admin_contracts(id) ON DELETE CASCADE,
+    document_id UUID NOT NULL REFERENCES admin_contract_documents(id) ON DELETE CASCADE,
+    document_hash TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'completed', 'cancelled')),
+    signed_document_id UUID REFERENCES admin_contract_documents(id) ON DELETE SET NULL,
+    created_by TEXT,
+    expires_at TIMESTAMPTZ NOT NULL DEFAULT NOW() + INTERVAL '14 days',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ
+);
+
+ALTER TABLE admin_signature_requests ADD COLUMN IF NOT EXISTS signed_hash TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_admin_signature_requests_contract
+    ON admin_signature_requests(contract_id, created_at DESC);
+
+CREATE TABLE IF NOT EXISTS admin_signers (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    request_id UUID NOT NULL REFERENCES admin_signature_requests(id) ON DELETE CASCADE,
+    role TEXT NOT NULL CHECK (role IN ('tenant', 'owner')),
+    name TEXT NOT NULL,
+    email TEXT NOT NULL DEFAULT '',
+    token_hash TEXT NOT NULL UNIQUE,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'signed')),
+    method TEXT CHECK (method IN ('drawn', 'typed')),
+    signature TEXT,
+    document_hash TEXT,
+    signed_at TIMESTAMPTZ,
+    ip TEXT,
+    user_agent TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_admin_signers_request ON admin_signers(request_id);
+
+COMMENT ON COLUMN admin_signature_requests.document_hash IS 'SHA-256 of the PDF sent for signature, hex';
+COMMENT ON COLUMN admin_signature_requests.signed_hash IS 'SHA-256 of the signed copy: the signed PDF with the evidence pages appended, hex';
+COMMENT ON COLUMN admin_signers.token_hash IS 'SHA-256 of the one-time signing token; the token itself is only returned when the request is created';
+COMMENT ON COLUMN admin_signers.signature IS 'Typed name, or a PNG data URL for a drawn signature';
+COMMENT ON COLUMN admin_signers.document_hash IS 'SHA-256 of the PDF the signer was shown';
@@ -0,0 +1,63 @@
+-- =============================================
+-- Move-in / Move-out Inspections
+-- =============================================
+
+-- One check-in and one check-out inventory per contract
+CREATE TABLE IF NOT EXISTS admin_inspections (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    contract_id UUID NOT NULL REFERENCES admin_contracts(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL CHECK (kind IN ('check_in', 'check_out')),
+    inspection_date DATE NOT NULL DEFAULT CURRENT_DATE,
+    inspector TEXT NOT NULL DEFAULT '',
+    notes TEXT NOT NULL DEFAULT '',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (contract_id, kind)
+);
+
+-- Condition: 5 Excellent, 4 Good, 3 Fair, 2 Poor, 1 Damaged, 0 Missing
+CREATE TABLE IF NOT EXISTS admin_inspection_items (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    inspection_id UUID NOT NULL REFERENCES admin_inspections(id) ON DELETE CASCADE,
+    room TEXT NOT NULL,
+    item TEXT NOT NULL,
+    condition SMALLINT NOT NULL CHECK (condition BETWEEN 0 AND 5),
+    notes TEXT NOT NULL DEFAULT '',
+    replacement_cost NUMERIC(10,2) CHECK (replacement_cost >= 0),
+    position INTEGER NOT NULL DEFAULT 0,
+    UNIQUE (inspection_id, room, item)
+);
+
+CREATE INDEX IF NOT EXISTS idx_admin_inspection_items_inspection
+    ON admin_inspection_items(inspection_id, position);
+
+CREATE TABLE IF NOT EXISTS admin_inspection_photos (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    item_id UUID NOT NULL REFERENCES admin_inspection_items(id) ON DELETE CASCADE,
+    caption TEXT NOT NULL DEFAULT '',
+    file_type TEXT NOT NULL,
+    size INTEGER NOT NULL,
+    content BYTEA NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_admin_inspection_photos_item ON admin_inspection_photos(item_id);
+
+-- Deductions taken from a deposit, itemised
+ALTER TABLE admin_deposits ADD COLUMN IF NOT EXISTS deductions NUMERIC(10,2) NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS admin_deposit_deductions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    deposit_id UUID NOT NULL REFERENCES admin_deposits(id) ON DELETE CASCADE,
+    inspection_id UUID REFERENCES admin_inspections(id) ON DELETE SET NULL,
+    room TEXT NOT NULL DEFAULT '',
+    item TEXT NOT NULL DEFAULT '',
+    reason TEXT NOT NULL DEFAULT '',
+    amount NUMERIC(10,2) NOT NULL CHECK (amount > 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_admin_deposit_deductions_deposit ON admin_deposit_deductions(deposit_id);
+
+COMMENT ON COLUMN admin_inspection_items.replacement_cost IS 'Cost to replace the item, the basis of suggested deductions';
+COMMENT ON COLUMN admin_deposits.deductions IS 'Sum of admin_deposit_deductions for the deposit';
@@ -0,0 +1,74 @@
+-- =============================================
+-- Tenant Portal
+-- =============================================
+
+-- OAuth users allowed into the portal and the record they act as. Tenants
+-- sign in through the property-portal client with the tenant scope and only
+-- ever see rows reached from their own tenant record.
+CREATE TABLE IF NOT EXISTS admin_portal_accounts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id TEXT NOT NULL,
+    role TEXT NOT NULL CHECK (role IN ('tenant')),
+    tenant_id UUID REFERENCES admin_tenants(id) ON DELETE CASCADE,
+    created_by TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, role)
+);
+
+CREATE INDEX IF NOT EXISTS idx_admin_portal_accounts_tenant
+    ON admin_portal_accounts(tenant_id);
+
+-- The tenant record a contract is with. The portal scopes by it rather than
+-- by tenant_name, which is free text and not unique.
+ALTER TABLE admin_contracts ADD COLUMN IF NOT EXISTS tenant_id UUID
+    REFERENCES admin_tenants(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_admin_contracts_tenant
+    ON admin_contracts(tenant_id) WHERE tenant_id IS NOT NULL;
+
+-- Link a contract saved without a tenant_id to the tenant it names, when
+-- exactly one live tenant has that name; others are left for an admin to link.
+CREATE OR REPLACE FUNCTION admin_link_contract_tenant() RETURNS trigger AS $$
+BEGIN
+    IF NEW.tenant_id IS NULL OR (
+        TG_OP = 'UPDATE' AND NEW.tenant_name IS DISTINCT FROM OLD.tenant_name
+        AND NEW.tenant_id IS NOT DISTINCT FROM OLD.tenant_id
+    ) THEN
+        NEW.tenant_id := (
+            SELECT CASE WHEN COUNT(*) = 1 THEN MIN(id::text)::uuid END
+            FROM admin_tenants WHERE name = NEW.tenant_name AND deleted_at IS NULL
+        );
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS admin_contracts_tenant ON admin_contracts;
+CREATE TRIGGER admin_contracts_tenant
+    BEFORE INSERT OR UPDATE OF tenant_name, tenant_id ON admin_contracts
+    FOR EACH ROW EXECUTE FUNCTION admin_link_contract_tenant();
+
+UPDATE admin_contracts SET tenant_name = tenant_name
+WHERE tenant_id IS NULL AND tenant_name IN (
+    SELECT name FROM admin_tenants WHERE deleted_at IS NULL GROUP BY name HAVING COUNT(*) = 1
+);
+
+-- Issues reported by a tenant from the portal
+ALTER TABLE admin_issues ADD COLUMN IF NOT EXISTS tenant_id UUID
+    REFERENCES admin_tenants(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_admin_issues_tenant
+    ON admin_issues(tenant_id, created_at DESC) WHERE tenant_id IS NOT NULL;
+
+CREATE TABLE IF NOT EXISTS admin_issue_photos (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    issue_id UUID NOT NULL REFERENCES admin_issues(id) ON DELETE CASCADE,
+    caption TEXT NOT NULL DEFAULT '',
+    file_type TEXT NOT NULL,
+    size INTEGER NOT NULL,
+    content BYTEA NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_admin_issue_photos_issue
+    ON admin_issue_photos(issue_id, created_at);
@@ -0,0 +1,15 @@
+-- =============================================
+-- Owner Portal
+-- =============================================
+
+-- Owners sign in through the same property-portal client with the owner
+-- scope and see only the properties listed on their owner record.
+ALTER TABLE admin_portal_accounts ADD COLUMN IF NOT EXISTS owner_id UUID
+    REFERENCES admin_owners(id) ON DELETE CASCADE;
+
+ALTER TABLE admin_portal_accounts DROP CONSTRAINT IF EXISTS admin_portal_accounts_role_check;
+ALTER TABLE admin_portal_accounts ADD CONSTRAINT admin_portal_accounts_role_check
+    CHECK (role IN ('tenant', 'owner'));
+
+CREATE INDEX IF NOT EXISTS idx_admin_portal_accounts_owner
+    ON admin_portal_accounts(owner_id);
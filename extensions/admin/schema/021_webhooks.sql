@@ -0,0 +1,86 @@
+-- =============================================
+-- Outbound Webhooks
+-- =============================================
+
+-- Endpoints that receive domain events. An empty event list subscribes to
+-- every event; entries may be exact (invoice.paid) or per entity (invoice.*).
+CREATE TABLE IF NOT EXISTS admin_webhooks (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name TEXT NOT NULL,
+    url TEXT NOT NULL,
+    secret TEXT NOT NULL,
+    events TEXT[] NOT NULL DEFAULT '{}',
+    is_active BOOLEAN NOT NULL DEFAULT true,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Outbox: written in the same transaction as the change that caused it
+CREATE TABLE IF NOT EXISTS admin_webhook_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    event_type TEXT NOT NULL,
+    entity_type TEXT NOT NULL,
+    entity_id UUID,
+    payload JSONB NOT NULL,
+    audit_id UUID REFERENCES admin_audit_log(id) ON DELETE SET NULL,
+    dedupe_key TEXT UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_admin_webhook_events_created
+    ON admin_webhook_events(created_at);
+
+COMMENT ON COLUMN admin_webhook_events.dedupe_key IS 'Set on scheduled events so each is queued once';
+
+-- One row per event and subscribed webhook
+CREATE TABLE IF NOT EXISTS admin_webhook_deliveries (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    webhook_id UUID NOT NULL REFERENCES admin_webhooks(id) ON DELETE CASCADE,
+    event_id UUID NOT NULL REFERENCES admin_webhook_events(id) ON DELETE CASCADE,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'delivered', 'failed')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    response_status INTEGER,
+    last_error TEXT,
+    delivered_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (webhook_id, event_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_admin_webhook_deliveries_due
+    ON admin_webhook_deliveries(next_attempt_at) WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_admin_webhook_deliveries_webhook
+    ON admin_webhook_deliveries(webhook_id, created_at DESC);
+
+-- Every POST made for a delivery
+CREATE TABLE IF NOT EXISTS admin_webhook_attempts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    delivery_id UUID NOT NULL REFERENCES admin_webhook_deliveries(id) ON DELETE CASCADE,
+    attempt INTEGER NOT NULL,
+    response_status INTEGER,
+    response_body TEXT NOT NULL DEFAULT '',
+    error TEXT,
+    duration_ms INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_admin_webhook_attempts_delivery
+    ON admin_webhook_attempts(delivery_id, attempt);
+
+-- Queue a delivery for every active webhook subscribed to a new event
+CREATE OR REPLACE FUNCTION admin_webhook_fan_out() RETURNS trigger AS $$
+BEGIN
+    INSERT INTO admin_webhook_deliveries (webhook_id, event_id)
+    SELECT w.id, NEW.id FROM admin_webhooks w
+    WHERE w.is_active
+      AND (cardinality(w.events) = 0
+           OR NEW.event_type = ANY(w.events)
+           OR split_part(NEW.event_type, '.', 1) || '.*' = ANY(w.events));
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS admin_webhook_events_fan_out ON admin_webhook_events;
+CREATE TRIGGER admin_webhook_events_fan_out AFTER INSERT ON admin_webhook_events
+    FOR EACH ROW EXECUTE FUNCTION admin_webhook_fan_out();
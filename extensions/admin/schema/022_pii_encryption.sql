@@ -0,0 +1,59 @@
+-- =============================================
+-- Encrypted Personal Data
+-- =============================================
+
+-- Tax ids, IBANs and phone numbers are sealed by the application (see
+-- services/pii.rs). Each sits next to a blind index, an HMAC of the
+-- normalised value, used for exact-match search and filters; NULL while the
+-- value is empty or not yet encrypted.
+ALTER TABLE admin_tenants ADD COLUMN IF NOT EXISTS tax_id_bidx TEXT;
+ALTER TABLE admin_tenants ADD COLUMN IF NOT EXISTS phone_bidx TEXT;
+ALTER TABLE admin_tenants ADD COLUMN IF NOT EXISTS bank_account_bidx TEXT;
+
+ALTER TABLE admin_owners ADD COLUMN IF NOT EXISTS tax_id_bidx TEXT;
+ALTER TABLE admin_owners ADD COLUMN IF NOT EXISTS phone_bidx TEXT;
+ALTER TABLE admin_owners ADD COLUMN IF NOT EXISTS bank_account_bidx TEXT;
+
+ALTER TABLE admin_contacts ADD COLUMN IF NOT EXISTS tax_id_bidx TEXT;
+ALTER TABLE admin_contacts ADD COLUMN IF NOT EXISTS iban_bidx TEXT;
+ALTER TABLE admin_contacts ADD COLUMN IF NOT EXISTS phone_bidx TEXT;
+
+-- Payouts copy the owner's and the paying account's IBANs
+ALTER TABLE admin_payout_batches ADD COLUMN IF NOT EXISTS debtor_iban_bidx TEXT;
+ALTER TABLE admin_payout_transfers ADD COLUMN IF NOT EXISTS creditor_iban_bidx TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_admin_tenants_tax_id_bidx ON admin_tenants(tax_id_bidx) WHERE tax_id_bidx IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_admin_tenants_phone_bidx ON admin_tenants(phone_bidx) WHERE phone_bidx IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_admin_tenants_bank_account_bidx ON admin_tenants(bank_account_bidx) WHERE bank_account_bidx IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_admin_owners_tax_id_bidx ON admin_owners(tax_id_bidx) WHERE tax_id_bidx IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_admin_owners_phone_bidx ON admin_owners(phone_bidx) WHERE phone_bidx IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_admin_owners_bank_account_bidx ON admin_owners(bank_account_bidx) WHERE bank_account_bidx IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_admin_contacts_tax_id_bidx ON admin_contacts(tax_id_bidx) WHERE tax_id_bidx IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_admin_contacts_iban_bidx ON admin_contacts(iban_bidx) WHERE iban_bidx IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_admin_contacts_phone_bidx ON admin_contacts(phone_bidx) WHERE phone_bidx IS NOT NULL;
+
+-- Ciphertext is not searchable text: global search now indexes only the
+-- clear columns. Must match the `fields` in services/search.rs.
+DROP INDEX IF EXISTS idx_admin_tenants_fts;
+DROP INDEX IF EXISTS idx_admin_tenants_trgm;
+DROP INDEX IF EXISTS idx_admin_owners_fts;
+DROP INDEX IF EXISTS idx_admin_owners_trgm;
+DROP INDEX IF EXISTS idx_admin_contacts_fts;
+DROP INDEX IF EXISTS idx_admin_contacts_trgm;
+
+CREATE INDEX IF NOT EXISTS idx_admin_tenants_search_fts ON admin_tenants
+    USING GIN (to_tsvector('admin_search', admin_search_text(ARRAY[name, email, property_name])));
+CREATE INDEX IF NOT EXISTS idx_admin_tenants_search_trgm ON admin_tenants
+    USING GIN (admin_search_key(ARRAY[name, email, property_name]) gin_trgm_ops);
+
+CREATE INDEX IF NOT EXISTS idx_admin_owners_search_fts ON admin_owners
+    USING GIN (to_tsvector('admin_search', admin_search_text(ARRAY[name, email, property_name])));
+CREATE INDEX IF NOT EXISTS idx_admin_owners_search_trgm ON admin_owners
+    USING GIN (admin_search_key(ARRAY[name, email, property_name]) gin_trgm_ops);
+
+CREATE INDEX IF NOT EXISTS idx_admin_contacts_search_fts ON admin_contacts
+    USING GIN (to_tsvector('admin_search', admin_search_text(ARRAY[name, email, notes])));
+CREATE INDEX IF NOT EXISTS idx_admin_contacts_search_trgm ON admin_contacts
+    USING GIN (admin_search_key(ARRAY[name, email, notes]) gin_trgm_ops);
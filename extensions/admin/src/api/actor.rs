@@ -0,0 +1,211 @@
+use std::convert::Infallible;
+use std::net::SocketAddr;
+
+use axum::extract::{ConnectInfo, FromRequestParts};
+use axum::http::header::{AUTHORIZATION, COOKIE, USER_AGENT};
+use axum::http::request::Parts;
+use axum::http::HeaderMap;
+use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+use serde::{Deserialize, Serialize};
+use systemprompt::models::SecretsBootstrap;
+
+use crate::error::AdminError;
+
+/// Proxies assumed in front of the server when `ADMIN_TRUSTED_PROXY_HOPS`
+/// is not set.
+const DEFAULT_PROXY_HOPS: usize = 1;
+
+/// Who made a request, recorded on audit log entries.
+///
+/// The user id is the `sub` claim of the access token sent as a Bearer header
+/// or the `access_token` session cookie, once its signature and expiry check
+/// out. The IP is the client address as recorded by the trusted proxies or
+/// the connection itself, never a value the client can set.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Actor {
+    pub user_id: Option<String>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+impl Actor {
+    pub fn from_parts(parts: &Parts) -> Self {
+        let headers = &parts.headers;
+        Self {
+            user_id: access_token(headers).and_then(token_subject),
+            ip: client_ip(parts),
+            user_agent: header_str(headers, USER_AGENT.as_str()).map(str::to_string),
+        }
+    }
+
+    /// The user of an access token received outside an HTTP request, such as
+    /// an MCP tool call.
+    pub fn from_token(token: &str) -> Self {
+        Self {
+            user_id: token_subject(token),
+            ..Self::default()
+        }
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for Actor {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self::from_parts(parts))
+    }
+}
+
+/// Claims read from a verified access token.
+#[derive(Debug, Deserialize)]
+pub(crate) struct Claims {
+    pub sub: String,
+    /// Space-separated scopes
+    #[serde(default)]
+    pub scope: String,
+}
+
+/// Check an access token's signature and expiry against the platform JWT
+/// secret and return its claims.
+pub(crate) fn verify_token(token: &str) -> Result<Claims, AdminError> {
+    let secret = SecretsBootstrap::jwt_secret().map_err(|e| {
+        tracing::error!(error = %e, "JWT secret unavailable to verify access tokens");
+        AdminError::Unauthorized("Access token cannot be verified".to_string())
+    })?;
+
+    let mut validation = Validation::new(Algorithm::HS256);
+    validation.validate_aud = false;
+    decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(secret.as_bytes()),
+        &validation,
+    )
+    .map(|data| data.claims)
+    .map_err(|_| AdminError::Unauthorized("Invalid or expired access token".to_string()))
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+}
+
+pub(crate) fn access_token(headers: &HeaderMap) -> Option<&str> {
+    if let Some(token) =
+        header_str(headers, AUTHORIZATION.as_str()).and_then(|v| v.strip_prefix("Bearer "))
+    {
+        return Some(token.trim());
+    }
+    header_str(headers, COOKIE.as_str())?
+        .split(';')
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(name, _)| *name == "access_token")
+        .map(|(_, value)| value)
+        .filter(|value| !value.is_empty())
+}
+
+fn token_subject(token: &str) -> Option<String> {
+    verify_token(token).ok().map(|claims| claims.sub)
+}
+
+/// Number of proxies in front of the server, each appending the address it
+/// received the request from to `X-Forwarded-For`. `ADMIN_TRUSTED_PROXY_HOPS`,
+/// 0 when the server is reached directly.
+fn trusted_proxy_hops() -> usize {
+    std::env::var("ADMIN_TRUSTED_PROXY_HOPS")
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(DEFAULT_PROXY_HOPS)
+}
+
+/// The client address recorded by the outermost trusted proxy: the entry
+/// that many places from the right of `X-Forwarded-For`. Entries further
+/// left come from the client and are ignored. Without proxies, or when the
+/// header is missing, the peer address of the connection if the server
+/// records it.
+fn client_ip(parts: &Parts) -> Option<String> {
+    forwarded_for(&parts.headers, trusted_proxy_hops()).or_else(|| {
+        parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip().to_string())
+    })
+}
+
+fn forwarded_for(headers: &HeaderMap, hops: usize) -> Option<String> {
+    if hops == 0 {
+        return None;
+    }
+    let entries: Vec<&str> = header_str(headers, "x-forwarded-for")?
+        .split(',')
+        .map(str::trim)
+        .collect();
+    let index = entries.len().checked_sub(hops)?;
+    entries
+        .get(index)
+        .filter(|ip| !ip.is_empty())
+        .map(|ip| (*ip).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.append(*name, HeaderValue::from_static(value));
+        }
+        map
+    }
+
+    #[test]
+    fn bearer_token_wins_over_the_cookie() {
+        let map = headers(&[
+            ("authorization", "Bearer  abc.def.ghi "),
+            ("cookie", "access_token=from-cookie"),
+        ]);
+        assert_eq!(access_token(&map), Some("abc.def.ghi"));
+    }
+
+    #[test]
+    fn token_is_read_from_the_session_cookie() {
+        let map = headers(&[("cookie", "theme=dark; access_token=jwt-value; lang=es")]);
+        assert_eq!(access_token(&map), Some("jwt-value"));
+    }
+
+    #[test]
+    fn no_token_without_bearer_or_cookie() {
+        assert_eq!(
+            access_token(&headers(&[("authorization", "Basic dXNlcg==")])),
+            None
+        );
+        assert_eq!(access_token(&headers(&[("cookie", "access_token=")])), None);
+        assert_eq!(access_token(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn forwarded_for_takes_the_entry_of_the_outermost_trusted_proxy() {
+        let map = headers(&[("x-forwarded-for", "6.6.6.6, 203.0.113.7, 10.0.0.2")]);
+        assert_eq!(forwarded_for(&map, 1).as_deref(), Some("10.0.0.2"));
+        assert_eq!(forwarded_for(&map, 2).as_deref(), Some("203.0.113.7"));
+    }
+
+    #[test]
+    fn forwarded_for_ignores_the_header_without_enough_hops() {
+        let map = headers(&[("x-forwarded-for", "203.0.113.7")]);
+        assert_eq!(forwarded_for(&map, 0), None);
+        assert_eq!(forwarded_for(&map, 2), None);
+        assert_eq!(forwarded_for(&HeaderMap::new(), 1), None);
+    }
+
+    #[test]
+    fn forwarded_for_skips_an_empty_entry() {
+        let map = headers(&[("x-forwarded-for", "203.0.113.7, ")]);
+        assert_eq!(forwarded_for(&map, 1), None);
+    }
+}
@@ -0,0 +1,473 @@
+use std::collections::{HashMap, HashSet};
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use super::actor::Actor;
+use super::filters::is_filter_key;
+use super::generic::{
+    build_filters, fetch_row_for_update, is_deleted, keep_unchanged, set_deleted, set_value,
+    write_audit, AdminEntity, AdminState, EntityInfo, SealedValue,
+};
+use super::types::PaginationQuery;
+use crate::error::AdminError;
+use crate::services::custom_fields::{definitions, validate_values, CustomField};
+
+/// Most records one bulk request may change.
+pub const MAX_BULK_ROWS: usize = 1000;
+
+/// Body of `PATCH /{entity}` and `POST /{entity}/bulk-delete`.
+///
+/// Targets either `ids` or the records matching `filter` (filter grammar
+/// keys, as on the list endpoint) and `search`. Bulk changes do not take
+/// `If-Match`; every row is still locked and audited individually.
+#[derive(Debug, Deserialize, Default)]
+pub struct BulkRequest {
+    #[serde(default)]
+    pub ids: Vec<Uuid>,
+    pub filter: Option<HashMap<String, String>>,
+    pub search: Option<String>,
+    /// Columns to set on every record (PATCH only)
+    #[serde(default)]
+    pub changes: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkResult {
+    pub id: Uuid,
+    /// `updated`, `deleted`, `not_found`, or `rolled_back` when another
+    /// record failed and nothing was applied
+    pub status: &'static str,
+}
+
+/// `applied` is false when any record could not be changed; the
+/// transaction is then rolled back and nothing is written.
+#[derive(Debug, Serialize)]
+pub struct BulkOutcome {
+    pub applied: bool,
+    pub count: usize,
+    pub results: Vec<BulkResult>,
+}
+
+enum BulkAction {
+    /// `UPDATE` statement with the id bound last, its values and the
+    /// sensitive ones among them
+    Update {
+        sql: String,
+        values: Vec<String>,
+        sealed: Vec<SealedValue>,
+    },
+    Delete,
+}
+
+/// PATCH /{entity}: set the same columns on many records in one transaction.
+pub async fn generic_bulk_update<E: AdminEntity>(
+    State(state): State<AdminState>,
+    actor: Actor,
+    Json(body): Json<BulkRequest>,
+) -> Response {
+    let info = EntityInfo::of::<E>();
+    let result = async {
+        let custom = definitions(&state.pool, info.label).await?;
+        let action = update_action(&info, &custom, &body.changes)?;
+        run_bulk(&state.pool, &info, &custom, &actor, &body, &action).await
+    }
+    .await;
+    respond(result, E::TABLE_NAME)
+}
+
+/// POST /{entity}/bulk-delete: move many records to the trash in one
+/// transaction.
+pub async fn generic_bulk_delete<E: AdminEntity>(
+    State(state): State<AdminState>,
+    actor: Actor,
+    Json(body): Json<BulkRequest>,
+) -> Response {
+    let info = EntityInfo::of::<E>();
+    let result = async {
+        let custom = definitions(&state.pool, info.label).await?;
+        run_bulk(
+            &state.pool,
+            &info,
+            &custom,
+            &actor,
+            &body,
+            &BulkAction::Delete,
+        )
+        .await
+    }
+    .await;
+    respond(result, E::TABLE_NAME)
+}
+
+fn respond(result: Result<BulkOutcome, AdminError>, table: &str) -> Response {
+    match result {
+        Ok(outcome) if outcome.applied => Json(outcome).into_response(),
+        Ok(outcome) => (StatusCode::CONFLICT, Json(outcome)).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, table, "Bulk change failed");
+            e.into_response()
+        }
+    }
+}
+
+/// The `UPDATE` for `changes`, which may only name writable columns and
+/// `custom_fields`.
+fn update_action(
+    info: &EntityInfo,
+    custom: &[CustomField],
+    changes: &serde_json::Map<String, serde_json::Value>,
+) -> Result<BulkAction, AdminError> {
+    if changes.is_empty() {
+        return Err(AdminError::BadRequest("changes is required".to_string()));
+    }
+    let not_writable: Vec<&str> = changes
+        .keys()
+        .map(String::as_str)
+        .filter(|field| *field != "custom_fields" && !info.writable_fields.contains(field))
+        .collect();
+    if !not_writable.is_empty() {
+        return Err(AdminError::BadRequest(format!(
+            "Fields cannot be changed: {}",
+            not_writable.join(", ")
+        )));
+    }
+
+    let mut sets = Vec::with_capacity(changes.len() + 1);
+    let mut values = Vec::with_capacity(changes.len());
+    let mut sealed = Vec::new();
+    for (field, value) in changes {
+        if field == "custom_fields" {
+            let merged = serde_json::Value::Object(validate_values(custom, value)?);
+            values.push(merged.to_string());
+            sets.push(format!(
+                "custom_fields = jsonb_strip_nulls(custom_fields || ${}::jsonb)",
+                values.len()
+            ));
+        } else {
+            set_value(
+                info.sensitive_fields,
+                field,
+                value,
+                &mut sets,
+                &mut values,
+                &mut sealed,
+            )?;
+        }
+    }
+    if sets.is_empty() {
+        return Err(AdminError::BadRequest("No valid fields to update".to_string()));
+    }
+    sets.push("updated_at = NOW()".to_string());
+
+    Ok(BulkAction::Update {
+        sql: format!(
+            "UPDATE {table} SET {} WHERE id = ${} RETURNING row_to_json({table}.*)",
+            sets.join(", "),
+            values.len() + 1,
+            table = info.table,
+        ),
+        values,
+        sealed,
+    })
+}
+
+/// Apply `action` to every target in one transaction, auditing each record.
+/// Commits only when every record was changed.
+async fn run_bulk(
+    pool: &PgPool,
+    info: &EntityInfo,
+    custom: &[CustomField],
+    actor: &Actor,
+    body: &BulkRequest,
+    action: &BulkAction,
+) -> Result<BulkOutcome, AdminError> {
+    let mut tx = pool.begin().await?;
+    let ids = target_ids(&mut tx, info, custom, body).await?;
+
+    let mut results = Vec::with_capacity(ids.len());
+    let mut applied = true;
+    for id in ids {
+        let Some(old) = fetch_row_for_update(&mut tx, info.table, id)
+            .await?
+            .filter(|row| !is_deleted(row))
+        else {
+            applied = false;
+            results.push(BulkResult {
+                id,
+                status: "not_found",
+            });
+            continue;
+        };
+
+        let (audit_action, status, new) = match action {
+            BulkAction::Update { sql, values, sealed } => {
+                let mut values = values.clone();
+                keep_unchanged(&old, sealed, &mut values);
+                let mut query = sqlx::query_scalar::<_, serde_json::Value>(sql);
+                for value in &values {
+                    query = query.bind(value.as_str());
+                }
+                let new = query.bind(id).fetch_one(&mut *tx).await?;
+                ("update", "updated", new)
+            }
+            BulkAction::Delete => {
+                let (_, new) = set_deleted(&mut tx, info.table, id, true)
+                    .await?
+                    .ok_or_else(|| AdminError::NotFound(info.label.to_string()))?;
+                ("delete", "deleted", new)
+            }
+        };
+        write_audit(
+            &mut *tx,
+            actor,
+            info.label,
+            &id.to_string(),
+            audit_action,
+            Some(&old),
+            Some(&new),
+        )
+        .await?;
+        results.push(BulkResult { id, status });
+    }
+
+    if applied {
+        tx.commit().await?;
+    } else {
+        // Dropping the transaction rolls it back
+        for result in &mut results {
+            if result.status != "not_found" {
+                result.status = "rolled_back";
+            }
+        }
+    }
+    let count = if applied { results.len() } else { 0 };
+    Ok(BulkOutcome {
+        applied,
+        count,
+        results,
+    })
+}
+
+/// What a bulk request targets: explicit ids, deduplicated, or a filter.
+enum Targets<'a> {
+    Ids(Vec<Uuid>),
+    Filter(&'a HashMap<String, String>),
+}
+
+impl<'a> Targets<'a> {
+    fn of(body: &'a BulkRequest) -> Result<Self, AdminError> {
+        match (&body.filter, body.ids.is_empty()) {
+            (Some(_), false) => Err(AdminError::BadRequest(
+                "Send either ids or filter, not both".to_string(),
+            )),
+            (None, true) => Err(AdminError::BadRequest(
+                "ids or filter is required".to_string(),
+            )),
+            (None, false) => {
+                let mut ids = body.ids.clone();
+                let mut seen = HashSet::new();
+                ids.retain(|id| seen.insert(*id));
+                Ok(Self::Ids(ids))
+            }
+            (Some(filter), true) => Ok(Self::Filter(filter)),
+        }
+    }
+}
+
+/// The ids a request targets, deduplicated. Records matched by a filter are
+/// locked as they are selected.
+async fn target_ids(
+    tx: &mut Transaction<'_, Postgres>,
+    info: &EntityInfo,
+    custom: &[CustomField],
+    body: &BulkRequest,
+) -> Result<Vec<Uuid>, AdminError> {
+    let ids = match Targets::of(body)? {
+        Targets::Ids(ids) => ids,
+        Targets::Filter(filter) => filtered_ids(tx, info, custom, filter, body.search.clone()).await?,
+    };
+
+    if ids.len() > MAX_BULK_ROWS {
+        return Err(AdminError::BadRequest(format!(
+            "At most {MAX_BULK_ROWS} records can be changed at once"
+        )));
+    }
+    Ok(ids)
+}
+
+async fn filtered_ids(
+    tx: &mut Transaction<'_, Postgres>,
+    info: &EntityInfo,
+    custom: &[CustomField],
+    filter: &HashMap<String, String>,
+    search: Option<String>,
+) -> Result<Vec<Uuid>, AdminError> {
+    let params = filter_params(info, custom, filter, search)?;
+    let qb = build_filters(info, custom, &params)?;
+    let sql = format!(
+        "SELECT id FROM {} {} ORDER BY id LIMIT {} FOR UPDATE",
+        info.table,
+        qb.where_clause(),
+        MAX_BULK_ROWS + 1
+    );
+    Ok(qb.fetch_ids(&sql, tx).await?)
+}
+
+/// List parameters for a bulk filter, which must name known filter fields
+/// and restrict the records in some way.
+fn filter_params(
+    info: &EntityInfo,
+    custom: &[CustomField],
+    filter: &HashMap<String, String>,
+    search: Option<String>,
+) -> Result<PaginationQuery, AdminError> {
+    // An unknown key would be ignored and widen the match, possibly to
+    // every record, so it is an error here
+    if let Some(key) = filter
+        .keys()
+        .find(|k| !is_filter_key(info.filter_fields, custom, k))
+    {
+        return Err(AdminError::BadRequest(format!(
+            "Unknown filter field: {key}"
+        )));
+    }
+    let search = search.filter(|s| !s.trim().is_empty());
+    let restricted = filter
+        .iter()
+        .any(|(key, value)| key.contains('[') || !value.trim().is_empty());
+    if !restricted && search.is_none() {
+        return Err(AdminError::BadRequest(
+            "filter must restrict the records to change".to_string(),
+        ));
+    }
+
+    Ok(PaginationQuery {
+        search,
+        filters: filter.clone(),
+        ..PaginationQuery::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::api::generic::InvoiceEntity;
+
+    fn invoices() -> EntityInfo {
+        EntityInfo::of::<InvoiceEntity>()
+    }
+
+    fn filter(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    fn rejection<T>(result: Result<T, AdminError>) -> String {
+        match result {
+            Err(AdminError::BadRequest(msg)) => msg,
+            Err(other) => panic!("expected a bad request, got {other}"),
+            Ok(_) => panic!("expected a bad request"),
+        }
+    }
+
+    #[test]
+    fn ids_are_deduplicated_in_order() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let body = BulkRequest {
+            ids: vec![a, b, a],
+            ..BulkRequest::default()
+        };
+        assert!(matches!(Targets::of(&body), Ok(Targets::Ids(ids)) if ids == [a, b]));
+    }
+
+    #[test]
+    fn ids_and_filter_are_exclusive() {
+        let body = BulkRequest {
+            ids: vec![Uuid::new_v4()],
+            filter: Some(filter(&[("status", "Unpaid")])),
+            ..BulkRequest::default()
+        };
+        assert_eq!(
+            rejection(Targets::of(&body)),
+            "Send either ids or filter, not both"
+        );
+        assert_eq!(
+            rejection(Targets::of(&BulkRequest::default())),
+            "ids or filter is required"
+        );
+    }
+
+    #[test]
+    fn unknown_filter_field_is_rejected() {
+        let result = filter_params(
+            &invoices(),
+            &[],
+            &filter(&[("status", "Unpaid"), ("tenant_phone", "600")]),
+            None,
+        );
+        assert_eq!(rejection(result), "Unknown filter field: tenant_phone");
+    }
+
+    #[test]
+    fn empty_filter_is_rejected() {
+        for empty in [filter(&[]), filter(&[("status", " ")])] {
+            let result = filter_params(&invoices(), &[], &empty, Some("  ".to_string()));
+            assert_eq!(
+                rejection(result),
+                "filter must restrict the records to change"
+            );
+        }
+    }
+
+    #[test]
+    fn filter_with_operators_or_search_restricts() {
+        let params =
+            filter_params(&invoices(), &[], &filter(&[("amount[gte]", "")]), None).unwrap();
+        assert_eq!(params.filters["amount[gte]"], "");
+
+        let params =
+            filter_params(&invoices(), &[], &filter(&[]), Some("garcia".to_string())).unwrap();
+        assert_eq!(params.search.as_deref(), Some("garcia"));
+    }
+
+    #[test]
+    fn update_needs_writable_changes() {
+        let empty = serde_json::Map::new();
+        assert_eq!(
+            rejection(update_action(&invoices(), &[], &empty)),
+            "changes is required"
+        );
+
+        let changes = json!({"status": "Paid", "id": "x", "created_at": "2026-01-01"});
+        let changes = changes.as_object().unwrap();
+        assert_eq!(
+            rejection(update_action(&invoices(), &[], changes)),
+            "Fields cannot be changed: id, created_at"
+        );
+    }
+
+    #[test]
+    fn update_binds_the_id_last() {
+        let changes = json!({"status": "Paid", "notes": "Settled"});
+        let action = update_action(&invoices(), &[], changes.as_object().unwrap()).unwrap();
+        let BulkAction::Update { sql, values, .. } = action else {
+            panic!("expected an update");
+        };
+        assert_eq!(values, ["Paid", "Settled"]);
+        assert_eq!(
+            sql,
+            "UPDATE admin_invoices SET status = $1, notes = $2, updated_at = NOW() \
+             WHERE id = $3 RETURNING row_to_json(admin_invoices.*)"
+        );
+    }
+}
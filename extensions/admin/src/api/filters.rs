@@ -0,0 +1,476 @@
+//! Typed list filters: `?field=value` or `?field[op]=value`.
+//!
+//! | op                     | meaning                                   | types              |
+//! |------------------------|-------------------------------------------|--------------------|
+//! | `eq` (default)         | equal; on tags, contains the tag          | all                |
+//! | `ne`                   | not equal (alias of `not_eq`)             | all                |
+//! | `gt` `gte` `lt` `lte`  | comparison                                | number, date, time |
+//! | `between`              | inclusive range: `a,b`                    | number, date, time |
+//! | `in`, `nin`            | one of / none of: `a,b,c`                 | all but tags       |
+//! | `like`                 | case-insensitive substring                | text               |
+//! | `null`                 | `true` or `false`                         | all                |
+//! | `contains`             | has every tag in `a,b`                    | tags               |
+//! | `overlaps`             | has any tag in `a,b`                      | tags               |
+//!
+//! Any op can be negated with a `not_` prefix (`tags[not_contains]=sea`);
+//! negated filters also match rows where the column is NULL. Only fields in
+//! the entity's `FILTER_FIELDS` (plus `created_at` and `updated_at`) and its
+//! custom fields (`custom.<key>`) are accepted, and values are checked
+//! against the field type before binding. Encrypted (sensitive) fields only
+//! take `eq`, `in` and `null`, compared through their blind indexes.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDate};
+use uuid::Uuid;
+
+use super::generic::QueryBuilder;
+use crate::error::AdminError;
+use crate::services::custom_fields::{self, CustomField};
+use crate::services::pii;
+
+/// Column type of a filterable field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    Text,
+    Number,
+    Date,
+    Timestamp,
+    Bool,
+    Uuid,
+    /// `TEXT[]` tag list
+    Tags,
+    /// Encrypted text, matched whole on its `<column>_bidx` blind index
+    Sensitive,
+}
+
+impl FieldType {
+    fn sql_type(self) -> &'static str {
+        match self {
+            Self::Text | Self::Sensitive => "text",
+            Self::Number => "numeric",
+            Self::Date => "date",
+            Self::Timestamp => "timestamptz",
+            Self::Bool => "boolean",
+            Self::Uuid => "uuid",
+            Self::Tags => "text[]",
+        }
+    }
+
+    fn ordered(self) -> bool {
+        matches!(self, Self::Number | Self::Date | Self::Timestamp)
+    }
+
+    /// Reject values the column cannot hold, so bad input is a 400 rather
+    /// than a failed query.
+    fn check(self, field: &str, value: &str) -> Result<(), AdminError> {
+        let valid = match self {
+            Self::Text | Self::Tags | Self::Sensitive => true,
+            Self::Number => value.parse::<f64>().is_ok_and(f64::is_finite),
+            Self::Date => NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok(),
+            Self::Timestamp => {
+                NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok()
+                    || DateTime::parse_from_rfc3339(value).is_ok()
+            }
+            Self::Bool => matches!(value, "true" | "false"),
+            Self::Uuid => Uuid::parse_str(value).is_ok(),
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(AdminError::BadRequest(format!(
+                "Invalid value for {field}: '{value}'"
+            )))
+        }
+    }
+}
+
+/// Fields every entity can be filtered on.
+const COMMON_FIELDS: &[(&str, FieldType)] = &[
+    ("created_at", FieldType::Timestamp),
+    ("updated_at", FieldType::Timestamp),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    Between,
+    In,
+    Like,
+    Null,
+    Contains,
+    Overlaps,
+}
+
+impl Op {
+    /// Parse an op name into the op and whether it is negated.
+    fn parse(name: &str) -> Option<(Self, bool)> {
+        let (negated, name) = match name {
+            "ne" => (true, "eq"),
+            "nin" => (true, "in"),
+            other => other
+                .strip_prefix("not_")
+                .map_or((false, other), |rest| (true, rest)),
+        };
+        let op = match name {
+            "eq" => Self::Eq,
+            "gt" => Self::Gt,
+            "gte" => Self::Gte,
+            "lt" => Self::Lt,
+            "lte" => Self::Lte,
+            "between" => Self::Between,
+            "in" => Self::In,
+            "like" => Self::Like,
+            "null" => Self::Null,
+            "contains" => Self::Contains,
+            "overlaps" => Self::Overlaps,
+            _ => return None,
+        };
+        Some((op, negated))
+    }
+
+    fn allowed_for(self, ty: FieldType) -> bool {
+        match self {
+            Self::Eq | Self::Null => true,
+            Self::In => ty != FieldType::Tags,
+            Self::Gt | Self::Gte | Self::Lt | Self::Lte | Self::Between => ty.ordered(),
+            Self::Like => ty == FieldType::Text,
+            Self::Contains | Self::Overlaps => ty == FieldType::Tags,
+        }
+    }
+}
+
+/// Add a condition for every recognised filter in `params` to `qb`.
+///
+/// Plain keys that are not filter fields (`page`, `sort`, ...) are ignored;
+/// a bracketed key on an unknown field or with an unknown op is an error.
+pub(crate) fn apply_filters(
+    qb: &mut QueryBuilder,
+    fields: &[(&str, FieldType)],
+    custom: &[CustomField],
+    params: &HashMap<String, String>,
+) -> Result<(), AdminError> {
+    let mut keys: Vec<&String> = params.keys().collect();
+    keys.sort();
+
+    for key in keys {
+        let value = params[key].trim();
+        let (field, op_name) = match key.split_once('[') {
+            Some((field, rest)) => {
+                let op = rest
+                    .strip_suffix(']')
+                    .ok_or_else(|| AdminError::BadRequest(format!("Malformed filter: {key}")))?;
+                (field, Some(op))
+            }
+            None => (key.as_str(), None),
+        };
+
+        let Some((column, ty)) = lookup(fields, custom, field) else {
+            if op_name.is_some() {
+                return Err(AdminError::BadRequest(format!(
+                    "Unknown filter field: {field}"
+                )));
+            }
+            continue;
+        };
+        // A bare `?status=` is how the UI clears a filter
+        if op_name.is_none() && value.is_empty() {
+            continue;
+        }
+
+        let (op, negated) = match op_name {
+            Some(name) => Op::parse(name).ok_or_else(|| {
+                AdminError::BadRequest(format!("Unknown filter operator: {name}"))
+            })?,
+            None => (Op::Eq, false),
+        };
+        if !op.allowed_for(ty) {
+            return Err(AdminError::BadRequest(format!(
+                "Operator {} does not apply to {field}",
+                op_name.unwrap_or("eq")
+            )));
+        }
+
+        let condition = condition(qb, field, &column, ty, op, value)?;
+        qb.push_condition(if negated {
+            format!("({condition}) IS NOT TRUE")
+        } else {
+            condition
+        });
+    }
+    Ok(())
+}
+
+/// Whether `key` (`field` or `field[op]`) names a filterable field.
+pub(crate) fn is_filter_key(
+    fields: &[(&str, FieldType)],
+    custom: &[CustomField],
+    key: &str,
+) -> bool {
+    let field = key.split_once('[').map_or(key, |(field, _)| field);
+    lookup(fields, custom, field).is_some()
+}
+
+/// Column expression and type of a filterable field.
+fn lookup<'a>(
+    fields: &[(&'a str, FieldType)],
+    custom: &[CustomField],
+    field: &str,
+) -> Option<(Cow<'a, str>, FieldType)> {
+    if let Some(&(column, ty)) = fields
+        .iter()
+        .chain(COMMON_FIELDS)
+        .find(|(name, _)| *name == field)
+    {
+        return Some((Cow::Borrowed(column), ty));
+    }
+    custom_fields::find(custom, field).map(|f| (Cow::Owned(f.column()), f.filter_type()))
+}
+
+fn condition(
+    qb: &mut QueryBuilder,
+    field: &str,
+    column: &str,
+    ty: FieldType,
+    op: Op,
+    value: &str,
+) -> Result<String, AdminError> {
+    if ty == FieldType::Sensitive {
+        return sensitive_condition(qb, field, column, op, value);
+    }
+    let sql_type = ty.sql_type();
+    let bind = |qb: &mut QueryBuilder, v: &str| -> Result<String, AdminError> {
+        ty.check(field, v)?;
+        Ok(format!("${}::{sql_type}", qb.bind(v.to_string())))
+    };
+
+    Ok(match op {
+        Op::Eq if ty == FieldType::Tags => {
+            let idx = qb.bind(array_literal(&[value]));
+            format!("{column} @> ${idx}::text[]")
+        }
+        Op::Eq => format!("{column} = {}", bind(qb, value)?),
+        Op::Gt => format!("{column} > {}", bind(qb, value)?),
+        Op::Gte => format!("{column} >= {}", bind(qb, value)?),
+        Op::Lt => format!("{column} < {}", bind(qb, value)?),
+        Op::Lte => format!("{column} <= {}", bind(qb, value)?),
+        Op::Between => {
+            let (from, to) = value.split_once(',').ok_or_else(|| {
+                AdminError::BadRequest(format!("{field}[between] expects 'from,to'"))
+            })?;
+            format!(
+                "{column} BETWEEN {} AND {}",
+                bind(qb, from.trim())?,
+                bind(qb, to.trim())?
+            )
+        }
+        Op::In => {
+            let items = list(value);
+            for item in &items {
+                ty.check(field, item)?;
+            }
+            let idx = qb.bind(array_literal(&items));
+            format!("{column} = ANY(${idx}::{sql_type}[])")
+        }
+        Op::Like => {
+            let idx = qb.bind(format!("%{}%", escape_like(value)));
+            format!("{column} ILIKE ${idx}")
+        }
+        Op::Null => match value {
+            "true" | "" => format!("{column} IS NULL"),
+            "false" => format!("{column} IS NOT NULL"),
+            _ => {
+                return Err(AdminError::BadRequest(format!(
+                    "{field}[null] expects true or false"
+                )))
+            }
+        },
+        Op::Contains | Op::Overlaps => {
+            let idx = qb.bind(array_literal(&list(value)));
+            let operator = if op == Op::Contains { "@>" } else { "&&" };
+            format!("{column} {operator} ${idx}::text[]")
+        }
+    })
+}
+
+/// Condition on the blind index of an encrypted column. A value with
+/// nothing to index (blank once normalised) matches empty columns.
+fn sensitive_condition(
+    qb: &mut QueryBuilder,
+    field: &str,
+    column: &str,
+    op: Op,
+    value: &str,
+) -> Result<String, AdminError> {
+    let index_column = format!("{column}_bidx");
+    Ok(match op {
+        Op::Eq => match pii::blind_index(column, value)? {
+            Some(index) => format!("{index_column} = ${}", qb.bind(index)),
+            None => format!("{index_column} IS NULL"),
+        },
+        Op::In => {
+            let mut indexes = Vec::new();
+            for item in list(value) {
+                if let Some(index) = pii::blind_index(column, item)? {
+                    indexes.push(index);
+                }
+            }
+            let refs: Vec<&str> = indexes.iter().map(String::as_str).collect();
+            let idx = qb.bind(array_literal(&refs));
+            format!("{index_column} = ANY(${idx}::text[])")
+        }
+        Op::Null => match value {
+            "true" | "" => format!("{index_column} IS NULL"),
+            "false" => format!("{index_column} IS NOT NULL"),
+            _ => {
+                return Err(AdminError::BadRequest(format!(
+                    "{field}[null] expects true or false"
+                )))
+            }
+        },
+        _ => {
+            return Err(AdminError::BadRequest(format!(
+                "{field} is encrypted and only matches whole values"
+            )))
+        }
+    })
+}
+
+fn list(value: &str) -> Vec<&str> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .collect()
+}
+
+/// PostgreSQL array literal with every element quoted.
+fn array_literal(items: &[&str]) -> String {
+    let quoted: Vec<String> = items
+        .iter()
+        .map(|item| format!("\"{}\"", item.replace('\\', "\\\\").replace('"', "\\\"")))
+        .collect();
+    format!("{{{}}}", quoted.join(","))
+}
+
+/// Escape `\`, `%` and `_` so `value` matches itself in a `LIKE` pattern.
+pub(crate) fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIELDS: &[(&str, FieldType)] = &[
+        ("status", FieldType::Text),
+        ("rent", FieldType::Number),
+        ("start_date", FieldType::Date),
+        ("tags", FieldType::Tags),
+    ];
+
+    fn filter(params: &[(&str, &str)]) -> Result<String, AdminError> {
+        let params: HashMap<String, String> = params
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect();
+        let mut qb = QueryBuilder::new();
+        apply_filters(&mut qb, FIELDS, &[], &params)?;
+        Ok(qb.where_clause())
+    }
+
+    #[test]
+    fn plain_keys_are_equality() {
+        assert_eq!(
+            filter(&[("status", "Unpaid")]).unwrap(),
+            "WHERE status = $1::text"
+        );
+        assert_eq!(
+            filter(&[("tags", "sea")]).unwrap(),
+            "WHERE tags @> $1::text[]"
+        );
+    }
+
+    #[test]
+    fn operators_build_typed_conditions() {
+        assert_eq!(
+            filter(&[("rent[between]", "100, 200")]).unwrap(),
+            "WHERE rent BETWEEN $1::numeric AND $2::numeric"
+        );
+        assert_eq!(
+            filter(&[("start_date[gte]", "2026-01-01")]).unwrap(),
+            "WHERE start_date >= $1::date"
+        );
+        assert_eq!(
+            filter(&[("status[like]", "pa")]).unwrap(),
+            "WHERE status ILIKE $1"
+        );
+        assert_eq!(
+            filter(&[("tags[overlaps]", "sea,garden")]).unwrap(),
+            "WHERE tags && $1::text[]"
+        );
+        assert_eq!(
+            filter(&[("created_at[null]", "false")]).unwrap(),
+            "WHERE created_at IS NOT NULL"
+        );
+    }
+
+    #[test]
+    fn negated_operators_also_match_null() {
+        assert_eq!(
+            filter(&[("status[nin]", "Paid,Cancelled")]).unwrap(),
+            "WHERE (status = ANY($1::text[])) IS NOT TRUE"
+        );
+        assert_eq!(
+            filter(&[("tags[not_contains]", "sea")]).unwrap(),
+            "WHERE (tags @> $1::text[]) IS NOT TRUE"
+        );
+    }
+
+    #[test]
+    fn filters_apply_in_key_order() {
+        assert_eq!(
+            filter(&[("status", "Paid"), ("rent[gte]", "500")]).unwrap(),
+            "WHERE rent >= $1::numeric AND status = $2::text"
+        );
+    }
+
+    #[test]
+    fn other_parameters_and_cleared_filters_are_ignored() {
+        assert_eq!(filter(&[("page", "2"), ("status", "")]).unwrap(), "");
+    }
+
+    #[test]
+    fn rejects_bad_filters() {
+        assert!(filter(&[("rent", "lots")]).is_err());
+        assert!(filter(&[("rent[like]", "1")]).is_err());
+        assert!(filter(&[("tags[in]", "a,b")]).is_err());
+        assert!(filter(&[("status[bogus]", "x")]).is_err());
+        assert!(filter(&[("owner[eq]", "x")]).is_err());
+        assert!(filter(&[("status[eq", "x")]).is_err());
+        assert!(filter(&[("rent[between]", "100")]).is_err());
+        assert!(filter(&[("start_date[null]", "maybe")]).is_err());
+    }
+
+    #[test]
+    fn array_literals_quote_every_element() {
+        assert_eq!(array_literal(&["a", "b c"]), r#"{"a","b c"}"#);
+        assert_eq!(
+            array_literal(&[r#"say "hi""#, r"C:\x"]),
+            r#"{"say \"hi\"","C:\\x"}"#
+        );
+    }
+
+    #[test]
+    fn like_wildcards_are_escaped() {
+        assert_eq!(escape_like(r"50%_off\"), r"50\%\_off\\");
+    }
+}
@@ -1,15 +1,26 @@
+use std::borrow::Cow;
 use std::sync::Arc;
 
 use axum::extract::{Path, Query, State};
 use axum::response::{IntoResponse, Response};
 use axum::Json;
-use sqlx::PgPool;
+use sqlx::{PgExecutor, PgPool, Postgres, Transaction};
 use uuid::Uuid;
 
+use super::actor::Actor;
+use super::filters::{apply_filters, FieldType};
+use super::pagination::{per_page, CountMode, Cursor, KeyedRow};
+use super::precondition::{precondition_required, version_conflict, with_etag, IfMatch};
+use super::sensitive::SensitiveAccess;
 use super::types::{
     created_response, error_response, not_found, success_response, PaginatedResponse,
-    PaginationQuery,
+    PaginationQuery, RecordQuery,
 };
+use crate::error::AdminError;
+use crate::services::custom_fields::{self, definitions, CustomField};
+use crate::services::pii;
+use crate::services::views::{select_columns, with_saved_view};
+use crate::services::webhooks;
 
 /// Trait that each entity implements to define its table and query behavior.
 pub trait AdminEntity: Send + Sync + 'static {
@@ -17,14 +28,21 @@ pub trait AdminEntity: Send + Sync + 'static {
     const ENTITY_LABEL: &'static str;
     /// Fields searched with ILIKE when ?search= is provided
     const SEARCH_FIELDS: &'static [&'static str];
-    /// Fields allowed as exact-match query filters
-    const FILTER_FIELDS: &'static [&'static str];
+    /// Personal data stored encrypted (see `services::pii`). `?search=`
+    /// matches them whole through their blind indexes, and they are masked
+    /// in responses without the `sensitive` scope.
+    const SENSITIVE_FIELDS: &'static [&'static str] = &[];
+    /// Fields accepted by the filter grammar (see `filters`), with their types
+    const FILTER_FIELDS: &'static [(&'static str, FieldType)];
     /// Whitelist of columns allowed in ORDER BY
     const SORTABLE_FIELDS: &'static [&'static str];
     /// Columns accepted for INSERT/UPDATE (excludes id, created_at, updated_at)
     const WRITABLE_FIELDS: &'static [&'static str];
     /// Default ORDER BY column
     const DEFAULT_SORT: &'static str;
+    /// Whether records go to the trash when deleted or when their creation
+    /// is reverted, and are purged from it later
+    const TRASHABLE: bool = true;
 }
 
 // ── Entity definitions ──────────────────────────────────────────────────
@@ -35,7 +53,24 @@ impl AdminEntity for PropertyEntity {
     const ENTITY_LABEL: &'static str = "properties";
     const SEARCH_FIELDS: &'static [&'static str] =
         &["property_name", "address", "contract_ref", "status", "image_folder"];
-    const FILTER_FIELDS: &'static [&'static str] = &["status"];
+    const FILTER_FIELDS: &'static [(&'static str, FieldType)] = &[
+        ("status", FieldType::Text),
+        ("property_name", FieldType::Text),
+        ("rent", FieldType::Number),
+        ("start_date", FieldType::Date),
+        ("end_date", FieldType::Date),
+        ("tags", FieldType::Tags),
+        ("kind", FieldType::Text),
+        ("parent_id", FieldType::Uuid),
+        ("bedrooms", FieldType::Number),
+        ("bathrooms", FieldType::Number),
+        ("sqm", FieldType::Number),
+        ("floor", FieldType::Text),
+        ("energy_rating", FieldType::Text),
+        ("cadastral_ref", FieldType::Text),
+        ("furnished", FieldType::Bool),
+        ("community_fee", FieldType::Number),
+    ];
     const SORTABLE_FIELDS: &'static [&'static str] = &[
         "property_name",
         "address",
@@ -43,6 +78,11 @@ impl AdminEntity for PropertyEntity {
         "rent",
         "start_date",
         "end_date",
+        "bedrooms",
+        "bathrooms",
+        "sqm",
+        "energy_rating",
+        "community_fee",
         "created_at",
         "updated_at",
     ];
@@ -56,6 +96,19 @@ impl AdminEntity for PropertyEntity {
         "end_date",
         "tags",
         "image_folder",
+        "kind",
+        "parent_id",
+        "bedrooms",
+        "bathrooms",
+        "sqm",
+        "floor",
+        "energy_rating",
+        "cadastral_ref",
+        "furnished",
+        "latitude",
+        "longitude",
+        "community_fee",
+        "fee_share",
     ];
     const DEFAULT_SORT: &'static str = "created_at";
 }
@@ -64,9 +117,14 @@ pub struct TenantEntity;
 impl AdminEntity for TenantEntity {
     const TABLE_NAME: &'static str = "admin_tenants";
     const ENTITY_LABEL: &'static str = "tenants";
-    const SEARCH_FIELDS: &'static [&'static str] =
-        &["name", "tax_id", "email", "phone", "property_name"];
-    const FILTER_FIELDS: &'static [&'static str] = &["is_legacy"];
+    const SEARCH_FIELDS: &'static [&'static str] = &["name", "email", "property_name"];
+    const SENSITIVE_FIELDS: &'static [&'static str] = &["tax_id", "phone", "bank_account"];
+    const FILTER_FIELDS: &'static [(&'static str, FieldType)] = &[
+        ("is_legacy", FieldType::Bool),
+        ("irpf_withholding", FieldType::Bool),
+        ("property_name", FieldType::Text),
+        ("tax_id", FieldType::Sensitive),
+    ];
     const SORTABLE_FIELDS: &'static [&'static str] =
         &["name", "email", "property_name", "created_at", "updated_at"];
     const WRITABLE_FIELDS: &'static [&'static str] = &[
@@ -79,6 +137,7 @@ impl AdminEntity for TenantEntity {
         "property_name",
         "property_address",
         "is_legacy",
+        "irpf_withholding",
     ];
     const DEFAULT_SORT: &'static str = "created_at";
 }
@@ -87,9 +146,13 @@ pub struct OwnerEntity;
 impl AdminEntity for OwnerEntity {
     const TABLE_NAME: &'static str = "admin_owners";
     const ENTITY_LABEL: &'static str = "owners";
-    const SEARCH_FIELDS: &'static [&'static str] =
-        &["name", "tax_id", "email", "phone", "property_name"];
-    const FILTER_FIELDS: &'static [&'static str] = &[];
+    const SEARCH_FIELDS: &'static [&'static str] = &["name", "email", "property_name"];
+    const SENSITIVE_FIELDS: &'static [&'static str] = &["tax_id", "phone", "bank_account"];
+    const FILTER_FIELDS: &'static [(&'static str, FieldType)] = &[
+        ("name", FieldType::Text),
+        ("property_name", FieldType::Text),
+        ("management_fee_pct", FieldType::Number),
+    ];
     const SORTABLE_FIELDS: &'static [&'static str] =
         &["name", "email", "property_name", "created_at", "updated_at"];
     const WRITABLE_FIELDS: &'static [&'static str] = &[
@@ -101,6 +164,7 @@ impl AdminEntity for OwnerEntity {
         "bank_account",
         "property_name",
         "property_address",
+        "management_fee_pct",
     ];
     const DEFAULT_SORT: &'static str = "created_at";
 }
@@ -111,7 +175,17 @@ impl AdminEntity for ContractEntity {
     const ENTITY_LABEL: &'static str = "contracts";
     const SEARCH_FIELDS: &'static [&'static str] =
         &["contract_ref", "property_name", "address", "tenant_name", "status"];
-    const FILTER_FIELDS: &'static [&'static str] = &["status"];
+    const FILTER_FIELDS: &'static [(&'static str, FieldType)] = &[
+        ("status", FieldType::Text),
+        ("property_name", FieldType::Text),
+        ("tenant_name", FieldType::Text),
+        ("tenant_id", FieldType::Uuid),
+        ("rent", FieldType::Number),
+        ("total_value", FieldType::Number),
+        ("start_date", FieldType::Date),
+        ("end_date", FieldType::Date),
+        ("tags", FieldType::Tags),
+    ];
     const SORTABLE_FIELDS: &'static [&'static str] = &[
         "contract_ref",
         "property_name",
@@ -129,6 +203,7 @@ impl AdminEntity for ContractEntity {
         "property_name",
         "address",
         "tenant_name",
+        "tenant_id",
         "status",
         "rent",
         "total_value",
@@ -136,6 +211,7 @@ impl AdminEntity for ContractEntity {
         "end_date",
         "tags",
         "doc_count",
+        "notes",
     ];
     const DEFAULT_SORT: &'static str = "created_at";
 }
@@ -153,7 +229,20 @@ impl AdminEntity for InvoiceEntity {
         "payee",
         "status",
     ];
-    const FILTER_FIELDS: &'static [&'static str] = &["status", "type", "expense_category", "payee"];
+    const FILTER_FIELDS: &'static [(&'static str, FieldType)] = &[
+        ("status", FieldType::Text),
+        ("type", FieldType::Text),
+        ("expense_category", FieldType::Text),
+        ("payee", FieldType::Text),
+        ("payer", FieldType::Text),
+        ("property_name", FieldType::Text),
+        ("contract_ref", FieldType::Text),
+        ("amount", FieldType::Number),
+        ("paid", FieldType::Number),
+        ("vat", FieldType::Number),
+        ("invoice_date", FieldType::Date),
+        ("payment_date", FieldType::Date),
+    ];
     const SORTABLE_FIELDS: &'static [&'static str] = &[
         "reference",
         "description",
@@ -196,12 +285,21 @@ impl AdminEntity for DepositEntity {
     const ENTITY_LABEL: &'static str = "deposits";
     const SEARCH_FIELDS: &'static [&'static str] =
         &["property_name", "contract_ref", "payer", "payee", "status"];
-    const FILTER_FIELDS: &'static [&'static str] = &["status", "deposit_type"];
+    const FILTER_FIELDS: &'static [(&'static str, FieldType)] = &[
+        ("status", FieldType::Text),
+        ("deposit_type", FieldType::Text),
+        ("property_name", FieldType::Text),
+        ("amount", FieldType::Number),
+        ("deductions", FieldType::Number),
+        ("deposit_date", FieldType::Date),
+        ("refund_date", FieldType::Date),
+    ];
     const SORTABLE_FIELDS: &'static [&'static str] = &[
         "property_name",
         "contract_ref",
         "status",
         "amount",
+        "deductions",
         "deposit_date",
         "created_at",
         "updated_at",
@@ -235,7 +333,11 @@ impl AdminEntity for SepaBatchEntity {
         "debtor_iban",
         "reference",
     ];
-    const FILTER_FIELDS: &'static [&'static str] = &[];
+    const FILTER_FIELDS: &'static [(&'static str, FieldType)] = &[
+        ("creditor", FieldType::Text),
+        ("amount", FieldType::Number),
+        ("collection_date", FieldType::Date),
+    ];
     const SORTABLE_FIELDS: &'static [&'static str] = &[
         "batch_id",
         "collection_date",
@@ -260,13 +362,48 @@ impl AdminEntity for SepaBatchEntity {
     const DEFAULT_SORT: &'static str = "created_at";
 }
 
+/// Owner payout batches are created and transitioned through dedicated
+/// handlers, so the generic API only lists and reads them.
+pub struct PayoutBatchEntity;
+impl AdminEntity for PayoutBatchEntity {
+    const TABLE_NAME: &'static str = "admin_payout_batches";
+    const ENTITY_LABEL: &'static str = "payout_batches";
+    const SEARCH_FIELDS: &'static [&'static str] = &["batch_ref", "debtor_name", "status"];
+    const SENSITIVE_FIELDS: &'static [&'static str] = &["debtor_iban"];
+    const FILTER_FIELDS: &'static [(&'static str, FieldType)] = &[
+        ("status", FieldType::Text),
+        ("currency", FieldType::Text),
+        ("execution_date", FieldType::Date),
+        ("total_amount", FieldType::Number),
+    ];
+    const SORTABLE_FIELDS: &'static [&'static str] = &[
+        "batch_ref",
+        "period_from",
+        "execution_date",
+        "status",
+        "total_amount",
+        "created_at",
+        "updated_at",
+    ];
+    const WRITABLE_FIELDS: &'static [&'static str] = &[];
+    const DEFAULT_SORT: &'static str = "created_at";
+    // Batches are cancelled instead, which releases the invoice amounts
+    // their transfers settled
+    const TRASHABLE: bool = false;
+}
+
 pub struct IssueEntity;
 impl AdminEntity for IssueEntity {
     const TABLE_NAME: &'static str = "admin_issues";
     const ENTITY_LABEL: &'static str = "issues";
     const SEARCH_FIELDS: &'static [&'static str] =
         &["property_name", "title", "description", "priority", "status"];
-    const FILTER_FIELDS: &'static [&'static str] = &["status", "priority"];
+    const FILTER_FIELDS: &'static [(&'static str, FieldType)] = &[
+        ("status", FieldType::Text),
+        ("priority", FieldType::Text),
+        ("property_name", FieldType::Text),
+        ("cost", FieldType::Number),
+    ];
     const SORTABLE_FIELDS: &'static [&'static str] = &[
         "property_name",
         "title",
@@ -287,7 +424,14 @@ impl AdminEntity for InsuranceEntity {
     const ENTITY_LABEL: &'static str = "insurance";
     const SEARCH_FIELDS: &'static [&'static str] =
         &["property_name", "insurance_type", "company", "policy_number", "status"];
-    const FILTER_FIELDS: &'static [&'static str] = &["status", "insurance_type"];
+    const FILTER_FIELDS: &'static [(&'static str, FieldType)] = &[
+        ("status", FieldType::Text),
+        ("insurance_type", FieldType::Text),
+        ("property_name", FieldType::Text),
+        ("premium", FieldType::Number),
+        ("start_date", FieldType::Date),
+        ("end_date", FieldType::Date),
+    ];
     const SORTABLE_FIELDS: &'static [&'static str] = &[
         "property_name",
         "company",
@@ -319,7 +463,13 @@ impl AdminEntity for AlertEntity {
     const ENTITY_LABEL: &'static str = "alerts";
     const SEARCH_FIELDS: &'static [&'static str] =
         &["type", "entity_type", "title", "description", "status"];
-    const FILTER_FIELDS: &'static [&'static str] = &["status", "priority", "type"];
+    const FILTER_FIELDS: &'static [(&'static str, FieldType)] = &[
+        ("status", FieldType::Text),
+        ("priority", FieldType::Text),
+        ("type", FieldType::Text),
+        ("entity_type", FieldType::Text),
+        ("entity_id", FieldType::Uuid),
+    ];
     const SORTABLE_FIELDS: &'static [&'static str] = &[
         "type",
         "entity_type",
@@ -345,9 +495,10 @@ pub struct ContactEntity;
 impl AdminEntity for ContactEntity {
     const TABLE_NAME: &'static str = "admin_contacts";
     const ENTITY_LABEL: &'static str = "contacts";
-    const SEARCH_FIELDS: &'static [&'static str] =
-        &["name", "email", "phone", "contact_type"];
-    const FILTER_FIELDS: &'static [&'static str] = &["contact_type"];
+    const SEARCH_FIELDS: &'static [&'static str] = &["name", "email", "contact_type"];
+    const SENSITIVE_FIELDS: &'static [&'static str] = &["tax_id", "iban", "phone"];
+    const FILTER_FIELDS: &'static [(&'static str, FieldType)] =
+        &[("contact_type", FieldType::Text)];
     const SORTABLE_FIELDS: &'static [&'static str] = &[
         "name",
         "email",
@@ -374,7 +525,17 @@ impl AdminEntity for LeadEntity {
     const ENTITY_LABEL: &'static str = "leads";
     const SEARCH_FIELDS: &'static [&'static str] =
         &["name", "email", "phone", "company", "property_name", "status"];
-    const FILTER_FIELDS: &'static [&'static str] = &["status", "source", "interest_type", "assigned_to"];
+    const FILTER_FIELDS: &'static [(&'static str, FieldType)] = &[
+        ("status", FieldType::Text),
+        ("source", FieldType::Text),
+        ("interest_type", FieldType::Text),
+        ("assigned_to", FieldType::Text),
+        ("score", FieldType::Number),
+        ("budget_min", FieldType::Number),
+        ("budget_max", FieldType::Number),
+        ("contact_date", FieldType::Date),
+        ("follow_up_date", FieldType::Date),
+    ];
     const SORTABLE_FIELDS: &'static [&'static str] = &[
         "name",
         "company",
@@ -415,7 +576,10 @@ impl AdminEntity for LeadNoteEntity {
     const ENTITY_LABEL: &'static str = "lead_notes";
     const SEARCH_FIELDS: &'static [&'static str] =
         &["content", "author"];
-    const FILTER_FIELDS: &'static [&'static str] = &["lead_id", "author"];
+    const FILTER_FIELDS: &'static [(&'static str, FieldType)] = &[
+        ("lead_id", FieldType::Uuid),
+        ("author", FieldType::Text),
+    ];
     const SORTABLE_FIELDS: &'static [&'static str] = &[
         "author",
         "created_at",
@@ -444,39 +608,64 @@ impl AdminState {
 
 // ── Query builder helpers ───────────────────────────────────────────────
 
-struct QueryBuilder {
+#[derive(Clone)]
+pub(crate) struct QueryBuilder {
     conditions: Vec<String>,
     bind_values: Vec<String>,
 }
 
 impl QueryBuilder {
-    fn new() -> Self {
+    pub(crate) fn new() -> Self {
         Self {
             conditions: Vec::new(),
             bind_values: Vec::new(),
         }
     }
 
-    fn add_search(&mut self, search: &str, fields: &[&str]) {
-        if search.is_empty() || fields.is_empty() {
-            return;
+    /// Substring match on the searched fields, or an exact match on any
+    /// sensitive field through its blind index.
+    fn add_search(
+        &mut self,
+        search: &str,
+        fields: &[&str],
+        sensitive: &[&str],
+    ) -> Result<(), AdminError> {
+        if search.is_empty() || (fields.is_empty() && sensitive.is_empty()) {
+            return Ok(());
+        }
+        let mut conds: Vec<String> = Vec::new();
+        if !fields.is_empty() {
+            let idx = self.bind(format!("%{search}%"));
+            conds.extend(fields.iter().map(|f| format!("{f}::text ILIKE ${idx}")));
+        }
+        for field in sensitive {
+            if let Some(index) = pii::blind_index(field, search)? {
+                let idx = self.bind(index);
+                conds.push(format!("{field}_bidx = ${idx}"));
+            }
+        }
+        if conds.is_empty() {
+            conds.push("false".to_string());
         }
-        let idx = self.bind_values.len() + 1;
-        let conds: Vec<String> = fields
-            .iter()
-            .map(|f| format!("{f}::text ILIKE ${idx}"))
-            .collect();
         self.conditions.push(format!("({})", conds.join(" OR ")));
-        self.bind_values.push(format!("%{search}%"));
+        Ok(())
     }
 
-    fn add_filter(&mut self, field: &str, value: &str) {
-        let idx = self.bind_values.len() + 1;
-        self.conditions.push(format!("{field} = ${idx}"));
-        self.bind_values.push(value.to_string());
+    /// Bind a value and return its placeholder index.
+    pub(crate) fn bind(&mut self, value: String) -> usize {
+        self.bind_values.push(value);
+        self.bind_values.len()
     }
 
-    fn where_clause(&self) -> String {
+    pub(crate) fn push_condition(&mut self, condition: String) {
+        self.conditions.push(condition);
+    }
+
+    fn exclude_deleted(&mut self) {
+        self.conditions.push("deleted_at IS NULL".to_string());
+    }
+
+    pub(crate) fn where_clause(&self) -> String {
         if self.conditions.is_empty() {
             String::new()
         } else {
@@ -484,7 +673,7 @@ impl QueryBuilder {
         }
     }
 
-    async fn fetch_json(
+    pub(crate) async fn fetch_json(
         &self,
         sql: &str,
         pool: &PgPool,
@@ -496,6 +685,28 @@ impl QueryBuilder {
         query.fetch_all(pool).await
     }
 
+    /// Rows of a keyset page with their sort keys; see [`KeyedRow`].
+    async fn fetch_keyed(&self, sql: &str, pool: &PgPool) -> Result<Vec<KeyedRow>, sqlx::Error> {
+        let mut query = sqlx::query_as::<_, KeyedRow>(sql);
+        for val in &self.bind_values {
+            query = query.bind(val.as_str());
+        }
+        query.fetch_all(pool).await
+    }
+
+    /// Ids selected by `sql`, run on a transaction so it can lock the rows.
+    pub(crate) async fn fetch_ids(
+        &self,
+        sql: &str,
+        tx: &mut Transaction<'_, Postgres>,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        let mut query = sqlx::query_scalar::<_, Uuid>(sql);
+        for val in &self.bind_values {
+            query = query.bind(val.as_str());
+        }
+        query.fetch_all(&mut **tx).await
+    }
+
     async fn fetch_count(&self, sql: &str, pool: &PgPool) -> Result<i64, sqlx::Error> {
         let mut query = sqlx::query_scalar::<_, i64>(sql);
         for val in &self.bind_values {
@@ -503,14 +714,52 @@ impl QueryBuilder {
         }
         query.fetch_one(pool).await
     }
+
+    /// Total rows matching the conditions, as requested by `?count=`.
+    async fn fetch_total(
+        &self,
+        table: &str,
+        mode: CountMode,
+        pool: &PgPool,
+    ) -> Result<Option<i64>, sqlx::Error> {
+        let where_clause = self.where_clause();
+        match mode {
+            CountMode::None => Ok(None),
+            CountMode::Exact => self
+                .fetch_count(
+                    &format!("SELECT COUNT(*)::bigint FROM {table} {where_clause}"),
+                    pool,
+                )
+                .await
+                .map(Some),
+            CountMode::Estimate => {
+                let plan = self
+                    .fetch_json(
+                        &format!("EXPLAIN (FORMAT JSON) SELECT 1 FROM {table} {where_clause}"),
+                        pool,
+                    )
+                    .await?;
+                Ok(plan
+                    .first()
+                    .and_then(|p| p.pointer("/0/Plan/Plan Rows"))
+                    .and_then(serde_json::Value::as_i64))
+            }
+        }
+    }
 }
 
-fn validated_sort<E: AdminEntity>(params: &PaginationQuery) -> (&str, &str) {
-    let sort_field = params
-        .sort
-        .as_deref()
-        .filter(|s| E::SORTABLE_FIELDS.contains(s))
-        .unwrap_or(E::DEFAULT_SORT);
+/// ORDER BY column (or custom field expression) and direction.
+pub(crate) fn validated_sort<'a>(
+    info: &EntityInfo,
+    custom: &[CustomField],
+    params: &'a PaginationQuery,
+) -> (Cow<'a, str>, &'static str) {
+    let sort_field = match params.sort.as_deref() {
+        Some(s) if info.sortable_fields.contains(&s) => Cow::Borrowed(s),
+        Some(s) => custom_fields::find(custom, s)
+            .map_or(Cow::Borrowed(info.default_sort), |f| Cow::Owned(f.column())),
+        None => Cow::Borrowed(info.default_sort),
+    };
     let sort_order = match params.order.as_deref() {
         Some("asc") => "ASC",
         _ => "DESC",
@@ -518,83 +767,134 @@ fn validated_sort<E: AdminEntity>(params: &PaginationQuery) -> (&str, &str) {
     (sort_field, sort_order)
 }
 
-fn build_filters<E: AdminEntity>(params: &PaginationQuery) -> QueryBuilder {
+/// Conditions for `?search=`, `?include_deleted=` and the filter grammar.
+pub(crate) fn build_filters(
+    info: &EntityInfo,
+    custom: &[CustomField],
+    params: &PaginationQuery,
+) -> Result<QueryBuilder, AdminError> {
     let mut qb = QueryBuilder::new();
-    if let Some(ref search) = params.search {
-        qb.add_search(search, E::SEARCH_FIELDS);
+    if !params.include_deleted() {
+        qb.exclude_deleted();
     }
-    for field in E::FILTER_FIELDS {
-        if let Some(value) = params.filters.get(*field) {
-            if !value.is_empty() {
-                qb.add_filter(field, value);
-            }
-        }
+    if let Some(ref search) = params.search {
+        qb.add_search(search, info.search_fields, info.sensitive_fields)?;
     }
-    qb
+    apply_filters(&mut qb, info.filter_fields, custom, &params.filters)?;
+    Ok(qb)
 }
 
 // ── Generic handlers ────────────────────────────────────────────────────
 
+/// List endpoint. `?view=<id>` starts from a saved view's parameters.
 pub async fn generic_list<E: AdminEntity>(
     State(state): State<AdminState>,
+    actor: Actor,
+    access: SensitiveAccess,
     Query(params): Query<PaginationQuery>,
 ) -> Response {
-    let qb = build_filters::<E>(&params);
-    let where_clause = qb.where_clause();
-    let (sort_field, sort_order) = validated_sort::<E>(&params);
-
-    let page = params.page.unwrap_or(1).max(1);
-    let per_page = params.per_page.unwrap_or(25);
-    let limit_offset = if per_page > 0 {
-        format!("LIMIT {per_page} OFFSET {}", (page - 1) * per_page)
-    } else {
-        String::new()
+    let info = EntityInfo::of::<E>();
+    let result = async {
+        let (params, columns) = with_saved_view(&state.pool, &actor, info.label, params).await?;
+        let custom = definitions(&state.pool, info.label).await?;
+        let qb = build_filters(&info, &custom, &params)?;
+        let (sort_field, sort_order) = validated_sort(&info, &custom, &params);
+        let page = fetch_page(
+            &state.pool,
+            E::TABLE_NAME,
+            &qb,
+            (&sort_field, sort_order),
+            &params,
+        )
+        .await?;
+        Ok::<_, AdminError>((page, columns))
+    }
+    .await;
+
+    match result {
+        Ok((mut page, columns)) => {
+            for row in &mut page.data {
+                pii::reveal(row, E::SENSITIVE_FIELDS, access.granted());
+                select_columns(row, &columns);
+            }
+            Json(page).into_response()
+        }
+        Err(e) => {
+            tracing::error!(error = %e, table = E::TABLE_NAME, "List query failed");
+            e.into_response()
+        }
+    }
+}
+
+/// One page of `table` rows matching `qb`, ordered by the sort column and id.
+/// Pages by `?cursor=` when given, otherwise by `?page=`; the total follows
+/// `?count=`.
+pub(crate) async fn fetch_page(
+    pool: &PgPool,
+    table: &str,
+    qb: &QueryBuilder,
+    (sort_field, sort_order): (&str, &str),
+    params: &PaginationQuery,
+) -> Result<PaginatedResponse, AdminError> {
+    let count_mode = CountMode::parse(params.count.as_deref())?;
+    let per_page = per_page(params.per_page);
+
+    let mut page_qb = qb.clone();
+    let offset = match params.cursor.as_deref().filter(|c| !c.is_empty()) {
+        Some(token) => {
+            let cursor = Cursor::decode(token, sort_field, sort_order)?;
+            let value_idx = cursor.value.clone().map_or(0, |v| page_qb.bind(v));
+            let id_idx = page_qb.bind(cursor.id.to_string());
+            page_qb.push_condition(cursor.condition(value_idx, id_idx));
+            0
+        }
+        None => (params.page.unwrap_or(1).max(1) - 1) * per_page,
     };
 
+    // One row more than the page size tells whether there is a next page
     let data_sql = format!(
-        "SELECT row_to_json(t) FROM (SELECT * FROM {} {} ORDER BY {} {} {}) t",
-        E::TABLE_NAME,
-        where_clause,
-        sort_field,
-        sort_order,
-        limit_offset
+        "SELECT row_to_json(t), ({sort_field})::text, pg_typeof({sort_field})::text \
+         FROM (SELECT * FROM {table} {} \
+         ORDER BY {sort_field} {sort_order}, id {sort_order} LIMIT {} OFFSET {offset}) t",
+        page_qb.where_clause(),
+        per_page + 1,
     );
-    let count_sql = format!(
-        "SELECT COUNT(*)::bigint FROM {} {}",
-        E::TABLE_NAME, where_clause
+
+    let (rows, total) = tokio::join!(
+        page_qb.fetch_keyed(&data_sql, pool),
+        qb.fetch_total(table, count_mode, pool)
     );
 
-    let pool = &*state.pool;
-    let (data_result, count_result) =
-        tokio::join!(qb.fetch_json(&data_sql, pool), qb.fetch_count(&count_sql, pool));
+    let (data, next_cursor) = Cursor::next_page(rows?, per_page, sort_field, sort_order);
 
-    match (data_result, count_result) {
-        (Ok(data), Ok(total)) => Json(PaginatedResponse { data, total }).into_response(),
-        (Err(e), _) | (_, Err(e)) => {
-            tracing::error!(error = %e, table = E::TABLE_NAME, "List query failed");
-            error_response(
-                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                &e.to_string(),
-            )
-        }
-    }
+    Ok(PaginatedResponse {
+        data,
+        total: total?,
+        next_cursor,
+    })
 }
 
 pub async fn generic_get_by_id<E: AdminEntity>(
     State(state): State<AdminState>,
     Path(id): Path<Uuid>,
+    access: SensitiveAccess,
+    Query(params): Query<RecordQuery>,
 ) -> Response {
     let sql = format!(
-        "SELECT row_to_json(t) FROM (SELECT * FROM {} WHERE id = $1) t",
+        "SELECT row_to_json(t) FROM (SELECT * FROM {} WHERE id = $1 AND ($2 OR deleted_at IS NULL)) t",
         E::TABLE_NAME
     );
 
     match sqlx::query_scalar::<_, serde_json::Value>(&sql)
         .bind(id)
+        .bind(params.include_deleted)
         .fetch_optional(&*state.pool)
         .await
     {
-        Ok(Some(row)) => Json(row).into_response(),
+        Ok(Some(mut row)) => {
+            pii::reveal(&mut row, E::SENSITIVE_FIELDS, access.granted());
+            with_etag(&row, Json(&row))
+        }
         Ok(None) => not_found(E::ENTITY_LABEL),
         Err(e) => {
             tracing::error!(error = %e, "Get by ID failed");
@@ -608,6 +908,7 @@ pub async fn generic_get_by_id<E: AdminEntity>(
 
 pub async fn generic_create<E: AdminEntity>(
     State(state): State<AdminState>,
+    actor: Actor,
     Json(body): Json<serde_json::Value>,
 ) -> Response {
     let obj = match body.as_object() {
@@ -618,34 +919,39 @@ pub async fn generic_create<E: AdminEntity>(
     let mut columns = vec!["id".to_string()];
     let mut placeholders = vec!["gen_random_uuid()".to_string()];
     let mut values: Vec<String> = Vec::new();
-    let mut idx = 1usize;
 
     for field in E::WRITABLE_FIELDS {
         if let Some(val) = obj.get(*field) {
-            columns.push((*field).to_string());
-            placeholders.push(format!("${idx}"));
-            values.push(json_to_sql_string(val));
-            idx += 1;
+            if let Err(e) = insert_value::<E>(
+                field,
+                val,
+                &mut columns,
+                &mut placeholders,
+                &mut values,
+            ) {
+                return e.into_response();
+            }
+        }
+    }
+    match custom_values(&state.pool, E::ENTITY_LABEL, obj).await {
+        Ok(Some(custom)) => {
+            columns.push("custom_fields".to_string());
+            values.push(custom);
+            placeholders.push(format!("jsonb_strip_nulls(${}::jsonb)", values.len()));
         }
+        Ok(None) => {}
+        Err(e) => return e.into_response(),
     }
 
     let sql = format!(
-        "INSERT INTO {} ({}) VALUES ({}) RETURNING id::text",
-        E::TABLE_NAME,
+        "INSERT INTO {table} ({}) VALUES ({}) RETURNING id::text, row_to_json({table}.*)",
         columns.join(", "),
-        placeholders.join(", ")
+        placeholders.join(", "),
+        table = E::TABLE_NAME,
     );
 
-    let mut query = sqlx::query_scalar::<_, String>(&sql);
-    for val in &values {
-        query = query.bind(val.as_str());
-    }
-
-    match query.fetch_one(&*state.pool).await {
-        Ok(id) => {
-            let _ = write_audit(&state.pool, E::ENTITY_LABEL, &id, "create", None, Some(&body)).await;
-            created_response(id)
-        }
+    match create_with_audit::<E>(&state.pool, &sql, &values, &actor).await {
+        Ok(id) => created_response(id),
         Err(e) => {
             tracing::error!(error = %e, "Create failed");
             error_response(
@@ -656,11 +962,159 @@ pub async fn generic_create<E: AdminEntity>(
     }
 }
 
+/// Add one submitted field to an `INSERT`. A sensitive field is sealed and
+/// its blind index set; a mask sent back unchanged is skipped.
+fn insert_value<E: AdminEntity>(
+    field: &str,
+    val: &serde_json::Value,
+    columns: &mut Vec<String>,
+    placeholders: &mut Vec<String>,
+    values: &mut Vec<String>,
+) -> Result<(), AdminError> {
+    let value = json_to_sql_string(val);
+    if !E::SENSITIVE_FIELDS.contains(&field) {
+        columns.push(field.to_string());
+        values.push(value);
+        placeholders.push(format!("${}", values.len()));
+        return Ok(());
+    }
+    if pii::is_masked(&value) {
+        return Ok(());
+    }
+    let (sealed, index) = pii::protect(field, &value)?;
+    columns.push(field.to_string());
+    values.push(sealed);
+    placeholders.push(format!("${}", values.len()));
+    columns.push(format!("{field}_bidx"));
+    values.push(index.unwrap_or_default());
+    placeholders.push(format!("NULLIF(${}, '')", values.len()));
+    Ok(())
+}
+
+/// `SET` clauses for one submitted field of an `UPDATE`. A sensitive field
+/// is sealed and its blind index set; a mask sent back unchanged is skipped.
+/// Sealed values are listed in `sealed` for [`keep_unchanged`].
+pub(crate) fn set_value(
+    sensitive: &[&str],
+    field: &str,
+    val: &serde_json::Value,
+    sets: &mut Vec<String>,
+    values: &mut Vec<String>,
+    sealed: &mut Vec<SealedValue>,
+) -> Result<(), AdminError> {
+    let value = json_to_sql_string(val);
+    if !sensitive.contains(&field) {
+        values.push(value);
+        sets.push(format!("{field} = ${}", values.len()));
+        return Ok(());
+    }
+    if pii::is_masked(&value) {
+        return Ok(());
+    }
+    let (ciphertext, index) = pii::protect(field, &value)?;
+    values.push(ciphertext);
+    sets.push(format!("{field} = ${}", values.len()));
+    sealed.push(SealedValue {
+        field: field.to_string(),
+        position: values.len() - 1,
+        clear: value,
+    });
+    values.push(index.unwrap_or_default());
+    sets.push(format!("{field}_bidx = NULLIF(${}, '')", values.len()));
+    Ok(())
+}
+
+/// A sensitive value sealed by [`set_value`]: its field, where its
+/// ciphertext sits in the bound values, and the clear text.
+pub(crate) struct SealedValue {
+    field: String,
+    position: usize,
+    clear: String,
+}
+
+/// Bind the stored ciphertext instead of a fresh one where the clear text is
+/// byte-identical to what `old` holds, so resubmitting a value is not
+/// audited as a change. Any other edit is written, including one the blind
+/// index does not see, such as the spacing of an IBAN. A stored value that
+/// cannot be decrypted is replaced.
+pub(crate) fn keep_unchanged(
+    old: &serde_json::Value,
+    sealed: &[SealedValue],
+    values: &mut [String],
+) {
+    for value in sealed {
+        let Some(stored) = old[&value.field].as_str().filter(|s| !s.is_empty()) else {
+            continue;
+        };
+        if pii::open(&value.field, stored).is_ok_and(|clear| clear == value.clear) {
+            values[value.position] = stored.to_string();
+        }
+    }
+}
+
+/// The body's `custom_fields` object as JSON, checked against the entity's
+/// definitions.
+async fn custom_values(
+    pool: &PgPool,
+    entity: &str,
+    obj: &serde_json::Map<String, serde_json::Value>,
+) -> Result<Option<String>, AdminError> {
+    let Some(value) = obj.get("custom_fields") else {
+        return Ok(None);
+    };
+    let fields = definitions(pool, entity).await?;
+    let values = custom_fields::validate_values(&fields, value)?;
+    Ok(Some(serde_json::Value::Object(values).to_string()))
+}
+
+async fn create_with_audit<E: AdminEntity>(
+    pool: &PgPool,
+    sql: &str,
+    values: &[String],
+    actor: &Actor,
+) -> Result<String, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let mut query = sqlx::query_as::<_, (String, serde_json::Value)>(sql);
+    for val in values {
+        query = query.bind(val.as_str());
+    }
+    let (id, row) = query.fetch_one(&mut *tx).await?;
+
+    write_audit(
+        &mut *tx,
+        actor,
+        E::ENTITY_LABEL,
+        &id,
+        "create",
+        None,
+        Some(&row),
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(id)
+}
+
+/// Outcome of a conditional write to a single row.
+enum WriteOutcome {
+    /// Written; holds the new version of the row
+    Done(serde_json::Value),
+    NotFound,
+    /// `If-Match` names an older version; holds the current row
+    Stale(serde_json::Value),
+}
+
+/// PUT /{entity}/{id}: requires `If-Match` with the ETag from the last read.
 pub async fn generic_update<E: AdminEntity>(
     State(state): State<AdminState>,
     Path(id): Path<Uuid>,
+    actor: Actor,
+    if_match: IfMatch,
     Json(body): Json<serde_json::Value>,
 ) -> Response {
+    if !if_match.is_present() {
+        return precondition_required();
+    }
     let obj = match body.as_object() {
         Some(o) => o,
         None => return error_response(axum::http::StatusCode::BAD_REQUEST, "Expected JSON object"),
@@ -668,14 +1122,34 @@ pub async fn generic_update<E: AdminEntity>(
 
     let mut sets: Vec<String> = Vec::new();
     let mut values: Vec<String> = Vec::new();
-    let mut idx = 1usize;
+    let mut sealed: Vec<SealedValue> = Vec::new();
 
     for field in E::WRITABLE_FIELDS {
         if let Some(val) = obj.get(*field) {
-            sets.push(format!("{field} = ${idx}"));
-            values.push(json_to_sql_string(val));
-            idx += 1;
+            let set = set_value(
+                E::SENSITIVE_FIELDS,
+                field,
+                val,
+                &mut sets,
+                &mut values,
+                &mut sealed,
+            );
+            if let Err(e) = set {
+                return e.into_response();
+            }
+        }
+    }
+    // Custom fields are merged into the stored ones; null removes a value
+    match custom_values(&state.pool, E::ENTITY_LABEL, obj).await {
+        Ok(Some(custom)) => {
+            values.push(custom);
+            sets.push(format!(
+                "custom_fields = jsonb_strip_nulls(custom_fields || ${}::jsonb)",
+                values.len()
+            ));
         }
+        Ok(None) => {}
+        Err(e) => return e.into_response(),
     }
 
     if sets.is_empty() {
@@ -685,24 +1159,19 @@ pub async fn generic_update<E: AdminEntity>(
     sets.push(format!("updated_at = NOW()"));
 
     let sql = format!(
-        "UPDATE {} SET {} WHERE id = ${} RETURNING id::text",
-        E::TABLE_NAME,
+        "UPDATE {table} SET {} WHERE id = ${} RETURNING row_to_json({table}.*)",
         sets.join(", "),
-        idx
+        values.len() + 1,
+        table = E::TABLE_NAME,
     );
 
-    let mut query = sqlx::query_scalar::<_, String>(&sql);
-    for val in &values {
-        query = query.bind(val.as_str());
-    }
-    query = query.bind(id);
-
-    match query.fetch_optional(&*state.pool).await {
-        Ok(Some(id_str)) => {
-            let _ = write_audit(&state.pool, E::ENTITY_LABEL, &id_str, "update", None, Some(&body)).await;
-            success_response()
+    match update_with_audit::<E>(&state.pool, id, &sql, values, &sealed, &actor, &if_match).await {
+        Ok(WriteOutcome::Done(row)) => with_etag(&row, success_response()),
+        Ok(WriteOutcome::NotFound) => not_found(E::ENTITY_LABEL),
+        Ok(WriteOutcome::Stale(mut current)) => {
+            pii::reveal(&mut current, E::SENSITIVE_FIELDS, false);
+            version_conflict(E::ENTITY_LABEL, &current)
         }
-        Ok(None) => not_found(E::ENTITY_LABEL),
         Err(e) => {
             tracing::error!(error = %e, "Update failed");
             error_response(
@@ -713,25 +1182,68 @@ pub async fn generic_update<E: AdminEntity>(
     }
 }
 
+/// Lock the row, check its version, apply the update and audit both versions
+/// in one transaction. Rows in the trash count as not found.
+async fn update_with_audit<E: AdminEntity>(
+    pool: &PgPool,
+    id: Uuid,
+    sql: &str,
+    mut values: Vec<String>,
+    sealed: &[SealedValue],
+    actor: &Actor,
+    if_match: &IfMatch,
+) -> Result<WriteOutcome, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let Some(old) = fetch_row_for_update(&mut tx, E::TABLE_NAME, id)
+        .await?
+        .filter(|row| !is_deleted(row))
+    else {
+        return Ok(WriteOutcome::NotFound);
+    };
+    if !if_match.matches(&old) {
+        return Ok(WriteOutcome::Stale(old));
+    }
+    keep_unchanged(&old, sealed, &mut values);
+
+    let mut query = sqlx::query_scalar::<_, serde_json::Value>(sql);
+    for val in &values {
+        query = query.bind(val.as_str());
+    }
+    let new = query.bind(id).fetch_one(&mut *tx).await?;
+
+    write_audit(
+        &mut *tx,
+        actor,
+        E::ENTITY_LABEL,
+        &id.to_string(),
+        "update",
+        Some(&old),
+        Some(&new),
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(WriteOutcome::Done(new))
+}
+
+/// DELETE /{entity}/{id}: requires `If-Match` with the ETag from the last read.
 pub async fn generic_delete<E: AdminEntity>(
     State(state): State<AdminState>,
     Path(id): Path<Uuid>,
+    actor: Actor,
+    if_match: IfMatch,
 ) -> Response {
-    let sql = format!(
-        "DELETE FROM {} WHERE id = $1 RETURNING id::text",
-        E::TABLE_NAME
-    );
+    if !if_match.is_present() {
+        return precondition_required();
+    }
 
-    match sqlx::query_scalar::<_, String>(&sql)
-        .bind(id)
-        .fetch_optional(&*state.pool)
-        .await
-    {
-        Ok(Some(id_str)) => {
-            let _ = write_audit(&state.pool, E::ENTITY_LABEL, &id_str, "delete", None, None).await;
-            success_response()
+    match delete_with_audit::<E>(&state.pool, id, &actor, &if_match).await {
+        Ok(WriteOutcome::Done(_)) => success_response(),
+        Ok(WriteOutcome::NotFound) => not_found(E::ENTITY_LABEL),
+        Ok(WriteOutcome::Stale(mut current)) => {
+            pii::reveal(&mut current, E::SENSITIVE_FIELDS, false);
+            version_conflict(E::ENTITY_LABEL, &current)
         }
-        Ok(None) => not_found(E::ENTITY_LABEL),
         Err(e) => {
             tracing::error!(error = %e, "Delete failed");
             error_response(
@@ -742,33 +1254,230 @@ pub async fn generic_delete<E: AdminEntity>(
     }
 }
 
+/// Move the row to the trash; it is purged once the retention period ends.
+async fn delete_with_audit<E: AdminEntity>(
+    pool: &PgPool,
+    id: Uuid,
+    actor: &Actor,
+    if_match: &IfMatch,
+) -> Result<WriteOutcome, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let Some(current) = fetch_row_for_update(&mut tx, E::TABLE_NAME, id)
+        .await?
+        .filter(|row| !is_deleted(row))
+    else {
+        return Ok(WriteOutcome::NotFound);
+    };
+    if !if_match.matches(&current) {
+        return Ok(WriteOutcome::Stale(current));
+    }
+    let Some((old, new)) = set_deleted(&mut tx, E::TABLE_NAME, id, true).await? else {
+        return Ok(WriteOutcome::NotFound);
+    };
+
+    write_audit(
+        &mut *tx,
+        actor,
+        E::ENTITY_LABEL,
+        &id.to_string(),
+        "delete",
+        Some(&old),
+        Some(&new),
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(WriteOutcome::Done(new))
+}
+
+/// Current row as JSON, locked until the transaction ends.
+pub async fn fetch_row_for_update(
+    tx: &mut Transaction<'_, Postgres>,
+    table: &str,
+    id: Uuid,
+) -> Result<Option<serde_json::Value>, sqlx::Error> {
+    sqlx::query_scalar::<_, serde_json::Value>(&format!(
+        "SELECT row_to_json(t) FROM {table} t WHERE t.id = $1 FOR UPDATE"
+    ))
+    .bind(id)
+    .fetch_optional(&mut **tx)
+    .await
+}
+
+/// Move a row into (`deleted = true`) or out of the trash. Returns the row
+/// before and after, or `None` when it does not exist or is already there.
+pub async fn set_deleted(
+    tx: &mut Transaction<'_, Postgres>,
+    table: &str,
+    id: Uuid,
+    deleted: bool,
+) -> Result<Option<(serde_json::Value, serde_json::Value)>, sqlx::Error> {
+    let Some(old) = fetch_row_for_update(tx, table, id).await? else {
+        return Ok(None);
+    };
+    if is_deleted(&old) == deleted {
+        return Ok(None);
+    }
+
+    let deleted_at = if deleted { "NOW()" } else { "NULL" };
+    let new = sqlx::query_scalar::<_, serde_json::Value>(&format!(
+        "UPDATE {table} SET deleted_at = {deleted_at} WHERE id = $1 RETURNING row_to_json({table}.*)"
+    ))
+    .bind(id)
+    .fetch_one(&mut **tx)
+    .await?;
+    Ok(Some((old, new)))
+}
+
+/// Whether a row fetched as JSON is in the trash.
+pub fn is_deleted(row: &serde_json::Value) -> bool {
+    row.get("deleted_at").is_some_and(|v| !v.is_null())
+}
+
 // ── Audit helper ────────────────────────────────────────────────────────
 
-pub async fn write_audit(
-    pool: &PgPool,
+/// Record a change in `admin_audit_log` and return the entry id.
+///
+/// Pass the full row before and after the change; `changed_fields` is derived
+/// from the two. Run it on the same transaction as the change itself: the
+/// change's webhook events go into the outbox in the same statement, with
+/// sensitive fields masked.
+pub async fn write_audit<'e>(
+    executor: impl PgExecutor<'e>,
+    actor: &Actor,
     entity_type: &str,
     entity_id: &str,
     action: &str,
     old_values: Option<&serde_json::Value>,
     new_values: Option<&serde_json::Value>,
-) -> Result<(), sqlx::Error> {
-    sqlx::query(
-        "INSERT INTO admin_audit_log (entity_type, entity_id, action, old_values, new_values, changed_fields) \
-         VALUES ($1, $2::uuid, $3, $4, $5, '{}')"
+) -> Result<Uuid, sqlx::Error> {
+    sqlx::query_scalar(
+        "WITH audit AS (\
+            INSERT INTO admin_audit_log \
+            (entity_type, entity_id, action, old_values, new_values, changed_fields, \
+             actor_user_id, actor_ip, actor_user_agent) \
+            VALUES ($1, $2::uuid, $3, $4, $5, $6, $7, $8, $9) \
+            RETURNING id, entity_id, created_at\
+         ), events AS (\
+            INSERT INTO admin_webhook_events \
+            (event_type, entity_type, entity_id, payload, audit_id) \
+            SELECT e.event_type, $1, a.entity_id, \
+                jsonb_build_object('entity_type', $1, 'entity_id', a.entity_id, \
+                    'action', $3, 'data', $12, 'previous', $11, 'changed_fields', $6, \
+                    'actor', $7, 'occurred_at', a.created_at), \
+                a.id \
+            FROM audit a, unnest($10::text[]) AS e(event_type)\
+         ) \
+         SELECT id FROM audit",
     )
     .bind(entity_type)
     .bind(entity_id)
     .bind(action)
     .bind(old_values)
     .bind(new_values)
-    .execute(pool)
-    .await?;
-    Ok(())
+    .bind(changed_fields(old_values, new_values))
+    .bind(actor.user_id.as_deref())
+    .bind(actor.ip.as_deref())
+    .bind(actor.user_agent.as_deref())
+    .bind(webhooks::audit_events(entity_type, action, old_values, new_values))
+    .bind(masked(entity_type, old_values))
+    .bind(masked(entity_type, new_values))
+    .fetch_one(executor)
+    .await
+}
+
+/// A row with the entity's sensitive fields masked, for webhook payloads.
+fn masked(entity_type: &str, row: Option<&serde_json::Value>) -> Option<serde_json::Value> {
+    let mut row = row?.clone();
+    if let Some(info) = entity_info(entity_type) {
+        pii::reveal(&mut row, info.sensitive_fields, false);
+    }
+    Some(row)
+}
+
+/// Columns whose value differs between the two versions of a row. For a
+/// create or delete, every column of the row that exists.
+fn changed_fields(
+    old_values: Option<&serde_json::Value>,
+    new_values: Option<&serde_json::Value>,
+) -> Vec<String> {
+    const IGNORED: &[&str] = &["id", "created_at", "updated_at", "revision"];
+
+    let old = old_values.and_then(serde_json::Value::as_object);
+    let new = new_values.and_then(serde_json::Value::as_object);
+    let mut fields: Vec<String> = match (old, new) {
+        (Some(old), Some(new)) => new
+            .iter()
+            .filter(|(k, v)| old.get(*k) != Some(*v))
+            .map(|(k, _)| k.clone())
+            .collect(),
+        (None, Some(row)) | (Some(row), None) => row.keys().cloned().collect(),
+        (None, None) => Vec::new(),
+    };
+    // A blind index only ever changes with its encrypted field
+    fields.retain(|f| !IGNORED.contains(&f.as_str()) && !f.ends_with("_bidx"));
+    fields.sort();
+    fields
+}
+
+/// The `AdminEntity` consts of one entity, for code that picks the entity
+/// at runtime (audit, trash, export).
+#[derive(Debug, Clone, Copy)]
+pub struct EntityInfo {
+    pub label: &'static str,
+    pub table: &'static str,
+    pub search_fields: &'static [&'static str],
+    pub sensitive_fields: &'static [&'static str],
+    pub filter_fields: &'static [(&'static str, FieldType)],
+    pub sortable_fields: &'static [&'static str],
+    pub writable_fields: &'static [&'static str],
+    pub default_sort: &'static str,
+    pub trashable: bool,
+}
+
+impl EntityInfo {
+    pub fn of<E: AdminEntity>() -> Self {
+        Self {
+            label: E::ENTITY_LABEL,
+            table: E::TABLE_NAME,
+            search_fields: E::SEARCH_FIELDS,
+            sensitive_fields: E::SENSITIVE_FIELDS,
+            filter_fields: E::FILTER_FIELDS,
+            sortable_fields: E::SORTABLE_FIELDS,
+            writable_fields: E::WRITABLE_FIELDS,
+            default_sort: E::DEFAULT_SORT,
+            trashable: E::TRASHABLE,
+        }
+    }
+}
+
+/// Every entity served by the generic API.
+pub fn entities() -> [EntityInfo; 14] {
+    [
+        EntityInfo::of::<PropertyEntity>(),
+        EntityInfo::of::<TenantEntity>(),
+        EntityInfo::of::<OwnerEntity>(),
+        EntityInfo::of::<ContractEntity>(),
+        EntityInfo::of::<InvoiceEntity>(),
+        EntityInfo::of::<DepositEntity>(),
+        EntityInfo::of::<SepaBatchEntity>(),
+        EntityInfo::of::<PayoutBatchEntity>(),
+        EntityInfo::of::<IssueEntity>(),
+        EntityInfo::of::<InsuranceEntity>(),
+        EntityInfo::of::<AlertEntity>(),
+        EntityInfo::of::<ContactEntity>(),
+        EntityInfo::of::<LeadEntity>(),
+        EntityInfo::of::<LeadNoteEntity>(),
+    ]
+}
+
+/// The entity behind a label such as an audit `entity_type`.
+pub fn entity_info(label: &str) -> Option<EntityInfo> {
+    entities().into_iter().find(|info| info.label == label)
 }
 
 // ── Helpers ─────────────────────────────────────────────────────────────
 
-fn json_to_sql_string(val: &serde_json::Value) -> String {
+pub(crate) fn json_to_sql_string(val: &serde_json::Value) -> String {
     match val {
         serde_json::Value::String(s) => s.clone(),
         serde_json::Value::Number(n) => n.to_string(),
@@ -785,3 +1494,69 @@ fn json_to_sql_string(val: &serde_json::Value) -> String {
         other => other.to_string(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn changed_fields_lists_updated_columns_only() {
+        let old = json!({"id": 1, "status": "Active", "rent": 900, "updated_at": "a", "revision": 3});
+        let new = json!({"id": 1, "status": "Ended", "rent": 900, "updated_at": "b", "revision": 4});
+        assert_eq!(changed_fields(Some(&old), Some(&new)), vec!["status"]);
+    }
+
+    #[test]
+    fn changed_fields_lists_every_column_of_a_created_or_deleted_row() {
+        let row = json!({"id": 1, "status": "Active", "name": "Flat 1", "created_at": "a"});
+        assert_eq!(changed_fields(None, Some(&row)), vec!["name", "status"]);
+        assert_eq!(changed_fields(Some(&row), None), vec!["name", "status"]);
+        assert!(changed_fields(None, None).is_empty());
+    }
+
+    fn sealed(field: &str, position: usize, clear: &str) -> SealedValue {
+        SealedValue {
+            field: field.to_string(),
+            position,
+            clear: clear.to_string(),
+        }
+    }
+
+    #[test]
+    fn identical_clear_text_keeps_the_stored_value() {
+        // Values stored before encryption are opened as they are
+        let old = json!({"bank_account": "ES91 2100 0418", "tax_id": "12345678Z"});
+        let mut values = vec!["fresh".to_string(), "index".to_string()];
+        keep_unchanged(&old, &[sealed("bank_account", 0, "ES91 2100 0418")], &mut values);
+        assert_eq!(values, ["ES91 2100 0418", "index"]);
+    }
+
+    #[test]
+    fn formatting_edit_is_written() {
+        let old = json!({"bank_account": "ES91 2100 0418"});
+        let mut values = vec!["fresh".to_string()];
+        keep_unchanged(&old, &[sealed("bank_account", 0, "ES9121000418")], &mut values);
+        assert_eq!(values, ["fresh"]);
+    }
+
+    #[test]
+    fn empty_stored_value_is_replaced() {
+        let old = json!({"tax_id": "", "phone": null});
+        let mut values = vec!["fresh".to_string(), "other".to_string()];
+        keep_unchanged(
+            &old,
+            &[sealed("tax_id", 0, ""), sealed("phone", 1, "600")],
+            &mut values,
+        );
+        assert_eq!(values, ["fresh", "other"]);
+    }
+
+    #[test]
+    fn changed_fields_leaves_out_blind_indexes() {
+        let old = json!({"tax_id": "enc:1", "tax_id_bidx": "aa"});
+        let new = json!({"tax_id": "enc:2", "tax_id_bidx": "bb"});
+        assert_eq!(changed_fields(Some(&old), Some(&new)), vec!["tax_id"]);
+    }
+}
@@ -2,44 +2,88 @@ use axum::extract::{Path, Query, State};
 use axum::response::{IntoResponse, Response};
 use axum::Json;
 use serde::Deserialize;
+use sqlx::{Postgres, Transaction};
 use uuid::Uuid;
 
-use crate::api::generic::AdminState;
+use crate::api::actor::Actor;
+use crate::api::generic::{
+    entity_info, fetch_row_for_update, is_deleted, set_deleted, write_audit, AdminState,
+    EntityInfo,
+};
+use crate::api::pagination::{Cursor, KeyedRow, MAX_PER_PAGE};
+use crate::api::sensitive::SensitiveAccess;
 use crate::api::types::error_response;
+use crate::error::AdminError;
+use crate::services::pii;
 
 #[derive(Deserialize)]
 pub struct AuditQuery {
     pub limit: Option<i64>,
+    /// `next_cursor` of the previous page
+    pub cursor: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+pub struct RevertQuery {
+    /// Revert even if the record changed again after this entry
+    #[serde(default)]
+    pub force: bool,
 }
 
 pub async fn audit_recent_handler(
     State(state): State<AdminState>,
+    access: SensitiveAccess,
     Query(params): Query<AuditQuery>,
 ) -> Response {
-    let limit = params.limit.unwrap_or(50).min(500);
-
-    match sqlx::query_scalar::<_, serde_json::Value>(
-        "SELECT COALESCE(json_agg(row_to_json(t)), '[]') FROM (\
-            SELECT * FROM admin_audit_log ORDER BY created_at DESC LIMIT $1\
-         ) t",
-    )
-    .bind(limit)
-    .fetch_one(&*state.pool)
-    .await
-    {
-        Ok(data) => Json(serde_json::json!({ "data": data, "total": limit })).into_response(),
+    match recent_entries(&state, &params, access).await {
+        Ok(body) => Json(body).into_response(),
         Err(e) => {
             tracing::error!(error = %e, "Audit recent query failed");
-            error_response(
-                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                &e.to_string(),
-            )
+            e.into_response()
         }
     }
 }
 
+/// Newest entries first, paged by keyset on (`created_at`, `id`).
+async fn recent_entries(
+    state: &AdminState,
+    params: &AuditQuery,
+    access: SensitiveAccess,
+) -> Result<serde_json::Value, AdminError> {
+    let limit = params.limit.unwrap_or(50).clamp(1, MAX_PER_PAGE);
+    let cursor = params
+        .cursor
+        .as_deref()
+        .filter(|c| !c.is_empty())
+        .map(|token| Cursor::decode(token, "created_at", "DESC"))
+        .transpose()?;
+    let after = cursor.as_ref().map_or_else(
+        || "$3::uuid IS NULL".to_string(),
+        |c| c.condition(2, 3),
+    );
+
+    let rows = sqlx::query_as::<_, KeyedRow>(&format!(
+        "SELECT row_to_json(t), t.created_at::text, pg_typeof(t.created_at)::text \
+         FROM admin_audit_log t WHERE {after} \
+         ORDER BY created_at DESC, id DESC LIMIT $1"
+    ))
+    .bind(limit + 1)
+    .bind(cursor.as_ref().and_then(|c| c.value.clone()))
+    .bind(cursor.as_ref().map(|c| c.id))
+    .fetch_all(&*state.pool)
+    .await?;
+
+    let (mut rows, next_cursor) = Cursor::next_page(rows, limit, "created_at", "DESC");
+    for row in &mut rows {
+        reveal_entry(row, access);
+    }
+
+    Ok(serde_json::json!({ "data": rows, "next_cursor": next_cursor }))
+}
+
 pub async fn audit_entity_handler(
     State(state): State<AdminState>,
+    access: SensitiveAccess,
     Path((entity_type, entity_id)): Path<(String, Uuid)>,
 ) -> Response {
     match sqlx::query_scalar::<_, serde_json::Value>(
@@ -54,7 +98,14 @@ pub async fn audit_entity_handler(
     .fetch_one(&*state.pool)
     .await
     {
-        Ok(data) => Json(data).into_response(),
+        Ok(mut data) => {
+            if let Some(entries) = data.as_array_mut() {
+                for entry in entries {
+                    reveal_entry(entry, access);
+                }
+            }
+            Json(data).into_response()
+        }
         Err(e) => {
             tracing::error!(error = %e, "Audit entity query failed");
             error_response(
@@ -64,3 +115,277 @@ pub async fn audit_entity_handler(
         }
     }
 }
+
+/// Decrypt the sensitive fields of the row versions an entry holds, masked
+/// unless the request has the `sensitive` scope.
+fn reveal_entry(entry: &mut serde_json::Value, access: SensitiveAccess) {
+    let Some(info) = entry
+        .get("entity_type")
+        .and_then(serde_json::Value::as_str)
+        .and_then(entity_info)
+    else {
+        return;
+    };
+    for key in ["old_values", "new_values"] {
+        if let Some(row) = entry.get_mut(key) {
+            pii::reveal(row, info.sensitive_fields, access.granted());
+        }
+    }
+}
+
+/// Audit entry being reverted.
+#[derive(sqlx::FromRow)]
+struct AuditEntry {
+    entity_type: String,
+    entity_id: Uuid,
+    action: String,
+    old_values: Option<serde_json::Value>,
+    new_values: Option<serde_json::Value>,
+    changed_fields: Vec<String>,
+    reverted_by: Option<Uuid>,
+}
+
+type Versions = (Option<serde_json::Value>, Option<serde_json::Value>);
+
+/// Undo an audited change: an update restores the changed columns, a delete
+/// takes the row out of the trash (or re-inserts it once purged), and a create
+/// or restore moves it to the trash. The revert is audited itself.
+pub async fn audit_revert_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<RevertQuery>,
+    actor: Actor,
+) -> Response {
+    match revert(&state, id, params.force, &actor).await {
+        Ok(revert_id) => {
+            Json(serde_json::json!({ "success": true, "audit_id": revert_id })).into_response()
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "Audit revert failed");
+            e.into_response()
+        }
+    }
+}
+
+async fn revert(
+    state: &AdminState,
+    audit_id: Uuid,
+    force: bool,
+    actor: &Actor,
+) -> Result<Uuid, AdminError> {
+    let mut tx = state.pool.begin().await?;
+
+    let entry = sqlx::query_as::<_, AuditEntry>(
+        "SELECT entity_type, entity_id, action, old_values, new_values, \
+             COALESCE(changed_fields, '{}') AS changed_fields, reverted_by \
+         FROM admin_audit_log WHERE id = $1 FOR UPDATE",
+    )
+    .bind(audit_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AdminError::NotFound("audit entry".to_string()))?;
+
+    if entry.reverted_by.is_some() {
+        return Err(AdminError::Conflict(
+            "This change has already been reverted".to_string(),
+        ));
+    }
+    let info = entity_info(&entry.entity_type).ok_or_else(|| {
+        AdminError::BadRequest(format!(
+            "Changes to {} cannot be reverted",
+            entry.entity_type
+        ))
+    })?;
+    let table = info.table;
+    let current = fetch_row_for_update(&mut tx, table, entry.entity_id).await?;
+
+    let (old, new) = match entry.action.as_str() {
+        "update" => undo_update(&mut tx, &info, &entry, current, force).await?,
+        "delete" => undo_delete(&mut tx, table, &entry, current).await?,
+        "create" | "restore" if !info.trashable => {
+            return Err(AdminError::BadRequest(format!(
+                "{} cannot be moved to the trash",
+                entry.entity_type
+            )))
+        }
+        "create" | "restore" => {
+            let (old, new) = set_deleted(&mut tx, table, entry.entity_id, true)
+                .await?
+                .ok_or_else(|| {
+                    AdminError::Conflict(
+                        "The record no longer exists or is already in the trash".to_string(),
+                    )
+                })?;
+            (Some(old), Some(new))
+        }
+        other => {
+            return Err(AdminError::BadRequest(format!(
+                "'{other}' entries cannot be reverted"
+            )))
+        }
+    };
+
+    let revert_id = write_audit(
+        &mut *tx,
+        actor,
+        &entry.entity_type,
+        &entry.entity_id.to_string(),
+        "revert",
+        old.as_ref(),
+        new.as_ref(),
+    )
+    .await?;
+
+    sqlx::query("UPDATE admin_audit_log SET reverted_by = $1 WHERE id = $2")
+        .bind(revert_id)
+        .bind(audit_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(revert_id)
+}
+
+/// Restore the columns an update changed, refusing when they changed again
+/// since unless `force` is set.
+async fn undo_update(
+    tx: &mut Transaction<'_, Postgres>,
+    info: &EntityInfo,
+    entry: &AuditEntry,
+    current: Option<serde_json::Value>,
+    force: bool,
+) -> Result<Versions, AdminError> {
+    let old = entry.old_values.as_ref().ok_or_else(|| {
+        AdminError::BadRequest("Entry has no previous values to restore".to_string())
+    })?;
+    let current =
+        current.ok_or_else(|| AdminError::Conflict("The record no longer exists".to_string()))?;
+    if is_deleted(&current) {
+        return Err(AdminError::Conflict(
+            "The record is in the trash; restore it first".to_string(),
+        ));
+    }
+
+    // Older entries have no changed_fields; fall back to the submitted keys
+    let candidates: Vec<String> = if entry.changed_fields.is_empty() {
+        entry
+            .new_values
+            .as_ref()
+            .and_then(serde_json::Value::as_object)
+            .map(|o| o.keys().cloned().collect())
+            .unwrap_or_default()
+    } else {
+        entry.changed_fields.clone()
+    };
+    let fields: Vec<&str> = candidates
+        .iter()
+        .map(String::as_str)
+        .filter(|f| {
+            (info.writable_fields.contains(f) || *f == "custom_fields") && old.get(*f).is_some()
+        })
+        .collect();
+    if fields.is_empty() {
+        return Err(AdminError::BadRequest(
+            "Entry has no revertible fields".to_string(),
+        ));
+    }
+
+    if !force {
+        let stale: Vec<&str> = fields
+            .iter()
+            .copied()
+            .filter(|f| {
+                entry
+                    .new_values
+                    .as_ref()
+                    .and_then(|n| n.get(*f))
+                    .is_some_and(|v| !same_value(info, f, v, current.get(*f)))
+            })
+            .collect();
+        if !stale.is_empty() {
+            return Err(AdminError::Conflict(format!(
+                "Changed again since this entry: {} (use ?force=true)",
+                stale.join(", ")
+            )));
+        }
+    }
+
+    // An encrypted field takes its blind index back with it; entries from
+    // before encryption have none, and the rotation job fills it in
+    let mut sets: Vec<String> = fields.iter().map(|f| format!("{f} = r.{f}")).collect();
+    for field in fields.iter().filter(|f| info.sensitive_fields.contains(*f)) {
+        sets.push(format!("{field}_bidx = r.{field}_bidx"));
+    }
+    let table = info.table;
+    let sql = format!(
+        "UPDATE {table} SET {}, updated_at = NOW() \
+         FROM jsonb_populate_record(NULL::{table}, $1) r \
+         WHERE {table}.id = $2 RETURNING row_to_json({table}.*)",
+        sets.join(", ")
+    );
+    let new = sqlx::query_scalar::<_, serde_json::Value>(&sql)
+        .bind(old)
+        .bind(entry.entity_id)
+        .fetch_one(&mut **tx)
+        .await?;
+    Ok((Some(current), Some(new)))
+}
+
+/// Whether two stored versions of a field hold the same value. Encrypted
+/// values are compared in clear, as re-sealing changes the ciphertext.
+fn same_value(
+    info: &EntityInfo,
+    field: &str,
+    a: &serde_json::Value,
+    b: Option<&serde_json::Value>,
+) -> bool {
+    let Some(b) = b else {
+        return false;
+    };
+    if info.sensitive_fields.contains(&field) {
+        if let (Some(a), Some(b)) = (a.as_str(), b.as_str()) {
+            return matches!(
+                (pii::open(field, a), pii::open(field, b)),
+                (Ok(a), Ok(b)) if a == b
+            );
+        }
+    }
+    a == b
+}
+
+/// Take a deleted row out of the trash, or re-insert it from the entry when
+/// it has been purged (or was deleted before the trash existed).
+async fn undo_delete(
+    tx: &mut Transaction<'_, Postgres>,
+    table: &str,
+    entry: &AuditEntry,
+    current: Option<serde_json::Value>,
+) -> Result<Versions, AdminError> {
+    match current {
+        Some(row) if is_deleted(&row) => {
+            let (old, new) = set_deleted(tx, table, entry.entity_id, false)
+                .await?
+                .ok_or_else(|| {
+                    AdminError::Conflict("The record is not in the trash".to_string())
+                })?;
+            Ok((Some(old), Some(new)))
+        }
+        Some(_) => Err(AdminError::Conflict(
+            "The record already exists".to_string(),
+        )),
+        None => {
+            let old = entry.old_values.as_ref().ok_or_else(|| {
+                AdminError::BadRequest("Entry has no deleted row to restore".to_string())
+            })?;
+            let sql = format!(
+                "INSERT INTO {table} SELECT * FROM jsonb_populate_record(NULL::{table}, $1) \
+                 RETURNING row_to_json({table}.*)"
+            );
+            let new = sqlx::query_scalar::<_, serde_json::Value>(&sql)
+                .bind(old)
+                .fetch_one(&mut **tx)
+                .await?;
+            Ok((None, Some(new)))
+        }
+    }
+}
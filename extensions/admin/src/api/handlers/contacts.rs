@@ -8,7 +8,7 @@ use crate::api::types::error_response;
 pub async fn contacts_names_handler(State(state): State<AdminState>) -> Response {
     match sqlx::query_scalar::<_, serde_json::Value>(
         "SELECT COALESCE(json_agg(row_to_json(t)), '[]') FROM (\
-            SELECT id, name FROM admin_contacts ORDER BY name\
+            SELECT id, name FROM admin_contacts WHERE deleted_at IS NULL ORDER BY name\
          ) t",
     )
     .fetch_one(&*state.pool)
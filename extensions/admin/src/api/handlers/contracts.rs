@@ -1,21 +1,46 @@
-use axum::extract::{Path, State};
+use axum::extract::{Path, Query, State};
+use axum::http::{header, StatusCode};
 use axum::response::{IntoResponse, Response};
 use axum::Json;
+use serde::Deserialize;
 use uuid::Uuid;
 
+use crate::api::actor::Actor;
 use crate::api::generic::AdminState;
-use crate::api::types::{error_response, not_found};
+use crate::api::types::{error_response, not_found, success_response, RecordQuery};
+use crate::error::AdminError;
+use crate::services::contract_documents::{
+    delete_document, document_file, generate_contract_document, list_documents,
+};
 
+/// Query parameters for the document listing: ?contract_id=...
+#[derive(Debug, Deserialize, Default)]
+pub struct ContractDocumentsQuery {
+    pub contract_id: Option<Uuid>,
+}
+
+/// Body of POST /contracts/{id}/documents/generate.
+#[derive(Debug, Deserialize)]
+pub struct GenerateDocumentRequest {
+    pub template_id: Uuid,
+}
+
+/// A contract with its documents. Deleted contracts are not found unless
+/// ?include_deleted=true, as with the generic record lookup.
 pub async fn contract_detail_handler(
     State(state): State<AdminState>,
     Path(id): Path<Uuid>,
+    Query(params): Query<RecordQuery>,
 ) -> Response {
     let pool = &*state.pool;
 
     let contract = match sqlx::query_scalar::<_, serde_json::Value>(
-        "SELECT row_to_json(t) FROM (SELECT * FROM admin_contracts WHERE id = $1) t",
+        "SELECT row_to_json(t) FROM (\
+            SELECT * FROM admin_contracts WHERE id = $1 AND ($2 OR deleted_at IS NULL)\
+         ) t",
     )
     .bind(id)
+    .bind(params.include_deleted)
     .fetch_optional(pool)
     .await
     {
@@ -30,9 +55,17 @@ pub async fn contract_detail_handler(
         }
     };
 
+    let documents = match list_documents(pool, Some(id)).await {
+        Ok(d) => d,
+        Err(e) => {
+            tracing::error!(error = %e, "Contract documents query failed");
+            return AdminError::from(e).into_response();
+        }
+    };
+
     Json(serde_json::json!({
         "contract": contract,
-        "documents": [],
+        "documents": documents,
         "details": [],
     }))
     .into_response()
@@ -50,3 +83,66 @@ pub async fn contract_doc_text_handler(
     }))
     .into_response()
 }
+
+pub async fn contract_documents_list_handler(
+    State(state): State<AdminState>,
+    Query(params): Query<ContractDocumentsQuery>,
+) -> Response {
+    match list_documents(&state.pool, params.contract_id).await {
+        Ok(documents) => Json(serde_json::json!({ "data": documents })).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Contract documents listing failed");
+            AdminError::from(e).into_response()
+        }
+    }
+}
+
+/// Render a document template against the contract and attach the PDF.
+pub async fn contract_document_generate_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+    actor: Actor,
+    Json(body): Json<GenerateDocumentRequest>,
+) -> Response {
+    match generate_contract_document(&state.pool, &actor, id, body.template_id).await {
+        Ok(body) => (StatusCode::CREATED, Json(body)).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Contract document generation failed");
+            e.into_response()
+        }
+    }
+}
+
+pub async fn contract_document_download_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+) -> Response {
+    match document_file(&state.pool, id).await {
+        Ok((filename, file_type, content)) => (
+            [
+                (header::CONTENT_TYPE, file_type),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("inline; filename=\"{filename}\""),
+                ),
+            ],
+            content,
+        )
+            .into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn contract_document_delete_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+    actor: Actor,
+) -> Response {
+    match delete_document(&state.pool, &actor, id).await {
+        Ok(()) => success_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Contract document deletion failed");
+            e.into_response()
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::api::generic::AdminState;
+use crate::api::types::success_response;
+use crate::error::AdminError;
+use crate::services::custom_fields::{
+    create_field, delete_field, list_fields, update_field, CustomFieldRequest, CustomFieldUpdate,
+};
+
+/// Query parameters for the definitions listing: ?entity=properties
+#[derive(Debug, Deserialize, Default)]
+pub struct CustomFieldsQuery {
+    pub entity: Option<String>,
+}
+
+pub async fn custom_fields_list_handler(
+    State(state): State<AdminState>,
+    Query(params): Query<CustomFieldsQuery>,
+) -> Response {
+    let entity = params.entity.as_deref().filter(|e| !e.is_empty());
+    match list_fields(&state.pool, entity).await {
+        Ok(fields) => Json(serde_json::json!({ "data": fields })).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Custom fields listing failed");
+            AdminError::from(e).into_response()
+        }
+    }
+}
+
+pub async fn custom_field_create_handler(
+    State(state): State<AdminState>,
+    Json(body): Json<CustomFieldRequest>,
+) -> Response {
+    match create_field(&state.pool, &body).await {
+        Ok(field) => (StatusCode::CREATED, Json(field)).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Custom field creation failed");
+            e.into_response()
+        }
+    }
+}
+
+pub async fn custom_field_update_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+    Json(body): Json<CustomFieldUpdate>,
+) -> Response {
+    match update_field(&state.pool, id, &body).await {
+        Ok(field) => Json(field).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Custom field update failed");
+            e.into_response()
+        }
+    }
+}
+
+/// Delete a definition along with the values records hold for it.
+pub async fn custom_field_delete_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+) -> Response {
+    match delete_field(&state.pool, id).await {
+        Ok(()) => success_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Custom field deletion failed");
+            e.into_response()
+        }
+    }
+}
@@ -1,12 +1,82 @@
-use axum::extract::State;
+use axum::extract::{Query, State};
 use axum::response::{IntoResponse, Response};
 use axum::Json;
 
 use crate::api::generic::AdminState;
+use crate::error::AdminError;
+use crate::services::reporting::{
+    collection_rate, compare_periods, ReportFilter, AUDIT_FILTER, INVOICE_FILTER, PROPERTY_FILTER,
+};
 
-pub async fn dashboard_handler(State(state): State<AdminState>) -> Response {
+pub async fn dashboard_handler(
+    State(state): State<AdminState>,
+    Query(filter): Query<ReportFilter>,
+) -> Response {
+    let filter = match filter.validate() {
+        Ok(f) => f,
+        Err(e) => return e.into_response(),
+    };
     let pool = &*state.pool;
 
+    let properties_count_sql =
+        format!("SELECT COUNT(*)::bigint FROM admin_properties WHERE {PROPERTY_FILTER}");
+    let income_totals_sql = format!(
+        "SELECT \
+            COALESCE(SUM(amount), 0)::float8, \
+            COALESCE(SUM(paid), 0)::float8, \
+            COALESCE(SUM(amount - paid), 0)::float8, \
+            COUNT(*) FILTER (WHERE status IN ('Unpaid', 'Partial'))::bigint \
+         FROM admin_invoices WHERE type = 'income' AND {INVOICE_FILTER}"
+    );
+    let by_status_sql = format!(
+        "SELECT json_agg(row_to_json(t)) FROM (\
+            SELECT status, COUNT(*)::int as count FROM admin_properties \
+            WHERE {PROPERTY_FILTER} GROUP BY status ORDER BY count DESC\
+         ) t"
+    );
+    let overdue_sql = format!(
+        "SELECT COALESCE(json_agg(row_to_json(t)), '[]') FROM (\
+            SELECT * FROM admin_invoices \
+            WHERE status IN ('Unpaid', 'Partial') AND type = 'income' \
+            AND invoice_date < CURRENT_DATE - INTERVAL '15 days' AND {INVOICE_FILTER} \
+            ORDER BY invoice_date ASC\
+         ) t"
+    );
+    let expiring_sql = format!(
+        "SELECT COALESCE(json_agg(row_to_json(t)), '[]') FROM (\
+            SELECT * FROM admin_properties \
+            WHERE end_date IS NOT NULL AND end_date <= CURRENT_DATE + INTERVAL '90 days' \
+            AND status = 'Let' AND {PROPERTY_FILTER} ORDER BY end_date ASC\
+         ) t"
+    );
+    let recent_activity_sql = format!(
+        "SELECT COALESCE(json_agg(row_to_json(t)), '[]') FROM (\
+            SELECT * FROM admin_audit_log WHERE {AUDIT_FILTER} ORDER BY created_at DESC LIMIT 5\
+         ) t"
+    );
+    let by_payee_sql = format!(
+        "SELECT COALESCE(json_agg(row_to_json(t)), '[]') FROM (\
+            SELECT payee, \
+                SUM(amount)::float8 as total_invoiced, \
+                SUM(paid)::float8 as total_collected, \
+                SUM(amount - paid)::float8 as total_outstanding, \
+                COUNT(DISTINCT property_name)::int as num_properties \
+            FROM admin_invoices WHERE type = 'income' AND {INVOICE_FILTER} \
+            GROUP BY payee ORDER BY total_invoiced DESC\
+         ) t"
+    );
+    let by_property_sql = format!(
+        "SELECT COALESCE(json_agg(row_to_json(t)), '[]') FROM (\
+            SELECT property_name, \
+                SUM(amount)::float8 as total_invoiced, \
+                SUM(paid)::float8 as total_collected, \
+                SUM(amount - paid)::float8 as total_outstanding, \
+                COUNT(*)::int as num_invoices \
+            FROM admin_invoices WHERE type = 'income' AND {INVOICE_FILTER} \
+            GROUP BY property_name ORDER BY total_invoiced DESC\
+         ) t"
+    );
+
     let (
         properties_count,
         income_totals,
@@ -16,80 +86,52 @@ pub async fn dashboard_handler(State(state): State<AdminState>) -> Response {
         recent_activity,
         by_payee,
         by_property,
+        comparison,
     ) = tokio::join!(
         // Total properties
-        sqlx::query_scalar::<_, i64>("SELECT COUNT(*)::bigint FROM admin_properties")
-            .fetch_one(pool),
+        filter.scalar::<i64>(&properties_count_sql).fetch_one(pool),
         // Income totals
-        sqlx::query_as::<_, (f64, f64, f64, i64)>(
-            "SELECT \
-                COALESCE(SUM(amount), 0)::float8, \
-                COALESCE(SUM(paid), 0)::float8, \
-                COALESCE(SUM(amount - paid), 0)::float8, \
-                COUNT(*) FILTER (WHERE status IN ('Unpaid', 'Partial'))::bigint \
-             FROM admin_invoices WHERE type = 'income'"
-        ).fetch_one(pool),
+        filter
+            .query_as::<(f64, f64, f64, i64)>(&income_totals_sql)
+            .fetch_one(pool),
         // Properties by status
-        sqlx::query_scalar::<_, serde_json::Value>(
-            "SELECT json_agg(row_to_json(t)) FROM (\
-                SELECT status, COUNT(*)::int as count FROM admin_properties GROUP BY status ORDER BY count DESC\
-             ) t"
-        ).fetch_one(pool),
+        filter
+            .scalar::<serde_json::Value>(&by_status_sql)
+            .fetch_one(pool),
         // Overdue invoices (unpaid/partial income, older than 15 days)
-        sqlx::query_scalar::<_, serde_json::Value>(
-            "SELECT COALESCE(json_agg(row_to_json(t)), '[]') FROM (\
-                SELECT * FROM admin_invoices \
-                WHERE status IN ('Unpaid', 'Partial') AND type = 'income' \
-                AND invoice_date < CURRENT_DATE - INTERVAL '15 days' ORDER BY invoice_date ASC\
-             ) t"
-        ).fetch_one(pool),
+        filter
+            .scalar::<serde_json::Value>(&overdue_sql)
+            .fetch_one(pool),
         // Expiring leases (within 90 days)
-        sqlx::query_scalar::<_, serde_json::Value>(
-            "SELECT COALESCE(json_agg(row_to_json(t)), '[]') FROM (\
-                SELECT * FROM admin_properties \
-                WHERE end_date IS NOT NULL AND end_date <= CURRENT_DATE + INTERVAL '90 days' \
-                AND status = 'Let' ORDER BY end_date ASC\
-             ) t"
-        ).fetch_one(pool),
+        filter
+            .scalar::<serde_json::Value>(&expiring_sql)
+            .fetch_one(pool),
         // Recent activity (last 5 audit entries)
-        sqlx::query_scalar::<_, serde_json::Value>(
-            "SELECT COALESCE(json_agg(row_to_json(t)), '[]') FROM (\
-                SELECT * FROM admin_audit_log ORDER BY created_at DESC LIMIT 5\
-             ) t"
-        ).fetch_one(pool),
+        filter
+            .scalar::<serde_json::Value>(&recent_activity_sql)
+            .fetch_one(pool),
         // Financial by payee
-        sqlx::query_scalar::<_, serde_json::Value>(
-            "SELECT COALESCE(json_agg(row_to_json(t)), '[]') FROM (\
-                SELECT payee, \
-                    SUM(amount)::float8 as total_invoiced, \
-                    SUM(paid)::float8 as total_collected, \
-                    SUM(amount - paid)::float8 as total_outstanding, \
-                    COUNT(DISTINCT property_name)::int as num_properties \
-                FROM admin_invoices WHERE type = 'income' \
-                GROUP BY payee ORDER BY total_invoiced DESC\
-             ) t"
-        ).fetch_one(pool),
+        filter
+            .scalar::<serde_json::Value>(&by_payee_sql)
+            .fetch_one(pool),
         // Financial by property
-        sqlx::query_scalar::<_, serde_json::Value>(
-            "SELECT COALESCE(json_agg(row_to_json(t)), '[]') FROM (\
-                SELECT property_name, \
-                    SUM(amount)::float8 as total_invoiced, \
-                    SUM(paid)::float8 as total_collected, \
-                    SUM(amount - paid)::float8 as total_outstanding, \
-                    COUNT(*)::int as num_invoices \
-                FROM admin_invoices WHERE type = 'income' \
-                GROUP BY property_name ORDER BY total_invoiced DESC\
-             ) t"
-        ).fetch_one(pool),
+        filter
+            .scalar::<serde_json::Value>(&by_property_sql)
+            .fetch_one(pool),
+        // Period-over-period comparison
+        compare_periods(pool, &filter),
     );
 
     let total_properties = properties_count.unwrap_or(0);
     let (total_invoiced, total_collected, total_outstanding, pending_count) =
         income_totals.unwrap_or((0.0, 0.0, 0.0, 0));
-    let collection_rate = if total_invoiced > 0.0 {
-        (total_collected / total_invoiced * 1000.0).round() / 10.0
-    } else {
-        0.0
+    let collection_rate = collection_rate(total_invoiced, total_collected);
+    let comparison = match comparison {
+        // Dates the comparison periods cannot be computed from
+        Err(e @ AdminError::BadRequest(_)) => return e.into_response(),
+        other => other
+            .map_err(|e| tracing::error!(error = %e, "Dashboard period comparison failed"))
+            .ok(),
     };
 
     let result = serde_json::json!({
@@ -105,6 +147,8 @@ pub async fn dashboard_handler(State(state): State<AdminState>) -> Response {
         "recent_activity": recent_activity.unwrap_or(serde_json::json!([])),
         "financial_by_payee": by_payee.unwrap_or(serde_json::json!([])),
         "financial_by_property": by_property.unwrap_or(serde_json::json!([])),
+        "filters": filter,
+        "comparison": comparison,
     });
 
     Json(result).into_response()
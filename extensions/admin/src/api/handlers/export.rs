@@ -1,39 +1,48 @@
-use axum::extract::{Path, State};
+use axum::extract::{Path, Query, State};
 use axum::http::header;
 use axum::response::{IntoResponse, Response};
 
-use crate::api::generic::AdminState;
-use crate::api::types::error_response;
-
-const ALLOWED_TABLES: &[(&str, &str)] = &[
-    ("properties", "admin_properties"),
-    ("tenants", "admin_tenants"),
-    ("owners", "admin_owners"),
-    ("contracts", "admin_contracts"),
-    ("invoices", "admin_invoices"),
-    ("deposits", "admin_deposits"),
-    ("sepa-batches", "admin_sepa_batches"),
-    ("issues", "admin_issues"),
-    ("insurance", "admin_insurance"),
-    ("alerts", "admin_alerts"),
-    ("contacts", "admin_contacts"),
-    ("leads", "admin_leads"),
-    ("lead-notes", "admin_lead_notes"),
-];
+use crate::api::actor::Actor;
+use crate::api::generic::{build_filters, entity_info, validated_sort, AdminState, EntityInfo};
+use crate::api::sensitive::SensitiveAccess;
+use crate::api::types::{error_response, PaginationQuery};
+use crate::error::AdminError;
+use crate::services::custom_fields::{definitions, CustomField, CUSTOM_PREFIX};
+use crate::services::pii;
+use crate::services::views::{select_columns, with_saved_view};
 
+/// CSV of every matching row. Takes the same `?search=`, filter, sort,
+/// `?view=` and `?include_deleted=` parameters as the entity's list endpoint;
+/// a view with columns exports only those. Sensitive fields are masked unless
+/// the token has the `sensitive` scope.
 pub async fn export_handler(
     State(state): State<AdminState>,
+    actor: Actor,
+    access: SensitiveAccess,
     Path(entity): Path<String>,
+    Query(params): Query<PaginationQuery>,
 ) -> Response {
-    let table = match ALLOWED_TABLES.iter().find(|(name, _)| *name == entity.as_str()) {
-        Some((_, table)) => *table,
-        None => {
-            return error_response(
-                axum::http::StatusCode::BAD_REQUEST,
-                &format!("Unknown entity: {entity}"),
-            )
-        }
+    let Some(info) = entity_info(&entity.replace('-', "_")) else {
+        return error_response(
+            axum::http::StatusCode::BAD_REQUEST,
+            &format!("Unknown entity: {entity}"),
+        );
+    };
+    let table = info.table;
+    let (params, view_columns) =
+        match with_saved_view(&state.pool, &actor, info.label, params).await {
+            Ok(view) => view,
+            Err(e) => return e.into_response(),
+        };
+    let custom = match definitions(&state.pool, info.label).await {
+        Ok(custom) => custom,
+        Err(e) => return AdminError::from(e).into_response(),
     };
+    let qb = match build_filters(&info, &custom, &params) {
+        Ok(qb) => qb,
+        Err(e) => return e.into_response(),
+    };
+    let (sort_field, sort_order) = validated_sort(&info, &custom, &params);
 
     // Get column names
     let columns_sql = format!(
@@ -56,12 +65,11 @@ pub async fn export_handler(
 
     // Get all rows as CSV-ish format
     let data_sql = format!(
-        "SELECT row_to_json(t)::text FROM (SELECT * FROM {table} ORDER BY created_at DESC) t"
+        "SELECT row_to_json(t) FROM (SELECT * FROM {table} {} \
+         ORDER BY {sort_field} {sort_order}, id {sort_order}) t",
+        qb.where_clause()
     );
-    let rows: Vec<String> = match sqlx::query_scalar(&data_sql)
-        .fetch_all(&*state.pool)
-        .await
-    {
+    let rows = match qb.fetch_json(&data_sql, &state.pool).await {
         Ok(r) => r,
         Err(e) => {
             tracing::error!(error = %e, "Export data query failed");
@@ -72,10 +80,29 @@ pub async fn export_handler(
         }
     };
 
-    let mut csv = columns;
+    // Blind indexes are internal; custom fields get a column each, after
+    // the table's own columns. A view narrows both to its columns.
+    let in_view = |column: &str| {
+        view_columns.is_empty() || column == "id" || view_columns.iter().any(|c| c == column)
+    };
+    let custom: Vec<CustomField> = custom
+        .into_iter()
+        .filter(|field| in_view(&format!("{CUSTOM_PREFIX}{}", field.key)))
+        .collect();
+    let mut csv = columns
+        .split(',')
+        .filter(|column| !is_blind_index(&info, column) && in_view(column))
+        .collect::<Vec<_>>()
+        .join(",");
+    for field in &custom {
+        csv.push_str(&format!(",{CUSTOM_PREFIX}{}", field.key));
+    }
     csv.push('\n');
-    for row in rows {
-        csv.push_str(&row);
+    for mut row in rows {
+        pii::reveal(&mut row, info.sensitive_fields, access.granted());
+        flatten_custom_fields(&mut row, &custom);
+        select_columns(&mut row, &view_columns);
+        csv.push_str(&row.to_string());
         csv.push('\n');
     }
 
@@ -91,3 +118,21 @@ pub async fn export_handler(
     )
         .into_response()
 }
+
+fn is_blind_index(info: &EntityInfo, column: &str) -> bool {
+    column
+        .strip_suffix("_bidx")
+        .is_some_and(|field| info.sensitive_fields.contains(&field))
+}
+
+/// Copy each defined custom field of `row` to a `custom.<key>` entry.
+fn flatten_custom_fields(row: &mut serde_json::Value, custom: &[CustomField]) {
+    let Some(obj) = row.as_object_mut() else {
+        return;
+    };
+    let values = obj.get("custom_fields").cloned().unwrap_or_default();
+    for field in custom {
+        let value = values.get(&field.key).cloned().unwrap_or_default();
+        obj.insert(format!("{CUSTOM_PREFIX}{}", field.key), value);
+    }
+}
@@ -0,0 +1,155 @@
+use axum::extract::{Path, Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use uuid::Uuid;
+
+use crate::api::actor::Actor;
+use crate::api::generic::AdminState;
+use crate::api::types::success_response;
+use crate::error::AdminError;
+use crate::services::inspections::{
+    add_photo, apply_deductions, contract_inspections, delete_inspection, delete_photo,
+    inspection_diff, inspection_report_pdf, photo_file, save_inspection, ApplyDeductionsRequest,
+    DiffQuery, InspectionRequest, PhotoUpload,
+};
+
+pub async fn inspections_get_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+) -> Response {
+    match contract_inspections(&state.pool, id).await {
+        Ok((check_in, check_out)) => Json(serde_json::json!({
+            "check_in": check_in,
+            "check_out": check_out,
+        }))
+        .into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Inspections query failed");
+            AdminError::from(e).into_response()
+        }
+    }
+}
+
+/// Record or replace the check-in or check-out of a contract.
+pub async fn inspection_save_handler(
+    State(state): State<AdminState>,
+    Path((id, kind)): Path<(Uuid, String)>,
+    actor: Actor,
+    Json(body): Json<InspectionRequest>,
+) -> Response {
+    match save_inspection(&state.pool, &actor, id, &kind, &body).await {
+        Ok(inspection) => Json(inspection).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Inspection save failed");
+            e.into_response()
+        }
+    }
+}
+
+pub async fn inspection_delete_handler(
+    State(state): State<AdminState>,
+    Path((id, kind)): Path<(Uuid, String)>,
+    actor: Actor,
+) -> Response {
+    match delete_inspection(&state.pool, &actor, id, &kind).await {
+        Ok(()) => success_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Inspection deletion failed");
+            e.into_response()
+        }
+    }
+}
+
+/// Compare the check-out with the check-in and suggest deposit deductions.
+pub async fn inspection_diff_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<DiffQuery>,
+) -> Response {
+    match inspection_diff(&state.pool, id, &params).await {
+        Ok(diff) => Json(diff).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Inspection comparison failed");
+            e.into_response()
+        }
+    }
+}
+
+pub async fn inspection_deductions_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+    actor: Actor,
+    Json(body): Json<ApplyDeductionsRequest>,
+) -> Response {
+    match apply_deductions(&state.pool, &actor, id, &body).await {
+        Ok(result) => Json(result).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Deposit deductions failed");
+            e.into_response()
+        }
+    }
+}
+
+pub async fn inspection_pdf_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<DiffQuery>,
+) -> Response {
+    match inspection_report_pdf(&state.pool, id, &params).await {
+        Ok((filename, pdf)) => (
+            [
+                (header::CONTENT_TYPE, "application/pdf".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("inline; filename=\"{filename}\""),
+                ),
+            ],
+            pdf,
+        )
+            .into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Inspection report failed");
+            e.into_response()
+        }
+    }
+}
+
+pub async fn inspection_photo_upload_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+    actor: Actor,
+    Json(body): Json<PhotoUpload>,
+) -> Response {
+    match add_photo(&state.pool, &actor, id, &body).await {
+        Ok(photo) => (StatusCode::CREATED, Json(photo)).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Inspection photo upload failed");
+            e.into_response()
+        }
+    }
+}
+
+pub async fn inspection_photo_get_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+) -> Response {
+    match photo_file(&state.pool, id).await {
+        Ok((file_type, content)) => ([(header::CONTENT_TYPE, file_type)], content).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn inspection_photo_delete_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+    actor: Actor,
+) -> Response {
+    match delete_photo(&state.pool, &actor, id).await {
+        Ok(()) => success_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Inspection photo deletion failed");
+            e.into_response()
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use uuid::Uuid;
+
+use crate::api::actor::Actor;
+use crate::api::generic::AdminState;
+use crate::services::leads::{convert_lead, match_properties, ConvertLeadRequest, MatchQuery};
+
+/// `Available` properties ranked by how well they fit the lead's criteria.
+pub async fn lead_matches_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<MatchQuery>,
+) -> Response {
+    match match_properties(&state.pool, id, &params).await {
+        Ok(body) => Json(body).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Lead matching failed");
+            e.into_response()
+        }
+    }
+}
+
+/// Create a tenant and a draft contract from the lead.
+pub async fn lead_convert_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+    actor: Actor,
+    body: Option<Json<ConvertLeadRequest>>,
+) -> Response {
+    let request = body.map(|Json(b)| b).unwrap_or_default();
+    match convert_lead(&state.pool, &actor, id, &request).await {
+        Ok(body) => (StatusCode::CREATED, Json(body)).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Lead conversion failed");
+            e.into_response()
+        }
+    }
+}
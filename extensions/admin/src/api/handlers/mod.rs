@@ -2,11 +2,26 @@ pub mod audit;
 pub mod auth;
 pub mod contacts;
 pub mod contracts;
+pub mod custom_fields;
 pub mod dashboard;
 pub mod export;
+pub mod inspections;
 pub mod invoices;
+pub mod leads;
+pub mod openapi;
+pub mod owner_portal;
+pub mod payouts;
 pub mod pdf;
+pub mod portal;
 pub mod properties;
 pub mod reports;
+pub mod search;
 pub mod sepa_batches;
+pub mod signatures;
+pub mod statements;
+pub mod tax;
+pub mod templates;
 pub mod tenants;
+pub mod trash;
+pub mod views;
+pub mod webhooks;
@@ -0,0 +1,9 @@
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+
+use crate::services::openapi;
+
+/// OpenAPI 3 document of this API, for client generators and contract tests.
+pub async fn openapi_handler() -> Response {
+    Json(openapi::document()).into_response()
+}
@@ -0,0 +1,144 @@
+use axum::extract::{Path, Query, State};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use uuid::Uuid;
+
+use crate::api::generic::AdminState;
+use crate::api::handlers::contracts::contract_document_download_handler;
+use crate::api::handlers::statements::{
+    owner_statement_handler, owner_statement_pdf_handler, StatementQuery,
+};
+use crate::error::AdminError;
+use crate::services::owner_portal::{
+    owner_documents, owner_filter, owner_insurance, owner_occupancy, owner_open_issues,
+    owner_overview, owner_owns_document, owner_performance, PortalOwner,
+};
+use crate::services::reporting::ReportFilter;
+
+pub async fn owner_overview_handler(
+    State(state): State<AdminState>,
+    owner: PortalOwner,
+) -> Response {
+    match owner_overview(&state.pool, &owner).await {
+        Ok(body) => Json(body).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Owner portal overview failed");
+            e.into_response()
+        }
+    }
+}
+
+/// Per-property profitability and monthly income/expense of the portfolio:
+/// ?from=2026-01-01&to=2026-06-30&property=...
+pub async fn owner_performance_handler(
+    State(state): State<AdminState>,
+    owner: PortalOwner,
+    Query(filter): Query<ReportFilter>,
+) -> Response {
+    let filter = match owner_filter(&owner, filter) {
+        Ok(f) => f,
+        Err(e) => return e.into_response(),
+    };
+    match owner_performance(&state.pool, &filter).await {
+        Ok(body) => Json(body).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Owner portal performance failed");
+            AdminError::from(e).into_response()
+        }
+    }
+}
+
+pub async fn owner_occupancy_handler(
+    State(state): State<AdminState>,
+    owner: PortalOwner,
+    Query(filter): Query<ReportFilter>,
+) -> Response {
+    let filter = match owner_filter(&owner, filter) {
+        Ok(f) => f,
+        Err(e) => return e.into_response(),
+    };
+    match owner_occupancy(&state.pool, &filter).await {
+        Ok(body) => Json(body).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Owner portal occupancy failed");
+            AdminError::from(e).into_response()
+        }
+    }
+}
+
+/// The owner's statement at their own management fee; `fee_pct` is ignored.
+pub async fn owner_statement_portal_handler(
+    State(state): State<AdminState>,
+    owner: PortalOwner,
+    Query(params): Query<StatementQuery>,
+) -> Response {
+    let params = StatementQuery {
+        fee_pct: None,
+        ..params
+    };
+    owner_statement_handler(State(state), Path(owner.owner_id), Query(params)).await
+}
+
+pub async fn owner_statement_pdf_portal_handler(
+    State(state): State<AdminState>,
+    owner: PortalOwner,
+    Query(params): Query<StatementQuery>,
+) -> Response {
+    let params = StatementQuery {
+        fee_pct: None,
+        ..params
+    };
+    owner_statement_pdf_handler(State(state), Path(owner.owner_id), Query(params)).await
+}
+
+pub async fn owner_insurance_handler(
+    State(state): State<AdminState>,
+    owner: PortalOwner,
+) -> Response {
+    match owner_insurance(&state.pool, &owner).await {
+        Ok(policies) => Json(serde_json::json!({ "data": policies })).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Owner portal insurance failed");
+            AdminError::from(e).into_response()
+        }
+    }
+}
+
+pub async fn owner_issues_handler(State(state): State<AdminState>, owner: PortalOwner) -> Response {
+    match owner_open_issues(&state.pool, &owner).await {
+        Ok(issues) => Json(serde_json::json!({ "data": issues })).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Owner portal issues failed");
+            AdminError::from(e).into_response()
+        }
+    }
+}
+
+pub async fn owner_documents_handler(
+    State(state): State<AdminState>,
+    owner: PortalOwner,
+) -> Response {
+    match owner_documents(&state.pool, &owner).await {
+        Ok(documents) => Json(serde_json::json!({ "data": documents })).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Owner portal documents failed");
+            AdminError::from(e).into_response()
+        }
+    }
+}
+
+/// The document file, once the document is known to be on the owner's properties.
+pub async fn owner_document_download_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+    owner: PortalOwner,
+) -> Response {
+    match owner_owns_document(&state.pool, &owner, id).await {
+        Ok(true) => contract_document_download_handler(State(state), Path(id)).await,
+        Ok(false) => AdminError::NotFound("document".to_string()).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Owner portal document lookup failed");
+            AdminError::from(e).into_response()
+        }
+    }
+}
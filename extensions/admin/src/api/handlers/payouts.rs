@@ -0,0 +1,319 @@
+use axum::extract::{Path, State};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use chrono::NaiveDate;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::api::actor::Actor;
+use crate::api::generic::{fetch_row_for_update, write_audit, AdminState};
+use crate::api::sensitive::SensitiveAccess;
+use crate::api::types::{error_response, not_found, success_response};
+use crate::error::AdminError;
+use crate::services::payouts::{create_payout_batch, PayoutBatchRequest, BATCH_LABEL, BATCH_TABLE};
+use crate::services::pii;
+use crate::services::sepa::{build_pain_001, CreditTransfer, CreditTransferBatch};
+
+pub async fn payout_batch_create_handler(
+    State(state): State<AdminState>,
+    actor: Actor,
+    Json(body): Json<PayoutBatchRequest>,
+) -> Response {
+    match create_payout_batch(&state.pool, &actor, &body).await {
+        Ok(created) => (axum::http::StatusCode::CREATED, Json(created)).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Payout batch creation failed");
+            e.into_response()
+        }
+    }
+}
+
+/// A batch with its transfers. IBANs are masked unless the token has the
+/// `sensitive` scope.
+pub async fn payout_batch_detail_handler(
+    State(state): State<AdminState>,
+    access: SensitiveAccess,
+    Path(id): Path<Uuid>,
+) -> Response {
+    let pool = &*state.pool;
+
+    let (batch, transfers) = tokio::join!(
+        sqlx::query_scalar::<_, serde_json::Value>(
+            "SELECT row_to_json(t) FROM (SELECT * FROM admin_payout_batches WHERE id = $1) t"
+        )
+        .bind(id)
+        .fetch_optional(pool),
+        sqlx::query_scalar::<_, serde_json::Value>(
+            "SELECT COALESCE(json_agg(row_to_json(t)), '[]') FROM (\
+                SELECT tr.*, \
+                    (SELECT COALESCE(json_agg(json_build_object(\
+                        'reference', i.reference, 'amount', pi.amount) ORDER BY i.invoice_date), '[]') \
+                     FROM admin_payout_items pi JOIN admin_invoices i ON i.id = pi.invoice_id \
+                     WHERE pi.transfer_id = tr.id) AS invoices \
+                FROM admin_payout_transfers tr WHERE tr.batch_id = $1 ORDER BY tr.end_to_end_id\
+             ) t"
+        )
+        .bind(id)
+        .fetch_one(pool),
+    );
+
+    let mut batch = match batch {
+        Ok(Some(b)) => b,
+        Ok(None) => return not_found("payout batch"),
+        Err(e) => {
+            tracing::error!(error = %e, "Payout batch detail query failed");
+            return error_response(
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                &e.to_string(),
+            );
+        }
+    };
+
+    pii::reveal(&mut batch, &["debtor_iban"], access.granted());
+    let mut transfers = transfers.unwrap_or(serde_json::json!([]));
+    if let Some(transfers) = transfers.as_array_mut() {
+        for transfer in transfers {
+            pii::reveal(transfer, &["creditor_iban"], access.granted());
+        }
+    }
+
+    Json(serde_json::json!({
+        "batch": batch,
+        "transfers": transfers,
+    }))
+    .into_response()
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct ApproveRequest {
+    #[serde(default)]
+    pub approved_by: String,
+}
+
+pub async fn payout_batch_approve_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+    actor: Actor,
+    body: Option<Json<ApproveRequest>>,
+) -> Response {
+    let approved_by = body
+        .map(|Json(b)| b.approved_by)
+        .filter(|name| !name.trim().is_empty())
+        .or_else(|| actor.user_id.clone())
+        .unwrap_or_default();
+
+    transition(
+        &state,
+        &actor,
+        id,
+        "approve",
+        "UPDATE admin_payout_batches \
+         SET status = 'Approved', approved_by = $2, approved_at = NOW(), updated_at = NOW() \
+         WHERE id = $1 AND status = 'Draft' RETURNING row_to_json(admin_payout_batches.*)",
+        Some(&approved_by),
+        "Only draft batches can be approved",
+    )
+    .await
+}
+
+pub async fn payout_batch_cancel_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+    actor: Actor,
+) -> Response {
+    // Release the invoice amounts so they can be included in a new batch
+    let sql = "WITH b AS (\
+            UPDATE admin_payout_batches SET status = 'Cancelled', updated_at = NOW() \
+            WHERE id = $1 AND status IN ('Draft', 'Approved') RETURNING *\
+         ), i AS (\
+            DELETE FROM admin_payout_items \
+            WHERE transfer_id IN (\
+                SELECT tr.id FROM admin_payout_transfers tr JOIN b ON b.id = tr.batch_id\
+            )\
+         ) SELECT row_to_json(b) FROM b";
+
+    transition(
+        &state,
+        &actor,
+        id,
+        "cancel",
+        sql,
+        None,
+        "Exported or cancelled batches cannot be cancelled",
+    )
+    .await
+}
+
+/// Lock the batch, apply `sql` (which returns the updated row, or nothing
+/// when the batch's status does not allow the change) and audit both
+/// versions in one transaction.
+async fn transition(
+    state: &AdminState,
+    actor: &Actor,
+    id: Uuid,
+    action: &str,
+    sql: &str,
+    arg: Option<&str>,
+    conflict_message: &str,
+) -> Response {
+    let result = async {
+        let mut tx = state.pool.begin().await?;
+        let old = fetch_row_for_update(&mut tx, BATCH_TABLE, id)
+            .await?
+            .ok_or_else(|| AdminError::NotFound("payout batch".to_string()))?;
+
+        let mut query = sqlx::query_scalar::<_, serde_json::Value>(sql).bind(id);
+        if let Some(arg) = arg {
+            query = query.bind(arg);
+        }
+        let new = query
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| AdminError::Conflict(conflict_message.to_string()))?;
+
+        write_audit(
+            &mut *tx,
+            actor,
+            BATCH_LABEL,
+            &id.to_string(),
+            action,
+            Some(&old),
+            Some(&new),
+        )
+        .await?;
+        tx.commit().await?;
+        Ok::<_, AdminError>(())
+    }
+    .await;
+
+    match result {
+        Ok(()) => success_response(),
+        Err(e) => {
+            tracing::error!(error = %e, action, "Payout batch transition failed");
+            e.into_response()
+        }
+    }
+}
+
+/// End-to-end id, owner name, sealed IBAN, amount and remittance info.
+type TransferRow = (String, String, String, f64, String);
+
+/// Decrypt the batch's debtor IBAN and its transfers' creditor IBANs.
+fn open_ibans(
+    debtor_iban: &str,
+    transfers: Vec<TransferRow>,
+) -> Result<(String, Vec<CreditTransfer>), AdminError> {
+    let debtor_iban = pii::open("debtor_iban", debtor_iban)?;
+    let transfers = transfers
+        .into_iter()
+        .map(|t| {
+            Ok(CreditTransfer {
+                end_to_end_id: t.0,
+                creditor_name: t.1,
+                creditor_iban: pii::open("creditor_iban", &t.2)?,
+                amount: t.3,
+                remittance_info: t.4,
+            })
+        })
+        .collect::<Result<_, AdminError>>()?;
+    Ok((debtor_iban, transfers))
+}
+
+/// Batch ref, status, execution date, debtor name, sealed debtor IBAN,
+/// debtor BIC and currency.
+type BatchRow = (String, String, NaiveDate, String, String, String, String);
+
+/// Export an approved batch as pain.001 XML. The first export moves the
+/// batch to `Exported`, audited in the same transaction.
+pub async fn payout_batch_pain001_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+    actor: Actor,
+) -> Response {
+    let result = async {
+        let mut tx = state.pool.begin().await?;
+        let old = fetch_row_for_update(&mut tx, BATCH_TABLE, id)
+            .await?
+            .ok_or_else(|| AdminError::NotFound("payout batch".to_string()))?;
+        let batch = sqlx::query_as::<_, BatchRow>(
+            "SELECT batch_ref, status, execution_date, debtor_name, debtor_iban, debtor_bic, \
+             currency FROM admin_payout_batches WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if batch.1 != "Approved" && batch.1 != "Exported" {
+            return Err(AdminError::Conflict(
+                "Batch must be approved before it can be exported".to_string(),
+            ));
+        }
+
+        let transfers = sqlx::query_as::<_, TransferRow>(
+            "SELECT end_to_end_id, owner_name, creditor_iban, amount::float8, remittance_info \
+             FROM admin_payout_transfers WHERE batch_id = $1 ORDER BY end_to_end_id",
+        )
+        .bind(id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        // The bank needs the IBANs in clear
+        let (debtor_iban, transfers) = open_ibans(&batch.4, transfers)?;
+
+        let xml = build_pain_001(&CreditTransferBatch {
+            message_id: batch.0.clone(),
+            created_at: chrono::Utc::now().naive_utc(),
+            execution_date: batch.2,
+            debtor_name: batch.3,
+            debtor_iban,
+            debtor_bic: batch.5,
+            currency: batch.6,
+            transfers,
+        });
+
+        if batch.1 == "Approved" {
+            let new = sqlx::query_scalar::<_, serde_json::Value>(
+                "UPDATE admin_payout_batches SET status = 'Exported', exported_at = NOW(), \
+                 updated_at = NOW() WHERE id = $1 RETURNING row_to_json(admin_payout_batches.*)",
+            )
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await?;
+            write_audit(
+                &mut *tx,
+                &actor,
+                BATCH_LABEL,
+                &id.to_string(),
+                "export",
+                Some(&old),
+                Some(&new),
+            )
+            .await?;
+        }
+        tx.commit().await?;
+        Ok::<_, AdminError>((batch.0, xml))
+    }
+    .await;
+
+    match result {
+        Ok((batch_ref, xml)) => (
+            [
+                (
+                    header::CONTENT_TYPE,
+                    "application/xml; charset=utf-8".to_string(),
+                ),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"{batch_ref}.xml\""),
+                ),
+            ],
+            xml,
+        )
+            .into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Payout batch export failed");
+            e.into_response()
+        }
+    }
+}
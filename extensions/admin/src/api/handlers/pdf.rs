@@ -6,6 +6,7 @@ use uuid::Uuid;
 use crate::api::generic::AdminState;
 use crate::api::types::error_response;
 use crate::services::pdf::PdfService;
+use crate::services::pii;
 
 pub async fn invoice_pdf_handler(
     State(state): State<AdminState>,
@@ -62,7 +63,16 @@ pub async fn invoice_pdf_handler(
     .fetch_optional(pool)
     .await
     .ok()
-    .flatten();
+    .flatten()
+    .map(|(tax_id, address, email, phone, bank_account)| {
+        (
+            clear("tax_id", &tax_id),
+            address,
+            email,
+            clear("phone", &phone),
+            clear("bank_account", &bank_account),
+        )
+    });
 
     // Look up payer (tenant) details
     let payer_details = sqlx::query_as::<_, (String, String, String, String)>(
@@ -73,7 +83,10 @@ pub async fn invoice_pdf_handler(
     .fetch_optional(pool)
     .await
     .ok()
-    .flatten();
+    .flatten()
+    .map(|(tax_id, address, email, phone)| {
+        (clear("tax_id", &tax_id), address, email, clear("phone", &phone))
+    });
 
     // Look up property address
     let property_address: Option<String> = sqlx::query_scalar(
@@ -163,3 +176,12 @@ pub async fn invoice_pdf_handler(
         }
     }
 }
+
+/// A decrypted value for the document. One that cannot be decrypted is left
+/// off rather than failing the whole invoice.
+fn clear(column: &str, stored: &str) -> String {
+    pii::open(column, stored).unwrap_or_else(|e| {
+        tracing::warn!(error = %e, column, "Sensitive value left off invoice PDF");
+        String::new()
+    })
+}
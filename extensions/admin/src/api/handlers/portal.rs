@@ -0,0 +1,241 @@
+use axum::extract::{Path, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use uuid::Uuid;
+
+use crate::api::actor::Actor;
+use crate::api::generic::AdminState;
+use crate::api::handlers::pdf::invoice_pdf_handler;
+use crate::api::types::success_response;
+use crate::error::AdminError;
+use crate::services::inspections::PhotoUpload;
+use crate::services::portal::{
+    add_tenant_issue_photo, create_tenant_issue, grant_portal_access, issue_photo_file,
+    issue_photos, portal_accounts, revoke_portal_access, tenant_invoices, tenant_issues,
+    tenant_overview, tenant_owns_invoice, tenant_payments, PortalAccessRequest, PortalRole,
+    PortalTenant, TenantIssueRequest,
+};
+
+// ── Tenant portal ───────────────────────────────────────────────────────
+
+pub async fn tenant_overview_handler(
+    State(state): State<AdminState>,
+    tenant: PortalTenant,
+) -> Response {
+    match tenant_overview(&state.pool, &tenant).await {
+        Ok(body) => Json(body).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Tenant portal overview failed");
+            e.into_response()
+        }
+    }
+}
+
+pub async fn tenant_invoices_handler(
+    State(state): State<AdminState>,
+    tenant: PortalTenant,
+) -> Response {
+    match tenant_invoices(&state.pool, &tenant).await {
+        Ok(invoices) => Json(serde_json::json!({ "data": invoices })).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Tenant portal invoices failed");
+            AdminError::from(e).into_response()
+        }
+    }
+}
+
+/// The invoice PDF, once the invoice is known to be the tenant's.
+pub async fn tenant_invoice_pdf_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+    tenant: PortalTenant,
+) -> Response {
+    match tenant_owns_invoice(&state.pool, &tenant, id).await {
+        Ok(true) => invoice_pdf_handler(State(state), Path(id)).await,
+        Ok(false) => AdminError::NotFound("invoice".to_string()).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Tenant portal invoice lookup failed");
+            AdminError::from(e).into_response()
+        }
+    }
+}
+
+pub async fn tenant_payments_handler(
+    State(state): State<AdminState>,
+    tenant: PortalTenant,
+) -> Response {
+    match tenant_payments(&state.pool, &tenant).await {
+        Ok(body) => Json(body).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Tenant portal payments failed");
+            e.into_response()
+        }
+    }
+}
+
+pub async fn tenant_issues_handler(
+    State(state): State<AdminState>,
+    tenant: PortalTenant,
+) -> Response {
+    match tenant_issues(&state.pool, &tenant).await {
+        Ok(issues) => Json(serde_json::json!({ "data": issues })).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Tenant portal issues failed");
+            AdminError::from(e).into_response()
+        }
+    }
+}
+
+pub async fn tenant_issue_create_handler(
+    State(state): State<AdminState>,
+    actor: Actor,
+    tenant: PortalTenant,
+    Json(body): Json<TenantIssueRequest>,
+) -> Response {
+    match create_tenant_issue(&state.pool, &actor, &tenant, &body).await {
+        Ok(issue) => (StatusCode::CREATED, Json(issue)).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Tenant portal issue creation failed");
+            e.into_response()
+        }
+    }
+}
+
+pub async fn tenant_issue_photo_upload_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+    tenant: PortalTenant,
+    Json(body): Json<PhotoUpload>,
+) -> Response {
+    match add_tenant_issue_photo(&state.pool, &tenant, id, &body).await {
+        Ok(photo) => (StatusCode::CREATED, Json(photo)).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Tenant portal photo upload failed");
+            e.into_response()
+        }
+    }
+}
+
+pub async fn tenant_issue_photo_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+    tenant: PortalTenant,
+) -> Response {
+    photo_response(issue_photo_file(&state.pool, id, Some(&tenant)).await)
+}
+
+// ── Admin ───────────────────────────────────────────────────────────────
+
+pub async fn tenant_portal_access_list_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+) -> Response {
+    access_list_response(portal_accounts(&state.pool, PortalRole::Tenant, id).await)
+}
+
+pub async fn tenant_portal_access_grant_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+    actor: Actor,
+    Json(body): Json<PortalAccessRequest>,
+) -> Response {
+    access_grant_response(
+        grant_portal_access(&state.pool, &actor, PortalRole::Tenant, id, &body).await,
+    )
+}
+
+pub async fn tenant_portal_access_revoke_handler(
+    State(state): State<AdminState>,
+    Path((id, account_id)): Path<(Uuid, Uuid)>,
+    actor: Actor,
+) -> Response {
+    access_revoke_response(
+        revoke_portal_access(&state.pool, &actor, PortalRole::Tenant, id, account_id).await,
+    )
+}
+
+pub async fn owner_portal_access_list_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+) -> Response {
+    access_list_response(portal_accounts(&state.pool, PortalRole::Owner, id).await)
+}
+
+pub async fn owner_portal_access_grant_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+    actor: Actor,
+    Json(body): Json<PortalAccessRequest>,
+) -> Response {
+    access_grant_response(
+        grant_portal_access(&state.pool, &actor, PortalRole::Owner, id, &body).await,
+    )
+}
+
+pub async fn owner_portal_access_revoke_handler(
+    State(state): State<AdminState>,
+    Path((id, account_id)): Path<(Uuid, Uuid)>,
+    actor: Actor,
+) -> Response {
+    access_revoke_response(
+        revoke_portal_access(&state.pool, &actor, PortalRole::Owner, id, account_id).await,
+    )
+}
+
+fn access_list_response(accounts: Result<Vec<serde_json::Value>, sqlx::Error>) -> Response {
+    match accounts {
+        Ok(accounts) => Json(serde_json::json!({ "data": accounts })).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Portal accounts query failed");
+            AdminError::from(e).into_response()
+        }
+    }
+}
+
+fn access_grant_response(account: Result<serde_json::Value, AdminError>) -> Response {
+    match account {
+        Ok(account) => Json(account).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Portal access grant failed");
+            e.into_response()
+        }
+    }
+}
+
+fn access_revoke_response(result: Result<(), AdminError>) -> Response {
+    match result {
+        Ok(()) => success_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Portal access revocation failed");
+            e.into_response()
+        }
+    }
+}
+
+pub async fn issue_photos_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+) -> Response {
+    match issue_photos(&state.pool, id).await {
+        Ok(photos) => Json(serde_json::json!({ "data": photos })).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Issue photos query failed");
+            AdminError::from(e).into_response()
+        }
+    }
+}
+
+pub async fn issue_photo_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+) -> Response {
+    photo_response(issue_photo_file(&state.pool, id, None).await)
+}
+
+fn photo_response(file: Result<(String, Vec<u8>), AdminError>) -> Response {
+    match file {
+        Ok((file_type, content)) => ([(header::CONTENT_TYPE, file_type)], content).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
@@ -1,22 +1,45 @@
-use axum::extract::{Path, State};
+use axum::extract::{Path, Query, State};
 use axum::response::{IntoResponse, Response};
 use axum::Json;
 use uuid::Uuid;
 
 use crate::api::generic::AdminState;
-use crate::api::types::{error_response, not_found};
+use crate::api::types::{error_response, not_found, RecordQuery};
+use crate::services::buildings::property_rollup;
 
+/// A property with the insurance, issues and community fees of its building
+/// or, for a building, of its units.
+pub async fn property_rollup_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+) -> Response {
+    match property_rollup(&state.pool, id).await {
+        Ok(body) => Json(body).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Property rollup failed");
+            e.into_response()
+        }
+    }
+}
+
+/// A property with its income totals and latest invoices. Deleted
+/// properties are not found unless ?include_deleted=true, as with the generic
+/// record lookup.
 pub async fn property_detail_handler(
     State(state): State<AdminState>,
     Path(id): Path<Uuid>,
+    Query(params): Query<RecordQuery>,
 ) -> Response {
     let pool = &*state.pool;
 
     let (property, financial, invoices) = tokio::join!(
         sqlx::query_scalar::<_, serde_json::Value>(
-            "SELECT row_to_json(t) FROM (SELECT * FROM admin_properties WHERE id = $1) t"
+            "SELECT row_to_json(t) FROM (\
+                SELECT * FROM admin_properties WHERE id = $1 AND ($2 OR deleted_at IS NULL)\
+             ) t"
         )
         .bind(id)
+        .bind(params.include_deleted)
         .fetch_optional(pool),
         sqlx::query_as::<_, (f64, f64, f64)>(
             "SELECT \
@@ -24,7 +47,8 @@ pub async fn property_detail_handler(
                 COALESCE(SUM(paid), 0)::float8, \
                 COALESCE(SUM(amount - paid), 0)::float8 \
              FROM admin_invoices \
-             WHERE property_name = (SELECT property_name FROM admin_properties WHERE id = $1) AND type = 'income'"
+             WHERE property_name = (SELECT property_name FROM admin_properties WHERE id = $1) \
+                AND type = 'income' AND deleted_at IS NULL"
         )
         .bind(id)
         .fetch_one(pool),
@@ -32,6 +56,7 @@ pub async fn property_detail_handler(
             "SELECT COALESCE(json_agg(row_to_json(t)), '[]') FROM (\
                 SELECT * FROM admin_invoices \
                 WHERE property_name = (SELECT property_name FROM admin_properties WHERE id = $1) \
+                AND deleted_at IS NULL \
                 ORDER BY invoice_date DESC LIMIT 10\
              ) t"
         )
@@ -69,7 +94,7 @@ pub async fn property_detail_handler(
 pub async fn properties_names_handler(State(state): State<AdminState>) -> Response {
     match sqlx::query_scalar::<_, serde_json::Value>(
         "SELECT COALESCE(json_agg(t.property_name), '[]') FROM (\
-            SELECT DISTINCT property_name FROM admin_properties ORDER BY property_name\
+            SELECT DISTINCT property_name FROM admin_properties WHERE deleted_at IS NULL ORDER BY property_name\
          ) t",
     )
     .fetch_one(&*state.pool)
@@ -1,29 +1,23 @@
-use axum::extract::State;
+use axum::extract::{Query, State};
 use axum::response::{IntoResponse, Response};
 use axum::Json;
 
 use crate::api::generic::AdminState;
 use crate::api::types::error_response;
+use crate::services::reporting::{
+    arrears, occupancy, profitability, ReportFilter, LET_CONTRACT, PROPERTY_FILTER,
+};
 
-pub async fn arrears_handler(State(state): State<AdminState>) -> Response {
-    match sqlx::query_scalar::<_, serde_json::Value>(
-        "SELECT COALESCE(json_agg(row_to_json(t)), '[]') FROM (\
-            SELECT payer, property_name, \
-                COUNT(*)::int as outstanding_invoices, \
-                SUM(amount)::float8 as total_outstanding, \
-                SUM(paid)::float8 as total_paid, \
-                SUM(amount - paid)::float8 as debt, \
-                MIN(invoice_date) as oldest_date \
-            FROM admin_invoices \
-            WHERE status IN ('Unpaid', 'Partial') AND type = 'income' \
-            GROUP BY payer, property_name \
-            ORDER BY debt DESC\
-         ) t",
-    )
-    .fetch_one(&*state.pool)
-    .await
-    {
-        Ok(data) => Json(serde_json::json!({ "data": data })).into_response(),
+pub async fn arrears_handler(
+    State(state): State<AdminState>,
+    Query(filter): Query<ReportFilter>,
+) -> Response {
+    let filter = match filter.validate() {
+        Ok(f) => f,
+        Err(e) => return e.into_response(),
+    };
+    match arrears(&state.pool, &filter).await {
+        Ok(data) => Json(serde_json::json!({ "data": data, "filters": filter })).into_response(),
         Err(e) => {
             tracing::error!(error = %e, "Arrears report failed");
             error_response(
@@ -34,26 +28,174 @@ pub async fn arrears_handler(State(state): State<AdminState>) -> Response {
     }
 }
 
-pub async fn profitability_handler(State(state): State<AdminState>) -> Response {
-    match sqlx::query_scalar::<_, serde_json::Value>(
-        "SELECT COALESCE(json_agg(row_to_json(t)), '[]') FROM (\
-            SELECT property_name, \
-                COALESCE(SUM(CASE WHEN type = 'income' THEN amount ELSE 0 END), 0)::float8 as income, \
-                COALESCE(SUM(CASE WHEN type = 'expense' THEN amount ELSE 0 END), 0)::float8 as expenses, \
-                COALESCE(SUM(CASE WHEN type = 'income' THEN amount ELSE -amount END), 0)::float8 as margin, \
-                COALESCE(SUM(CASE WHEN type = 'income' THEN paid ELSE 0 END), 0)::float8 as collected \
-            FROM admin_invoices \
-            WHERE property_name != '' \
+pub async fn profitability_handler(
+    State(state): State<AdminState>,
+    Query(filter): Query<ReportFilter>,
+) -> Response {
+    let filter = match filter.validate() {
+        Ok(f) => f,
+        Err(e) => return e.into_response(),
+    };
+    match profitability(&state.pool, &filter).await {
+        Ok(data) => Json(serde_json::json!({ "data": data, "filters": filter })).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Profitability report failed");
+            error_response(
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                &e.to_string(),
+            )
+        }
+    }
+}
+
+/// Rent roll as of `to` (default today): every let contract running on that
+/// date with its rent, term and next annual increase (start date anniversary).
+pub async fn rent_roll_handler(
+    State(state): State<AdminState>,
+    Query(filter): Query<ReportFilter>,
+) -> Response {
+    let filter = match filter.validate() {
+        Ok(f) => f,
+        Err(e) => return e.into_response(),
+    };
+    let sql = format!(
+        "WITH roll AS (\
+            SELECT c.id AS contract_id, c.contract_ref, c.property_name, c.address, \
+                c.tenant_name, c.status, c.rent::float8 AS rent, c.start_date, c.end_date, \
+                (SELECT MIN(d)::date FROM generate_series(\
+                    c.start_date + INTERVAL '1 year', \
+                    COALESCE(c.end_date, COALESCE($2, CURRENT_DATE) + INTERVAL '1 year'), \
+                    INTERVAL '1 year') d \
+                 WHERE d > COALESCE($2, CURRENT_DATE)) AS next_increase, \
+                (c.end_date - COALESCE($2, CURRENT_DATE)) AS days_remaining \
+            FROM admin_contracts c \
+            WHERE {LET_CONTRACT} \
+                AND c.start_date <= COALESCE($2, CURRENT_DATE) \
+                AND (c.end_date IS NULL OR c.end_date >= COALESCE($2, CURRENT_DATE)) \
+                AND c.property_name IN (SELECT property_name FROM admin_properties WHERE {PROPERTY_FILTER})\
+         ) \
+         SELECT json_build_object(\
+            'as_of', COALESCE($2, CURRENT_DATE), \
+            'data', COALESCE((SELECT json_agg(row_to_json(r) ORDER BY r.property_name) FROM roll r), '[]'), \
+            'summary', (SELECT json_build_object(\
+                'contracts', COUNT(*), \
+                'monthly_rent', COALESCE(SUM(rent), 0), \
+                'annual_rent', COALESCE(SUM(rent), 0) * 12) FROM roll)\
+         )"
+    );
+
+    match filter
+        .scalar::<serde_json::Value>(&sql)
+        .fetch_one(&*state.pool)
+        .await
+    {
+        Ok(data) => Json(data).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Rent roll report failed");
+            error_response(
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                &e.to_string(),
+            )
+        }
+    }
+}
+
+/// Monthly occupancy between `from` and `to` (default: the last 12 months),
+/// from contract start and end dates. A property counts as occupied on a day
+/// when any let contract covers it.
+pub async fn occupancy_handler(
+    State(state): State<AdminState>,
+    Query(filter): Query<ReportFilter>,
+) -> Response {
+    let filter = match filter.validate().and_then(|f| f.window(12)) {
+        Ok(f) => f,
+        Err(e) => return e.into_response(),
+    };
+    match occupancy(&state.pool, &filter).await {
+        Ok(data) => Json(data).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Occupancy report failed");
+            error_response(
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                &e.to_string(),
+            )
+        }
+    }
+}
+
+/// Vacancies within `from`..`to` (default: the last 12 months): the time
+/// before a property's first let (from when it was added), gaps between
+/// consecutive contracts and the gap after the last one. A property never
+/// let is vacant for the whole range. Every gap is clipped to the range.
+/// Lost rent is the vacant days priced at the property's rent (monthly × 12 / 365).
+pub async fn vacancy_handler(
+    State(state): State<AdminState>,
+    Query(filter): Query<ReportFilter>,
+) -> Response {
+    let filter = match filter.validate().and_then(|f| f.window(12)) {
+        Ok(f) => f,
+        Err(e) => return e.into_response(),
+    };
+    let sql = format!(
+        "WITH props AS (\
+            SELECT property_name, rent, created_at::date AS since \
+            FROM admin_properties WHERE {PROPERTY_FILTER}\
+         ), leases AS (\
+            SELECT c.property_name, c.start_date, c.end_date, \
+                MAX(c.end_date) OVER (PARTITION BY c.property_name ORDER BY c.start_date \
+                    ROWS BETWEEN UNBOUNDED PRECEDING AND 1 PRECEDING) AS prev_end, \
+                bool_or(c.end_date IS NULL) OVER (PARTITION BY c.property_name ORDER BY c.start_date \
+                    ROWS BETWEEN UNBOUNDED PRECEDING AND 1 PRECEDING) AS prev_open, \
+                LEAD(c.start_date) OVER (PARTITION BY c.property_name ORDER BY c.start_date) AS next_start \
+            FROM admin_contracts c \
+            WHERE {LET_CONTRACT} AND c.property_name IN (SELECT property_name FROM props)\
+         ), gaps AS (\
+            SELECT p.property_name, p.since AS gap_start, \
+                COALESCE(MIN(l.start_date) - 1, $2) AS gap_end, \
+                MIN(l.start_date) IS NULL AS ongoing \
+            FROM props p LEFT JOIN leases l ON l.property_name = p.property_name \
+            GROUP BY p.property_name, p.since \
+            UNION ALL \
+            SELECT property_name, prev_end + 1, start_date - 1, false \
+            FROM leases WHERE prev_end IS NOT NULL AND NOT COALESCE(prev_open, false) \
+                AND start_date > prev_end + 1 \
+            UNION ALL \
+            SELECT property_name, MAX(end_date) + 1, $2, MAX(end_date) < CURRENT_DATE FROM leases \
             GROUP BY property_name \
-            ORDER BY margin DESC\
-         ) t",
-    )
-    .fetch_one(&*state.pool)
-    .await
+            HAVING NOT bool_or(end_date IS NULL)\
+         ), clipped AS (\
+            SELECT property_name, ongoing, \
+                LEAST(gap_end, $2) - GREATEST(gap_start, $1) + 1 AS days \
+            FROM gaps WHERE gap_start <= gap_end AND gap_start <= $2 AND gap_end >= $1\
+         ), by_property AS (\
+            SELECT p.property_name, p.rent::float8 AS rent, \
+                COUNT(g.days)::int AS vacancies, \
+                COALESCE(SUM(g.days), 0)::int AS vacancy_days, \
+                COALESCE(ROUND(AVG(g.days), 1), 0)::float8 AS avg_vacancy_days, \
+                COALESCE(bool_or(g.ongoing), false) AS currently_vacant, \
+                ROUND(COALESCE(SUM(g.days), 0) * p.rent * 12 / 365, 2)::float8 AS lost_rent \
+            FROM props p LEFT JOIN clipped g ON g.property_name = p.property_name \
+            GROUP BY p.property_name, p.rent\
+         ) \
+         SELECT json_build_object(\
+            'from', $1::date, 'to', $2::date, \
+            'data', COALESCE(json_agg(row_to_json(b) ORDER BY b.lost_rent DESC, b.property_name), '[]'), \
+            'summary', json_build_object(\
+                'vacancies', COALESCE(SUM(vacancies), 0), \
+                'vacancy_days', COALESCE(SUM(vacancy_days), 0), \
+                'avg_vacancy_days', COALESCE(ROUND(SUM(vacancy_days)::numeric / NULLIF(SUM(vacancies), 0), 1), 0), \
+                'lost_rent', COALESCE(ROUND(SUM(lost_rent)::numeric, 2), 0))\
+         ) FROM by_property b"
+    );
+
+    match filter
+        .scalar::<serde_json::Value>(&sql)
+        .fetch_one(&*state.pool)
+        .await
     {
-        Ok(data) => Json(serde_json::json!({ "data": data })).into_response(),
+        Ok(data) => Json(data).into_response(),
         Err(e) => {
-            tracing::error!(error = %e, "Profitability report failed");
+            tracing::error!(error = %e, "Vacancy report failed");
             error_response(
                 axum::http::StatusCode::INTERNAL_SERVER_ERROR,
                 &e.to_string(),
@@ -61,3 +203,59 @@ pub async fn profitability_handler(State(state): State<AdminState>) -> Response
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::http::StatusCode;
+    use chrono::NaiveDate;
+    use sqlx::postgres::PgPoolOptions;
+
+    use super::*;
+
+    /// State whose pool never connects: filter errors must be returned before
+    /// any query runs.
+    fn offline_state() -> State<AdminState> {
+        let pool = PgPoolOptions::new()
+            .connect_lazy("postgres://admin@127.0.0.1:1/admin")
+            .expect("lazy pool");
+        State(AdminState::new(Arc::new(pool)))
+    }
+
+    fn inverted() -> Query<ReportFilter> {
+        Query(ReportFilter {
+            from: NaiveDate::from_ymd_opt(2026, 3, 1),
+            to: NaiveDate::from_ymd_opt(2026, 1, 31),
+            ..ReportFilter::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn rent_roll_rejects_an_inverted_range() {
+        let response = rent_roll_handler(offline_state(), inverted()).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn occupancy_rejects_an_inverted_range() {
+        let response = occupancy_handler(offline_state(), inverted()).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn vacancy_rejects_an_inverted_range() {
+        let response = vacancy_handler(offline_state(), inverted()).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn occupancy_rejects_a_window_it_cannot_compute() {
+        let filter = Query(ReportFilter {
+            to: Some(NaiveDate::MIN),
+            ..ReportFilter::default()
+        });
+        let response = occupancy_handler(offline_state(), filter).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
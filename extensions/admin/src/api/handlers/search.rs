@@ -0,0 +1,20 @@
+use axum::extract::{Query, State};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+
+use crate::api::generic::AdminState;
+use crate::services::search::{search, SearchQuery};
+
+/// Global search: GET /search?q=garcia
+pub async fn search_handler(
+    State(state): State<AdminState>,
+    Query(params): Query<SearchQuery>,
+) -> Response {
+    match search(&state.pool, &params).await {
+        Ok(body) => Json(body).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Search failed");
+            e.into_response()
+        }
+    }
+}
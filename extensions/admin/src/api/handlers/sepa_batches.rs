@@ -8,7 +8,8 @@ use crate::api::types::error_response;
 pub async fn sepa_batches_creditors_handler(State(state): State<AdminState>) -> Response {
     match sqlx::query_scalar::<_, serde_json::Value>(
         "SELECT COALESCE(json_agg(t.creditor), '[]') FROM (\
-            SELECT DISTINCT creditor FROM admin_sepa_batches WHERE creditor != '' ORDER BY creditor\
+            SELECT DISTINCT creditor FROM admin_sepa_batches \
+            WHERE creditor != '' AND deleted_at IS NULL ORDER BY creditor\
          ) t",
     )
     .fetch_one(&*state.pool)
@@ -0,0 +1,144 @@
+use axum::extract::{Path, Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::api::actor::Actor;
+use crate::api::generic::AdminState;
+use crate::error::AdminError;
+use crate::services::signatures::{
+    cancel_signature_request, complete_signature_request, create_signature_request,
+    get_signature_request, list_signature_requests, sign, signing_document, signing_view, SignBody,
+    SignatureRequestBody,
+};
+
+/// Query parameters for the request listing: ?contract_id=...
+#[derive(Debug, Deserialize, Default)]
+pub struct SignatureRequestsQuery {
+    pub contract_id: Option<Uuid>,
+}
+
+/// Send a contract document to its signers; the response holds their links.
+pub async fn signature_request_create_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+    actor: Actor,
+    body: Option<Json<SignatureRequestBody>>,
+) -> Response {
+    let request = body.map(|Json(b)| b).unwrap_or_default();
+    match create_signature_request(&state.pool, &actor, id, &request).await {
+        Ok(body) => (StatusCode::CREATED, Json(body)).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Signature request creation failed");
+            e.into_response()
+        }
+    }
+}
+
+pub async fn signature_requests_list_handler(
+    State(state): State<AdminState>,
+    Query(params): Query<SignatureRequestsQuery>,
+) -> Response {
+    match list_signature_requests(&state.pool, params.contract_id).await {
+        Ok(requests) => Json(serde_json::json!({ "data": requests })).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Signature requests listing failed");
+            AdminError::from(e).into_response()
+        }
+    }
+}
+
+pub async fn signature_request_get_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+) -> Response {
+    match get_signature_request(&state.pool, id).await {
+        Ok(request) => Json(request).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn signature_request_cancel_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+    actor: Actor,
+) -> Response {
+    match cancel_signature_request(&state.pool, &actor, id).await {
+        Ok(request) => Json(request).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Signature request cancellation failed");
+            e.into_response()
+        }
+    }
+}
+
+/// Build the signed copy of a request whose signers have all signed, when
+/// building it on the last signature failed.
+pub async fn signature_request_complete_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+    actor: Actor,
+) -> Response {
+    let result = async {
+        complete_signature_request(&state.pool, &actor, id).await?;
+        get_signature_request(&state.pool, id).await
+    }
+    .await;
+    match result {
+        Ok(request) => Json(request).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Signature request completion failed");
+            e.into_response()
+        }
+    }
+}
+
+// ── Signing page (by one-time token) ────────────────────────────────────
+
+pub async fn signing_view_handler(
+    State(state): State<AdminState>,
+    Path(token): Path<String>,
+) -> Response {
+    match signing_view(&state.pool, &token).await {
+        Ok(view) => Json(view).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn signing_document_handler(
+    State(state): State<AdminState>,
+    Path(token): Path<String>,
+) -> Response {
+    match signing_document(&state.pool, &token).await {
+        Ok((filename, content)) => (
+            [
+                (header::CONTENT_TYPE, "application/pdf".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("inline; filename=\"{filename}\""),
+                ),
+            ],
+            content,
+        )
+            .into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Record the signer's signature with their IP address and browser.
+pub async fn sign_handler(
+    State(state): State<AdminState>,
+    Path(token): Path<String>,
+    actor: Actor,
+    Json(body): Json<SignBody>,
+) -> Response {
+    match sign(&state.pool, &actor, &token, &body).await {
+        Ok(result) => Json(result).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Signing failed");
+            e.into_response()
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use axum::extract::{Path, Query, State};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use chrono::NaiveDate;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::api::generic::AdminState;
+use crate::api::sensitive::SensitiveAccess;
+use crate::api::types::{error_response, not_found};
+use crate::error::AdminError;
+use crate::services::pdf::PdfService;
+use crate::services::statements::{
+    build_all_owner_statements, build_owner_statement, StatementPeriod,
+};
+
+/// Query parameters for owner statements: ?from=2026-01-01&to=2026-01-31&fee_pct=8
+#[derive(Debug, Deserialize, Default)]
+pub struct StatementQuery {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    /// Overrides each owner's own management fee percentage
+    pub fee_pct: Option<f64>,
+}
+
+impl StatementQuery {
+    fn period(&self) -> Result<StatementPeriod, AdminError> {
+        if self
+            .fee_pct
+            .is_some_and(|pct| !(0.0..=100.0).contains(&pct))
+        {
+            return Err(AdminError::BadRequest(
+                "fee_pct must be between 0 and 100".to_string(),
+            ));
+        }
+        StatementPeriod::resolve(self.from, self.to)
+    }
+}
+
+/// Summary of every owner's statement for the period (no invoice lines).
+pub async fn owner_statements_handler(
+    State(state): State<AdminState>,
+    Query(params): Query<StatementQuery>,
+) -> Response {
+    let period = match params.period() {
+        Ok(p) => p,
+        Err(e) => return e.into_response(),
+    };
+
+    match build_all_owner_statements(&state.pool, period, params.fee_pct).await {
+        Ok(statements) => {
+            let data: Vec<serde_json::Value> = statements
+                .iter()
+                .map(|s| {
+                    serde_json::json!({
+                        "owner_id": s.owner_id,
+                        "owner_name": s.owner_name,
+                        "currency": s.currency,
+                        "management_fee_pct": s.management_fee_pct,
+                        "rent_invoiced": s.rent_invoiced,
+                        "rent_collected": s.rent_collected,
+                        "expenses_total": s.expenses_total,
+                        "management_fee": s.management_fee,
+                        "net_transfer": s.net_transfer,
+                    })
+                })
+                .collect();
+            Json(serde_json::json!({ "period": period, "data": data })).into_response()
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "Owner statements report failed");
+            e.into_response()
+        }
+    }
+}
+
+/// One owner's statement with its invoice lines. The owner's tax id and
+/// IBAN are masked unless the token has the `sensitive` scope.
+pub async fn owner_statement_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+    access: SensitiveAccess,
+    Query(params): Query<StatementQuery>,
+) -> Response {
+    let period = match params.period() {
+        Ok(p) => p,
+        Err(e) => return e.into_response(),
+    };
+
+    match build_owner_statement(&state.pool, id, period, params.fee_pct).await {
+        Ok(Some(statement)) if access.granted() => Json(statement).into_response(),
+        Ok(Some(statement)) => Json(statement.masked()).into_response(),
+        Ok(None) => not_found("owner"),
+        Err(e) => {
+            tracing::error!(error = %e, "Owner statement query failed");
+            e.into_response()
+        }
+    }
+}
+
+/// The statement as a PDF, masked like the JSON statement.
+pub async fn owner_statement_pdf_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+    access: SensitiveAccess,
+    Query(params): Query<StatementQuery>,
+) -> Response {
+    let period = match params.period() {
+        Ok(p) => p,
+        Err(e) => return e.into_response(),
+    };
+
+    let statement = match build_owner_statement(&state.pool, id, period, params.fee_pct).await {
+        Ok(Some(s)) if access.granted() => s,
+        Ok(Some(s)) => s.masked(),
+        Ok(None) => return not_found("owner"),
+        Err(e) => {
+            tracing::error!(error = %e, "Owner statement query failed");
+            return e.into_response();
+        }
+    };
+
+    let filename = format!(
+        "statement-{}-{}.pdf",
+        slug(&statement.owner_name),
+        period.from.format("%Y-%m")
+    );
+
+    // Run PDF generation on blocking thread pool to avoid stalling async runtime
+    let pdf_result =
+        tokio::task::spawn_blocking(move || PdfService::generate_owner_statement_pdf(&statement))
+            .await;
+
+    match pdf_result {
+        Ok(Ok(pdf_bytes)) => (
+            [
+                (header::CONTENT_TYPE, "application/pdf".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"{filename}\""),
+                ),
+            ],
+            pdf_bytes,
+        )
+            .into_response(),
+        Ok(Err(e)) => {
+            tracing::error!(error = %e, "Statement PDF generation failed");
+            error_response(
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                &format!("PDF generation failed: {e}"),
+            )
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "Statement PDF task failed");
+            error_response(
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                &format!("PDF task failed: {e}"),
+            )
+        }
+    }
+}
+
+fn slug(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>()
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
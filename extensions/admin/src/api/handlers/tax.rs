@@ -0,0 +1,290 @@
+use axum::extract::{Query, State};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Deserialize;
+
+use crate::api::generic::AdminState;
+use crate::api::sensitive::SensitiveAccess;
+use crate::error::AdminError;
+use crate::services::aeat::{modelo_115_file, modelo_180_file, modelo_347_file, FilingOptions};
+use crate::services::tax::{
+    build_third_party_returns, build_withholding_returns, TaxPeriod, ThirdPartyReturn,
+    WithholdingReturn, DEFAULT_WITHHOLDING_RATE,
+};
+
+/// Query parameters for tax reports:
+/// ?year=2025&quarter=2&declarant=B12345678&rate=19
+#[derive(Debug, Deserialize, Default)]
+pub struct TaxReportQuery {
+    pub year: Option<i32>,
+    /// Modelo 115 only; defaults to the previous quarter
+    pub quarter: Option<u32>,
+    /// Tax ID of the declarant; required for file downloads
+    pub declarant: Option<String>,
+    /// IRPF withholding rate in percent (Modelo 115/180)
+    pub rate: Option<f64>,
+    #[serde(default)]
+    pub contact_name: String,
+    #[serde(default)]
+    pub declaration_id: String,
+    #[serde(default)]
+    pub iban: String,
+}
+
+impl TaxReportQuery {
+    fn rate(&self) -> Result<f64, AdminError> {
+        let rate = self.rate.unwrap_or(DEFAULT_WITHHOLDING_RATE);
+        if !(0.0..=100.0).contains(&rate) {
+            return Err(AdminError::BadRequest(
+                "rate must be between 0 and 100".to_string(),
+            ));
+        }
+        Ok(rate)
+    }
+
+    fn declarant(&self) -> Option<&str> {
+        self.declarant.as_deref().filter(|d| !d.trim().is_empty())
+    }
+
+    fn required_declarant(&self) -> Result<&str, AdminError> {
+        self.declarant().ok_or_else(|| {
+            AdminError::BadRequest("declarant (tax ID) is required for the file".to_string())
+        })
+    }
+
+    fn filing_options(&self) -> Result<FilingOptions, AdminError> {
+        let declaration_id = self.declaration_id.trim();
+        if !declaration_id.is_empty()
+            && (declaration_id.len() != 13 || !declaration_id.chars().all(|c| c.is_ascii_digit()))
+        {
+            return Err(AdminError::BadRequest(
+                "declaration_id must be 13 digits".to_string(),
+            ));
+        }
+        Ok(FilingOptions {
+            contact_name: self.contact_name.clone(),
+            declaration_id: declaration_id.to_string(),
+            iban: self.iban.replace(' ', "").to_uppercase(),
+        })
+    }
+}
+
+/// Modelo 115 figures: quarterly rent withholdings per withholding tenant.
+pub async fn modelo_115_handler(
+    State(state): State<AdminState>,
+    access: SensitiveAccess,
+    Query(params): Query<TaxReportQuery>,
+) -> Response {
+    withholding_report(&state, &params, true, access).await
+}
+
+/// Modelo 180 figures: annual rent withholdings per withholding tenant and owner.
+pub async fn modelo_180_handler(
+    State(state): State<AdminState>,
+    access: SensitiveAccess,
+    Query(params): Query<TaxReportQuery>,
+) -> Response {
+    withholding_report(&state, &params, false, access).await
+}
+
+/// Tax ids and phone numbers are masked unless the token has the
+/// `sensitive` scope.
+async fn withholding_report(
+    state: &AdminState,
+    params: &TaxReportQuery,
+    quarterly: bool,
+    access: SensitiveAccess,
+) -> Response {
+    let result = async {
+        let period = if quarterly {
+            TaxPeriod::quarter(params.year, params.quarter)?
+        } else {
+            TaxPeriod::year(params.year)?
+        };
+        let mut returns =
+            build_withholding_returns(&state.pool, period, params.rate()?, params.declarant())
+                .await?;
+        if !access.granted() {
+            returns = returns.into_iter().map(WithholdingReturn::masked).collect();
+        }
+        Ok::<_, AdminError>(serde_json::json!({ "period": period, "data": returns }))
+    }
+    .await;
+
+    match result {
+        Ok(data) => Json(data).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Withholding tax report failed");
+            e.into_response()
+        }
+    }
+}
+
+/// Modelo 347 figures: owners' operations with third parties over €3,005.06.
+/// Tax ids and phone numbers are masked unless the token has the
+/// `sensitive` scope.
+pub async fn modelo_347_handler(
+    State(state): State<AdminState>,
+    access: SensitiveAccess,
+    Query(params): Query<TaxReportQuery>,
+) -> Response {
+    let result = async {
+        let period = TaxPeriod::year(params.year)?;
+        let mut returns =
+            build_third_party_returns(&state.pool, period, params.declarant()).await?;
+        if !access.granted() {
+            returns = returns.into_iter().map(ThirdPartyReturn::masked).collect();
+        }
+        Ok::<_, AdminError>(serde_json::json!({ "period": period, "data": returns }))
+    }
+    .await;
+
+    match result {
+        Ok(data) => Json(data).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Modelo 347 report failed");
+            e.into_response()
+        }
+    }
+}
+
+pub async fn modelo_115_file_handler(
+    State(state): State<AdminState>,
+    access: SensitiveAccess,
+    Query(params): Query<TaxReportQuery>,
+) -> Response {
+    withholding_file(&state, &params, true, access).await
+}
+
+pub async fn modelo_180_file_handler(
+    State(state): State<AdminState>,
+    access: SensitiveAccess,
+    Query(params): Query<TaxReportQuery>,
+) -> Response {
+    withholding_file(&state, &params, false, access).await
+}
+
+/// The AEAT files carry tax ids in clear, so they need the `sensitive`
+/// scope.
+async fn withholding_file(
+    state: &AdminState,
+    params: &TaxReportQuery,
+    quarterly: bool,
+    access: SensitiveAccess,
+) -> Response {
+    let result = async {
+        access.require()?;
+        let declarant = params.required_declarant()?;
+        let options = params.filing_options()?;
+        let period = if quarterly {
+            TaxPeriod::quarter(params.year, params.quarter)?
+        } else {
+            TaxPeriod::year(params.year)?
+        };
+        let ret = build_withholding_returns(&state.pool, period, params.rate()?, Some(declarant))
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| {
+                AdminError::NotFound(format!("withholdings for {declarant} in this period"))
+            })?;
+        check_tax_ids(
+            ret.lines
+                .iter()
+                .map(|l| (&l.perceptor_tax_id, &l.perceptor_name)),
+        )?;
+
+        let nif = file_safe(declarant);
+        Ok::<_, AdminError>(if quarterly {
+            (
+                format!("115_{nif}_{}{}.txt", period.year, period.code()),
+                modelo_115_file(&ret, &options),
+            )
+        } else {
+            (
+                format!("180_{nif}_{}.txt", period.year),
+                modelo_180_file(&ret, &options),
+            )
+        })
+    }
+    .await;
+
+    file_response(result)
+}
+
+pub async fn modelo_347_file_handler(
+    State(state): State<AdminState>,
+    access: SensitiveAccess,
+    Query(params): Query<TaxReportQuery>,
+) -> Response {
+    let result = async {
+        access.require()?;
+        let declarant = params.required_declarant()?;
+        let options = params.filing_options()?;
+        let period = TaxPeriod::year(params.year)?;
+        let ret = build_third_party_returns(&state.pool, period, Some(declarant))
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| {
+                AdminError::NotFound(format!(
+                    "operations over the Modelo 347 threshold for {declarant}"
+                ))
+            })?;
+        check_tax_ids(ret.lines.iter().map(|l| (&l.tax_id, &l.name)))?;
+
+        Ok::<_, AdminError>((
+            format!("347_{}_{}.txt", file_safe(declarant), period.year),
+            modelo_347_file(&ret, &options),
+        ))
+    }
+    .await;
+
+    file_response(result)
+}
+
+/// AEAT rejects records without a tax ID, so refuse to build the file.
+fn check_tax_ids<'a>(
+    parties: impl Iterator<Item = (&'a String, &'a String)>,
+) -> Result<(), AdminError> {
+    let missing: Vec<&str> = parties
+        .filter(|(tax_id, _)| tax_id.trim().is_empty())
+        .map(|(_, name)| name.as_str())
+        .collect();
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(AdminError::BadRequest(format!(
+            "Missing tax_id for: {}",
+            missing.join(", ")
+        )))
+    }
+}
+
+fn file_safe(tax_id: &str) -> String {
+    tax_id.chars().filter(char::is_ascii_alphanumeric).collect()
+}
+
+fn file_response(result: Result<(String, Vec<u8>), AdminError>) -> Response {
+    match result {
+        Ok((filename, bytes)) => (
+            [
+                (
+                    header::CONTENT_TYPE,
+                    "text/plain; charset=iso-8859-1".to_string(),
+                ),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"{filename}\""),
+                ),
+            ],
+            bytes,
+        )
+            .into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "AEAT file generation failed");
+            e.into_response()
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::api::generic::AdminState;
+use crate::api::types::success_response;
+use crate::error::AdminError;
+use crate::services::templates::{
+    create_template, delete_template, get_template, list_templates, update_template,
+    DocumentTemplateRequest,
+};
+
+/// Query parameters for the template listing: ?kind=lease
+#[derive(Debug, Deserialize, Default)]
+pub struct TemplatesQuery {
+    pub kind: Option<String>,
+}
+
+pub async fn templates_list_handler(
+    State(state): State<AdminState>,
+    Query(params): Query<TemplatesQuery>,
+) -> Response {
+    let kind = params.kind.as_deref().filter(|k| !k.is_empty());
+    match list_templates(&state.pool, kind).await {
+        Ok(templates) => Json(serde_json::json!({ "data": templates })).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Document templates listing failed");
+            AdminError::from(e).into_response()
+        }
+    }
+}
+
+pub async fn template_get_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+) -> Response {
+    match get_template(&state.pool, id).await {
+        Ok(template) => Json(template).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn template_create_handler(
+    State(state): State<AdminState>,
+    Json(body): Json<DocumentTemplateRequest>,
+) -> Response {
+    match create_template(&state.pool, &body).await {
+        Ok(template) => (StatusCode::CREATED, Json(template)).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Document template creation failed");
+            e.into_response()
+        }
+    }
+}
+
+pub async fn template_update_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+    Json(body): Json<DocumentTemplateRequest>,
+) -> Response {
+    match update_template(&state.pool, id, &body).await {
+        Ok(template) => Json(template).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Document template update failed");
+            e.into_response()
+        }
+    }
+}
+
+pub async fn template_delete_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+) -> Response {
+    match delete_template(&state.pool, id).await {
+        Ok(()) => success_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Document template deletion failed");
+            e.into_response()
+        }
+    }
+}
@@ -8,7 +8,7 @@ use crate::api::types::error_response;
 pub async fn tenants_names_handler(State(state): State<AdminState>) -> Response {
     match sqlx::query_scalar::<_, serde_json::Value>(
         "SELECT COALESCE(json_agg(t.name), '[]') FROM (\
-            SELECT DISTINCT name FROM admin_tenants ORDER BY name\
+            SELECT DISTINCT name FROM admin_tenants WHERE deleted_at IS NULL ORDER BY name\
          ) t",
     )
     .fetch_one(&*state.pool)
@@ -0,0 +1,208 @@
+use axum::extract::{Path, Query, State};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::api::actor::Actor;
+use crate::api::generic::{
+    entities, entity_info, set_deleted, write_audit, AdminState, EntityInfo,
+};
+use crate::api::sensitive::SensitiveAccess;
+use crate::api::types::success_response;
+use crate::error::AdminError;
+use crate::services::pii;
+use crate::services::trash::{retention_days, unreferenced};
+
+/// Query parameters for the trash listing: ?entity=tenants&page=1&per_page=25
+#[derive(Debug, Deserialize, Default)]
+pub struct TrashQuery {
+    pub entity: Option<String>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+/// Deleted records across all entities (or one), most recently deleted first,
+/// with the date each will be purged. Sensitive fields are masked unless the
+/// token has the `sensitive` scope.
+pub async fn trash_list_handler(
+    State(state): State<AdminState>,
+    access: SensitiveAccess,
+    Query(params): Query<TrashQuery>,
+) -> Response {
+    let result = async {
+        let entity = params.entity.as_deref().filter(|e| !e.is_empty());
+        if let Some(entity) = entity {
+            trash_entity(entity)?;
+        }
+
+        let deleted: Vec<String> = entities()
+            .into_iter()
+            .filter(|info| info.trashable && (entity.is_none() || entity == Some(info.label)))
+            .map(|info| {
+                format!(
+                    "SELECT '{}' AS entity, t.id, t.deleted_at, row_to_json(t)::jsonb AS record \
+                     FROM {} t WHERE t.deleted_at IS NOT NULL",
+                    info.label, info.table
+                )
+            })
+            .collect();
+        let deleted = deleted.join(" UNION ALL ");
+
+        let page = params.page.unwrap_or(1).max(1);
+        let per_page = params.per_page.unwrap_or(25).clamp(1, 500);
+        let days = retention_days();
+
+        let data_sql = format!(
+            "SELECT COALESCE(json_agg(row_to_json(x)), '[]') FROM (\
+                SELECT d.*, d.deleted_at + make_interval(days => $1) AS purge_at \
+                FROM ({deleted}) d ORDER BY d.deleted_at DESC LIMIT {per_page} OFFSET {}\
+             ) x",
+            (page - 1) * per_page
+        );
+        let count_sql = format!("SELECT COUNT(*)::bigint FROM ({deleted}) d");
+
+        let pool = &*state.pool;
+        let (data, total) = tokio::join!(
+            sqlx::query_scalar::<_, serde_json::Value>(&data_sql)
+                .bind(days)
+                .fetch_one(pool),
+            sqlx::query_scalar::<_, i64>(&count_sql).fetch_one(pool),
+        );
+
+        let mut data = data?;
+        for item in data.as_array_mut().into_iter().flatten() {
+            let info = item
+                .get("entity")
+                .and_then(serde_json::Value::as_str)
+                .and_then(entity_info);
+            if let (Some(info), Some(record)) = (info, item.get_mut("record")) {
+                pii::reveal(record, info.sensitive_fields, access.granted());
+            }
+        }
+
+        Ok::<_, AdminError>(serde_json::json!({
+            "data": data,
+            "total": total?,
+            "retention_days": days,
+        }))
+    }
+    .await;
+
+    match result {
+        Ok(body) => Json(body).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Trash listing failed");
+            e.into_response()
+        }
+    }
+}
+
+/// Take a record out of the trash.
+pub async fn trash_restore_handler(
+    State(state): State<AdminState>,
+    Path((entity, id)): Path<(String, Uuid)>,
+    actor: Actor,
+) -> Response {
+    let result = async {
+        let table = trash_entity(&entity)?.table;
+
+        let mut tx = state.pool.begin().await?;
+        let (old, new) = set_deleted(&mut tx, table, id, false)
+            .await?
+            .ok_or_else(|| AdminError::NotFound(format!("{entity} in the trash")))?;
+        write_audit(
+            &mut *tx,
+            &actor,
+            &entity,
+            &id.to_string(),
+            "restore",
+            Some(&old),
+            Some(&new),
+        )
+        .await?;
+        tx.commit().await?;
+        Ok::<_, AdminError>(())
+    }
+    .await;
+
+    match result {
+        Ok(()) => success_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Trash restore failed");
+            e.into_response()
+        }
+    }
+}
+
+/// Permanently delete a record that is already in the trash, without waiting
+/// for the retention period, unless other records still refer to it. The
+/// audit entry keeps no row data.
+pub async fn trash_purge_handler(
+    State(state): State<AdminState>,
+    Path((entity, id)): Path<(String, Uuid)>,
+    actor: Actor,
+) -> Response {
+    let result = async {
+        let table = trash_entity(&entity)?.table;
+
+        let unreferenced = unreferenced(&state.pool, table).await?;
+
+        let mut tx = state.pool.begin().await?;
+        let in_trash: bool = sqlx::query_scalar(&format!(
+            "SELECT EXISTS (SELECT 1 FROM {table} WHERE id = $1 AND deleted_at IS NOT NULL)"
+        ))
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+        if !in_trash {
+            return Err(AdminError::NotFound(format!("{entity} in the trash")));
+        }
+        let purged = sqlx::query(&format!(
+            "DELETE FROM {table} t WHERE t.id = $1 AND {unreferenced}"
+        ))
+        .bind(id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        if purged == 0 {
+            return Err(AdminError::Conflict(format!(
+                "Other records still refer to this {entity} record; remove them first"
+            )));
+        }
+        write_audit(
+            &mut *tx,
+            &actor,
+            &entity,
+            &id.to_string(),
+            "purge",
+            None,
+            None,
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+    .await;
+
+    match result {
+        Ok(()) => success_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Trash purge failed");
+            e.into_response()
+        }
+    }
+}
+
+/// The entity behind `label`, refusing those that are never in the trash.
+fn trash_entity(label: &str) -> Result<EntityInfo, AdminError> {
+    let info = entity_info(label)
+        .ok_or_else(|| AdminError::BadRequest(format!("Unknown entity: {label}")))?;
+    if info.trashable {
+        Ok(info)
+    } else {
+        Err(AdminError::BadRequest(format!(
+            "{label} are not kept in the trash"
+        )))
+    }
+}
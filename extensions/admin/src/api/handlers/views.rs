@@ -0,0 +1,89 @@
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::api::actor::Actor;
+use crate::api::generic::AdminState;
+use crate::api::types::success_response;
+use crate::services::views::{
+    create_view, delete_view, get_view, list_views, update_view, SavedViewRequest,
+};
+
+/// Query parameters for the view listing: ?entity=invoices
+#[derive(Debug, Deserialize, Default)]
+pub struct ViewsQuery {
+    pub entity: Option<String>,
+}
+
+/// The caller's own views plus those shared with the agency.
+pub async fn views_list_handler(
+    State(state): State<AdminState>,
+    actor: Actor,
+    Query(params): Query<ViewsQuery>,
+) -> Response {
+    let entity = params.entity.as_deref().filter(|e| !e.is_empty());
+    match list_views(&state.pool, &actor, entity).await {
+        Ok(views) => Json(serde_json::json!({ "data": views })).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Saved views listing failed");
+            e.into_response()
+        }
+    }
+}
+
+pub async fn view_get_handler(
+    State(state): State<AdminState>,
+    actor: Actor,
+    Path(id): Path<Uuid>,
+) -> Response {
+    match get_view(&state.pool, &actor, id).await {
+        Ok(view) => Json(view).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn view_create_handler(
+    State(state): State<AdminState>,
+    actor: Actor,
+    Json(body): Json<SavedViewRequest>,
+) -> Response {
+    match create_view(&state.pool, &actor, &body).await {
+        Ok(view) => (StatusCode::CREATED, Json(view)).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Saved view creation failed");
+            e.into_response()
+        }
+    }
+}
+
+pub async fn view_update_handler(
+    State(state): State<AdminState>,
+    actor: Actor,
+    Path(id): Path<Uuid>,
+    Json(body): Json<SavedViewRequest>,
+) -> Response {
+    match update_view(&state.pool, &actor, id, &body).await {
+        Ok(view) => Json(view).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Saved view update failed");
+            e.into_response()
+        }
+    }
+}
+
+pub async fn view_delete_handler(
+    State(state): State<AdminState>,
+    actor: Actor,
+    Path(id): Path<Uuid>,
+) -> Response {
+    match delete_view(&state.pool, &actor, id).await {
+        Ok(()) => success_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Saved view deletion failed");
+            e.into_response()
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use uuid::Uuid;
+
+use crate::api::actor::Actor;
+use crate::api::generic::AdminState;
+use crate::api::types::success_response;
+use crate::error::AdminError;
+use crate::services::webhooks::{
+    create_webhook, delete_webhook, delivery_detail, get_webhook, list_webhooks, retry_delivery,
+    update_webhook, webhook_deliveries, DeliveryQuery, WebhookRequest,
+};
+
+pub async fn webhooks_list_handler(State(state): State<AdminState>) -> Response {
+    match list_webhooks(&state.pool).await {
+        Ok(webhooks) => Json(serde_json::json!({ "data": webhooks })).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Webhooks query failed");
+            AdminError::from(e).into_response()
+        }
+    }
+}
+
+pub async fn webhook_get_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+) -> Response {
+    match get_webhook(&state.pool, id).await {
+        Ok(webhook) => Json(webhook).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Register a webhook; the response carries its signing secret.
+pub async fn webhook_create_handler(
+    State(state): State<AdminState>,
+    actor: Actor,
+    Json(body): Json<WebhookRequest>,
+) -> Response {
+    match create_webhook(&state.pool, &actor, &body).await {
+        Ok(webhook) => (StatusCode::CREATED, Json(webhook)).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Webhook creation failed");
+            e.into_response()
+        }
+    }
+}
+
+pub async fn webhook_update_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+    actor: Actor,
+    Json(body): Json<WebhookRequest>,
+) -> Response {
+    match update_webhook(&state.pool, &actor, id, &body).await {
+        Ok(webhook) => Json(webhook).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Webhook update failed");
+            e.into_response()
+        }
+    }
+}
+
+pub async fn webhook_delete_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+    actor: Actor,
+) -> Response {
+    match delete_webhook(&state.pool, &actor, id).await {
+        Ok(()) => success_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Webhook deletion failed");
+            e.into_response()
+        }
+    }
+}
+
+/// Delivery log of a webhook: ?status=failed&limit=100
+pub async fn webhook_deliveries_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<DeliveryQuery>,
+) -> Response {
+    match webhook_deliveries(&state.pool, id, &params).await {
+        Ok(deliveries) => Json(serde_json::json!({ "data": deliveries })).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Webhook deliveries query failed");
+            AdminError::from(e).into_response()
+        }
+    }
+}
+
+pub async fn delivery_get_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+) -> Response {
+    match delivery_detail(&state.pool, id).await {
+        Ok(delivery) => Json(delivery).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+pub async fn delivery_retry_handler(
+    State(state): State<AdminState>,
+    Path(id): Path<Uuid>,
+) -> Response {
+    match retry_delivery(&state.pool, id).await {
+        Ok(delivery) => Json(delivery).into_response(),
+        Err(e) => {
+            tracing::error!(error = %e, "Webhook delivery retry failed");
+            e.into_response()
+        }
+    }
+}
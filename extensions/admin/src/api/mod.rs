@@ -1,17 +1,24 @@
+pub mod actor;
+pub mod bulk;
+pub mod filters;
 pub mod generic;
 pub mod handlers;
+pub mod pagination;
+pub mod portal;
+pub mod precondition;
+pub mod sensitive;
 pub mod types;
 
 use std::sync::Arc;
 
-use axum::routing::{get, post};
+use axum::routing::{delete, get, post, put};
 use axum::Router;
 use sqlx::PgPool;
 
 use generic::{
     AdminState, AlertEntity, ContactEntity, ContractEntity, DepositEntity, InsuranceEntity,
-    IssueEntity, LeadEntity, LeadNoteEntity, OwnerEntity, PropertyEntity, SepaBatchEntity,
-    TenantEntity,
+    IssueEntity, LeadEntity, LeadNoteEntity, OwnerEntity, PayoutBatchEntity, PropertyEntity,
+    SepaBatchEntity, TenantEntity,
 };
 
 pub fn router(pool: Arc<PgPool>) -> Router {
@@ -23,6 +30,8 @@ pub fn router(pool: Arc<PgPool>) -> Router {
             "/auth/session",
             post(handlers::auth::set_session).delete(handlers::auth::clear_session),
         )
+        // ── API description ─────────────────────────────────
+        .route("/openapi.json", get(handlers::openapi::openapi_handler))
         // ── Dashboard & Reports ─────────────────────────────
         .route("/dashboard", get(handlers::dashboard::dashboard_handler))
         .route(
@@ -33,12 +42,128 @@ pub fn router(pool: Arc<PgPool>) -> Router {
             "/reports/profitability",
             get(handlers::reports::profitability_handler),
         )
+        .route(
+            "/reports/rent-roll",
+            get(handlers::reports::rent_roll_handler),
+        )
+        .route(
+            "/reports/occupancy",
+            get(handlers::reports::occupancy_handler),
+        )
+        .route(
+            "/reports/vacancy",
+            get(handlers::reports::vacancy_handler),
+        )
+        .route(
+            "/reports/owner-statements",
+            get(handlers::statements::owner_statements_handler),
+        )
+        .route(
+            "/reports/tax/modelo-115",
+            get(handlers::tax::modelo_115_handler),
+        )
+        .route(
+            "/reports/tax/modelo-115/file",
+            get(handlers::tax::modelo_115_file_handler),
+        )
+        .route(
+            "/reports/tax/modelo-180",
+            get(handlers::tax::modelo_180_handler),
+        )
+        .route(
+            "/reports/tax/modelo-180/file",
+            get(handlers::tax::modelo_180_file_handler),
+        )
+        .route(
+            "/reports/tax/modelo-347",
+            get(handlers::tax::modelo_347_handler),
+        )
+        .route(
+            "/reports/tax/modelo-347/file",
+            get(handlers::tax::modelo_347_file_handler),
+        )
+        // ── Search ──────────────────────────────────────────
+        .route("/search", get(handlers::search::search_handler))
+        // ── Saved Views ─────────────────────────────────────
+        .route(
+            "/views",
+            get(handlers::views::views_list_handler).post(handlers::views::view_create_handler),
+        )
+        .route(
+            "/views/{id}",
+            get(handlers::views::view_get_handler)
+                .put(handlers::views::view_update_handler)
+                .delete(handlers::views::view_delete_handler),
+        )
+        // ── Custom Fields ───────────────────────────────────
+        .route(
+            "/custom-fields",
+            get(handlers::custom_fields::custom_fields_list_handler)
+                .post(handlers::custom_fields::custom_field_create_handler),
+        )
+        .route(
+            "/custom-fields/{id}",
+            put(handlers::custom_fields::custom_field_update_handler)
+                .delete(handlers::custom_fields::custom_field_delete_handler),
+        )
+        // ── Document Templates ──────────────────────────────
+        .route(
+            "/document-templates",
+            get(handlers::templates::templates_list_handler)
+                .post(handlers::templates::template_create_handler),
+        )
+        .route(
+            "/document-templates/{id}",
+            get(handlers::templates::template_get_handler)
+                .put(handlers::templates::template_update_handler)
+                .delete(handlers::templates::template_delete_handler),
+        )
         // ── Audit ───────────────────────────────────────────
         .route("/audit/recent", get(handlers::audit::audit_recent_handler))
+        // POST /audit/{id}/revert — the segment shares its parameter name with
+        // the entity route below, as the router allows one name per position
+        .route(
+            "/audit/{entity_type}/revert",
+            post(handlers::audit::audit_revert_handler),
+        )
         .route(
             "/audit/{entity_type}/{entity_id}",
             get(handlers::audit::audit_entity_handler),
         )
+        // ── Webhooks ────────────────────────────────────────
+        .route(
+            "/webhooks",
+            get(handlers::webhooks::webhooks_list_handler)
+                .post(handlers::webhooks::webhook_create_handler),
+        )
+        .route(
+            "/webhooks/{id}",
+            get(handlers::webhooks::webhook_get_handler)
+                .put(handlers::webhooks::webhook_update_handler)
+                .delete(handlers::webhooks::webhook_delete_handler),
+        )
+        .route(
+            "/webhooks/{id}/deliveries",
+            get(handlers::webhooks::webhook_deliveries_handler),
+        )
+        .route(
+            "/webhook-deliveries/{id}",
+            get(handlers::webhooks::delivery_get_handler),
+        )
+        .route(
+            "/webhook-deliveries/{id}/retry",
+            post(handlers::webhooks::delivery_retry_handler),
+        )
+        // ── Trash ───────────────────────────────────────────
+        .route("/trash", get(handlers::trash::trash_list_handler))
+        .route(
+            "/trash/{entity}/{id}",
+            delete(handlers::trash::trash_purge_handler),
+        )
+        .route(
+            "/trash/{entity}/{id}/restore",
+            post(handlers::trash::trash_restore_handler),
+        )
         // ── Properties (specific routes before generic) ──────
         .route(
             "/properties/names",
@@ -52,20 +177,199 @@ pub fn router(pool: Arc<PgPool>) -> Router {
             "/properties/{id}/detail",
             get(handlers::properties::property_detail_handler),
         )
+        .route(
+            "/properties/{id}/rollup",
+            get(handlers::properties::property_rollup_handler),
+        )
         // ── Contracts (specific) ────────────────────────────
         .route(
             "/contracts/{id}/detail",
             get(handlers::contracts::contract_detail_handler),
         )
+        .route(
+            "/contracts/{id}/documents/generate",
+            post(handlers::contracts::contract_document_generate_handler),
+        )
+        .route(
+            "/contract-documents",
+            get(handlers::contracts::contract_documents_list_handler),
+        )
+        .route(
+            "/contract-documents/{id}",
+            delete(handlers::contracts::contract_document_delete_handler),
+        )
+        .route(
+            "/contract-documents/{id}/download",
+            get(handlers::contracts::contract_document_download_handler),
+        )
+        .route(
+            "/contract-documents/{id}/signature-requests",
+            post(handlers::signatures::signature_request_create_handler),
+        )
         .route(
             "/contract-documents/{id}/text",
             post(handlers::contracts::contract_doc_text_handler),
         )
+        // ── Inspections ─────────────────────────────────────
+        .route(
+            "/contracts/{id}/inspections",
+            get(handlers::inspections::inspections_get_handler),
+        )
+        .route(
+            "/contracts/{id}/inspections/diff",
+            get(handlers::inspections::inspection_diff_handler),
+        )
+        .route(
+            "/contracts/{id}/inspections/deductions",
+            post(handlers::inspections::inspection_deductions_handler),
+        )
+        .route(
+            "/contracts/{id}/inspections/pdf",
+            get(handlers::inspections::inspection_pdf_handler),
+        )
+        .route(
+            "/contracts/{id}/inspections/{kind}",
+            put(handlers::inspections::inspection_save_handler)
+                .delete(handlers::inspections::inspection_delete_handler),
+        )
+        .route(
+            "/inspection-items/{id}/photos",
+            post(handlers::inspections::inspection_photo_upload_handler),
+        )
+        .route(
+            "/inspection-photos/{id}",
+            get(handlers::inspections::inspection_photo_get_handler)
+                .delete(handlers::inspections::inspection_photo_delete_handler),
+        )
+        // ── Signatures ──────────────────────────────────────
+        .route(
+            "/signature-requests",
+            get(handlers::signatures::signature_requests_list_handler),
+        )
+        .route(
+            "/signature-requests/{id}",
+            get(handlers::signatures::signature_request_get_handler),
+        )
+        .route(
+            "/signature-requests/{id}/cancel",
+            post(handlers::signatures::signature_request_cancel_handler),
+        )
+        .route(
+            "/signature-requests/{id}/complete",
+            post(handlers::signatures::signature_request_complete_handler),
+        )
+        // Opened from the one-time links sent to signers
+        .route(
+            "/sign/{token}",
+            get(handlers::signatures::signing_view_handler)
+                .post(handlers::signatures::sign_handler),
+        )
+        .route(
+            "/sign/{token}/document",
+            get(handlers::signatures::signing_document_handler),
+        )
+        // ── Owners (specific) ──────────────────────────────
+        .route(
+            "/owners/{id}/statement",
+            get(handlers::statements::owner_statement_handler),
+        )
+        .route(
+            "/owners/{id}/statement/pdf",
+            get(handlers::statements::owner_statement_pdf_handler),
+        )
+        .route(
+            "/owners/{id}/portal-access",
+            get(handlers::portal::owner_portal_access_list_handler)
+                .put(handlers::portal::owner_portal_access_grant_handler),
+        )
+        .route(
+            "/owners/{id}/portal-access/{account_id}",
+            delete(handlers::portal::owner_portal_access_revoke_handler),
+        )
         // ── Tenants (specific) ─────────────────────────────
         .route(
             "/tenants/names",
             get(handlers::tenants::tenants_names_handler),
         )
+        .route(
+            "/tenants/{id}/portal-access",
+            get(handlers::portal::tenant_portal_access_list_handler)
+                .put(handlers::portal::tenant_portal_access_grant_handler),
+        )
+        .route(
+            "/tenants/{id}/portal-access/{account_id}",
+            delete(handlers::portal::tenant_portal_access_revoke_handler),
+        )
+        // ── Issues (specific) ─────────────────────────────
+        .route(
+            "/issues/{id}/photos",
+            get(handlers::portal::issue_photos_handler),
+        )
+        .route(
+            "/issue-photos/{id}",
+            get(handlers::portal::issue_photo_handler),
+        )
+        // ── Tenant portal (tenant-scoped tokens only) ─────
+        .route("/portal/tenant", get(handlers::portal::tenant_overview_handler))
+        .route(
+            "/portal/tenant/invoices",
+            get(handlers::portal::tenant_invoices_handler),
+        )
+        .route(
+            "/portal/tenant/invoices/{id}/pdf",
+            get(handlers::portal::tenant_invoice_pdf_handler),
+        )
+        .route(
+            "/portal/tenant/payments",
+            get(handlers::portal::tenant_payments_handler),
+        )
+        .route(
+            "/portal/tenant/issues",
+            get(handlers::portal::tenant_issues_handler)
+                .post(handlers::portal::tenant_issue_create_handler),
+        )
+        .route(
+            "/portal/tenant/issues/{id}/photos",
+            post(handlers::portal::tenant_issue_photo_upload_handler),
+        )
+        .route(
+            "/portal/tenant/issue-photos/{id}",
+            get(handlers::portal::tenant_issue_photo_handler),
+        )
+        // ── Owner portal (owner-scoped tokens only) ───────
+        .route("/portal/owner", get(handlers::owner_portal::owner_overview_handler))
+        .route(
+            "/portal/owner/performance",
+            get(handlers::owner_portal::owner_performance_handler),
+        )
+        .route(
+            "/portal/owner/occupancy",
+            get(handlers::owner_portal::owner_occupancy_handler),
+        )
+        .route(
+            "/portal/owner/statement",
+            get(handlers::owner_portal::owner_statement_portal_handler),
+        )
+        .route(
+            "/portal/owner/statement/pdf",
+            get(handlers::owner_portal::owner_statement_pdf_portal_handler),
+        )
+        .route(
+            "/portal/owner/insurance",
+            get(handlers::owner_portal::owner_insurance_handler),
+        )
+        .route(
+            "/portal/owner/issues",
+            get(handlers::owner_portal::owner_issues_handler),
+        )
+        .route(
+            "/portal/owner/documents",
+            get(handlers::owner_portal::owner_documents_handler),
+        )
+        .route(
+            "/portal/owner/documents/{id}/download",
+            get(handlers::owner_portal::owner_document_download_handler),
+        )
         // ── Contacts (specific) ───────────────────────────
         .route(
             "/contacts/names",
@@ -76,6 +380,28 @@ pub fn router(pool: Arc<PgPool>) -> Router {
             "/sepa-batches/creditors",
             get(handlers::sepa_batches::sepa_batches_creditors_handler),
         )
+        // ── Payout Batches ─────────────────────────────────
+        .route(
+            "/payout-batches",
+            get(generic::generic_list::<PayoutBatchEntity>)
+                .post(handlers::payouts::payout_batch_create_handler),
+        )
+        .route(
+            "/payout-batches/{id}",
+            get(handlers::payouts::payout_batch_detail_handler),
+        )
+        .route(
+            "/payout-batches/{id}/approve",
+            post(handlers::payouts::payout_batch_approve_handler),
+        )
+        .route(
+            "/payout-batches/{id}/cancel",
+            post(handlers::payouts::payout_batch_cancel_handler),
+        )
+        .route(
+            "/payout-batches/{id}/pain001",
+            get(handlers::payouts::payout_batch_pain001_handler),
+        )
         // ── Invoices (custom list + payees + owners + PDF) ─
         .route(
             "/invoices/owners",
@@ -92,7 +418,12 @@ pub fn router(pool: Arc<PgPool>) -> Router {
         .route(
             "/invoices",
             get(handlers::invoices::invoices_list_handler)
-                .post(generic::generic_create::<generic::InvoiceEntity>),
+                .post(generic::generic_create::<generic::InvoiceEntity>)
+                .patch(bulk::generic_bulk_update::<generic::InvoiceEntity>),
+        )
+        .route(
+            "/invoices/bulk-delete",
+            post(bulk::generic_bulk_delete::<generic::InvoiceEntity>),
         )
         .route(
             "/invoices/{id}",
@@ -106,7 +437,12 @@ pub fn router(pool: Arc<PgPool>) -> Router {
         .route(
             "/properties",
             get(generic::generic_list::<PropertyEntity>)
-                .post(generic::generic_create::<PropertyEntity>),
+                .post(generic::generic_create::<PropertyEntity>)
+                .patch(bulk::generic_bulk_update::<PropertyEntity>),
+        )
+        .route(
+            "/properties/bulk-delete",
+            post(bulk::generic_bulk_delete::<PropertyEntity>),
         )
         .route(
             "/properties/{id}",
@@ -118,7 +454,12 @@ pub fn router(pool: Arc<PgPool>) -> Router {
         .route(
             "/contracts",
             get(generic::generic_list::<ContractEntity>)
-                .post(generic::generic_create::<ContractEntity>),
+                .post(generic::generic_create::<ContractEntity>)
+                .patch(bulk::generic_bulk_update::<ContractEntity>),
+        )
+        .route(
+            "/contracts/bulk-delete",
+            post(bulk::generic_bulk_delete::<ContractEntity>),
         )
         .route(
             "/contracts/{id}",
@@ -130,7 +471,12 @@ pub fn router(pool: Arc<PgPool>) -> Router {
         .route(
             "/tenants",
             get(generic::generic_list::<TenantEntity>)
-                .post(generic::generic_create::<TenantEntity>),
+                .post(generic::generic_create::<TenantEntity>)
+                .patch(bulk::generic_bulk_update::<TenantEntity>),
+        )
+        .route(
+            "/tenants/bulk-delete",
+            post(bulk::generic_bulk_delete::<TenantEntity>),
         )
         .route(
             "/tenants/{id}",
@@ -142,7 +488,12 @@ pub fn router(pool: Arc<PgPool>) -> Router {
         .route(
             "/owners",
             get(generic::generic_list::<OwnerEntity>)
-                .post(generic::generic_create::<OwnerEntity>),
+                .post(generic::generic_create::<OwnerEntity>)
+                .patch(bulk::generic_bulk_update::<OwnerEntity>),
+        )
+        .route(
+            "/owners/bulk-delete",
+            post(bulk::generic_bulk_delete::<OwnerEntity>),
         )
         .route(
             "/owners/{id}",
@@ -154,7 +505,12 @@ pub fn router(pool: Arc<PgPool>) -> Router {
         .route(
             "/deposits",
             get(generic::generic_list::<DepositEntity>)
-                .post(generic::generic_create::<DepositEntity>),
+                .post(generic::generic_create::<DepositEntity>)
+                .patch(bulk::generic_bulk_update::<DepositEntity>),
+        )
+        .route(
+            "/deposits/bulk-delete",
+            post(bulk::generic_bulk_delete::<DepositEntity>),
         )
         .route(
             "/deposits/{id}",
@@ -166,7 +522,12 @@ pub fn router(pool: Arc<PgPool>) -> Router {
         .route(
             "/sepa-batches",
             get(generic::generic_list::<SepaBatchEntity>)
-                .post(generic::generic_create::<SepaBatchEntity>),
+                .post(generic::generic_create::<SepaBatchEntity>)
+                .patch(bulk::generic_bulk_update::<SepaBatchEntity>),
+        )
+        .route(
+            "/sepa-batches/bulk-delete",
+            post(bulk::generic_bulk_delete::<SepaBatchEntity>),
         )
         .route(
             "/sepa-batches/{id}",
@@ -178,7 +539,12 @@ pub fn router(pool: Arc<PgPool>) -> Router {
         .route(
             "/issues",
             get(generic::generic_list::<IssueEntity>)
-                .post(generic::generic_create::<IssueEntity>),
+                .post(generic::generic_create::<IssueEntity>)
+                .patch(bulk::generic_bulk_update::<IssueEntity>),
+        )
+        .route(
+            "/issues/bulk-delete",
+            post(bulk::generic_bulk_delete::<IssueEntity>),
         )
         .route(
             "/issues/{id}",
@@ -190,7 +556,12 @@ pub fn router(pool: Arc<PgPool>) -> Router {
         .route(
             "/insurance",
             get(generic::generic_list::<InsuranceEntity>)
-                .post(generic::generic_create::<InsuranceEntity>),
+                .post(generic::generic_create::<InsuranceEntity>)
+                .patch(bulk::generic_bulk_update::<InsuranceEntity>),
+        )
+        .route(
+            "/insurance/bulk-delete",
+            post(bulk::generic_bulk_delete::<InsuranceEntity>),
         )
         .route(
             "/insurance/{id}",
@@ -202,7 +573,12 @@ pub fn router(pool: Arc<PgPool>) -> Router {
         .route(
             "/alerts",
             get(generic::generic_list::<AlertEntity>)
-                .post(generic::generic_create::<AlertEntity>),
+                .post(generic::generic_create::<AlertEntity>)
+                .patch(bulk::generic_bulk_update::<AlertEntity>),
+        )
+        .route(
+            "/alerts/bulk-delete",
+            post(bulk::generic_bulk_delete::<AlertEntity>),
         )
         .route(
             "/alerts/{id}",
@@ -214,7 +590,12 @@ pub fn router(pool: Arc<PgPool>) -> Router {
         .route(
             "/contacts",
             get(generic::generic_list::<ContactEntity>)
-                .post(generic::generic_create::<ContactEntity>),
+                .post(generic::generic_create::<ContactEntity>)
+                .patch(bulk::generic_bulk_update::<ContactEntity>),
+        )
+        .route(
+            "/contacts/bulk-delete",
+            post(bulk::generic_bulk_delete::<ContactEntity>),
         )
         .route(
             "/contacts/{id}",
@@ -226,7 +607,12 @@ pub fn router(pool: Arc<PgPool>) -> Router {
         .route(
             "/leads",
             get(generic::generic_list::<LeadEntity>)
-                .post(generic::generic_create::<LeadEntity>),
+                .post(generic::generic_create::<LeadEntity>)
+                .patch(bulk::generic_bulk_update::<LeadEntity>),
+        )
+        .route(
+            "/leads/bulk-delete",
+            post(bulk::generic_bulk_delete::<LeadEntity>),
         )
         .route(
             "/leads/{id}",
@@ -234,11 +620,24 @@ pub fn router(pool: Arc<PgPool>) -> Router {
                 .put(generic::generic_update::<LeadEntity>)
                 .delete(generic::generic_delete::<LeadEntity>),
         )
+        .route(
+            "/leads/{id}/matches",
+            get(handlers::leads::lead_matches_handler),
+        )
+        .route(
+            "/leads/{id}/convert",
+            post(handlers::leads::lead_convert_handler),
+        )
         // ── Generic CRUD: Lead Notes ────────────────────────
         .route(
             "/lead-notes",
             get(generic::generic_list::<LeadNoteEntity>)
-                .post(generic::generic_create::<LeadNoteEntity>),
+                .post(generic::generic_create::<LeadNoteEntity>)
+                .patch(bulk::generic_bulk_update::<LeadNoteEntity>),
+        )
+        .route(
+            "/lead-notes/bulk-delete",
+            post(bulk::generic_bulk_delete::<LeadNoteEntity>),
         )
         .route(
             "/lead-notes/{id}",
@@ -0,0 +1,255 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::error::AdminError;
+
+pub const DEFAULT_PER_PAGE: i64 = 25;
+/// Upper bound for `?per_page=`; larger values are clamped.
+pub const MAX_PER_PAGE: i64 = 500;
+
+/// Page size from `?per_page=`, bounded to `1..=MAX_PER_PAGE`.
+pub fn per_page(requested: Option<i64>) -> i64 {
+    requested.unwrap_or(DEFAULT_PER_PAGE).clamp(1, MAX_PER_PAGE)
+}
+
+/// How a list reports its total: ?count=exact|estimate|none
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CountMode {
+    /// `COUNT(*)` over the filtered rows
+    #[default]
+    Exact,
+    /// Row estimate from the query planner; cheap on large tables
+    Estimate,
+    /// No total; page with `next_cursor` instead
+    None,
+}
+
+impl CountMode {
+    pub fn parse(value: Option<&str>) -> Result<Self, AdminError> {
+        match value.map(str::trim) {
+            None | Some("" | "exact") => Ok(Self::Exact),
+            Some("estimate") => Ok(Self::Estimate),
+            Some("none") => Ok(Self::None),
+            Some(other) => Err(AdminError::BadRequest(format!(
+                "count must be exact, estimate or none, not '{other}'"
+            ))),
+        }
+    }
+}
+
+/// SQL types a cursor's sort value may be cast back to. The type travels in
+/// the client-held token, so only these are accepted.
+const SORT_TYPES: &[&str] = &[
+    "text",
+    "character varying",
+    "uuid",
+    "boolean",
+    "smallint",
+    "integer",
+    "bigint",
+    "numeric",
+    "real",
+    "double precision",
+    "date",
+    "timestamp without time zone",
+    "timestamp with time zone",
+];
+
+/// A row of a keyset page with its sort key: the row, the sort value as
+/// text (`None` when NULL) and the SQL type of the sort value. Select it as
+/// `row_to_json(t), ({sort})::text, pg_typeof({sort})::text`.
+pub type KeyedRow = (serde_json::Value, Option<String>, String);
+
+/// Keyset position: the sort value and id of the last row of the previous
+/// page, for the sort it was fetched with. Sent to clients as an opaque
+/// base64 token.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Cursor {
+    pub sort: String,
+    pub order: String,
+    /// Sort value of the last row as text; `None` when it is NULL
+    pub value: Option<String>,
+    /// SQL type the value is cast back to, one of `SORT_TYPES`
+    pub value_type: String,
+    pub id: Uuid,
+}
+
+impl Cursor {
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).unwrap_or_default())
+    }
+
+    /// Decode `?cursor=`, rejecting tokens issued for a different sort.
+    pub fn decode(token: &str, sort: &str, order: &str) -> Result<Self, AdminError> {
+        let cursor: Self = URL_SAFE_NO_PAD
+            .decode(token.trim())
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .filter(|c: &Self| SORT_TYPES.contains(&c.value_type.as_str()))
+            .ok_or_else(|| AdminError::BadRequest("Invalid cursor".to_string()))?;
+        if cursor.sort != sort || cursor.order != order {
+            return Err(AdminError::BadRequest(
+                "Cursor was issued for a different sort order".to_string(),
+            ));
+        }
+        Ok(cursor)
+    }
+
+    /// Trim a page fetched with one row more than `per_page` and return its
+    /// rows with the cursor for the next page, or `None` when this page is
+    /// the last.
+    pub fn next_page(
+        mut rows: Vec<KeyedRow>,
+        per_page: i64,
+        sort: &str,
+        order: &str,
+    ) -> (Vec<serde_json::Value>, Option<String>) {
+        let limit = usize::try_from(per_page).unwrap_or_default();
+        let has_more = rows.len() > limit;
+        rows.truncate(limit);
+        let cursor = rows
+            .last()
+            .filter(|_| has_more)
+            .and_then(|(row, value, value_type)| {
+                let id = row
+                    .get("id")
+                    .and_then(serde_json::Value::as_str)
+                    .and_then(|id| Uuid::parse_str(id).ok())?;
+                Some(
+                    Self {
+                        sort: sort.to_string(),
+                        order: order.to_string(),
+                        value: value.clone(),
+                        value_type: value_type.clone(),
+                        id,
+                    }
+                    .encode(),
+                )
+            });
+        (rows.into_iter().map(|(row, _, _)| row).collect(), cursor)
+    }
+
+    /// Condition selecting the rows after the cursor for
+    /// `ORDER BY {sort} {order}, id {order}`, with the cursor's sort value
+    /// bound as text at `$value_idx` (unused when it is NULL) and its id at
+    /// `$id_idx`. `sort` is a column or an expression over the table's
+    /// columns.
+    ///
+    /// Compares `({sort}, id)` as a row, so an index on the sort column and
+    /// id serves it. NULLs sort last ascending and first descending, as
+    /// PostgreSQL does by default.
+    pub fn condition(&self, value_idx: usize, id_idx: usize) -> String {
+        let sort = &self.sort;
+        let value = format!("${value_idx}::{}", self.value_type);
+        let id = format!("${id_idx}::uuid");
+        match (self.order == "ASC", self.value.is_some()) {
+            (true, true) => format!("(({sort}, id) > ({value}, {id}) OR {sort} IS NULL)"),
+            (true, false) => format!("({sort} IS NULL AND id > {id})"),
+            (false, true) => format!("({sort}, id) < ({value}, {id})"),
+            (false, false) => format!("({sort} IS NOT NULL OR id < {id})"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn cursor(order: &str, value: Option<&str>) -> Cursor {
+        Cursor {
+            sort: "rent".to_string(),
+            order: order.to_string(),
+            value: value.map(str::to_string),
+            value_type: "numeric".to_string(),
+            id: Uuid::nil(),
+        }
+    }
+
+    fn keyed(id: u128, rent: &str) -> KeyedRow {
+        (
+            json!({ "id": Uuid::from_u128(id), "rent": rent }),
+            Some(rent.to_string()),
+            "numeric".to_string(),
+        )
+    }
+
+    #[test]
+    fn per_page_is_bounded() {
+        assert_eq!(per_page(None), DEFAULT_PER_PAGE);
+        assert_eq!(per_page(Some(0)), 1);
+        assert_eq!(per_page(Some(10_000)), MAX_PER_PAGE);
+    }
+
+    #[test]
+    fn count_mode_parses() {
+        assert_eq!(CountMode::parse(None).unwrap(), CountMode::Exact);
+        assert_eq!(CountMode::parse(Some(" none ")).unwrap(), CountMode::None);
+        assert_eq!(
+            CountMode::parse(Some("estimate")).unwrap(),
+            CountMode::Estimate
+        );
+        assert!(CountMode::parse(Some("all")).is_err());
+    }
+
+    #[test]
+    fn cursor_round_trips() {
+        let token = cursor("ASC", Some("1250.00")).encode();
+        let decoded = Cursor::decode(&token, "rent", "ASC").unwrap();
+        assert_eq!(decoded.value.as_deref(), Some("1250.00"));
+        assert_eq!(decoded.value_type, "numeric");
+        assert_eq!(decoded.id, Uuid::nil());
+    }
+
+    #[test]
+    fn cursor_rejects_another_sort() {
+        let token = cursor("ASC", Some("1250.00")).encode();
+        assert!(Cursor::decode(&token, "rent", "DESC").is_err());
+        assert!(Cursor::decode(&token, "created_at", "ASC").is_err());
+    }
+
+    #[test]
+    fn cursor_rejects_unknown_types_and_garbage() {
+        let mut forged = cursor("ASC", Some("1"));
+        forged.value_type = "int); DROP TABLE admin_invoices; --".to_string();
+        assert!(Cursor::decode(&forged.encode(), "rent", "ASC").is_err());
+        assert!(Cursor::decode("not a cursor", "rent", "ASC").is_err());
+    }
+
+    #[test]
+    fn next_page_returns_a_cursor_only_when_more_rows_exist() {
+        let rows = vec![keyed(1, "100"), keyed(2, "200"), keyed(3, "300")];
+        let (page, next) = Cursor::next_page(rows.clone(), 2, "rent", "ASC");
+        assert_eq!(page.len(), 2);
+        let next = Cursor::decode(&next.unwrap(), "rent", "ASC").unwrap();
+        assert_eq!(next.id, Uuid::from_u128(2));
+        assert_eq!(next.value.as_deref(), Some("200"));
+
+        let (page, next) = Cursor::next_page(rows, 3, "rent", "ASC");
+        assert_eq!(page.len(), 3);
+        assert!(next.is_none());
+    }
+
+    #[test]
+    fn condition_compares_rows_and_handles_nulls() {
+        assert_eq!(
+            cursor("ASC", Some("1")).condition(3, 4),
+            "((rent, id) > ($3::numeric, $4::uuid) OR rent IS NULL)"
+        );
+        assert_eq!(
+            cursor("ASC", None).condition(3, 4),
+            "(rent IS NULL AND id > $4::uuid)"
+        );
+        assert_eq!(
+            cursor("DESC", Some("1")).condition(3, 4),
+            "(rent, id) < ($3::numeric, $4::uuid)"
+        );
+        assert_eq!(
+            cursor("DESC", None).condition(3, 4),
+            "(rent IS NOT NULL OR id < $4::uuid)"
+        );
+    }
+}
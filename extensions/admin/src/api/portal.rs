@@ -0,0 +1,76 @@
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+use axum::http::HeaderMap;
+
+use crate::api::actor::{access_token, verify_token};
+use crate::api::generic::AdminState;
+use crate::error::AdminError;
+use crate::services::owner_portal::{portal_owner, PortalOwner};
+use crate::services::portal::{portal_tenant, PortalRole, PortalTenant};
+
+/// Unlike the admin API, portal routes verify the access token themselves:
+/// the signature and expiry, then that it carries `scope`. Returns the
+/// token's subject. Also used for scopes within the admin API, such as
+/// `sensitive`.
+pub(crate) fn verified_subject(headers: &HeaderMap, scope: &str) -> Result<String, AdminError> {
+    let token = access_token(headers)
+        .ok_or_else(|| AdminError::Unauthorized("Sign in to use the portal".to_string()))?;
+    let claims = verify_token(token)?;
+
+    if !claims.scope.split_whitespace().any(|s| s == scope) {
+        return Err(AdminError::Forbidden(format!(
+            "The {scope} scope is required"
+        )));
+    }
+    Ok(claims.sub)
+}
+
+/// The tenant a portal request acts as. Handlers taking this extractor can
+/// only be reached with a valid tenant token linked to a tenant record.
+impl FromRequestParts<AdminState> for PortalTenant {
+    type Rejection = AdminError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AdminState,
+    ) -> Result<Self, Self::Rejection> {
+        let user_id = verified_subject(&parts.headers, PortalRole::Tenant.as_str())?;
+        portal_tenant(&state.pool, &user_id).await
+    }
+}
+
+/// The owner a portal request acts as, from a valid owner token linked to
+/// an owner record.
+impl FromRequestParts<AdminState> for PortalOwner {
+    type Rejection = AdminError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AdminState,
+    ) -> Result<Self, Self::Rejection> {
+        let user_id = verified_subject(&parts.headers, PortalRole::Owner.as_str())?;
+        portal_owner(&state.pool, &user_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::header::AUTHORIZATION;
+
+    use super::*;
+
+    #[test]
+    fn portal_needs_an_access_token() {
+        assert!(matches!(
+            verified_subject(&HeaderMap::new(), "tenant"),
+            Err(AdminError::Unauthorized(_))
+        ));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, "Basic dGVuYW50".parse().unwrap());
+        assert!(matches!(
+            verified_subject(&headers, "tenant"),
+            Err(AdminError::Unauthorized(_))
+        ));
+    }
+}
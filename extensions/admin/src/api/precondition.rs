@@ -0,0 +1,148 @@
+use std::convert::Infallible;
+
+use axum::extract::FromRequestParts;
+use axum::http::header::{ETAG, IF_MATCH};
+use axum::http::request::Parts;
+use axum::http::{HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+
+use super::types::error_response;
+
+/// ETag of a record fetched as JSON: its `revision`, quoted.
+pub fn etag(row: &serde_json::Value) -> Option<String> {
+    row.get("revision")
+        .and_then(serde_json::Value::as_i64)
+        .map(|revision| format!("\"{revision}\""))
+}
+
+/// Attach the record's ETag to a response.
+pub fn with_etag(row: &serde_json::Value, response: impl IntoResponse) -> Response {
+    let mut response = response.into_response();
+    if let Some(value) = etag(row).and_then(|tag| HeaderValue::from_str(&tag).ok()) {
+        response.headers_mut().insert(ETAG, value);
+    }
+    response
+}
+
+/// The `If-Match` header of a PUT or DELETE. `*` matches any existing record.
+#[derive(Debug, Clone, Default)]
+pub struct IfMatch(Option<String>);
+
+impl IfMatch {
+    pub fn is_present(&self) -> bool {
+        self.0.is_some()
+    }
+
+    /// Whether the header names the current version of the row. Weak tags
+    /// (`W/"3"`) compare by their value.
+    pub fn matches(&self, row: &serde_json::Value) -> bool {
+        let Some(header) = self.0.as_deref() else {
+            return false;
+        };
+        if header.trim() == "*" {
+            return true;
+        }
+        let Some(current) = etag(row) else {
+            return false;
+        };
+        header
+            .split(',')
+            .map(|tag| tag.trim().trim_start_matches("W/"))
+            .any(|tag| tag == current)
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for IfMatch {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self(
+            parts
+                .headers
+                .get(IF_MATCH)
+                .and_then(|v| v.to_str().ok())
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string),
+        ))
+    }
+}
+
+/// 428 for a PUT or DELETE sent without `If-Match`.
+pub fn precondition_required() -> Response {
+    error_response(
+        StatusCode::PRECONDITION_REQUIRED,
+        "If-Match header with the record's ETag is required",
+    )
+}
+
+/// 409 with the current row and its ETag, so the client can merge and retry.
+pub fn version_conflict(entity: &str, current: &serde_json::Value) -> Response {
+    with_etag(
+        current,
+        (
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({
+                "error": format!("{entity} was changed by someone else"),
+                "current": current,
+            })),
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn if_match(value: &str) -> IfMatch {
+        IfMatch(Some(value.to_string()))
+    }
+
+    #[test]
+    fn etag_is_the_quoted_revision() {
+        assert_eq!(etag(&json!({"revision": 7})).as_deref(), Some("\"7\""));
+        assert_eq!(etag(&json!({"id": 1})), None);
+    }
+
+    #[test]
+    fn matches_the_current_revision_only() {
+        let row = json!({"revision": 3});
+        assert!(if_match("\"3\"").matches(&row));
+        assert!(!if_match("\"2\"").matches(&row));
+        assert!(!if_match("3").matches(&row));
+    }
+
+    #[test]
+    fn weak_tags_and_lists_compare_by_value() {
+        let row = json!({"revision": 3});
+        assert!(if_match("W/\"3\"").matches(&row));
+        assert!(if_match("\"1\", \"3\"").matches(&row));
+    }
+
+    #[test]
+    fn star_matches_any_existing_row() {
+        assert!(if_match(" * ").matches(&json!({"revision": 9})));
+        assert!(if_match("*").matches(&json!({})));
+    }
+
+    #[test]
+    fn missing_header_or_revision_never_matches() {
+        assert!(!IfMatch::default().is_present());
+        assert!(!IfMatch::default().matches(&json!({"revision": 1})));
+        assert!(!if_match("\"1\"").matches(&json!({"id": 1})));
+    }
+
+    #[test]
+    fn conflict_carries_the_current_etag() {
+        let response = version_conflict("Contract", &json!({"revision": 5}));
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(response.headers().get(ETAG).unwrap(), "\"5\"");
+        assert_eq!(
+            precondition_required().status(),
+            StatusCode::PRECONDITION_REQUIRED
+        );
+    }
+}
@@ -0,0 +1,45 @@
+use std::convert::Infallible;
+
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+
+use crate::api::portal::verified_subject;
+use crate::error::AdminError;
+
+/// Scope that lets an access token see personal data in clear.
+pub const SENSITIVE_SCOPE: &str = "sensitive";
+
+/// Whether a request may see sensitive fields (tax ids, IBANs, phone
+/// numbers) in clear. Only a verified access token carrying the
+/// `sensitive` scope may; everyone else gets masked values.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SensitiveAccess(bool);
+
+impl SensitiveAccess {
+    #[must_use]
+    pub const fn granted(self) -> bool {
+        self.0
+    }
+
+    /// Refuse requests that may not see personal data in clear, for
+    /// downloads that cannot be masked (tax filings, bank files).
+    pub fn require(self) -> Result<(), AdminError> {
+        if self.0 {
+            Ok(())
+        } else {
+            Err(AdminError::Forbidden(format!(
+                "This download needs a token with the `{SENSITIVE_SCOPE}` scope"
+            )))
+        }
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for SensitiveAccess {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self(
+            verified_subject(&parts.headers, SENSITIVE_SCOPE).is_ok(),
+        ))
+    }
+}
@@ -13,23 +13,50 @@ pub struct PaginationQuery {
     pub search: Option<String>,
     pub sort: Option<String>,
     pub order: Option<String>,
-    /// Catch-all for field-specific filters (e.g. ?status=Let)
+    /// `next_cursor` of the previous page; replaces `page` for keyset paging
+    pub cursor: Option<String>,
+    /// exact (default), estimate or none
+    pub count: Option<String>,
+    /// Catch-all for field-specific filters (e.g. ?status=Let) and the
+    /// saved `?view=<id>`
     #[serde(flatten)]
     pub filters: HashMap<String, String>,
 }
 
-/// Standard paginated response: { data: [...], total: N }
+impl PaginationQuery {
+    /// `?include_deleted=true` lists records in the trash alongside live ones.
+    /// Read from the filter map, as flattened fields only ever see strings.
+    pub fn include_deleted(&self) -> bool {
+        self.filters
+            .get("include_deleted")
+            .is_some_and(|v| v == "true" || v == "1")
+    }
+}
+
+/// Query parameters for single-record lookups: ?include_deleted=true
+#[derive(Debug, Deserialize, Default)]
+pub struct RecordQuery {
+    #[serde(default)]
+    pub include_deleted: bool,
+}
+
+/// Standard paginated response: { data: [...], total: N, next_cursor: "..." }
 #[derive(Debug, Serialize)]
 pub struct PaginatedResponse {
     pub data: Vec<serde_json::Value>,
-    pub total: i64,
+    /// Omitted with ?count=none
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<i64>,
+    /// Pass as ?cursor= for the next page; omitted on the last page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
 }
 
-/// Paginated response with totals row (for invoices): { data, total, totals }
+/// Paginated response with totals row (for invoices): { data, total, next_cursor, totals }
 #[derive(Debug, Serialize)]
 pub struct PaginatedWithTotals {
-    pub data: Vec<serde_json::Value>,
-    pub total: i64,
+    #[serde(flatten)]
+    pub page: PaginatedResponse,
     pub totals: serde_json::Value,
 }
 
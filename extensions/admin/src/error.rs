@@ -14,8 +14,20 @@ pub enum AdminError {
     #[error("Bad request: {0}")]
     BadRequest(String),
 
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
     #[error("PDF generation error: {0}")]
     PdfGeneration(String),
+
+    #[error("Encryption error: {0}")]
+    Encryption(String),
 }
 
 impl AdminError {
@@ -24,7 +36,11 @@ impl AdminError {
             Self::NotFound(_) => StatusCode::NOT_FOUND,
             Self::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
             Self::BadRequest(_) => StatusCode::BAD_REQUEST,
+            Self::Conflict(_) => StatusCode::CONFLICT,
+            Self::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            Self::Forbidden(_) => StatusCode::FORBIDDEN,
             Self::PdfGeneration(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::Encryption(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
 }
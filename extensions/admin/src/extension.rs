@@ -7,6 +7,26 @@ use systemprompt::traits::Job;
 
 pub const SCHEMA_ADMIN_TABLES: &str = include_str!("../schema/001_admin_tables.sql");
 pub const SCHEMA_ADMIN_SEED: &str = include_str!("../schema/002_admin_seed.sql");
+pub const SCHEMA_OWNER_STATEMENTS: &str = include_str!("../schema/003_owner_statements.sql");
+pub const SCHEMA_OWNER_PAYOUTS: &str = include_str!("../schema/004_owner_payouts.sql");
+pub const SCHEMA_TAX_REPORTS: &str = include_str!("../schema/005_tax_reports.sql");
+pub const SCHEMA_AUDIT_TRAIL: &str = include_str!("../schema/006_audit_trail.sql");
+pub const SCHEMA_SOFT_DELETE: &str = include_str!("../schema/007_soft_delete.sql");
+pub const SCHEMA_ROW_VERSIONS: &str = include_str!("../schema/008_row_versions.sql");
+pub const SCHEMA_KEYSET_PAGINATION: &str = include_str!("../schema/009_keyset_pagination.sql");
+pub const SCHEMA_FILTER_INDEXES: &str = include_str!("../schema/010_filter_indexes.sql");
+pub const SCHEMA_SEARCH: &str = include_str!("../schema/011_search.sql");
+pub const SCHEMA_SAVED_VIEWS: &str = include_str!("../schema/012_saved_views.sql");
+pub const SCHEMA_CUSTOM_FIELDS: &str = include_str!("../schema/013_custom_fields.sql");
+pub const SCHEMA_LEAD_PIPELINE: &str = include_str!("../schema/014_lead_pipeline.sql");
+pub const SCHEMA_PROPERTY_ATTRIBUTES: &str = include_str!("../schema/015_property_attributes.sql");
+pub const SCHEMA_CONTRACT_DOCUMENTS: &str = include_str!("../schema/016_contract_documents.sql");
+pub const SCHEMA_E_SIGNATURES: &str = include_str!("../schema/017_e_signatures.sql");
+pub const SCHEMA_INSPECTIONS: &str = include_str!("../schema/018_inspections.sql");
+pub const SCHEMA_TENANT_PORTAL: &str = include_str!("../schema/019_tenant_portal.sql");
+pub const SCHEMA_OWNER_PORTAL: &str = include_str!("../schema/020_owner_portal.sql");
+pub const SCHEMA_WEBHOOKS: &str = include_str!("../schema/021_webhooks.sql");
+pub const SCHEMA_PII_ENCRYPTION: &str = include_str!("../schema/022_pii_encryption.sql");
 
 #[derive(Debug, Default, Clone)]
 pub struct AdminExtension;
@@ -37,6 +57,27 @@ impl Extension for AdminExtension {
     fn schemas(&self) -> Vec<SchemaDefinition> {
         vec![
             SchemaDefinition::inline("admin_tables", SCHEMA_ADMIN_TABLES),
+            // Table changes run before the seed, which truncates and reloads every admin table
+            SchemaDefinition::inline("admin_owner_statements", SCHEMA_OWNER_STATEMENTS),
+            SchemaDefinition::inline("admin_owner_payouts", SCHEMA_OWNER_PAYOUTS),
+            SchemaDefinition::inline("admin_tax_reports", SCHEMA_TAX_REPORTS),
+            SchemaDefinition::inline("admin_audit_trail", SCHEMA_AUDIT_TRAIL),
+            SchemaDefinition::inline("admin_soft_delete", SCHEMA_SOFT_DELETE),
+            SchemaDefinition::inline("admin_row_versions", SCHEMA_ROW_VERSIONS),
+            SchemaDefinition::inline("admin_keyset_pagination", SCHEMA_KEYSET_PAGINATION),
+            SchemaDefinition::inline("admin_filter_indexes", SCHEMA_FILTER_INDEXES),
+            SchemaDefinition::inline("admin_search", SCHEMA_SEARCH),
+            SchemaDefinition::inline("admin_saved_views", SCHEMA_SAVED_VIEWS),
+            SchemaDefinition::inline("admin_custom_fields", SCHEMA_CUSTOM_FIELDS),
+            SchemaDefinition::inline("admin_lead_pipeline", SCHEMA_LEAD_PIPELINE),
+            SchemaDefinition::inline("admin_property_attributes", SCHEMA_PROPERTY_ATTRIBUTES),
+            SchemaDefinition::inline("admin_contract_documents", SCHEMA_CONTRACT_DOCUMENTS),
+            SchemaDefinition::inline("admin_e_signatures", SCHEMA_E_SIGNATURES),
+            SchemaDefinition::inline("admin_inspections", SCHEMA_INSPECTIONS),
+            SchemaDefinition::inline("admin_tenant_portal", SCHEMA_TENANT_PORTAL),
+            SchemaDefinition::inline("admin_owner_portal", SCHEMA_OWNER_PORTAL),
+            SchemaDefinition::inline("admin_webhooks", SCHEMA_WEBHOOKS),
+            SchemaDefinition::inline("admin_pii_encryption", SCHEMA_PII_ENCRYPTION),
             SchemaDefinition::inline("admin_seed", SCHEMA_ADMIN_SEED),
             SchemaDefinition::inline(
                 "admin_oauth_client",
@@ -58,7 +99,23 @@ VALUES ('property-admin', 'code')
 ON CONFLICT (client_id, response_type) DO NOTHING;
 
 INSERT INTO oauth_client_scopes (client_id, scope)
-VALUES ('property-admin', 'admin')
+VALUES ('property-admin', 'admin'), ('property-admin', 'sensitive')
+ON CONFLICT (client_id, scope) DO NOTHING;
+
+INSERT INTO oauth_clients (client_id, client_secret_hash, client_name, token_endpoint_auth_method, is_active)
+VALUES ('property-portal', NULL, 'Property Management Portal', 'none', true)
+ON CONFLICT (client_id) DO NOTHING;
+
+INSERT INTO oauth_client_grant_types (client_id, grant_type)
+VALUES ('property-portal', 'authorization_code'), ('property-portal', 'refresh_token')
+ON CONFLICT (client_id, grant_type) DO NOTHING;
+
+INSERT INTO oauth_client_response_types (client_id, response_type)
+VALUES ('property-portal', 'code')
+ON CONFLICT (client_id, response_type) DO NOTHING;
+
+INSERT INTO oauth_client_scopes (client_id, scope)
+VALUES ('property-portal', 'tenant'), ('property-portal', 'owner')
 ON CONFLICT (client_id, scope) DO NOTHING;
                 ",
             ),
@@ -78,7 +135,13 @@ ON CONFLICT (client_id, scope) DO NOTHING;
         Some(SiteAuthConfig {
             login_path: "/admin/login",
             protected_prefixes: &["/admin"],
-            public_prefixes: &["/admin/login", "/admin/api/auth", "/admin/api"],
+            public_prefixes: &[
+                "/admin/login",
+                "/admin/sign",
+                "/admin/portal",
+                "/admin/api/auth",
+                "/admin/api",
+            ],
             required_scope: "admin",
         })
     }
@@ -87,6 +150,11 @@ ON CONFLICT (client_id, scope) DO NOTHING;
         vec![
             Arc::new(crate::jobs::DemoResetJob),
             Arc::new(crate::jobs::RegisterOAuthClientJob),
+            Arc::new(crate::jobs::TrashPurgeJob),
+            Arc::new(crate::jobs::LeadScoreJob),
+            Arc::new(crate::jobs::WebhookDeliveryJob),
+            Arc::new(crate::jobs::WebhookEventsJob),
+            Arc::new(crate::jobs::PiiKeyRotationJob),
         ]
     }
 
@@ -115,6 +183,14 @@ ON CONFLICT (client_id, scope) DO NOTHING;
                 paths.storage_files().join("admin/login/index.html"),
                 "admin/login/index.html",
             ),
+            AssetDefinition::html(
+                paths.storage_files().join("admin/sign/index.html"),
+                "admin/sign/index.html",
+            ),
+            AssetDefinition::html(
+                paths.storage_files().join("admin/portal/index.html"),
+                "admin/portal/index.html",
+            ),
         ]
     }
 }
@@ -0,0 +1,43 @@
+use async_trait::async_trait;
+use systemprompt::database::DbPool;
+use systemprompt::traits::{Job, JobContext, JobResult};
+
+use crate::services::leads::recompute_scores;
+
+/// Recomputes lead scores from recency, budget fit against the available
+/// properties and recent note activity.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LeadScoreJob;
+
+#[async_trait]
+impl Job for LeadScoreJob {
+    fn name(&self) -> &'static str {
+        "admin_lead_scores"
+    }
+
+    fn description(&self) -> &'static str {
+        "Recomputes the score of open leads"
+    }
+
+    fn schedule(&self) -> &'static str {
+        "0 0 4 * * *"
+    }
+
+    async fn execute(&self, ctx: &JobContext) -> anyhow::Result<JobResult> {
+        let db = ctx
+            .db_pool::<DbPool>()
+            .ok_or_else(|| anyhow::anyhow!("Database not available in job context"))?;
+
+        let pool = db
+            .pool()
+            .ok_or_else(|| anyhow::anyhow!("PgPool not available from database"))?;
+
+        let updated = recompute_scores(&pool).await?;
+
+        tracing::info!(updated, "Admin lead scores recomputed");
+
+        Ok(JobResult::success()
+            .with_stats(updated, 0)
+            .with_message(format!("Lead scores: {updated} leads changed")))
+    }
+}
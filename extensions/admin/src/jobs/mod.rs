@@ -1,4 +1,8 @@
+mod lead_scores;
+mod pii_rotation;
 mod register_oauth_client;
+mod trash_purge;
+mod webhooks;
 
 use std::path::Path;
 
@@ -6,7 +10,11 @@ use async_trait::async_trait;
 use systemprompt::database::DbPool;
 use systemprompt::traits::{Job, JobContext, JobResult};
 
+pub use lead_scores::LeadScoreJob;
+pub use pii_rotation::PiiKeyRotationJob;
 pub use register_oauth_client::RegisterOAuthClientJob;
+pub use trash_purge::TrashPurgeJob;
+pub use webhooks::{WebhookDeliveryJob, WebhookEventsJob};
 
 #[derive(Debug, Clone, Copy, Default)]
 pub struct DemoResetJob;
@@ -41,6 +49,9 @@ impl Job for DemoResetJob {
             .execute(&*pool)
             .await?;
 
+        // The seed is in clear; seal its personal data straight away
+        let sealed = crate::services::pii::reseal_pending(&pool).await?;
+
         // Clean uploaded files from DB
         let deleted = sqlx::query_scalar::<_, i64>(
             "WITH d AS (DELETE FROM files RETURNING 1) SELECT COUNT(*) FROM d",
@@ -58,7 +69,11 @@ impl Job for DemoResetJob {
             let _ = tokio::fs::create_dir_all(uploads_dir).await;
         }
 
-        tracing::info!(files_deleted = deleted, "Admin demo data reset complete");
+        tracing::info!(
+            files_deleted = deleted,
+            values_sealed = sealed,
+            "Admin demo data reset complete"
+        );
 
         Ok(JobResult::success()
             .with_stats(1, 0)
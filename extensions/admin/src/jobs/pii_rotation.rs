@@ -0,0 +1,57 @@
+use async_trait::async_trait;
+use systemprompt::database::DbPool;
+use systemprompt::traits::{Job, JobContext, JobResult};
+
+use crate::services::pii::{reseal_pending, scrub_history};
+
+/// Seals personal data still in clear and re-seals values under retired
+/// keys with the active one (see `services::pii`). Once a run reports
+/// nothing left, a retired key can be dropped from `ADMIN_PII_KEYS`. Also
+/// seals or masks values left in clear in audit entries and webhook events.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PiiKeyRotationJob;
+
+#[async_trait]
+impl Job for PiiKeyRotationJob {
+    fn name(&self) -> &'static str {
+        "admin_pii_key_rotation"
+    }
+
+    fn description(&self) -> &'static str {
+        "Encrypts admin personal data with the active key and fills in blind indexes"
+    }
+
+    fn schedule(&self) -> &'static str {
+        "0 */10 * * * *"
+    }
+
+    fn run_on_startup(&self) -> bool {
+        true
+    }
+
+    async fn execute(&self, ctx: &JobContext) -> anyhow::Result<JobResult> {
+        let db = ctx
+            .db_pool::<DbPool>()
+            .ok_or_else(|| anyhow::anyhow!("Database not available in job context"))?;
+
+        let pool = db
+            .pool()
+            .ok_or_else(|| anyhow::anyhow!("PgPool not available from database"))?;
+
+        let resealed = reseal_pending(&pool).await?;
+        let scrubbed = scrub_history(&pool).await?;
+
+        tracing::info!(
+            resealed,
+            scrubbed,
+            "Admin personal data key rotation complete"
+        );
+
+        Ok(JobResult::success()
+            .with_stats(resealed + scrubbed, 0)
+            .with_message(format!(
+                "Key rotation: {resealed} values sealed with the active key, \
+                 {scrubbed} audit entries and events scrubbed"
+            )))
+    }
+}
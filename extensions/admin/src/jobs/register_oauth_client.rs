@@ -13,7 +13,7 @@ impl Job for RegisterOAuthClientJob {
     }
 
     fn description(&self) -> &'static str {
-        "Registers OAuth client redirect URI using api_external_url from profile"
+        "Registers OAuth client redirect URIs using api_external_url from profile"
     }
 
     fn schedule(&self) -> &'static str {
@@ -27,6 +27,7 @@ impl Job for RegisterOAuthClientJob {
     async fn execute(&self, ctx: &JobContext) -> Result<JobResult> {
         let config = Config::get()?;
         let redirect_uri = format!("{}/admin/login", config.api_external_url);
+        let portal_redirect_uri = format!("{}/admin/portal/", config.api_external_url);
 
         let db = ctx
             .db_pool::<DbPool>()
@@ -44,6 +45,15 @@ impl Job for RegisterOAuthClientJob {
         .execute(pool.as_ref())
         .await?;
 
+        sqlx::query!(
+            "INSERT INTO oauth_client_redirect_uris (client_id, redirect_uri, is_primary)
+             VALUES ('property-portal', $1, true)
+             ON CONFLICT (client_id, redirect_uri) DO NOTHING",
+            &portal_redirect_uri,
+        )
+        .execute(pool.as_ref())
+        .await?;
+
         sqlx::query!(
             "DELETE FROM oauth_client_redirect_uris
              WHERE client_id = 'property-admin' AND redirect_uri = '/admin/login'",
@@ -51,8 +61,14 @@ impl Job for RegisterOAuthClientJob {
         .execute(pool.as_ref())
         .await?;
 
-        tracing::info!(redirect_uri = %redirect_uri, "OAuth client redirect URI registered");
+        tracing::info!(
+            redirect_uri = %redirect_uri,
+            portal_redirect_uri = %portal_redirect_uri,
+            "OAuth client redirect URIs registered"
+        );
 
-        Ok(JobResult::success().with_message(format!("Registered redirect URI: {redirect_uri}")))
+        Ok(JobResult::success().with_message(format!(
+            "Registered redirect URIs: {redirect_uri}, {portal_redirect_uri}"
+        )))
     }
 }
@@ -0,0 +1,46 @@
+use async_trait::async_trait;
+use systemprompt::database::DbPool;
+use systemprompt::traits::{Job, JobContext, JobResult};
+
+use crate::services::trash::{purge_expired, retention_days};
+
+/// Permanently removes records that have been in the trash for longer than
+/// the retention period (`ADMIN_TRASH_RETENTION_DAYS`, default 30).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TrashPurgeJob;
+
+#[async_trait]
+impl Job for TrashPurgeJob {
+    fn name(&self) -> &'static str {
+        "admin_trash_purge"
+    }
+
+    fn description(&self) -> &'static str {
+        "Purges admin records deleted longer ago than the trash retention period"
+    }
+
+    fn schedule(&self) -> &'static str {
+        "0 30 3 * * *"
+    }
+
+    async fn execute(&self, ctx: &JobContext) -> anyhow::Result<JobResult> {
+        let db = ctx
+            .db_pool::<DbPool>()
+            .ok_or_else(|| anyhow::anyhow!("Database not available in job context"))?;
+
+        let pool = db
+            .pool()
+            .ok_or_else(|| anyhow::anyhow!("PgPool not available from database"))?;
+
+        let days = retention_days();
+        let purged = purge_expired(&pool, days).await;
+
+        tracing::info!(purged, retention_days = days, "Admin trash purge complete");
+
+        Ok(JobResult::success()
+            .with_stats(purged, 0)
+            .with_message(format!(
+                "Trash purge: {purged} records older than {days} days removed"
+            )))
+    }
+}
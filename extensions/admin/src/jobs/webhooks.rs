@@ -0,0 +1,104 @@
+use async_trait::async_trait;
+use systemprompt::database::DbPool;
+use systemprompt::traits::{Job, JobContext, JobResult};
+
+use crate::services::webhooks::{
+    deliver_pending, delivery_client, event_retention_days, expiring_days, prune_events,
+    queue_expiring_contracts,
+};
+
+/// Sends due webhook deliveries from the outbox, retrying failures with
+/// exponential backoff.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WebhookDeliveryJob;
+
+#[async_trait]
+impl Job for WebhookDeliveryJob {
+    fn name(&self) -> &'static str {
+        "admin_webhook_delivery"
+    }
+
+    fn description(&self) -> &'static str {
+        "Delivers queued admin events to registered webhooks"
+    }
+
+    fn schedule(&self) -> &'static str {
+        "0 * * * * *"
+    }
+
+    async fn execute(&self, ctx: &JobContext) -> anyhow::Result<JobResult> {
+        let db = ctx
+            .db_pool::<DbPool>()
+            .ok_or_else(|| anyhow::anyhow!("Database not available in job context"))?;
+
+        let pool = db
+            .pool()
+            .ok_or_else(|| anyhow::anyhow!("PgPool not available from database"))?;
+
+        let client = delivery_client()?;
+        let stats = deliver_pending(&pool, &client).await?;
+
+        if stats.delivered + stats.retrying + stats.failed > 0 {
+            tracing::info!(
+                delivered = stats.delivered,
+                retrying = stats.retrying,
+                failed = stats.failed,
+                "Admin webhook deliveries sent"
+            );
+        }
+
+        Ok(JobResult::success()
+            .with_stats(stats.delivered, stats.retrying + stats.failed)
+            .with_message(format!(
+                "Webhooks: {} delivered, {} to retry, {} failed",
+                stats.delivered, stats.retrying, stats.failed
+            )))
+    }
+}
+
+/// Raises `contract.expiring` for contracts nearing their end date and
+/// prunes delivered events past the retention period.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WebhookEventsJob;
+
+#[async_trait]
+impl Job for WebhookEventsJob {
+    fn name(&self) -> &'static str {
+        "admin_webhook_events"
+    }
+
+    fn description(&self) -> &'static str {
+        "Queues scheduled admin events and prunes old webhook events"
+    }
+
+    fn schedule(&self) -> &'static str {
+        "0 0 6 * * *"
+    }
+
+    async fn execute(&self, ctx: &JobContext) -> anyhow::Result<JobResult> {
+        let db = ctx
+            .db_pool::<DbPool>()
+            .ok_or_else(|| anyhow::anyhow!("Database not available in job context"))?;
+
+        let pool = db
+            .pool()
+            .ok_or_else(|| anyhow::anyhow!("PgPool not available from database"))?;
+
+        let days = expiring_days();
+        let queued = queue_expiring_contracts(&pool, days).await?;
+        let pruned = prune_events(&pool, event_retention_days()).await?;
+
+        tracing::info!(
+            queued,
+            pruned,
+            expiring_days = days,
+            "Admin webhook events updated"
+        );
+
+        Ok(JobResult::success()
+            .with_stats(queued, 0)
+            .with_message(format!(
+                "Webhook events: {queued} contract.expiring queued, {pruned} old events pruned"
+            )))
+    }
+}
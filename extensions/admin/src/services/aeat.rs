@@ -0,0 +1,463 @@
+//! AEAT fixed-width files for the Spanish tax returns built in `services::tax`.
+//!
+//! Modelo 180 and 347 use the 500-character record layouts for electronic
+//! filing (type 1 declarant record followed by type 2 records); Modelo 115
+//! uses the `<T115...>` import format accepted by the online form. Files are
+//! ISO-8859-1 encoded, upper-case, with CRLF line endings.
+
+use crate::services::tax::{
+    postal_code, TaxParty, ThirdPartyLine, ThirdPartyReturn, WithholdingLine, WithholdingReturn,
+};
+
+const RECORD_LEN: usize = 500;
+
+/// Filing details that are not stored in the admin tables.
+#[derive(Debug, Default)]
+pub struct FilingOptions {
+    /// Person to contact about the return; defaults to the declarant name
+    pub contact_name: String,
+    /// 13-digit declaration number from the AEAT receipt; zero-filled when unknown
+    pub declaration_id: String,
+    /// Account to debit the payment from (Modelo 115 only)
+    pub iban: String,
+}
+
+/// Fixed-width record builder. Fields are appended left to right.
+struct Record(String);
+
+impl Record {
+    fn new() -> Self {
+        Self(String::with_capacity(RECORD_LEN))
+    }
+
+    /// Alphanumeric field: normalized, left-aligned, blank-padded.
+    fn alpha(mut self, len: usize, value: &str) -> Self {
+        let value: String = normalize(value).chars().take(len).collect();
+        let padding = len - value.chars().count();
+        self.0.push_str(&value);
+        self.0.push_str(&" ".repeat(padding));
+        self
+    }
+
+    /// Numeric field: right-aligned, zero-padded.
+    fn num(mut self, len: usize, value: u64) -> Self {
+        let digits = value.to_string();
+        let digits = &digits[digits.len().saturating_sub(len)..];
+        self.0.push_str(&format!("{digits:0>len$}"));
+        self
+    }
+
+    /// Amount in cents without decimal point: right-aligned, zero-padded.
+    fn amount(self, len: usize, value: f64) -> Self {
+        self.num(len, cents(value))
+    }
+
+    /// `N` for negative amounts, blank otherwise.
+    fn sign(mut self, value: f64) -> Self {
+        self.0.push(if value < 0.0 { 'N' } else { ' ' });
+        self
+    }
+
+    fn blank(mut self, len: usize) -> Self {
+        self.0.push_str(&" ".repeat(len));
+        self
+    }
+
+    fn text(mut self, value: &str) -> Self {
+        self.0.push_str(value);
+        self
+    }
+
+    /// Pad to the full record length.
+    fn finish(self) -> String {
+        let mut s = self.0;
+        let len = s.chars().count();
+        if len < RECORD_LEN {
+            s.push_str(&" ".repeat(RECORD_LEN - len));
+        }
+        s
+    }
+}
+
+/// Modelo 115 import file for one withholding agent and quarter.
+pub fn modelo_115_file(ret: &WithholdingReturn, options: &FilingOptions) -> Vec<u8> {
+    let year = u64::try_from(ret.period.year).unwrap_or_default();
+    let period = ret.period.code();
+    let kind = if ret.total_withholding <= 0.0 {
+        "N"
+    } else if options.iban.is_empty() {
+        "I"
+    } else {
+        "U"
+    };
+
+    let body = Record::new()
+        .text(&format!("<T1150{year:04}{period}0000>"))
+        .text("<AUX>")
+        .blank(70)
+        .text("0100")
+        .blank(4)
+        .blank(9)
+        .blank(213)
+        .text("</AUX>")
+        .text("<T11501000>")
+        .blank(1)
+        .alpha(1, kind)
+        .alpha(9, &ret.declarant.tax_id)
+        .alpha(60, &ret.declarant.name)
+        .blank(20)
+        .num(4, year)
+        .alpha(2, &period)
+        .num(15, ret.num_perceptors as u64)
+        .amount(17, ret.total_base)
+        .amount(17, ret.total_withholding)
+        .amount(17, 0.0)
+        .amount(17, ret.total_withholding)
+        .blank(1)
+        .blank(13)
+        .alpha(34, &options.iban)
+        .blank(389)
+        .blank(13)
+        .text("</T11501000>")
+        .text(&format!("</T1150{year:04}{period}0000>"));
+
+    encode(&body.0)
+}
+
+/// Modelo 180 file: annual summary of rent withholdings for one withholding agent.
+pub fn modelo_180_file(ret: &WithholdingReturn, options: &FilingOptions) -> Vec<u8> {
+    let year = u64::try_from(ret.period.year).unwrap_or_default();
+    let mut records = vec![declarant_record("180", year, &ret.declarant, options)
+        .num(9, ret.lines.len() as u64)
+        .amount(15, ret.total_base)
+        .amount(15, ret.total_withholding)
+        .finish()];
+
+    records.extend(
+        ret.lines
+            .iter()
+            .map(|line| withholding_record(year, &ret.declarant, line)),
+    );
+    encode(&records.join("\r\n"))
+}
+
+fn withholding_record(year: u64, declarant: &TaxParty, line: &WithholdingLine) -> String {
+    let postcode = postal_code(&line.property_address).unwrap_or_default();
+    let property_province = postcode.get(..2).unwrap_or_default();
+
+    Record::new()
+        .text("2180")
+        .num(4, year)
+        .alpha(9, &declarant.tax_id)
+        .alpha(9, &line.perceptor_tax_id)
+        .blank(9)
+        .alpha(40, &line.perceptor_name)
+        .alpha(2, &line.province_code)
+        .text("1")
+        .amount(13, line.base)
+        .amount(4, line.rate)
+        .amount(13, line.withholding)
+        .num(4, year)
+        // Situation 3: located in Spain, no cadastral reference on file
+        .text("3")
+        .blank(20)
+        .blank(5)
+        .alpha(50, &line.property_address)
+        .blank(3 + 5 + 3 + 3 + 3 + 3 + 3 + 3 + 40 + 30 + 30 + 5)
+        .alpha(2, property_province)
+        .alpha(5, &postcode)
+        .finish()
+}
+
+/// Modelo 347 file: one owner's operations with third parties over the threshold.
+pub fn modelo_347_file(ret: &ThirdPartyReturn, options: &FilingOptions) -> Vec<u8> {
+    let year = u64::try_from(ret.period.year).unwrap_or_default();
+    let mut records = vec![declarant_record("347", year, &ret.declarant, options)
+        .num(9, ret.lines.len() as u64)
+        .sign(ret.total_amount)
+        .amount(15, ret.total_amount.abs())
+        .num(9, 0)
+        .sign(0.0)
+        .amount(15, 0.0)
+        .finish()];
+
+    records.extend(
+        ret.lines
+            .iter()
+            .map(|line| third_party_record(year, &ret.declarant, line)),
+    );
+    encode(&records.join("\r\n"))
+}
+
+fn third_party_record(year: u64, declarant: &TaxParty, line: &ThirdPartyLine) -> String {
+    let mut record = Record::new()
+        .text("2347")
+        .num(4, year)
+        .alpha(9, &declarant.tax_id)
+        .alpha(9, &line.tax_id)
+        .blank(9)
+        .alpha(40, &line.name)
+        .text("D")
+        .alpha(2, &line.province_code)
+        .blank(2)
+        .blank(1)
+        .text(&line.operation_key.to_string())
+        .sign(line.annual_amount)
+        .amount(15, line.annual_amount.abs())
+        .blank(1)
+        .blank(1)
+        .amount(15, 0.0)
+        .sign(0.0)
+        .amount(15, 0.0)
+        .num(4, 0);
+
+    for amount in line.quarterly_amounts {
+        record = record
+            .sign(amount)
+            .amount(15, amount.abs())
+            .sign(0.0)
+            .amount(15, 0.0);
+    }
+
+    record.blank(17).blank(3).sign(0.0).amount(15, 0.0).finish()
+}
+
+/// Type 1 record fields shared by Modelo 180 and 347 (positions 1-135).
+fn declarant_record(
+    model: &str,
+    year: u64,
+    declarant: &TaxParty,
+    options: &FilingOptions,
+) -> Record {
+    let contact = if options.contact_name.is_empty() {
+        &declarant.name
+    } else {
+        &options.contact_name
+    };
+    let phone: String = declarant
+        .phone
+        .chars()
+        .filter(char::is_ascii_digit)
+        .collect();
+    let phone = phone
+        .get(phone.len().saturating_sub(9)..)
+        .unwrap_or_default();
+    let declaration_id: u64 = options.declaration_id.trim().parse().unwrap_or_default();
+
+    Record::new()
+        .text("1")
+        .text(model)
+        .num(4, year)
+        .alpha(9, &declarant.tax_id)
+        .alpha(40, &declarant.name)
+        .text("T")
+        .num(9, phone.parse().unwrap_or_default())
+        .alpha(40, contact)
+        .num(13, declaration_id)
+        .blank(2)
+        .num(13, 0)
+}
+
+/// Amount in whole cents, rounded.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn cents(value: f64) -> u64 {
+    (value.abs() * 100.0).round() as u64
+}
+
+/// Upper-case and strip accents; AEAT accepts only `Ñ` and `Ç` outside ASCII.
+fn normalize(value: &str) -> String {
+    value
+        .trim()
+        .to_uppercase()
+        .chars()
+        .map(|c| match c {
+            'Á' | 'À' | 'Â' | 'Ä' => 'A',
+            'É' | 'È' | 'Ê' | 'Ë' => 'E',
+            'Í' | 'Ì' | 'Î' | 'Ï' => 'I',
+            'Ó' | 'Ò' | 'Ô' | 'Ö' => 'O',
+            'Ú' | 'Ù' | 'Û' | 'Ü' => 'U',
+            'Ñ' | 'Ç' => c,
+            c if c.is_ascii_graphic() || c == ' ' => c,
+            _ => ' ',
+        })
+        .collect()
+}
+
+/// Encode as ISO-8859-1. Every character left after `normalize` fits in one byte.
+fn encode(value: &str) -> Vec<u8> {
+    value
+        .chars()
+        .map(|c| u8::try_from(u32::from(c)).unwrap_or(b' '))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::tax::TaxPeriod;
+
+    fn declarant() -> TaxParty {
+        TaxParty {
+            tax_id: "b12345678".to_string(),
+            name: "Inmobiliaria Peña S.L.".to_string(),
+            phone: "+34 912 345 678".to_string(),
+        }
+    }
+
+    fn withholding_return(period: TaxPeriod) -> WithholdingReturn {
+        WithholdingReturn {
+            declarant: declarant(),
+            period,
+            num_perceptors: 1,
+            total_base: 1234.5,
+            total_withholding: 234.56,
+            lines: vec![WithholdingLine {
+                perceptor_tax_id: "12345678Z".to_string(),
+                perceptor_name: "José Núñez".to_string(),
+                province_code: "28".to_string(),
+                property_name: "Atocha 1".to_string(),
+                property_address: "Calle Atocha 1, 28012 Madrid".to_string(),
+                base: 1234.5,
+                rate: 19.0,
+                withholding: 234.56,
+            }],
+        }
+    }
+
+    fn third_party_return() -> ThirdPartyReturn {
+        ThirdPartyReturn {
+            declarant: declarant(),
+            period: TaxPeriod::year(Some(2025)).unwrap(),
+            num_counterparties: 1,
+            total_amount: 3600.0,
+            lines: vec![ThirdPartyLine {
+                tax_id: "12345678Z".to_string(),
+                name: "José Núñez".to_string(),
+                province_code: "08".to_string(),
+                operation_key: 'B',
+                annual_amount: 3600.0,
+                quarterly_amounts: [1200.0, 1200.0, 1450.75, -250.75],
+            }],
+        }
+    }
+
+    /// Records of a fixed-width file, checking they are separated by CRLF.
+    fn records(file: &[u8]) -> Vec<&[u8]> {
+        let lines: Vec<&[u8]> = file.split(|b| *b == b'\n').collect();
+        let (last, rest) = lines.split_last().expect("at least one record");
+        assert!(
+            rest.iter().all(|r| r.ends_with(b"\r")),
+            "records end in CRLF"
+        );
+        assert!(!last.contains(&b'\r'));
+        rest.iter()
+            .map(|r| &r[..r.len() - 1])
+            .chain(std::iter::once(*last))
+            .collect()
+    }
+
+    #[test]
+    fn modelo_180_has_a_declarant_and_a_perceptor_record() {
+        let ret = withholding_return(TaxPeriod::year(Some(2025)).unwrap());
+        let file = modelo_180_file(&ret, &FilingOptions::default());
+        let records = records(&file);
+        assert_eq!(records.len(), 2);
+        assert!(records.iter().all(|r| r.len() == RECORD_LEN));
+
+        let header = records[0];
+        assert_eq!(&header[..8], b"11802025");
+        assert_eq!(&header[8..17], b"B12345678");
+        assert_eq!(&header[17..39], b"INMOBILIARIA PE\xD1A S.L.");
+        assert_eq!(&header[57..67], b"T912345678");
+        assert_eq!(&header[107..120], b"0000000000000");
+        assert_eq!(&header[135..144], b"000000001");
+        assert_eq!(&header[144..159], b"000000000123450");
+        assert_eq!(&header[159..174], b"000000000023456");
+
+        let line = records[1];
+        assert_eq!(&line[..8], b"21802025");
+        assert_eq!(&line[17..26], b"12345678Z");
+        assert_eq!(&line[35..45], b"JOSE NU\xD1EZ");
+        assert!(line[45..75].iter().all(|b| *b == b' '));
+        assert_eq!(&line[75..78], b"281");
+        assert_eq!(&line[78..91], b"0000000123450");
+        assert_eq!(&line[91..95], b"1900");
+        assert_eq!(&line[95..108], b"0000000023456");
+        assert_eq!(&line[319..326], b"2828012");
+    }
+
+    #[test]
+    fn modelo_347_signs_negative_quarters() {
+        let file = modelo_347_file(&third_party_return(), &FilingOptions::default());
+        let records = records(&file);
+        assert_eq!(records.len(), 2);
+        assert!(records.iter().all(|r| r.len() == RECORD_LEN));
+
+        let header = records[0];
+        assert_eq!(&header[..8], b"13472025");
+        assert_eq!(&header[135..144], b"000000001");
+        assert_eq!(&header[144..160], b" 000000000360000");
+
+        let line = records[1];
+        assert_eq!(&line[..8], b"23472025");
+        assert_eq!(&line[17..26], b"12345678Z");
+        assert_eq!(&line[35..45], b"JOSE NU\xD1EZ");
+        assert_eq!(&line[75..78], b"D08");
+        assert_eq!(&line[81..98], b"B 000000000360000");
+        assert_eq!(&line[135..151], b" 000000000120000");
+        assert_eq!(&line[199..215], b" 000000000145075");
+        assert_eq!(&line[231..247], b"N000000000025075");
+    }
+
+    #[test]
+    fn modelo_115_wraps_the_page_in_its_tags() {
+        let ret = withholding_return(TaxPeriod::quarter(Some(2025), Some(2)).unwrap());
+        let options = FilingOptions {
+            iban: "ES9121000418450200051332".to_string(),
+            ..FilingOptions::default()
+        };
+        let file = modelo_115_file(&ret, &options);
+        assert!(file.starts_with(b"<T115020252T0000><AUX>"));
+        assert!(file.ends_with(b"</T11501000></T115020252T0000>"));
+        assert!(!file.contains(&b'\n'));
+
+        let page = b"<T11501000>";
+        let start = file
+            .windows(page.len())
+            .position(|w| w == page)
+            .expect("page 1")
+            + page.len();
+        let body = &file[start..];
+        assert_eq!(&body[..11], b" UB12345678");
+        assert_eq!(&body[91..97], b"20252T");
+        assert_eq!(&body[97..112], b"000000000000001");
+        assert_eq!(&body[112..129], b"00000000000123450");
+        assert_eq!(&body[129..146], b"00000000000023456");
+        assert_eq!(&body[163..180], b"00000000000023456");
+        assert_eq!(&body[194..218], b"ES9121000418450200051332");
+    }
+
+    #[test]
+    fn modelo_115_kind_follows_the_result_and_iban() {
+        let mut ret = withholding_return(TaxPeriod::quarter(Some(2025), Some(1)).unwrap());
+        let kind = |ret: &WithholdingReturn| {
+            let file = modelo_115_file(ret, &FilingOptions::default());
+            let at = file.windows(11).position(|w| w == b"<T11501000>").unwrap();
+            file[at + 12]
+        };
+        assert_eq!(kind(&ret), b'I');
+        ret.total_withholding = 0.0;
+        assert_eq!(kind(&ret), b'N');
+    }
+
+    #[test]
+    fn text_is_upper_case_latin_1_without_accents() {
+        assert_eq!(normalize(" Peña, Müller & Cía. "), "PEÑA, MULLER & CIA.");
+        assert_eq!(normalize("Zoë €"), "ZOE  ");
+        assert_eq!(encode("PEÑA Ç"), b"PE\xD1A \xC7");
+        assert_eq!(Record::new().alpha(6, "Núñez García").0, "NUÑEZ ");
+        assert_eq!(Record::new().num(4, 123_456).0, "3456");
+        assert_eq!(Record::new().amount(8, -12.35).0, "00001235");
+        assert_eq!(Record::new().sign(-1.0).sign(0.0).0, "N ");
+    }
+}
@@ -0,0 +1,447 @@
+//! Property-management operations for the AI agent.
+//!
+//! The MCP server exposes these as typed tools; the request types here are
+//! the tools' inputs. Reads reuse the admin reports and writes are audited
+//! like any other change, with the agent's user as the actor.
+
+use chrono::{Datelike, Local, NaiveDate};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::api::actor::Actor;
+use crate::api::filters::escape_like;
+use crate::api::generic::write_audit;
+use crate::error::AdminError;
+use crate::services::contract_documents::list_documents;
+use crate::services::portal::ISSUE_PRIORITIES;
+use crate::services::reporting::{arrears, ReportFilter};
+
+pub const DEFAULT_LIMIT: i64 = 10;
+pub const MAX_LIMIT: i64 = 50;
+
+const PROPERTY_JSON: &str = "json_build_object('id', p.id, 'property_name', p.property_name, \
+    'address', p.address, 'status', p.status, 'rent', p.rent::float8, \
+    'bedrooms', p.bedrooms, 'bathrooms', p.bathrooms, 'sqm', p.sqm::float8, \
+    'contract_ref', p.contract_ref, 'tenant_name', c.tenant_name, \
+    'tags', p.tags)";
+
+const ISSUE_JSON: &str = "json_build_object('id', id, 'property_name', property_name, \
+    'title', title, 'description', description, 'priority', priority, \
+    'status', status, 'created_at', created_at)";
+
+// ── Properties ──────────────────────────────────────────────────────────
+
+/// Input of `search_properties`.
+#[derive(Debug, Deserialize, Default)]
+pub struct PropertySearch {
+    /// Words in the name, address or contract reference; accents and case
+    /// are ignored
+    pub query: Option<String>,
+    /// Available, Let, ...
+    pub status: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// Live properties matching the search, with the tenant of their contract.
+pub async fn search_properties(
+    pool: &PgPool,
+    params: &PropertySearch,
+) -> Result<Vec<Value>, sqlx::Error> {
+    let query = params
+        .query
+        .as_deref()
+        .map(str::trim)
+        .filter(|q| !q.is_empty());
+    let status = params
+        .status
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+    let limit = params.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    sqlx::query_scalar(&format!(
+        "SELECT {PROPERTY_JSON} FROM admin_properties p \
+         LEFT JOIN admin_contracts c ON c.contract_ref = p.contract_ref \
+            AND p.contract_ref <> '' AND c.deleted_at IS NULL \
+         WHERE p.deleted_at IS NULL \
+            AND ($1::text IS NULL OR lower(admin_unaccent(\
+                p.property_name || ' ' || p.address || ' ' || p.contract_ref)) \
+                LIKE '%' || lower(admin_unaccent($1)) || '%') \
+            AND ($2::text IS NULL OR p.status = $2) \
+         ORDER BY p.property_name LIMIT $3"
+    ))
+    .bind(query)
+    .bind(status)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+// ── Contracts ───────────────────────────────────────────────────────────
+
+/// A contract by id or by reference. Input of `get_contract`.
+#[derive(Debug, Deserialize, Default)]
+pub struct ContractLookup {
+    pub contract_id: Option<Uuid>,
+    pub contract_ref: Option<String>,
+}
+
+/// The contract with its documents, deposits, latest invoices and the open
+/// issues on its property.
+pub async fn contract_detail(pool: &PgPool, lookup: &ContractLookup) -> Result<Value, AdminError> {
+    let (id, contract) = find_contract(pool, lookup).await?;
+    let documents = list_documents(pool, Some(id)).await?;
+    let related: Value = sqlx::query_scalar(&format!(
+        "SELECT json_build_object(\
+            'deposits', (SELECT COALESCE(json_agg(json_build_object('id', id, \
+                'deposit_type', deposit_type, 'status', status, 'amount', amount::float8, \
+                'paid', paid::float8, 'refunded', refunded::float8) ORDER BY deposit_date), '[]') \
+                FROM admin_deposits WHERE contract_ref = $1 AND deleted_at IS NULL), \
+            'invoices', (SELECT COALESCE(json_agg(t ORDER BY t.invoice_date DESC), '[]') FROM (\
+                SELECT id, reference, description, status, amount::float8 AS amount, \
+                    paid::float8 AS paid, invoice_date, payment_date \
+                FROM admin_invoices WHERE contract_ref = $1 AND deleted_at IS NULL \
+                ORDER BY invoice_date DESC NULLS LAST LIMIT 12) t), \
+            'open_issues', (SELECT COALESCE(json_agg({ISSUE_JSON} ORDER BY created_at DESC), '[]') \
+                FROM admin_issues WHERE property_name = $2 AND deleted_at IS NULL \
+                AND status NOT IN ('Resolved', 'Closed')))"
+    ))
+    .bind(contract["contract_ref"].as_str().unwrap_or_default())
+    .bind(contract["property_name"].as_str().unwrap_or_default())
+    .fetch_one(pool)
+    .await?;
+    Ok(json!({
+        "contract": contract,
+        "documents": documents,
+        "deposits": related["deposits"],
+        "invoices": related["invoices"],
+        "open_issues": related["open_issues"],
+    }))
+}
+
+async fn find_contract(
+    pool: &PgPool,
+    lookup: &ContractLookup,
+) -> Result<(Uuid, Value), AdminError> {
+    let reference = lookup
+        .contract_ref
+        .as_deref()
+        .map(str::trim)
+        .filter(|r| !r.is_empty());
+    if lookup.contract_id.is_none() && reference.is_none() {
+        return Err(AdminError::BadRequest(
+            "contract_id or contract_ref is required".to_string(),
+        ));
+    }
+    let row: Option<(Uuid, Value)> = sqlx::query_as(
+        "SELECT id, row_to_json(c) FROM admin_contracts c \
+         WHERE deleted_at IS NULL AND ($1::uuid IS NULL OR id = $1) \
+            AND ($2::text IS NULL OR lower(contract_ref) = lower($2)) \
+         ORDER BY start_date DESC NULLS LAST LIMIT 1",
+    )
+    .bind(lookup.contract_id)
+    .bind(reference)
+    .fetch_optional(pool)
+    .await?;
+    row.ok_or_else(|| AdminError::NotFound("contract".to_string()))
+}
+
+// ── Arrears ─────────────────────────────────────────────────────────────
+
+/// Outstanding rent by tenant and property, with the total owed.
+pub async fn arrears_summary(pool: &PgPool, filter: ReportFilter) -> Result<Value, AdminError> {
+    let filter = filter.validate()?;
+    let rows = arrears(pool, &filter).await?;
+    let total_debt: f64 = rows
+        .as_array()
+        .map(|rows| rows.iter().filter_map(|r| r["debt"].as_f64()).sum())
+        .unwrap_or_default();
+    Ok(json!({
+        "data": rows,
+        "total_debt": (total_debt * 100.0).round() / 100.0,
+        "filters": filter,
+    }))
+}
+
+// ── Issues ──────────────────────────────────────────────────────────────
+
+/// Input of `create_issue`.
+#[derive(Debug, Deserialize)]
+pub struct IssueRequest {
+    /// Property name as listed, or a unique part of it ("Flat 3B")
+    pub property: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    pub priority: Option<String>,
+}
+
+impl IssueRequest {
+    /// The trimmed title and the priority, `Medium` by default.
+    fn validate(&self) -> Result<(&str, &str), AdminError> {
+        let title = self.title.trim();
+        if title.is_empty() {
+            return Err(AdminError::BadRequest("title is required".to_string()));
+        }
+        let priority = self.priority.as_deref().unwrap_or("Medium");
+        if !ISSUE_PRIORITIES.contains(&priority) {
+            return Err(AdminError::BadRequest(format!(
+                "priority must be one of: {}",
+                ISSUE_PRIORITIES.join(", ")
+            )));
+        }
+        Ok((title, priority))
+    }
+}
+
+/// Open a maintenance issue on a property.
+pub async fn create_issue(
+    pool: &PgPool,
+    actor: &Actor,
+    request: &IssueRequest,
+) -> Result<Value, AdminError> {
+    let (title, priority) = request.validate()?;
+    let property_name = resolve_property(pool, &request.property).await?;
+
+    let mut tx = pool.begin().await?;
+    let (id, issue): (Uuid, Value) = sqlx::query_as(&format!(
+        "INSERT INTO admin_issues (property_name, title, description, priority, status) \
+         VALUES ($1, $2, $3, $4, 'Open') RETURNING id, {ISSUE_JSON}"
+    ))
+    .bind(&property_name)
+    .bind(title)
+    .bind(request.description.trim())
+    .bind(priority)
+    .fetch_one(&mut *tx)
+    .await?;
+    write_audit(
+        &mut *tx,
+        actor,
+        "issues",
+        &id.to_string(),
+        "create",
+        None,
+        Some(&issue),
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(issue)
+}
+
+/// The one live property whose name is, or else contains, `property`.
+async fn resolve_property(pool: &PgPool, property: &str) -> Result<String, AdminError> {
+    let property = property.trim();
+    if property.is_empty() {
+        return Err(AdminError::BadRequest("property is required".to_string()));
+    }
+    let names: Vec<String> = sqlx::query_scalar(
+        "SELECT property_name FROM admin_properties \
+         WHERE deleted_at IS NULL AND lower(admin_unaccent(property_name)) \
+            LIKE '%' || lower(admin_unaccent($2)) || '%' \
+         ORDER BY lower(property_name) = lower($1) DESC, property_name LIMIT 6",
+    )
+    .bind(property)
+    .bind(escape_like(property))
+    .fetch_all(pool)
+    .await?;
+    pick_property(property, &names)
+}
+
+/// The exact match among `names` (listed first), or the only partial one.
+fn pick_property(property: &str, names: &[String]) -> Result<String, AdminError> {
+    match names {
+        [] => Err(AdminError::NotFound(format!("property '{property}'"))),
+        [first, ..] if first.eq_ignore_ascii_case(property) => Ok(first.clone()),
+        [only] => Ok(only.clone()),
+        many => Err(AdminError::BadRequest(format!(
+            "'{property}' matches several properties: {}",
+            many.join(", ")
+        ))),
+    }
+}
+
+// ── Invoices ────────────────────────────────────────────────────────────
+
+/// Input of `draft_invoice`. Fields left out are taken from the contract.
+#[derive(Debug, Deserialize, Default)]
+pub struct InvoiceDraftRequest {
+    #[serde(flatten)]
+    pub contract: ContractLookup,
+    /// Defaults to today
+    pub invoice_date: Option<NaiveDate>,
+    /// Defaults to the contract rent
+    pub amount: Option<f64>,
+    pub description: Option<String>,
+    /// Record the invoice; otherwise it is only returned for review
+    #[serde(default)]
+    pub save: bool,
+}
+
+/// A rent invoice for the contract: the tenant pays the property's owner.
+/// Unless `save` is set nothing is written, so the draft can be confirmed
+/// with the user first.
+pub async fn draft_invoice(
+    pool: &PgPool,
+    actor: &Actor,
+    request: &InvoiceDraftRequest,
+) -> Result<Value, AdminError> {
+    let (_, contract) = find_contract(pool, &request.contract).await?;
+    let text = |key: &str| contract[key].as_str().unwrap_or_default().to_string();
+    let (contract_ref, property_name) = (text("contract_ref"), text("property_name"));
+    let amount = request
+        .amount
+        .or_else(|| contract["rent"].as_f64())
+        .unwrap_or_default();
+    if amount <= 0.0 {
+        return Err(AdminError::BadRequest(
+            "amount must be positive".to_string(),
+        ));
+    }
+    let invoice_date = request
+        .invoice_date
+        .unwrap_or_else(|| Local::now().date_naive());
+    let payee: Option<String> = sqlx::query_scalar(
+        "SELECT name FROM admin_owners WHERE deleted_at IS NULL \
+            AND $1 = ANY(string_to_array(property_name, ' / ')) \
+         ORDER BY name LIMIT 1",
+    )
+    .bind(&property_name)
+    .fetch_optional(pool)
+    .await?;
+    let description = request
+        .description
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map_or_else(|| format!("Monthly rent - {property_name}"), str::to_string);
+    let mut invoice = json!({
+        "description": description,
+        "contract_ref": contract_ref,
+        "property_name": property_name,
+        "payer": text("tenant_name"),
+        "payee": payee.unwrap_or_default(),
+        "status": "Unpaid",
+        "amount": amount,
+        "invoice_date": invoice_date,
+        "type": "income",
+    });
+    if !request.save {
+        let mut conn = pool.acquire().await?;
+        invoice["reference"] = json!(next_reference(&mut conn, invoice_date.year()).await?);
+        return Ok(json!({ "saved": false, "invoice": invoice }));
+    }
+    let saved = insert_invoice(pool, actor, invoice, invoice_date.year()).await?;
+    Ok(json!({ "saved": true, "invoice": saved }))
+}
+
+/// Next free `INV-<year>-NNN` reference. Inside a transaction the number
+/// stays reserved until it commits: concurrent callers wait on the same
+/// advisory lock and then see the new invoice.
+async fn next_reference(conn: &mut PgConnection, year: i32) -> Result<String, sqlx::Error> {
+    let prefix = format!("INV-{year}-");
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtext('admin_invoices.reference'))")
+        .execute(&mut *conn)
+        .await?;
+    let last: i64 = sqlx::query_scalar(
+        "SELECT COALESCE(MAX(substring(reference FROM length($1) + 1)::bigint), 0) \
+         FROM admin_invoices WHERE reference LIKE $1 || '%' \
+            AND substring(reference FROM length($1) + 1) ~ '^[0-9]+$'",
+    )
+    .bind(&prefix)
+    .fetch_one(&mut *conn)
+    .await?;
+    Ok(format!("{prefix}{:03}", last + 1))
+}
+
+/// Insert a drafted invoice under a reference allocated in the same
+/// transaction, so two saves cannot take the same number.
+async fn insert_invoice(
+    pool: &PgPool,
+    actor: &Actor,
+    mut invoice: Value,
+    year: i32,
+) -> Result<Value, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    invoice["reference"] = json!(next_reference(&mut tx, year).await?);
+    let (id, row): (Uuid, Value) = sqlx::query_as(
+        "INSERT INTO admin_invoices (reference, description, contract_ref, property_name, \
+            payer, payee, status, amount, invoice_date, type) \
+         SELECT reference, description, contract_ref, property_name, payer, payee, status, \
+            amount, invoice_date, type \
+         FROM jsonb_populate_record(NULL::admin_invoices, $1) \
+         RETURNING id, row_to_json(admin_invoices.*)",
+    )
+    .bind(&invoice)
+    .fetch_one(&mut *tx)
+    .await?;
+    write_audit(
+        &mut *tx,
+        actor,
+        "invoices",
+        &id.to_string(),
+        "create",
+        None,
+        Some(&row),
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(row)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| (*n).to_string()).collect()
+    }
+
+    fn issue(title: &str, priority: Option<&str>) -> IssueRequest {
+        IssueRequest {
+            property: "Flat 3B".to_string(),
+            title: title.to_string(),
+            description: String::new(),
+            priority: priority.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn exact_property_name_wins_over_partial_matches() {
+        let found = pick_property("flat 3", &names(&["Flat 3", "Flat 3A", "Flat 3B"])).unwrap();
+        assert_eq!(found, "Flat 3");
+    }
+
+    #[test]
+    fn single_partial_match_is_taken() {
+        let found = pick_property("3b", &names(&["Calle Mayor 3B"])).unwrap();
+        assert_eq!(found, "Calle Mayor 3B");
+    }
+
+    #[test]
+    fn ambiguous_or_missing_property_is_an_error() {
+        assert!(matches!(
+            pick_property("Flat", &names(&["Flat 3A", "Flat 3B"])),
+            Err(AdminError::BadRequest(msg))
+                if msg == "'Flat' matches several properties: Flat 3A, Flat 3B"
+        ));
+        assert!(matches!(
+            pick_property("Villa", &[]),
+            Err(AdminError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn issue_needs_a_title_and_a_known_priority() {
+        assert_eq!(
+            issue(" Leak ", None).validate().unwrap(),
+            ("Leak", "Medium")
+        );
+        assert_eq!(
+            issue("Leak", Some("Low")).validate().unwrap(),
+            ("Leak", "Low")
+        );
+        assert!(issue("  ", None).validate().is_err());
+        assert!(issue("Leak", Some("medium")).validate().is_err());
+    }
+}
@@ -0,0 +1,258 @@
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AdminError;
+
+/// A property with everything attached to it directly or through its
+/// building.
+///
+/// For a unit in a building, the building's insurance and issues are listed
+/// with the unit's own (`scope` says which), and `community_fee` adds the
+/// unit's share of the building fee to its own. For a building, `units`
+/// lists the units with their fee shares and `totals` rolls them up, and the
+/// issues of every unit are listed with the building's.
+pub async fn property_rollup(pool: &PgPool, id: Uuid) -> Result<Value, AdminError> {
+    let property = fetch_property(pool, id)
+        .await?
+        .ok_or_else(|| AdminError::NotFound("property".to_string()))?;
+
+    let building = if property["kind"] == "building" {
+        Some(property.clone())
+    } else {
+        match record_id(&property["parent_id"]) {
+            Some(parent_id) => fetch_property(pool, parent_id).await?,
+            None => None,
+        }
+    };
+    let units = match building.as_ref().and_then(|b| record_id(&b["id"])) {
+        Some(building_id) => fetch_units(pool, building_id).await?,
+        None => Vec::new(),
+    };
+
+    let building_name = building
+        .as_ref()
+        .and_then(|b| b["property_name"].as_str())
+        .unwrap_or_default();
+    let is_building = property["kind"] == "building";
+    let names = attached_names(&property, building_name, &units);
+
+    let (insurance, issues) = tokio::join!(
+        attached(pool, "admin_insurance", &names, building_name),
+        attached(pool, "admin_issues", &names, building_name),
+    );
+    let (insurance, issues) = (insurance?, issues?);
+
+    let own_fee = property["community_fee"].as_f64().unwrap_or_default();
+    let from_building = if is_building {
+        0.0
+    } else {
+        units
+            .iter()
+            .find(|u| u["id"] == property["id"])
+            .and_then(|u| u["building_fee_share"].as_f64())
+            .unwrap_or_default()
+    };
+
+    let mut body = json!({
+        "property": property,
+        "building": building,
+        "community_fee": {
+            "own": own_fee,
+            "from_building": from_building,
+            "total": own_fee + from_building,
+        },
+        "insurance": insurance,
+        "issues": issues,
+    });
+    if is_building {
+        body["totals"] = unit_totals(&units, &issues);
+        body["units"] = Value::Array(units);
+    }
+    Ok(body)
+}
+
+async fn fetch_property(pool: &PgPool, id: Uuid) -> Result<Option<Value>, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT row_to_json(p) FROM admin_properties p WHERE p.id = $1 AND p.deleted_at IS NULL",
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Live units of a building with their monthly share of its community fee:
+/// `fee_share` percent where set, the rest split among the other units by
+/// floor area, or evenly when any of them has no area recorded.
+async fn fetch_units(pool: &PgPool, building_id: Uuid) -> Result<Vec<Value>, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT row_to_json(u) FROM (\
+            SELECT p.id, p.property_name, p.status, p.rent, p.bedrooms, p.bathrooms, p.sqm, \
+                p.floor, p.fee_share, p.community_fee, \
+                round(b.community_fee * CASE \
+                    WHEN p.fee_share IS NOT NULL THEN p.fee_share / 100 \
+                    ELSE GREATEST(0, 100 - COALESCE(SUM(p.fee_share) OVER (), 0)) / 100 * CASE \
+                        WHEN COUNT(*) FILTER (WHERE p.fee_share IS NULL AND p.sqm IS NULL) OVER () = 0 \
+                        THEN p.sqm / SUM(p.sqm) FILTER (WHERE p.fee_share IS NULL) OVER () \
+                        ELSE 1.0 / COUNT(*) FILTER (WHERE p.fee_share IS NULL) OVER () \
+                    END \
+                END, 2)::float8 AS building_fee_share \
+            FROM admin_properties p JOIN admin_properties b ON b.id = p.parent_id \
+            WHERE p.parent_id = $1 AND p.deleted_at IS NULL \
+            ORDER BY p.property_name\
+         ) u",
+    )
+    .bind(building_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Live rows of `table` attached to any of `names`, newest first, each with
+/// a `scope` of `building` or `unit`.
+async fn attached(
+    pool: &PgPool,
+    table: &str,
+    names: &[&str],
+    building_name: &str,
+) -> Result<Vec<Value>, sqlx::Error> {
+    sqlx::query_scalar(&format!(
+        "SELECT row_to_json(t) FROM (\
+            SELECT r.*, CASE WHEN r.property_name = $2 THEN 'building' ELSE 'unit' END AS scope \
+            FROM {table} r \
+            WHERE r.property_name = ANY($1) AND r.deleted_at IS NULL \
+            ORDER BY r.created_at DESC\
+         ) t"
+    ))
+    .bind(names)
+    .bind(building_name)
+    .fetch_all(pool)
+    .await
+}
+
+/// Property names whose records belong on the rollup: a unit sees its
+/// building's records, a building sees its units'.
+fn attached_names<'a>(
+    property: &'a Value,
+    building_name: &'a str,
+    units: &'a [Value],
+) -> Vec<&'a str> {
+    let mut names = vec![property["property_name"].as_str().unwrap_or_default()];
+    if property["kind"] == "building" {
+        names.extend(units.iter().filter_map(|u| u["property_name"].as_str()));
+    } else if !building_name.is_empty() {
+        names.push(building_name);
+    }
+    names
+}
+
+/// Unit counts, rent and area of a building, and the state of its issues.
+fn unit_totals(units: &[Value], issues: &[Value]) -> Value {
+    let count_status = |status: &str| units.iter().filter(|u| u["status"] == status).count();
+    let sum = |rows: &[Value], field: &str| -> f64 {
+        rows.iter().filter_map(|r| r[field].as_f64()).sum()
+    };
+    let open_issues: Vec<Value> = issues
+        .iter()
+        .filter(|i| !matches!(i["status"].as_str(), Some("Resolved" | "Closed")))
+        .cloned()
+        .collect();
+    json!({
+        "units": units.len(),
+        "let": count_status("Let"),
+        "available": count_status("Available"),
+        "rent": sum(units, "rent"),
+        "sqm": sum(units, "sqm"),
+        "building_fee_allocated": sum(units, "building_fee_share"),
+        "open_issues": open_issues.len(),
+        "open_issue_cost": sum(&open_issues, "cost"),
+    })
+}
+
+fn record_id(value: &Value) -> Option<Uuid> {
+    value.as_str().and_then(|id| Uuid::parse_str(id).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit(name: &str, status: &str, rent: f64, sqm: Option<f64>, fee_share: f64) -> Value {
+        json!({
+            "property_name": name,
+            "status": status,
+            "rent": rent,
+            "sqm": sqm,
+            "building_fee_share": fee_share,
+        })
+    }
+
+    fn units() -> Vec<Value> {
+        vec![
+            unit("Mayor 3 1A", "Let", 900.0, Some(70.0), 60.5),
+            unit("Mayor 3 1B", "Available", 750.0, None, 39.5),
+            unit("Mayor 3 Bajo", "Let", 1200.0, Some(95.5), 0.0),
+        ]
+    }
+
+    #[test]
+    fn building_sees_its_units_records() {
+        let building = json!({"property_name": "Mayor 3", "kind": "building"});
+        let units = units();
+        assert_eq!(
+            attached_names(&building, "Mayor 3", &units),
+            ["Mayor 3", "Mayor 3 1A", "Mayor 3 1B", "Mayor 3 Bajo"]
+        );
+    }
+
+    #[test]
+    fn unit_sees_its_buildings_records() {
+        let unit = json!({"property_name": "Mayor 3 1A", "kind": "unit"});
+        assert_eq!(
+            attached_names(&unit, "Mayor 3", &units()),
+            ["Mayor 3 1A", "Mayor 3"]
+        );
+        let standalone = json!({"property_name": "Calle Sol 8", "kind": "unit"});
+        assert_eq!(attached_names(&standalone, "", &[]), ["Calle Sol 8"]);
+    }
+
+    #[test]
+    fn totals_roll_up_units_and_open_issues() {
+        let issues = vec![
+            json!({"status": "Open", "cost": 120.0}),
+            json!({"status": "In Progress", "cost": 80.0}),
+            json!({"status": "Resolved", "cost": 300.0}),
+            json!({"status": "Closed", "cost": 50.0}),
+            json!({"status": "Open", "cost": null}),
+        ];
+        assert_eq!(
+            unit_totals(&units(), &issues),
+            json!({
+                "units": 3,
+                "let": 2,
+                "available": 1,
+                "rent": 2850.0,
+                "sqm": 165.5,
+                "building_fee_allocated": 100.0,
+                "open_issues": 3,
+                "open_issue_cost": 200.0,
+            })
+        );
+    }
+
+    #[test]
+    fn empty_building_totals_are_zero() {
+        assert_eq!(
+            unit_totals(&[], &[]),
+            json!({
+                "units": 0,
+                "let": 0,
+                "available": 0,
+                "rent": 0.0,
+                "sqm": 0.0,
+                "building_fee_allocated": 0.0,
+                "open_issues": 0,
+                "open_issue_cost": 0.0,
+            })
+        );
+    }
+}
@@ -0,0 +1,250 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use serde_json::{json, Value};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::api::actor::Actor;
+use crate::api::generic::{write_audit, AdminEntity, OwnerEntity, TenantEntity};
+use crate::error::AdminError;
+use crate::services::pdf::PdfService;
+use crate::services::pii;
+use crate::services::signatures::sha256_hex;
+use crate::services::templates::{get_template, render};
+
+/// A file attached to a contract, without its content.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct ContractDocument {
+    pub id: Uuid,
+    pub contract_id: Uuid,
+    pub name: String,
+    #[serde(rename = "type")]
+    #[sqlx(rename = "type")]
+    pub doc_type: String,
+    pub file_type: String,
+    pub original_file: String,
+    pub size: i32,
+    /// SHA-256 of the file, hex
+    pub sha256: String,
+    pub template_id: Option<Uuid>,
+    pub document_date: NaiveDate,
+    pub created_at: DateTime<Utc>,
+}
+
+const DOCUMENT_COLUMNS: &str = "id, contract_id, name, type, file_type, original_file, size, \
+                                sha256, template_id, document_date, created_at";
+
+/// Documents of one contract, or of every contract, newest first.
+pub async fn list_documents(
+    pool: &PgPool,
+    contract_id: Option<Uuid>,
+) -> Result<Vec<ContractDocument>, sqlx::Error> {
+    sqlx::query_as::<_, ContractDocument>(&format!(
+        "SELECT {DOCUMENT_COLUMNS} FROM admin_contract_documents \
+         WHERE $1::uuid IS NULL OR contract_id = $1 ORDER BY created_at DESC"
+    ))
+    .bind(contract_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// File name, content type and bytes of a document.
+pub async fn document_file(
+    pool: &PgPool,
+    id: Uuid,
+) -> Result<(String, String, Vec<u8>), AdminError> {
+    let row: Option<(String, String, Option<Vec<u8>>)> = sqlx::query_as(
+        "SELECT original_file, file_type, content FROM admin_contract_documents WHERE id = $1",
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await?;
+    match row {
+        Some((name, file_type, Some(content))) => Ok((name, file_type, content)),
+        Some(_) => Err(AdminError::NotFound("document file".to_string())),
+        None => Err(AdminError::NotFound("document".to_string())),
+    }
+}
+
+/// Delete a document and take it off its contract's document count.
+pub async fn delete_document(pool: &PgPool, actor: &Actor, id: Uuid) -> Result<(), AdminError> {
+    let mut tx = pool.begin().await?;
+    let document = sqlx::query_as::<_, ContractDocument>(&format!(
+        "DELETE FROM admin_contract_documents WHERE id = $1 RETURNING {DOCUMENT_COLUMNS}"
+    ))
+    .bind(id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AdminError::NotFound("document".to_string()))?;
+
+    sqlx::query("UPDATE admin_contracts SET doc_count = GREATEST(doc_count - 1, 0) WHERE id = $1")
+        .bind(document.contract_id)
+        .execute(&mut *tx)
+        .await?;
+    write_audit(
+        &mut *tx,
+        actor,
+        "contracts",
+        &document.contract_id.to_string(),
+        "delete_document",
+        Some(&json!({ "document_id": document.id, "name": document.name })),
+        None,
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Render a template against a contract, store the PDF as one of the
+/// contract's documents and return it with the merge fields that had no
+/// value.
+pub async fn generate_contract_document(
+    pool: &PgPool,
+    actor: &Actor,
+    contract_id: Uuid,
+    template_id: Uuid,
+) -> Result<Value, AdminError> {
+    let template = get_template(pool, template_id).await?;
+    let context = merge_context(pool, contract_id).await?;
+    let (blocks, unresolved) = render(&template, &context);
+    // Kept with the file; marks it as generated, so it can be sent for signature
+    let stored_blocks = serde_json::to_value(&blocks).unwrap_or_default();
+
+    let contract_ref = context["contract"]["contract_ref"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+    let title = template.name.clone();
+    let note = format!("{} \u{2022} {contract_ref}", template.name);
+    // Run PDF generation on blocking thread pool to avoid stalling async runtime
+    let pdf = tokio::task::spawn_blocking(move || {
+        PdfService::generate_document_pdf(&title, &blocks, &note)
+    })
+    .await
+    .map_err(|e| AdminError::PdfGeneration(format!("PDF task failed: {e}")))??;
+
+    let name = format!("{} - {contract_ref}", template.name);
+    let file_name = format!("{}.pdf", file_stem(&name));
+
+    let mut tx = pool.begin().await?;
+    let document = attach_document(
+        &mut tx,
+        contract_id,
+        &NewDocument {
+            name: &name,
+            doc_type: &template.kind,
+            file_name: &file_name,
+            content: &pdf,
+            template_id: Some(template.id),
+            blocks: Some(&stored_blocks),
+        },
+    )
+    .await?;
+    write_audit(
+        &mut *tx,
+        actor,
+        "contracts",
+        &contract_id.to_string(),
+        "generate_document",
+        None,
+        Some(&json!({
+            "document_id": document.id,
+            "template_id": template.id,
+            "name": document.name,
+        })),
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(json!({ "document": document, "unresolved": unresolved }))
+}
+
+/// A file to attach to a contract.
+pub(crate) struct NewDocument<'a> {
+    pub name: &'a str,
+    pub doc_type: &'a str,
+    pub file_name: &'a str,
+    pub content: &'a [u8],
+    pub template_id: Option<Uuid>,
+    pub blocks: Option<&'a Value>,
+}
+
+/// Store a PDF as one of the contract's documents and count it on the
+/// contract.
+pub(crate) async fn attach_document(
+    tx: &mut Transaction<'_, Postgres>,
+    contract_id: Uuid,
+    document: &NewDocument<'_>,
+) -> Result<ContractDocument, sqlx::Error> {
+    let stored = sqlx::query_as::<_, ContractDocument>(&format!(
+        "INSERT INTO admin_contract_documents \
+         (contract_id, name, type, original_file, size, sha256, content, template_id, blocks) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) RETURNING {DOCUMENT_COLUMNS}"
+    ))
+    .bind(contract_id)
+    .bind(document.name)
+    .bind(document.doc_type)
+    .bind(document.file_name)
+    .bind(i32::try_from(document.content.len()).unwrap_or(i32::MAX))
+    .bind(sha256_hex(document.content))
+    .bind(document.content)
+    .bind(document.template_id)
+    .bind(document.blocks)
+    .fetch_one(&mut **tx)
+    .await?;
+    sqlx::query("UPDATE admin_contracts SET doc_count = doc_count + 1 WHERE id = $1")
+        .bind(contract_id)
+        .execute(&mut **tx)
+        .await?;
+    Ok(stored)
+}
+
+/// Values for merge fields: the contract with its tenant (by name), its
+/// property and the property's owner (by property name), and today's date.
+/// Parties that cannot be found are left null and their fields unresolved.
+/// The parties' personal data is decrypted for the document.
+async fn merge_context(pool: &PgPool, contract_id: Uuid) -> Result<Value, AdminError> {
+    let mut context: Value = sqlx::query_scalar(
+        "SELECT json_build_object(\
+            'contract', row_to_json(c), \
+            'tenant', (SELECT row_to_json(t) FROM admin_tenants t \
+                       WHERE t.name = c.tenant_name AND t.deleted_at IS NULL \
+                       ORDER BY t.created_at DESC LIMIT 1), \
+            'property', (SELECT row_to_json(p) FROM admin_properties p \
+                         WHERE p.property_name = c.property_name AND p.deleted_at IS NULL \
+                         ORDER BY p.created_at DESC LIMIT 1), \
+            'owner', (SELECT row_to_json(o) FROM admin_owners o \
+                      WHERE o.property_name = c.property_name AND o.deleted_at IS NULL \
+                      ORDER BY o.created_at DESC LIMIT 1), \
+            'today', CURRENT_DATE) \
+         FROM admin_contracts c WHERE c.id = $1 AND c.deleted_at IS NULL",
+    )
+    .bind(contract_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AdminError::NotFound("contract".to_string()))?;
+    if let Some(tenant) = context.get_mut("tenant") {
+        pii::reveal(tenant, TenantEntity::SENSITIVE_FIELDS, true);
+    }
+    if let Some(owner) = context.get_mut("owner") {
+        pii::reveal(owner, OwnerEntity::SENSITIVE_FIELDS, true);
+    }
+    Ok(context)
+}
+
+/// A download-safe file name without extension.
+pub(crate) fn file_stem(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>()
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
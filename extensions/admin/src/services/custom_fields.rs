@@ -0,0 +1,372 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::api::filters::FieldType;
+use crate::api::generic::entity_info;
+use crate::error::AdminError;
+
+/// Prefix of custom fields in filter and sort keys: `?custom.epc_rating=B`.
+pub const CUSTOM_PREFIX: &str = "custom.";
+
+/// An agency-defined field of one entity.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct CustomField {
+    pub id: Uuid,
+    pub entity: String,
+    pub key: String,
+    pub label: String,
+    /// text, number, date, boolean or select
+    pub field_type: String,
+    pub options: Vec<String>,
+    pub position: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl CustomField {
+    pub fn filter_type(&self) -> FieldType {
+        match self.field_type.as_str() {
+            "number" => FieldType::Number,
+            "date" => FieldType::Date,
+            "boolean" => FieldType::Bool,
+            _ => FieldType::Text,
+        }
+    }
+
+    /// SQL expression for the stored value, cast to its type. Values are
+    /// checked on write, so the cast cannot fail.
+    pub fn column(&self) -> String {
+        let value = format!("(custom_fields->>'{}')", self.key);
+        match self.filter_type() {
+            FieldType::Number => format!("{value}::numeric"),
+            FieldType::Date => format!("{value}::date"),
+            FieldType::Bool => format!("{value}::boolean"),
+            _ => value,
+        }
+    }
+
+    /// Check one value against the field; `null` clears it.
+    fn check(&self, value: &Value) -> Result<(), AdminError> {
+        let valid = match (self.field_type.as_str(), value) {
+            (_, Value::Null)
+            | ("number", Value::Number(_))
+            | ("boolean", Value::Bool(_))
+            | ("text", Value::String(_)) => true,
+            ("date", Value::String(s)) => NaiveDate::parse_from_str(s, "%Y-%m-%d").is_ok(),
+            ("select", Value::String(s)) => self.options.contains(s),
+            _ => false,
+        };
+        if valid {
+            Ok(())
+        } else if self.field_type == "select" {
+            Err(AdminError::BadRequest(format!(
+                "{} must be one of: {}",
+                self.key,
+                self.options.join(", ")
+            )))
+        } else {
+            Err(AdminError::BadRequest(format!(
+                "{} must be a {} value",
+                self.key, self.field_type
+            )))
+        }
+    }
+}
+
+/// Body of POST /custom-fields.
+#[derive(Debug, Deserialize)]
+pub struct CustomFieldRequest {
+    pub entity: String,
+    pub key: String,
+    pub label: String,
+    pub field_type: String,
+    #[serde(default)]
+    pub options: Vec<String>,
+    #[serde(default)]
+    pub position: i32,
+}
+
+/// Body of PUT /custom-fields/{id}. The entity, key and type of a field
+/// are fixed once records may hold values for it.
+#[derive(Debug, Deserialize)]
+pub struct CustomFieldUpdate {
+    pub label: String,
+    #[serde(default)]
+    pub options: Vec<String>,
+    #[serde(default)]
+    pub position: i32,
+}
+
+const FIELD_COLUMNS: &str =
+    "id, entity, key, label, field_type, options, position, created_at, updated_at";
+
+/// Fields defined for an entity, in display order.
+pub async fn definitions(pool: &PgPool, entity: &str) -> Result<Vec<CustomField>, sqlx::Error> {
+    sqlx::query_as::<_, CustomField>(&format!(
+        "SELECT {FIELD_COLUMNS} FROM admin_custom_fields WHERE entity = $1 ORDER BY position, key"
+    ))
+    .bind(entity)
+    .fetch_all(pool)
+    .await
+}
+
+/// Every definition, or those of one entity.
+pub async fn list_fields(
+    pool: &PgPool,
+    entity: Option<&str>,
+) -> Result<Vec<CustomField>, sqlx::Error> {
+    sqlx::query_as::<_, CustomField>(&format!(
+        "SELECT {FIELD_COLUMNS} FROM admin_custom_fields \
+         WHERE $1::text IS NULL OR entity = $1 ORDER BY entity, position, key"
+    ))
+    .bind(entity)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn create_field(
+    pool: &PgPool,
+    request: &CustomFieldRequest,
+) -> Result<CustomField, AdminError> {
+    if entity_info(&request.entity).is_none() {
+        return Err(AdminError::BadRequest(format!(
+            "Unknown entity: {}",
+            request.entity
+        )));
+    }
+    check_key(&request.key)?;
+    if !["text", "number", "date", "boolean", "select"].contains(&request.field_type.as_str()) {
+        return Err(AdminError::BadRequest(
+            "field_type must be text, number, date, boolean or select".to_string(),
+        ));
+    }
+    check_label_and_options(&request.label, &request.field_type, &request.options)?;
+
+    sqlx::query_as::<_, CustomField>(&format!(
+        "INSERT INTO admin_custom_fields (entity, key, label, field_type, options, position) \
+         VALUES ($1, $2, $3, $4, $5, $6) RETURNING {FIELD_COLUMNS}"
+    ))
+    .bind(&request.entity)
+    .bind(&request.key)
+    .bind(request.label.trim())
+    .bind(&request.field_type)
+    .bind(&request.options)
+    .bind(request.position)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| match &e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => AdminError::Conflict(format!(
+            "{} already has a field {}",
+            request.entity, request.key
+        )),
+        _ => AdminError::Database(e),
+    })
+}
+
+pub async fn update_field(
+    pool: &PgPool,
+    id: Uuid,
+    request: &CustomFieldUpdate,
+) -> Result<CustomField, AdminError> {
+    let field_type: String =
+        sqlx::query_scalar("SELECT field_type FROM admin_custom_fields WHERE id = $1")
+            .bind(id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or_else(|| AdminError::NotFound("custom field".to_string()))?;
+    check_label_and_options(&request.label, &field_type, &request.options)?;
+
+    Ok(sqlx::query_as::<_, CustomField>(&format!(
+        "UPDATE admin_custom_fields SET label = $2, options = $3, position = $4, updated_at = NOW() \
+         WHERE id = $1 RETURNING {FIELD_COLUMNS}"
+    ))
+    .bind(id)
+    .bind(request.label.trim())
+    .bind(&request.options)
+    .bind(request.position)
+    .fetch_one(pool)
+    .await?)
+}
+
+/// Remove a definition and its values from every record of the entity.
+pub async fn delete_field(pool: &PgPool, id: Uuid) -> Result<(), AdminError> {
+    let mut tx = pool.begin().await?;
+    let (entity, key): (String, String) =
+        sqlx::query_as("DELETE FROM admin_custom_fields WHERE id = $1 RETURNING entity, key")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| AdminError::NotFound("custom field".to_string()))?;
+    if let Some(info) = entity_info(&entity) {
+        sqlx::query(&format!(
+            "UPDATE {} SET custom_fields = custom_fields - $1 WHERE custom_fields ? $1",
+            info.table
+        ))
+        .bind(&key)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Keys end up in SQL as `custom_fields->>'<key>'`, so only identifier
+/// characters are allowed.
+fn check_key(key: &str) -> Result<(), AdminError> {
+    let valid = key.len() <= 63
+        && key.starts_with(|c: char| c.is_ascii_lowercase())
+        && key
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(AdminError::BadRequest(
+            "key must be lowercase letters, digits and underscores, starting with a letter"
+                .to_string(),
+        ))
+    }
+}
+
+fn check_label_and_options(
+    label: &str,
+    field_type: &str,
+    options: &[String],
+) -> Result<(), AdminError> {
+    if label.trim().is_empty() {
+        return Err(AdminError::BadRequest("label is required".to_string()));
+    }
+    if field_type == "select" && options.is_empty() {
+        return Err(AdminError::BadRequest(
+            "A select field needs options".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Validate the `custom_fields` object of a create or update body against
+/// the entity's definitions.
+pub fn validate_values(
+    fields: &[CustomField],
+    values: &Value,
+) -> Result<Map<String, Value>, AdminError> {
+    let Some(values) = values.as_object() else {
+        return Err(AdminError::BadRequest(
+            "custom_fields must be an object".to_string(),
+        ));
+    };
+    for (key, value) in values {
+        let field = fields
+            .iter()
+            .find(|f| f.key == *key)
+            .ok_or_else(|| AdminError::BadRequest(format!("Unknown custom field: {key}")))?;
+        field.check(value)?;
+    }
+    Ok(values.clone())
+}
+
+/// The field named by a `custom.<key>` filter or sort key.
+pub fn find<'a>(fields: &'a [CustomField], name: &str) -> Option<&'a CustomField> {
+    let key = name.strip_prefix(CUSTOM_PREFIX)?;
+    fields.iter().find(|f| f.key == key)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn field(key: &str, field_type: &str, options: &[&str]) -> CustomField {
+        CustomField {
+            id: Uuid::nil(),
+            entity: "properties".to_string(),
+            key: key.to_string(),
+            label: key.to_string(),
+            field_type: field_type.to_string(),
+            options: options.iter().map(|o| (*o).to_string()).collect(),
+            position: 0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn keys_are_lowercase_identifiers() {
+        for key in ["floor", "energy_rating", "a1"] {
+            assert!(check_key(key).is_ok(), "{key}");
+        }
+        for key in ["", "Floor", "1st", "_floor", "floor-no", "floor'", "año"] {
+            assert!(check_key(key).is_err(), "{key}");
+        }
+        assert!(check_key(&"k".repeat(63)).is_ok());
+        assert!(check_key(&"k".repeat(64)).is_err());
+    }
+
+    #[test]
+    fn select_fields_need_options_and_labels_are_required() {
+        assert!(check_label_and_options("Floor", "number", &[]).is_ok());
+        assert!(check_label_and_options(" ", "number", &[]).is_err());
+        assert!(check_label_and_options("Rating", "select", &[]).is_err());
+        assert!(check_label_and_options("Rating", "select", &["A".to_string()]).is_ok());
+    }
+
+    #[test]
+    fn values_are_checked_against_their_type() {
+        let fields = [
+            field("floor", "number", &[]),
+            field("inspected", "date", &[]),
+            field("lift", "boolean", &[]),
+            field("rating", "select", &["A", "B"]),
+        ];
+        let ok = json!({"floor": 3, "inspected": "2026-02-01", "lift": true, "rating": "B"});
+        assert_eq!(
+            validate_values(&fields, &ok).unwrap(),
+            *ok.as_object().unwrap()
+        );
+        assert!(validate_values(&fields, &json!({"floor": null})).is_ok());
+
+        for bad in [
+            json!({"floor": "3"}),
+            json!({"inspected": "01/02/2026"}),
+            json!({"lift": "yes"}),
+            json!({"rating": "C"}),
+            json!({"parking": 1}),
+            json!(["floor"]),
+        ] {
+            assert!(validate_values(&fields, &bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn select_error_lists_the_options() {
+        let fields = [field("rating", "select", &["A", "B"])];
+        assert!(matches!(
+            validate_values(&fields, &json!({"rating": "C"})),
+            Err(AdminError::BadRequest(msg)) if msg == "rating must be one of: A, B"
+        ));
+    }
+
+    #[test]
+    fn find_needs_the_custom_prefix() {
+        let fields = [field("floor", "number", &[])];
+        assert!(find(&fields, "custom.floor").is_some());
+        assert!(find(&fields, "floor").is_none());
+        assert!(find(&fields, "custom.lift").is_none());
+    }
+
+    #[test]
+    fn column_casts_to_the_field_type() {
+        assert_eq!(
+            field("floor", "number", &[]).column(),
+            "(custom_fields->>'floor')::numeric"
+        );
+        assert_eq!(
+            field("rating", "select", &["A"]).column(),
+            "(custom_fields->>'rating')"
+        );
+    }
+}
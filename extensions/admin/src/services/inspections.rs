@@ -0,0 +1,932 @@
+//! Check-in and check-out inventories of a contract and what changed
+//! between them.
+//!
+//! Each inspection lists rooms and items with a condition from 5 (Excellent)
+//! to 0 (Missing), notes and photos. Comparing the check-out with the check-in
+//! item by item gives the grades each item lost; deductions are suggested
+//! from the item's replacement cost, allowing one grade for fair wear and
+//! tear, and can be applied to the contract's deposit.
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::api::actor::Actor;
+use crate::api::generic::write_audit;
+use crate::error::AdminError;
+use crate::services::contract_documents::file_stem;
+use crate::services::pdf::PdfService;
+
+pub const INSPECTION_KINDS: &[&str] = &["check_in", "check_out"];
+
+/// Condition names by grade.
+pub const CONDITIONS: &[&str] = &["Missing", "Damaged", "Poor", "Fair", "Good", "Excellent"];
+
+/// Grades an item may lose over a tenancy without a deduction.
+pub const FAIR_WEAR_GRADES: i16 = 1;
+
+/// Deduction per grade lost when an item has no replacement cost recorded.
+pub const DEFAULT_COST_PER_GRADE: f64 = 50.0;
+
+/// Largest photo accepted, in bytes.
+const MAX_PHOTO_BYTES: usize = 5 * 1024 * 1024;
+
+/// Content types accepted for photos.
+const PHOTO_TYPES: &[&str] = &["image/jpeg", "image/png", "image/webp"];
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct Inspection {
+    pub id: Uuid,
+    pub contract_id: Uuid,
+    /// `check_in` or `check_out`
+    pub kind: String,
+    pub inspection_date: NaiveDate,
+    pub inspector: String,
+    pub notes: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[sqlx(skip)]
+    pub items: Vec<InspectionItem>,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct InspectionItem {
+    pub id: Uuid,
+    pub room: String,
+    pub item: String,
+    pub condition: i16,
+    pub notes: String,
+    pub replacement_cost: Option<f64>,
+    pub photo_ids: Vec<Uuid>,
+}
+
+/// Body of PUT /contracts/{id}/inspections/{kind}. Items are matched to
+/// those already recorded by room and item, so their photos are kept;
+/// items left out are removed.
+#[derive(Debug, Deserialize)]
+pub struct InspectionRequest {
+    pub inspection_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub inspector: String,
+    #[serde(default)]
+    pub notes: String,
+    pub items: Vec<InspectionItemInput>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InspectionItemInput {
+    pub room: String,
+    pub item: String,
+    pub condition: i16,
+    #[serde(default)]
+    pub notes: String,
+    pub replacement_cost: Option<f64>,
+}
+
+/// A photo sent as JSON, for inspection items and portal issues.
+#[derive(Debug, Deserialize)]
+pub struct PhotoUpload {
+    /// `data:image/jpeg;base64,...`
+    pub data: String,
+    #[serde(default)]
+    pub caption: String,
+}
+
+/// Query parameters of the comparison: `?cost_per_grade=50`
+#[derive(Debug, Deserialize, Default)]
+pub struct DiffQuery {
+    pub cost_per_grade: Option<f64>,
+}
+
+/// One item compared between check-in and check-out.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConditionChange {
+    pub room: String,
+    pub item: String,
+    pub check_in: Option<i16>,
+    pub check_out: Option<i16>,
+    /// unchanged, worse, better, missing (not found at check-out) or new
+    pub change: &'static str,
+    pub grades_lost: i16,
+    pub check_in_notes: String,
+    pub check_out_notes: String,
+    pub replacement_cost: Option<f64>,
+    pub suggested_deduction: f64,
+    pub reason: String,
+}
+
+/// Both inspections of a contract and their comparison.
+#[derive(Debug, Clone, Serialize)]
+pub struct InspectionDiff {
+    pub contract: Value,
+    pub check_in: Option<Inspection>,
+    pub check_out: Option<Inspection>,
+    pub changes: Vec<ConditionChange>,
+    pub deposit: Option<Value>,
+    /// Sum of suggested deductions, capped at the deposit paid
+    pub suggested_total: f64,
+    pub capped: bool,
+}
+
+// ── Inspections ─────────────────────────────────────────────────────────
+
+/// The check-in and check-out of a contract, each with its items.
+pub async fn contract_inspections(
+    pool: &PgPool,
+    contract_id: Uuid,
+) -> Result<(Option<Inspection>, Option<Inspection>), sqlx::Error> {
+    let (check_in, check_out) = tokio::join!(
+        load_inspection(pool, contract_id, "check_in"),
+        load_inspection(pool, contract_id, "check_out"),
+    );
+    Ok((check_in?, check_out?))
+}
+
+async fn load_inspection(
+    pool: &PgPool,
+    contract_id: Uuid,
+    kind: &str,
+) -> Result<Option<Inspection>, sqlx::Error> {
+    let inspection = sqlx::query_as::<_, Inspection>(
+        "SELECT id, contract_id, kind, inspection_date, inspector, notes, created_at, updated_at \
+         FROM admin_inspections WHERE contract_id = $1 AND kind = $2",
+    )
+    .bind(contract_id)
+    .bind(kind)
+    .fetch_optional(pool)
+    .await?;
+    let Some(mut inspection) = inspection else {
+        return Ok(None);
+    };
+    inspection.items = sqlx::query_as::<_, InspectionItem>(
+        "SELECT i.id, i.room, i.item, i.condition, i.notes, i.replacement_cost::float8, \
+            ARRAY(SELECT p.id FROM admin_inspection_photos p \
+                  WHERE p.item_id = i.id ORDER BY p.created_at) AS photo_ids \
+         FROM admin_inspection_items i WHERE i.inspection_id = $1 \
+         ORDER BY i.position, i.room, i.item",
+    )
+    .bind(inspection.id)
+    .fetch_all(pool)
+    .await?;
+    Ok(Some(inspection))
+}
+
+/// Record or replace the check-in or check-out of a contract.
+pub async fn save_inspection(
+    pool: &PgPool,
+    actor: &Actor,
+    contract_id: Uuid,
+    kind: &str,
+    request: &InspectionRequest,
+) -> Result<Inspection, AdminError> {
+    if !INSPECTION_KINDS.contains(&kind) {
+        return Err(AdminError::BadRequest(format!(
+            "Inspection must be one of: {}",
+            INSPECTION_KINDS.join(", ")
+        )));
+    }
+    validate_items(&request.items)?;
+
+    let mut tx = pool.begin().await?;
+    let exists: bool = sqlx::query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM admin_contracts WHERE id = $1 AND deleted_at IS NULL)",
+    )
+    .bind(contract_id)
+    .fetch_one(&mut *tx)
+    .await?;
+    if !exists {
+        return Err(AdminError::NotFound("contract".to_string()));
+    }
+
+    let (inspection_id, created): (Uuid, bool) = sqlx::query_as(
+        "INSERT INTO admin_inspections (contract_id, kind, inspection_date, inspector, notes) \
+         VALUES ($1, $2, COALESCE($3, CURRENT_DATE), $4, $5) \
+         ON CONFLICT (contract_id, kind) DO UPDATE SET \
+            inspection_date = COALESCE($3, admin_inspections.inspection_date), \
+            inspector = EXCLUDED.inspector, notes = EXCLUDED.notes, updated_at = NOW() \
+         RETURNING id, xmax = 0",
+    )
+    .bind(contract_id)
+    .bind(kind)
+    .bind(request.inspection_date)
+    .bind(request.inspector.trim())
+    .bind(request.notes.trim())
+    .fetch_one(&mut *tx)
+    .await?;
+    replace_items(&mut tx, inspection_id, &request.items).await?;
+
+    write_audit(
+        &mut *tx,
+        actor,
+        "contracts",
+        &contract_id.to_string(),
+        if created {
+            "create_inspection"
+        } else {
+            "update_inspection"
+        },
+        None,
+        Some(&json!({
+            "inspection_id": inspection_id,
+            "kind": kind,
+            "items": request.items.len(),
+        })),
+    )
+    .await?;
+    tx.commit().await?;
+
+    load_inspection(pool, contract_id, kind)
+        .await?
+        .ok_or_else(|| AdminError::NotFound("inspection".to_string()))
+}
+
+fn validate_items(items: &[InspectionItemInput]) -> Result<(), AdminError> {
+    let mut seen = std::collections::HashSet::new();
+    for item in items {
+        if item.room.trim().is_empty() || item.item.trim().is_empty() {
+            return Err(AdminError::BadRequest(
+                "Every item needs a room and a name".to_string(),
+            ));
+        }
+        if !(0..=5).contains(&item.condition) {
+            return Err(AdminError::BadRequest(format!(
+                "condition must be 0 to 5 ({})",
+                CONDITIONS.join(", ")
+            )));
+        }
+        if item.replacement_cost.is_some_and(|c| c < 0.0) {
+            return Err(AdminError::BadRequest(
+                "replacement_cost cannot be negative".to_string(),
+            ));
+        }
+        if !seen.insert(item_key(&item.room, &item.item)) {
+            return Err(AdminError::BadRequest(format!(
+                "{} / {} is listed twice",
+                item.room.trim(),
+                item.item.trim()
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Upsert the items in order and remove those no longer listed.
+async fn replace_items(
+    tx: &mut Transaction<'_, Postgres>,
+    inspection_id: Uuid,
+    items: &[InspectionItemInput],
+) -> Result<(), sqlx::Error> {
+    let mut kept = Vec::with_capacity(items.len());
+    for (position, item) in items.iter().enumerate() {
+        let id: Uuid = sqlx::query_scalar(
+            "INSERT INTO admin_inspection_items \
+             (inspection_id, room, item, condition, notes, replacement_cost, position) \
+             VALUES ($1, $2, $3, $4, $5, $6::numeric, $7) \
+             ON CONFLICT (inspection_id, room, item) DO UPDATE SET \
+                condition = EXCLUDED.condition, notes = EXCLUDED.notes, \
+                replacement_cost = EXCLUDED.replacement_cost, position = EXCLUDED.position \
+             RETURNING id",
+        )
+        .bind(inspection_id)
+        .bind(item.room.trim())
+        .bind(item.item.trim())
+        .bind(item.condition)
+        .bind(item.notes.trim())
+        .bind(item.replacement_cost)
+        .bind(i32::try_from(position).unwrap_or(i32::MAX))
+        .fetch_one(&mut **tx)
+        .await?;
+        kept.push(id);
+    }
+    sqlx::query("DELETE FROM admin_inspection_items WHERE inspection_id = $1 AND id <> ALL($2)")
+        .bind(inspection_id)
+        .bind(&kept)
+        .execute(&mut **tx)
+        .await?;
+    Ok(())
+}
+
+pub async fn delete_inspection(
+    pool: &PgPool,
+    actor: &Actor,
+    contract_id: Uuid,
+    kind: &str,
+) -> Result<(), AdminError> {
+    let mut tx = pool.begin().await?;
+    let deleted: Option<Uuid> = sqlx::query_scalar(
+        "DELETE FROM admin_inspections WHERE contract_id = $1 AND kind = $2 RETURNING id",
+    )
+    .bind(contract_id)
+    .bind(kind)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some(inspection_id) = deleted else {
+        return Err(AdminError::NotFound("inspection".to_string()));
+    };
+    write_audit(
+        &mut *tx,
+        actor,
+        "contracts",
+        &contract_id.to_string(),
+        "delete_inspection",
+        Some(&json!({ "inspection_id": inspection_id, "kind": kind })),
+        None,
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+// ── Photos ──────────────────────────────────────────────────────────────
+
+/// Attach a photo to an inspection item. Returns the photo's id.
+pub async fn add_photo(
+    pool: &PgPool,
+    actor: &Actor,
+    item_id: Uuid,
+    upload: &PhotoUpload,
+) -> Result<Value, AdminError> {
+    let (file_type, content) = decode_photo(&upload.data)?;
+    let mut tx = pool.begin().await?;
+    let photo: Option<(Value, Uuid)> = sqlx::query_as(
+        "WITH p AS (\
+            INSERT INTO admin_inspection_photos (item_id, caption, file_type, size, content) \
+            SELECT i.id, $2, $3, $4, $5 FROM admin_inspection_items i WHERE i.id = $1 \
+            RETURNING id, item_id, caption, file_type, size, created_at\
+         ) SELECT json_build_object('id', p.id, 'item_id', p.item_id, 'caption', p.caption, \
+            'file_type', p.file_type, 'size', p.size, 'created_at', p.created_at), \
+            ins.contract_id \
+         FROM p JOIN admin_inspection_items i ON i.id = p.item_id \
+         JOIN admin_inspections ins ON ins.id = i.inspection_id",
+    )
+    .bind(item_id)
+    .bind(upload.caption.trim())
+    .bind(file_type)
+    .bind(i32::try_from(content.len()).unwrap_or(i32::MAX))
+    .bind(&content)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let (photo, contract_id) =
+        photo.ok_or_else(|| AdminError::NotFound("inspection item".to_string()))?;
+    write_audit(
+        &mut *tx,
+        actor,
+        "contracts",
+        &contract_id.to_string(),
+        "add_inspection_photo",
+        None,
+        Some(&json!({
+            "photo_id": photo["id"],
+            "item_id": item_id,
+            "caption": photo["caption"],
+            "size": photo["size"],
+        })),
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(photo)
+}
+
+/// Content type and bytes of a photo.
+pub async fn photo_file(pool: &PgPool, id: Uuid) -> Result<(String, Vec<u8>), AdminError> {
+    sqlx::query_as("SELECT file_type, content FROM admin_inspection_photos WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AdminError::NotFound("photo".to_string()))
+}
+
+pub async fn delete_photo(pool: &PgPool, actor: &Actor, id: Uuid) -> Result<(), AdminError> {
+    let mut tx = pool.begin().await?;
+    let deleted: Option<(Uuid, Uuid, String)> = sqlx::query_as(
+        "DELETE FROM admin_inspection_photos p \
+         USING admin_inspection_items i, admin_inspections ins \
+         WHERE p.id = $1 AND i.id = p.item_id AND ins.id = i.inspection_id \
+         RETURNING ins.contract_id, p.item_id, p.caption",
+    )
+    .bind(id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some((contract_id, item_id, caption)) = deleted else {
+        return Err(AdminError::NotFound("photo".to_string()));
+    };
+    write_audit(
+        &mut *tx,
+        actor,
+        "contracts",
+        &contract_id.to_string(),
+        "delete_inspection_photo",
+        Some(&json!({ "photo_id": id, "item_id": item_id, "caption": caption })),
+        None,
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Content type and bytes of a photo sent as a data URL.
+pub(crate) fn decode_photo(data_url: &str) -> Result<(&'static str, Vec<u8>), AdminError> {
+    let invalid = || {
+        AdminError::BadRequest(format!(
+            "data must be a base64 data URL of a {} image of at most {} MB",
+            PHOTO_TYPES.join(", "),
+            MAX_PHOTO_BYTES / (1024 * 1024)
+        ))
+    };
+    let (header, encoded) = data_url
+        .strip_prefix("data:")
+        .and_then(|rest| rest.split_once(";base64,"))
+        .ok_or_else(invalid)?;
+    let file_type = PHOTO_TYPES
+        .iter()
+        .find(|t| **t == header)
+        .copied()
+        .ok_or_else(invalid)?;
+    let content = STANDARD.decode(encoded.trim()).map_err(|_| invalid())?;
+    if content.is_empty() || content.len() > MAX_PHOTO_BYTES {
+        return Err(invalid());
+    }
+    Ok((file_type, content))
+}
+
+// ── Comparison and deductions ───────────────────────────────────────────
+
+/// Compare the check-out with the check-in and suggest deductions from the
+/// contract's deposit.
+pub async fn inspection_diff(
+    pool: &PgPool,
+    contract_id: Uuid,
+    query: &DiffQuery,
+) -> Result<InspectionDiff, AdminError> {
+    let cost_per_grade = query.cost_per_grade.unwrap_or(DEFAULT_COST_PER_GRADE);
+    if cost_per_grade < 0.0 {
+        return Err(AdminError::BadRequest(
+            "cost_per_grade cannot be negative".to_string(),
+        ));
+    }
+    let contract: Value = sqlx::query_scalar(
+        "SELECT row_to_json(c) FROM admin_contracts c WHERE c.id = $1 AND c.deleted_at IS NULL",
+    )
+    .bind(contract_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AdminError::NotFound("contract".to_string()))?;
+    let deposit = contract_deposit(pool, &contract).await?;
+    let (check_in, check_out) = contract_inspections(pool, contract_id).await?;
+
+    let changes = match (&check_in, &check_out) {
+        (Some(check_in), Some(check_out)) => compare(check_in, check_out, cost_per_grade),
+        _ => Vec::new(),
+    };
+    let total: f64 = changes.iter().map(|c| c.suggested_deduction).sum();
+    let available = deposit
+        .as_ref()
+        .and_then(|d| d["paid"].as_f64())
+        .unwrap_or(f64::INFINITY);
+    let suggested_total = round2(total.min(available));
+
+    Ok(InspectionDiff {
+        contract,
+        check_in,
+        check_out,
+        changes,
+        deposit,
+        suggested_total,
+        capped: total > available,
+    })
+}
+
+/// Items of both inspections in check-in order, then those only found at
+/// check-out.
+fn compare(
+    check_in: &Inspection,
+    check_out: &Inspection,
+    cost_per_grade: f64,
+) -> Vec<ConditionChange> {
+    let mut changes: Vec<ConditionChange> = check_in
+        .items
+        .iter()
+        .map(|before| {
+            let after = check_out
+                .items
+                .iter()
+                .find(|i| item_key(&i.room, &i.item) == item_key(&before.room, &before.item));
+            change(before, after, cost_per_grade)
+        })
+        .collect();
+    changes.extend(
+        check_out
+            .items
+            .iter()
+            .filter(|after| {
+                !check_in
+                    .items
+                    .iter()
+                    .any(|i| item_key(&i.room, &i.item) == item_key(&after.room, &after.item))
+            })
+            .map(|after| ConditionChange {
+                room: after.room.clone(),
+                item: after.item.clone(),
+                check_in: None,
+                check_out: Some(after.condition),
+                change: "new",
+                grades_lost: 0,
+                check_in_notes: String::new(),
+                check_out_notes: after.notes.clone(),
+                replacement_cost: after.replacement_cost,
+                suggested_deduction: 0.0,
+                reason: String::new(),
+            }),
+    );
+    changes
+}
+
+fn change(
+    before: &InspectionItem,
+    after: Option<&InspectionItem>,
+    cost_per_grade: f64,
+) -> ConditionChange {
+    // An item not listed at check-out is treated as missing
+    let after_condition = after.map_or(0, |a| a.condition);
+    let grades_lost = (before.condition - after_condition).max(0);
+    let chargeable = (grades_lost - FAIR_WEAR_GRADES).max(0);
+    let change = match (after, after_condition.cmp(&before.condition)) {
+        (None, _) => "missing",
+        (Some(_), std::cmp::Ordering::Less) => "worse",
+        (Some(_), std::cmp::Ordering::Greater) => "better",
+        (Some(_), std::cmp::Ordering::Equal) => "unchanged",
+    };
+
+    // Each chargeable grade costs a fifth of the replacement cost, so an
+    // Excellent item found missing costs its full replacement
+    let suggested_deduction = match before.replacement_cost {
+        Some(cost) if after_condition == 0 => cost,
+        Some(cost) => cost * f64::from(chargeable) / 5.0,
+        None => cost_per_grade * f64::from(chargeable),
+    };
+    let reason = if chargeable == 0 {
+        String::new()
+    } else {
+        format!(
+            "{} at check-in, {} at check-out",
+            condition_name(before.condition),
+            condition_name(after_condition)
+        )
+    };
+
+    ConditionChange {
+        room: before.room.clone(),
+        item: before.item.clone(),
+        check_in: Some(before.condition),
+        check_out: after.map(|a| a.condition),
+        change,
+        grades_lost,
+        check_in_notes: before.notes.clone(),
+        check_out_notes: after.map(|a| a.notes.clone()).unwrap_or_default(),
+        replacement_cost: before.replacement_cost,
+        suggested_deduction: round2(suggested_deduction),
+        reason,
+    }
+}
+
+/// Body of POST /contracts/{id}/inspections/deductions. Without `lines`,
+/// the suggested deductions are applied as they are.
+#[derive(Debug, Deserialize, Default)]
+pub struct ApplyDeductionsRequest {
+    pub lines: Option<Vec<DeductionLine>>,
+    pub cost_per_grade: Option<f64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DeductionLine {
+    #[serde(default)]
+    pub room: String,
+    #[serde(default)]
+    pub item: String,
+    #[serde(default)]
+    pub reason: String,
+    pub amount: f64,
+}
+
+/// Replace the deductions on the contract's deposit with the given lines or
+/// the suggested ones.
+pub async fn apply_deductions(
+    pool: &PgPool,
+    actor: &Actor,
+    contract_id: Uuid,
+    request: &ApplyDeductionsRequest,
+) -> Result<Value, AdminError> {
+    let diff = inspection_diff(
+        pool,
+        contract_id,
+        &DiffQuery {
+            cost_per_grade: request.cost_per_grade,
+        },
+    )
+    .await?;
+    let (Some(deposit), Some(check_out)) = (&diff.deposit, &diff.check_out) else {
+        return Err(AdminError::BadRequest(
+            "Deductions need a check-out inspection and a deposit for the contract".to_string(),
+        ));
+    };
+    let lines: Vec<DeductionLine> = match &request.lines {
+        Some(lines) => lines.clone(),
+        None => diff
+            .changes
+            .iter()
+            .filter(|c| c.suggested_deduction > 0.0)
+            .map(|c| DeductionLine {
+                room: c.room.clone(),
+                item: c.item.clone(),
+                reason: c.reason.clone(),
+                amount: c.suggested_deduction,
+            })
+            .collect(),
+    };
+    if lines
+        .iter()
+        .any(|l| !l.amount.is_finite() || l.amount <= 0.0)
+    {
+        return Err(AdminError::BadRequest(
+            "Every deduction must have a positive amount".to_string(),
+        ));
+    }
+    let total = round2(lines.iter().map(|l| l.amount).sum());
+
+    let deposit_id = deposit["id"].as_str().unwrap_or_default();
+    let mut tx = pool.begin().await?;
+    // Check against the deposit as it stands once locked, not as read above
+    let locked: Option<(f64, f64, f64)> = sqlx::query_as(
+        "SELECT paid::float8, refunded::float8, deductions::float8 FROM admin_deposits \
+         WHERE id = $1::uuid AND deleted_at IS NULL FOR UPDATE",
+    )
+    .bind(deposit_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let (paid, refunded, previous) =
+        locked.ok_or_else(|| AdminError::NotFound("deposit".to_string()))?;
+    let available = round2(paid - refunded);
+    if total > available {
+        return Err(AdminError::BadRequest(format!(
+            "Deductions of {total:.2} exceed the {available:.2} paid and not yet refunded"
+        )));
+    }
+
+    replace_deductions(&mut tx, deposit_id, check_out.id, &lines, total).await?;
+    write_audit(
+        &mut *tx,
+        actor,
+        "deposits",
+        deposit_id,
+        "deduct",
+        Some(&json!({ "deductions": previous })),
+        Some(&json!({ "deductions": total, "lines": lines })),
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(json!({
+        "deposit_id": deposit_id,
+        "deductions": total,
+        "refundable": round2(available - total),
+        "lines": lines,
+    }))
+}
+
+/// Store `lines` as the deposit's deductions, in place of any earlier ones.
+async fn replace_deductions(
+    tx: &mut Transaction<'_, Postgres>,
+    deposit_id: &str,
+    inspection_id: Uuid,
+    lines: &[DeductionLine],
+    total: f64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM admin_deposit_deductions WHERE deposit_id = $1::uuid")
+        .bind(deposit_id)
+        .execute(&mut **tx)
+        .await?;
+    for line in lines {
+        sqlx::query(
+            "INSERT INTO admin_deposit_deductions \
+             (deposit_id, inspection_id, room, item, reason, amount) \
+             VALUES ($1::uuid, $2, $3, $4, $5, $6::numeric)",
+        )
+        .bind(deposit_id)
+        .bind(inspection_id)
+        .bind(line.room.trim())
+        .bind(line.item.trim())
+        .bind(line.reason.trim())
+        .bind(line.amount)
+        .execute(&mut **tx)
+        .await?;
+    }
+    sqlx::query(
+        "UPDATE admin_deposits SET deductions = $2::numeric, updated_at = NOW() \
+         WHERE id = $1::uuid",
+    )
+    .bind(deposit_id)
+    .bind(total)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+/// The live deposit of a contract, by contract reference.
+async fn contract_deposit(pool: &PgPool, contract: &Value) -> Result<Option<Value>, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT json_build_object('id', d.id, 'status', d.status, 'amount', d.amount::float8, \
+            'paid', d.paid::float8, 'refunded', d.refunded::float8, \
+            'deductions', d.deductions::float8) \
+         FROM admin_deposits d WHERE d.contract_ref = $1 AND d.deleted_at IS NULL \
+         ORDER BY d.created_at DESC LIMIT 1",
+    )
+    .bind(contract["contract_ref"].as_str().unwrap_or_default())
+    .fetch_optional(pool)
+    .await
+}
+
+// ── Report ──────────────────────────────────────────────────────────────
+
+/// The comparison with every photo of both inspections, for the PDF.
+pub struct InspectionReport {
+    pub diff: InspectionDiff,
+    pub photos: Vec<ReportPhoto>,
+}
+
+#[derive(sqlx::FromRow)]
+pub struct ReportPhoto {
+    pub kind: String,
+    pub room: String,
+    pub item: String,
+    pub caption: String,
+    pub content: Vec<u8>,
+}
+
+/// File name and bytes of the inspection report of a contract.
+pub async fn inspection_report_pdf(
+    pool: &PgPool,
+    contract_id: Uuid,
+    query: &DiffQuery,
+) -> Result<(String, Vec<u8>), AdminError> {
+    let diff = inspection_diff(pool, contract_id, query).await?;
+    if diff.check_in.is_none() && diff.check_out.is_none() {
+        return Err(AdminError::NotFound("inspection".to_string()));
+    }
+    let photos = sqlx::query_as::<_, ReportPhoto>(
+        "SELECT n.kind, i.room, i.item, p.caption, p.content \
+         FROM admin_inspection_photos p \
+         JOIN admin_inspection_items i ON i.id = p.item_id \
+         JOIN admin_inspections n ON n.id = i.inspection_id \
+         WHERE n.contract_id = $1 ORDER BY n.kind, i.position, p.created_at",
+    )
+    .bind(contract_id)
+    .fetch_all(pool)
+    .await?;
+
+    let contract_ref = diff.contract["contract_ref"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+    let report = InspectionReport { diff, photos };
+    // Run PDF generation on blocking thread pool to avoid stalling async runtime
+    let pdf = tokio::task::spawn_blocking(move || PdfService::generate_inspection_pdf(&report))
+        .await
+        .map_err(|e| AdminError::PdfGeneration(format!("PDF task failed: {e}")))??;
+    Ok((
+        format!("{}.pdf", file_stem(&format!("inspection {contract_ref}"))),
+        pdf,
+    ))
+}
+
+pub fn condition_name(condition: i16) -> &'static str {
+    usize::try_from(condition)
+        .ok()
+        .and_then(|c| CONDITIONS.get(c))
+        .copied()
+        .unwrap_or("Unknown")
+}
+
+/// Rooms and items match regardless of case and surrounding spaces.
+fn item_key(room: &str, item: &str) -> (String, String) {
+    (room.trim().to_lowercase(), item.trim().to_lowercase())
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(room: &str, name: &str, condition: i16, cost: Option<f64>) -> InspectionItem {
+        InspectionItem {
+            id: Uuid::new_v4(),
+            room: room.to_string(),
+            item: name.to_string(),
+            condition,
+            notes: String::new(),
+            replacement_cost: cost,
+            photo_ids: Vec::new(),
+        }
+    }
+
+    fn inspection(kind: &str, items: Vec<InspectionItem>) -> Inspection {
+        Inspection {
+            id: Uuid::new_v4(),
+            contract_id: Uuid::nil(),
+            kind: kind.to_string(),
+            inspection_date: NaiveDate::default(),
+            inspector: String::new(),
+            notes: String::new(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            items,
+        }
+    }
+
+    fn diff(before: Vec<InspectionItem>, after: Vec<InspectionItem>) -> Vec<ConditionChange> {
+        compare(
+            &inspection("check_in", before),
+            &inspection("check_out", after),
+            DEFAULT_COST_PER_GRADE,
+        )
+    }
+
+    #[test]
+    fn items_match_by_room_and_item_ignoring_case() {
+        let changes = diff(
+            vec![item("Kitchen", "Oven", 4, None)],
+            vec![item(" kitchen ", "OVEN", 4, None)],
+        );
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].change, "unchanged");
+        assert_eq!(changes[0].check_out, Some(4));
+        assert!(changes[0].suggested_deduction.abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn fair_wear_is_not_charged() {
+        let changes = diff(
+            vec![item("Kitchen", "Oven", 4, Some(500.0))],
+            vec![item("Kitchen", "Oven", 3, Some(500.0))],
+        );
+        assert_eq!(changes[0].change, "worse");
+        assert_eq!(changes[0].grades_lost, 1);
+        assert!(changes[0].suggested_deduction.abs() < f64::EPSILON);
+        assert!(changes[0].reason.is_empty());
+    }
+
+    #[test]
+    fn chargeable_grades_cost_a_fifth_of_the_replacement() {
+        let changes = diff(
+            vec![item("Kitchen", "Oven", 5, Some(500.0))],
+            vec![item("Kitchen", "Oven", 2, Some(500.0))],
+        );
+        assert_eq!(changes[0].grades_lost, 3);
+        assert!((changes[0].suggested_deduction - 200.0).abs() < f64::EPSILON);
+        assert_eq!(
+            changes[0].reason,
+            "Excellent at check-in, Poor at check-out"
+        );
+    }
+
+    #[test]
+    fn without_a_replacement_cost_each_grade_costs_the_default() {
+        let changes = diff(
+            vec![item("Bedroom", "Carpet", 4, None)],
+            vec![item("Bedroom", "Carpet", 1, None)],
+        );
+        assert!((changes[0].suggested_deduction - 2.0 * DEFAULT_COST_PER_GRADE).abs() < 1e-9);
+    }
+
+    #[test]
+    fn missing_items_cost_their_full_replacement() {
+        let changes = diff(vec![item("Hall", "Mirror", 3, Some(80.0))], Vec::new());
+        assert_eq!(changes[0].change, "missing");
+        assert_eq!(changes[0].check_out, None);
+        assert_eq!(changes[0].grades_lost, 3);
+        assert!((changes[0].suggested_deduction - 80.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn better_and_new_items_are_not_charged() {
+        let changes = diff(
+            vec![item("Hall", "Lamp", 2, Some(40.0))],
+            vec![
+                item("Hall", "Lamp", 4, Some(40.0)),
+                item("Hall", "Rug", 5, None),
+            ],
+        );
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].change, "better");
+        assert_eq!(changes[0].grades_lost, 0);
+        assert_eq!(changes[1].change, "new");
+        assert_eq!(changes[1].check_in, None);
+        assert!(changes[1].suggested_deduction.abs() < f64::EPSILON);
+    }
+}
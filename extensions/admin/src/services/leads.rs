@@ -0,0 +1,400 @@
+use chrono::NaiveDate;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::api::actor::Actor;
+use crate::api::generic::{write_audit, AdminEntity, TenantEntity};
+use crate::error::AdminError;
+use crate::services::pii;
+
+/// Matches returned unless `?limit=` says otherwise.
+pub const DEFAULT_MATCHES: i64 = 10;
+pub const MAX_MATCHES: i64 = 50;
+
+/// Property kinds a lead's `interest_type` can name, matched against property
+/// tags. A lead interested in one kind is not shown properties tagged only as
+/// another.
+const PROPERTY_KINDS: &[&str] = &["Residential", "Commercial"];
+
+/// Lead statuses that are no longer worked and keep their last score.
+const CLOSED_STATUSES: &[&str] = &["Converted", "Lost"];
+
+/// Query parameters of the matching endpoint: ?limit=10
+#[derive(Debug, Deserialize, Default)]
+pub struct MatchQuery {
+    pub limit: Option<i64>,
+}
+
+/// Body of POST /leads/{id}/convert. Everything is optional: the property
+/// defaults to the one named on the lead, the rent to the property's.
+#[derive(Debug, Deserialize, Default)]
+pub struct ConvertLeadRequest {
+    pub property_id: Option<Uuid>,
+    pub contract_ref: Option<String>,
+    pub rent: Option<f64>,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+}
+
+/// `Available` properties ranked for a lead, best first.
+///
+/// Each match has a `budget_fit` (see `admin_budget_fit`) and an `area_fit`
+/// (trigram word similarity of `preferred_area` to the property name and
+/// address), both from 0 to 1 and null when the lead gave no such criterion.
+/// `match_score` is their weighted mean (60/40) on a 0–100 scale. Units out
+/// of budget, smaller than `min_bedrooms` or `min_sqm`, or of another kind
+/// than the lead's `interest_type` are left out; buildings are never matched.
+/// Properties without a recorded size are kept.
+pub async fn match_properties(
+    pool: &PgPool,
+    lead_id: Uuid,
+    params: &MatchQuery,
+) -> Result<Value, AdminError> {
+    let lead: Value = sqlx::query_scalar(
+        "SELECT json_build_object('id', id, 'name', name, 'interest_type', interest_type, \
+             'budget_min', budget_min, 'budget_max', budget_max, 'min_bedrooms', min_bedrooms, \
+             'min_sqm', min_sqm, 'preferred_area', preferred_area) \
+         FROM admin_leads WHERE id = $1 AND deleted_at IS NULL",
+    )
+    .bind(lead_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AdminError::NotFound("lead".to_string()))?;
+
+    let interest = lead["interest_type"].as_str().unwrap_or_default();
+    let (kinds, mut other_kinds): (Vec<&str>, Vec<&str>) = PROPERTY_KINDS
+        .iter()
+        .copied()
+        .partition(|kind| interest.contains(kind));
+    if kinds.is_empty() {
+        other_kinds.clear();
+    }
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_MATCHES)
+        .clamp(1, MAX_MATCHES);
+
+    let matches: Vec<Value> = sqlx::query_scalar(
+        "WITH l AS (SELECT * FROM admin_leads WHERE id = $1), \
+         m AS (\
+            SELECT p.id, p.property_name, p.address, p.rent, p.tags, p.bedrooms, p.sqm, \
+                admin_budget_fit(p.rent, l.budget_min, l.budget_max) AS budget_fit, \
+                CASE WHEN l.preferred_area <> '' THEN word_similarity(\
+                    admin_search_key(ARRAY[l.preferred_area]), \
+                    admin_search_key(ARRAY[p.property_name, p.address]))::float8 \
+                END AS area_fit \
+            FROM admin_properties p, l \
+            WHERE p.deleted_at IS NULL AND p.status = 'Available' AND p.kind = 'unit' \
+              AND (p.bedrooms IS NULL OR p.bedrooms >= COALESCE(l.min_bedrooms, 0)) \
+              AND (p.sqm IS NULL OR p.sqm >= COALESCE(l.min_sqm, 0)) \
+              AND NOT (COALESCE(p.tags, '{}') && $2::text[] \
+                       AND NOT COALESCE(p.tags, '{}') && $3::text[])\
+         ), \
+         scored AS (\
+            SELECT m.*, round(100 * \
+                (COALESCE(0.6 * budget_fit, 0) + COALESCE(0.4 * area_fit, 0)) / \
+                NULLIF(CASE WHEN budget_fit IS NULL THEN 0 ELSE 0.6 END \
+                     + CASE WHEN area_fit IS NULL THEN 0 ELSE 0.4 END, 0))::int AS match_score \
+            FROM m WHERE budget_fit IS NULL OR budget_fit > 0\
+         ) \
+         SELECT row_to_json(s) FROM scored s \
+         ORDER BY s.match_score DESC NULLS LAST, s.rent, s.id LIMIT $4",
+    )
+    .bind(lead_id)
+    .bind(&other_kinds)
+    .bind(&kinds)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(json!({ "lead": lead, "data": matches }))
+}
+
+/// Recompute the score of every open lead, from 0 to 100:
+///
+/// - recency, up to 40: the latest of creation, contact date and last note,
+///   falling to 0 over 90 days;
+/// - budget fit, up to 35: the best fit of any `Available` unit large
+///   enough for the lead;
+/// - note activity, up to 25: 5 per note in the last 30 days.
+///
+/// Only leads whose score changes are written. Returns how many were.
+pub async fn recompute_scores(pool: &PgPool) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        "WITH activity AS (\
+            SELECT l.id, \
+                GREATEST(l.created_at, l.contact_date::timestamptz, MAX(n.created_at)) AS last_activity, \
+                COUNT(n.id) FILTER (WHERE n.created_at > NOW() - INTERVAL '30 days') AS recent_notes, \
+                (SELECT MAX(admin_budget_fit(p.rent, l.budget_min, l.budget_max)) \
+                 FROM admin_properties p \
+                 WHERE p.deleted_at IS NULL AND p.status = 'Available' AND p.kind = 'unit' \
+                   AND (p.bedrooms IS NULL OR p.bedrooms >= COALESCE(l.min_bedrooms, 0)) \
+                   AND (p.sqm IS NULL OR p.sqm >= COALESCE(l.min_sqm, 0))) AS budget_fit \
+            FROM admin_leads l \
+            LEFT JOIN admin_lead_notes n ON n.lead_id = l.id AND n.deleted_at IS NULL \
+            WHERE l.deleted_at IS NULL AND l.status <> ALL($1) \
+            GROUP BY l.id\
+         ), scored AS (\
+            SELECT id, round(\
+                40 * GREATEST(0, 1 - EXTRACT(EPOCH FROM NOW() - last_activity) / 86400 / 90) \
+              + 35 * COALESCE(budget_fit, 0) \
+              + 5 * LEAST(recent_notes, 5))::int AS score \
+            FROM activity\
+         ) \
+         UPDATE admin_leads l SET score = s.score \
+         FROM scored s WHERE l.id = s.id AND l.score IS DISTINCT FROM s.score",
+    )
+    .bind(CLOSED_STATUSES)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Turn a lead into a tenant and a `Draft` contract in one transaction.
+///
+/// The contract's notes are the lead's own notes followed by every lead
+/// note, oldest first. The lead is marked `Converted` and linked to both
+/// records; all three changes are audited.
+pub async fn convert_lead(
+    pool: &PgPool,
+    actor: &Actor,
+    lead_id: Uuid,
+    request: &ConvertLeadRequest,
+) -> Result<Value, AdminError> {
+    let mut tx = pool.begin().await?;
+
+    let lead: Value = sqlx::query_scalar(
+        "SELECT row_to_json(l) FROM admin_leads l \
+         WHERE l.id = $1 AND l.deleted_at IS NULL FOR UPDATE",
+    )
+    .bind(lead_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AdminError::NotFound("lead".to_string()))?;
+    if !lead["converted_tenant_id"].is_null() {
+        return Err(AdminError::Conflict(
+            "The lead has already been converted".to_string(),
+        ));
+    }
+    if lead["status"] == "Lost" {
+        return Err(AdminError::Conflict(
+            "A lost lead cannot be converted".to_string(),
+        ));
+    }
+
+    let property = lead_property(&mut tx, &lead, request.property_id).await?;
+    let property_name = property["property_name"].as_str().unwrap_or_default();
+    let address = property["address"].as_str().unwrap_or_default();
+    let name = lead["name"].as_str().unwrap_or_default();
+
+    let (phone, phone_index) = pii::protect("phone", lead["phone"].as_str().unwrap_or_default())?;
+    let mut tenant: Value = sqlx::query_scalar(
+        "INSERT INTO admin_tenants \
+             (name, email, phone, phone_bidx, property_name, property_address) \
+         VALUES ($1, $2, $3, $4, $5, $6) RETURNING row_to_json(admin_tenants.*)",
+    )
+    .bind(name)
+    .bind(lead["email"].as_str().unwrap_or_default())
+    .bind(phone)
+    .bind(phone_index)
+    .bind(property_name)
+    .bind(address)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let contract = insert_contract(&mut tx, &lead, &tenant, &property, request).await?;
+
+    let converted: Value = sqlx::query_scalar(
+        "UPDATE admin_leads SET status = 'Converted', converted_tenant_id = $2, \
+             converted_contract_id = $3, converted_at = NOW(), updated_at = NOW() \
+         WHERE id = $1 RETURNING row_to_json(admin_leads.*)",
+    )
+    .bind(lead_id)
+    .bind(record_id(&tenant))
+    .bind(record_id(&contract))
+    .fetch_one(&mut *tx)
+    .await?;
+
+    for (label, row) in [("tenants", &tenant), ("contracts", &contract)] {
+        let id = row["id"].as_str().unwrap_or_default();
+        write_audit(&mut *tx, actor, label, id, "create", None, Some(row)).await?;
+    }
+    write_audit(
+        &mut *tx,
+        actor,
+        "leads",
+        &lead_id.to_string(),
+        "convert",
+        Some(&lead),
+        Some(&converted),
+    )
+    .await?;
+    tx.commit().await?;
+
+    pii::reveal(&mut tenant, TenantEntity::SENSITIVE_FIELDS, false);
+    Ok(json!({ "lead": converted, "tenant": tenant, "contract": contract }))
+}
+
+/// The `Draft` contract of a conversion with the new tenant, carrying the
+/// lead's notes.
+async fn insert_contract(
+    tx: &mut Transaction<'_, Postgres>,
+    lead: &Value,
+    tenant: &Value,
+    property: &Value,
+    request: &ConvertLeadRequest,
+) -> Result<Value, sqlx::Error> {
+    let property_name = property["property_name"].as_str().unwrap_or_default();
+    let name = lead["name"].as_str().unwrap_or_default();
+    let contract_ref = request
+        .contract_ref
+        .as_deref()
+        .map(str::trim)
+        .filter(|r| !r.is_empty())
+        .map_or_else(|| default_contract_ref(property_name, name), str::to_string);
+    let rent = request
+        .rent
+        .or_else(|| property["rent"].as_f64())
+        .unwrap_or_default();
+    let notes = carried_notes(tx, lead).await?;
+
+    sqlx::query_scalar(
+        "INSERT INTO admin_contracts \
+         (contract_ref, property_name, address, tenant_name, tenant_id, status, rent, \
+          start_date, end_date, notes) \
+         VALUES ($1, $2, $3, $4, $9, 'Draft', $5::numeric, $6, $7, $8) \
+         RETURNING row_to_json(admin_contracts.*)",
+    )
+    .bind(&contract_ref)
+    .bind(property_name)
+    .bind(property["address"].as_str().unwrap_or_default())
+    .bind(name)
+    .bind(rent)
+    .bind(request.start_date)
+    .bind(request.end_date)
+    .bind(notes)
+    .bind(record_id(tenant))
+    .fetch_one(&mut **tx)
+    .await
+}
+
+/// The property a conversion is for: the requested one, else the live
+/// property named on the lead. An empty object when there is none.
+async fn lead_property(
+    tx: &mut Transaction<'_, Postgres>,
+    lead: &Value,
+    property_id: Option<Uuid>,
+) -> Result<Value, AdminError> {
+    let property = match property_id {
+        Some(id) => Some(
+            sqlx::query_scalar::<_, Value>(
+                "SELECT row_to_json(p) FROM admin_properties p \
+                 WHERE p.id = $1 AND p.deleted_at IS NULL",
+            )
+            .bind(id)
+            .fetch_optional(&mut **tx)
+            .await?
+            .ok_or_else(|| AdminError::NotFound("property".to_string()))?,
+        ),
+        None => {
+            sqlx::query_scalar::<_, Value>(
+                "SELECT row_to_json(p) FROM admin_properties p \
+                 WHERE p.property_name = $1 AND p.property_name <> '' AND p.deleted_at IS NULL \
+                 ORDER BY p.created_at LIMIT 1",
+            )
+            .bind(lead["property_name"].as_str().unwrap_or_default())
+            .fetch_optional(&mut **tx)
+            .await?
+        }
+    };
+    Ok(property.unwrap_or_else(|| json!({})))
+}
+
+/// The lead's notes, then one line per lead note: `2025-03-01 Sarah: ...`.
+async fn carried_notes(
+    tx: &mut Transaction<'_, Postgres>,
+    lead: &Value,
+) -> Result<String, sqlx::Error> {
+    let lines: Vec<String> = sqlx::query_scalar(
+        "SELECT to_char(created_at, 'YYYY-MM-DD') \
+             || CASE WHEN author <> '' THEN ' ' || author ELSE '' END || ': ' || content \
+         FROM admin_lead_notes \
+         WHERE lead_id = $1::uuid AND deleted_at IS NULL ORDER BY created_at",
+    )
+    .bind(lead["id"].as_str().unwrap_or_default())
+    .fetch_all(&mut **tx)
+    .await?;
+
+    Ok(join_notes(
+        lead["notes"].as_str().unwrap_or_default(),
+        &lines,
+    ))
+}
+
+fn join_notes(own: &str, lines: &[String]) -> String {
+    let mut notes = own.trim().to_string();
+    for line in lines {
+        if !notes.is_empty() {
+            notes.push('\n');
+        }
+        notes.push_str(line);
+    }
+    notes
+}
+
+/// `KING STREET 15 - JOHNSON`, after the references of existing contracts.
+fn default_contract_ref(property_name: &str, tenant_name: &str) -> String {
+    let surname = tenant_name.split_whitespace().last().unwrap_or("TENANT");
+    let property = if property_name.is_empty() {
+        "DRAFT"
+    } else {
+        property_name
+    };
+    format!("{} - {}", property.to_uppercase(), surname.to_uppercase())
+}
+
+fn record_id(row: &Value) -> Option<Uuid> {
+    row["id"].as_str().and_then(|id| Uuid::parse_str(id).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contract_ref_uses_the_property_and_tenant_surname() {
+        assert_eq!(
+            default_contract_ref("King Street 15", "Mary Ann Johnson"),
+            "KING STREET 15 - JOHNSON"
+        );
+        assert_eq!(default_contract_ref("", "Núñez"), "DRAFT - NÚÑEZ");
+        assert_eq!(default_contract_ref("Flat 2", "  "), "FLAT 2 - TENANT");
+    }
+
+    #[test]
+    fn lead_notes_come_before_the_note_lines() {
+        let lines = vec![
+            "2025-03-01 Sarah: Viewed the flat".to_string(),
+            "2025-03-04: Sent the offer".to_string(),
+        ];
+        assert_eq!(
+            join_notes(" Wants a garden \n", &lines),
+            "Wants a garden\n2025-03-01 Sarah: Viewed the flat\n2025-03-04: Sent the offer"
+        );
+        assert_eq!(
+            join_notes("", &lines),
+            "2025-03-01 Sarah: Viewed the flat\n2025-03-04: Sent the offer"
+        );
+        assert_eq!(join_notes("", &[]), "");
+    }
+
+    #[test]
+    fn record_id_reads_a_uuid_id() {
+        let id = Uuid::new_v4();
+        assert_eq!(record_id(&json!({ "id": id })), Some(id));
+        assert_eq!(record_id(&json!({"id": "42"})), None);
+        assert_eq!(record_id(&json!({})), None);
+    }
+}
@@ -1 +1,23 @@
+pub mod aeat;
+pub mod agent;
+pub mod buildings;
+pub mod contract_documents;
+pub mod custom_fields;
+pub mod inspections;
+pub mod leads;
+pub mod openapi;
+pub mod owner_portal;
+pub mod payouts;
 pub mod pdf;
+pub mod pii;
+pub mod portal;
+pub mod reporting;
+pub mod search;
+pub mod sepa;
+pub mod signatures;
+pub mod statements;
+pub mod tax;
+pub mod templates;
+pub mod trash;
+pub mod views;
+pub mod webhooks;
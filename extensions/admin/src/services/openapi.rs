@@ -0,0 +1,1108 @@
+//! OpenAPI 3 description of the admin API.
+//!
+//! The generic CRUD paths and record schemas come from the [`AdminEntity`]
+//! consts through [`entities`], so they cannot drift from the router. The
+//! custom handlers are listed in [`CUSTOM_ROUTES`], which has to be kept in
+//! step with `api::router` by hand.
+//!
+//! [`AdminEntity`]: crate::api::generic::AdminEntity
+
+use serde_json::{json, Map, Value};
+
+use crate::api::filters::FieldType;
+use crate::api::generic::{entities, EntityInfo};
+
+const JSON: &str = "application/json";
+const PDF: &str = "application/pdf";
+const CSV: &str = "text/csv";
+const XML: &str = "application/xml";
+const TEXT: &str = "text/plain";
+const HTML: &str = "text/html";
+const BINARY: &str = "application/octet-stream";
+
+/// Entities whose only generic route is the list; the rest of their paths
+/// are custom handlers.
+const LIST_ONLY: &[&str] = &["payout_batches"];
+
+/// One route served by a custom handler.
+struct Route {
+    method: &'static str,
+    path: &'static str,
+    tag: &'static str,
+    summary: &'static str,
+    produces: &'static str,
+}
+
+const fn route(
+    method: &'static str,
+    path: &'static str,
+    tag: &'static str,
+    summary: &'static str,
+) -> Route {
+    Route {
+        method,
+        path,
+        tag,
+        summary,
+        produces: JSON,
+    }
+}
+
+impl Route {
+    const fn produces(self, media_type: &'static str) -> Self {
+        Self {
+            produces: media_type,
+            ..self
+        }
+    }
+}
+
+/// Routes outside the generic CRUD, in router order.
+const CUSTOM_ROUTES: &[Route] = &[
+    route(
+        "post",
+        "/auth/session",
+        "auth",
+        "Store the access token in a session cookie",
+    ),
+    route(
+        "delete",
+        "/auth/session",
+        "auth",
+        "Clear the session cookie",
+    ),
+    route("get", "/dashboard", "dashboard", "Dashboard figures"),
+    route("get", "/reports/arrears", "reports", "Arrears by tenant"),
+    route(
+        "get",
+        "/reports/profitability",
+        "reports",
+        "Profitability by property",
+    ),
+    route("get", "/reports/rent-roll", "reports", "Rent roll"),
+    route("get", "/reports/occupancy", "reports", "Monthly occupancy"),
+    route("get", "/reports/vacancy", "reports", "Vacant properties"),
+    route(
+        "get",
+        "/reports/owner-statements",
+        "reports",
+        "Statements of every owner",
+    ),
+    route(
+        "get",
+        "/reports/tax/modelo-115",
+        "reports",
+        "Modelo 115 withholding summary",
+    ),
+    route(
+        "get",
+        "/reports/tax/modelo-115/file",
+        "reports",
+        "Modelo 115 AEAT file",
+    )
+    .produces(TEXT),
+    route(
+        "get",
+        "/reports/tax/modelo-180",
+        "reports",
+        "Modelo 180 annual summary",
+    ),
+    route(
+        "get",
+        "/reports/tax/modelo-180/file",
+        "reports",
+        "Modelo 180 AEAT file",
+    )
+    .produces(TEXT),
+    route(
+        "get",
+        "/reports/tax/modelo-347",
+        "reports",
+        "Modelo 347 third-party operations",
+    ),
+    route(
+        "get",
+        "/reports/tax/modelo-347/file",
+        "reports",
+        "Modelo 347 AEAT file",
+    )
+    .produces(TEXT),
+    route("get", "/search", "search", "Search across entities"),
+    route("get", "/views", "views", "List saved views"),
+    route("post", "/views", "views", "Save a view"),
+    route("get", "/views/{id}", "views", "Get a saved view"),
+    route("put", "/views/{id}", "views", "Update a saved view"),
+    route("delete", "/views/{id}", "views", "Delete a saved view"),
+    route(
+        "get",
+        "/custom-fields",
+        "custom-fields",
+        "List custom field definitions",
+    ),
+    route(
+        "post",
+        "/custom-fields",
+        "custom-fields",
+        "Define a custom field",
+    ),
+    route(
+        "put",
+        "/custom-fields/{id}",
+        "custom-fields",
+        "Update a custom field",
+    ),
+    route(
+        "delete",
+        "/custom-fields/{id}",
+        "custom-fields",
+        "Delete a custom field",
+    ),
+    route(
+        "get",
+        "/document-templates",
+        "document-templates",
+        "List document templates",
+    ),
+    route(
+        "post",
+        "/document-templates",
+        "document-templates",
+        "Create a document template",
+    ),
+    route(
+        "get",
+        "/document-templates/{id}",
+        "document-templates",
+        "Get a document template",
+    ),
+    route(
+        "put",
+        "/document-templates/{id}",
+        "document-templates",
+        "Update a document template",
+    ),
+    route(
+        "delete",
+        "/document-templates/{id}",
+        "document-templates",
+        "Delete a document template",
+    ),
+    route("get", "/audit/recent", "audit", "Recent audit log entries"),
+    route(
+        "post",
+        "/audit/{entity_type}/revert",
+        "audit",
+        "Revert an audited change",
+    ),
+    route(
+        "get",
+        "/audit/{entity_type}/{entity_id}",
+        "audit",
+        "History of a record",
+    ),
+    route("get", "/webhooks", "webhooks", "List webhooks"),
+    route(
+        "post",
+        "/webhooks",
+        "webhooks",
+        "Register a webhook; returns its signing secret",
+    ),
+    route("get", "/webhooks/{id}", "webhooks", "Get a webhook"),
+    route("put", "/webhooks/{id}", "webhooks", "Update a webhook"),
+    route("delete", "/webhooks/{id}", "webhooks", "Delete a webhook"),
+    route(
+        "get",
+        "/webhooks/{id}/deliveries",
+        "webhooks",
+        "Delivery log of a webhook",
+    ),
+    route(
+        "get",
+        "/webhook-deliveries/{id}",
+        "webhooks",
+        "A delivery and its attempts",
+    ),
+    route(
+        "post",
+        "/webhook-deliveries/{id}/retry",
+        "webhooks",
+        "Retry a delivery now",
+    ),
+    route("get", "/trash", "trash", "Deleted records of every entity"),
+    route(
+        "delete",
+        "/trash/{entity}/{id}",
+        "trash",
+        "Purge a deleted record",
+    ),
+    route(
+        "post",
+        "/trash/{entity}/{id}/restore",
+        "trash",
+        "Restore a deleted record",
+    ),
+    route("get", "/properties/names", "properties", "Property names"),
+    route(
+        "get",
+        "/properties/images/{folder}",
+        "properties",
+        "Images of a property",
+    ),
+    route(
+        "get",
+        "/properties/{id}/detail",
+        "properties",
+        "Property with related records",
+    ),
+    route(
+        "get",
+        "/properties/{id}/rollup",
+        "properties",
+        "Building roll-up of its units",
+    ),
+    route(
+        "get",
+        "/contracts/{id}/detail",
+        "contracts",
+        "Contract with related records",
+    ),
+    route(
+        "post",
+        "/contracts/{id}/documents/generate",
+        "contracts",
+        "Generate a document from a template",
+    ),
+    route(
+        "get",
+        "/contract-documents",
+        "contracts",
+        "List contract documents",
+    ),
+    route(
+        "delete",
+        "/contract-documents/{id}",
+        "contracts",
+        "Delete a contract document",
+    ),
+    route(
+        "get",
+        "/contract-documents/{id}/download",
+        "contracts",
+        "Download a contract document",
+    )
+    .produces(BINARY),
+    route(
+        "post",
+        "/contract-documents/{id}/signature-requests",
+        "signatures",
+        "Request signatures on a document",
+    ),
+    route(
+        "post",
+        "/contract-documents/{id}/text",
+        "contracts",
+        "Replace the text of a document",
+    ),
+    route(
+        "get",
+        "/contracts/{id}/inspections",
+        "inspections",
+        "Move-in and move-out inspections",
+    ),
+    route(
+        "get",
+        "/contracts/{id}/inspections/diff",
+        "inspections",
+        "Condition changes between inspections",
+    ),
+    route(
+        "post",
+        "/contracts/{id}/inspections/deductions",
+        "inspections",
+        "Deduct damages from the deposit",
+    ),
+    route(
+        "get",
+        "/contracts/{id}/inspections/pdf",
+        "inspections",
+        "Inspection report",
+    )
+    .produces(PDF),
+    route(
+        "put",
+        "/contracts/{id}/inspections/{kind}",
+        "inspections",
+        "Save an inspection",
+    ),
+    route(
+        "delete",
+        "/contracts/{id}/inspections/{kind}",
+        "inspections",
+        "Delete an inspection",
+    ),
+    route(
+        "post",
+        "/inspection-items/{id}/photos",
+        "inspections",
+        "Attach a photo to an item",
+    ),
+    route(
+        "get",
+        "/inspection-photos/{id}",
+        "inspections",
+        "Inspection photo",
+    )
+    .produces(BINARY),
+    route(
+        "delete",
+        "/inspection-photos/{id}",
+        "inspections",
+        "Delete an inspection photo",
+    ),
+    route(
+        "get",
+        "/signature-requests",
+        "signatures",
+        "List signature requests",
+    ),
+    route(
+        "get",
+        "/signature-requests/{id}",
+        "signatures",
+        "A signature request and its evidence",
+    ),
+    route(
+        "post",
+        "/signature-requests/{id}/cancel",
+        "signatures",
+        "Cancel a signature request",
+    ),
+    route(
+        "post",
+        "/signature-requests/{id}/complete",
+        "signatures",
+        "Build the signed copy of a fully signed request",
+    ),
+    route(
+        "get",
+        "/sign/{token}",
+        "signatures",
+        "Signing page of a one-time link",
+    )
+    .produces(HTML),
+    route(
+        "post",
+        "/sign/{token}",
+        "signatures",
+        "Sign through a one-time link",
+    ),
+    route(
+        "get",
+        "/sign/{token}/document",
+        "signatures",
+        "Document to sign",
+    )
+    .produces(PDF),
+    route("get", "/owners/{id}/statement", "owners", "Owner statement"),
+    route(
+        "get",
+        "/owners/{id}/statement/pdf",
+        "owners",
+        "Owner statement",
+    )
+    .produces(PDF),
+    route(
+        "get",
+        "/owners/{id}/portal-access",
+        "owners",
+        "Portal accounts of an owner",
+    ),
+    route(
+        "put",
+        "/owners/{id}/portal-access",
+        "owners",
+        "Grant an owner portal access",
+    ),
+    route(
+        "delete",
+        "/owners/{id}/portal-access/{account_id}",
+        "owners",
+        "Revoke owner portal access",
+    ),
+    route("get", "/tenants/names", "tenants", "Tenant names"),
+    route(
+        "get",
+        "/tenants/{id}/portal-access",
+        "tenants",
+        "Portal accounts of a tenant",
+    ),
+    route(
+        "put",
+        "/tenants/{id}/portal-access",
+        "tenants",
+        "Grant a tenant portal access",
+    ),
+    route(
+        "delete",
+        "/tenants/{id}/portal-access/{account_id}",
+        "tenants",
+        "Revoke tenant portal access",
+    ),
+    route("get", "/issues/{id}/photos", "issues", "Photos of an issue"),
+    route("get", "/issue-photos/{id}", "issues", "Issue photo").produces(BINARY),
+    route(
+        "get",
+        "/portal/tenant",
+        "tenant-portal",
+        "The tenant's contract and balance",
+    ),
+    route(
+        "get",
+        "/portal/tenant/invoices",
+        "tenant-portal",
+        "The tenant's invoices",
+    ),
+    route(
+        "get",
+        "/portal/tenant/invoices/{id}/pdf",
+        "tenant-portal",
+        "Invoice",
+    )
+    .produces(PDF),
+    route(
+        "get",
+        "/portal/tenant/payments",
+        "tenant-portal",
+        "The tenant's payments",
+    ),
+    route(
+        "get",
+        "/portal/tenant/issues",
+        "tenant-portal",
+        "Issues reported by the tenant",
+    ),
+    route(
+        "post",
+        "/portal/tenant/issues",
+        "tenant-portal",
+        "Report an issue",
+    ),
+    route(
+        "post",
+        "/portal/tenant/issues/{id}/photos",
+        "tenant-portal",
+        "Attach a photo to an issue",
+    ),
+    route(
+        "get",
+        "/portal/tenant/issue-photos/{id}",
+        "tenant-portal",
+        "Issue photo",
+    )
+    .produces(BINARY),
+    route(
+        "get",
+        "/portal/owner",
+        "owner-portal",
+        "The owner's profile and properties",
+    ),
+    route(
+        "get",
+        "/portal/owner/performance",
+        "owner-portal",
+        "Portfolio performance",
+    ),
+    route(
+        "get",
+        "/portal/owner/occupancy",
+        "owner-portal",
+        "Portfolio occupancy",
+    ),
+    route(
+        "get",
+        "/portal/owner/statement",
+        "owner-portal",
+        "The owner's statement",
+    ),
+    route(
+        "get",
+        "/portal/owner/statement/pdf",
+        "owner-portal",
+        "The owner's statement",
+    )
+    .produces(PDF),
+    route(
+        "get",
+        "/portal/owner/insurance",
+        "owner-portal",
+        "Insurance on the portfolio",
+    ),
+    route(
+        "get",
+        "/portal/owner/issues",
+        "owner-portal",
+        "Open issues on the portfolio",
+    ),
+    route(
+        "get",
+        "/portal/owner/documents",
+        "owner-portal",
+        "Contract documents of the portfolio",
+    ),
+    route(
+        "get",
+        "/portal/owner/documents/{id}/download",
+        "owner-portal",
+        "Download a document",
+    )
+    .produces(BINARY),
+    route("get", "/contacts/names", "contacts", "Contact names"),
+    route(
+        "get",
+        "/sepa-batches/creditors",
+        "sepa_batches",
+        "Configured SEPA creditors",
+    ),
+    route(
+        "post",
+        "/payout-batches",
+        "payout_batches",
+        "Draft an owner payout batch",
+    ),
+    route(
+        "get",
+        "/payout-batches/{id}",
+        "payout_batches",
+        "A payout batch and its payments",
+    ),
+    route(
+        "post",
+        "/payout-batches/{id}/approve",
+        "payout_batches",
+        "Approve a payout batch",
+    ),
+    route(
+        "post",
+        "/payout-batches/{id}/cancel",
+        "payout_batches",
+        "Cancel a payout batch",
+    ),
+    route(
+        "get",
+        "/payout-batches/{id}/pain001",
+        "payout_batches",
+        "SEPA credit transfer file",
+    )
+    .produces(XML),
+    route(
+        "get",
+        "/invoices/owners",
+        "invoices",
+        "Owners with invoices",
+    ),
+    route("get", "/invoices/payees", "invoices", "Invoice payees"),
+    route("get", "/invoices/{id}/pdf", "invoices", "Invoice").produces(PDF),
+    route("get", "/export/{entity}", "export", "Export a list as CSV").produces(CSV),
+    route(
+        "get",
+        "/leads/{id}/matches",
+        "leads",
+        "Properties matching a lead",
+    ),
+    route(
+        "post",
+        "/leads/{id}/convert",
+        "leads",
+        "Convert a lead into a tenant",
+    ),
+    route("get", "/openapi.json", "meta", "This document"),
+];
+
+/// The OpenAPI document served at `/admin/api/openapi.json`.
+pub fn document() -> Value {
+    let mut paths = Map::new();
+    let mut schemas = Map::new();
+    schemas.insert("Error".to_string(), error_schema());
+    schemas.insert(
+        "Success".to_string(),
+        json!({
+            "type": "object",
+            "properties": { "success": { "type": "boolean" } },
+        }),
+    );
+    schemas.insert("BulkRequest".to_string(), bulk_schema());
+
+    for info in entities() {
+        let name = schema_name(info.label);
+        schemas.insert(name.clone(), record_schema(&info));
+        schemas.insert(format!("{name}Input"), input_schema(&info));
+        entity_paths(&mut paths, &info, &name);
+    }
+    for route in CUSTOM_ROUTES {
+        let operation = custom_operation(route);
+        insert_operation(&mut paths, route.path, route.method, operation);
+    }
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "Admin API",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "servers": [{ "url": "/admin/api" }],
+        "security": [{ "bearerAuth": [] }, { "sessionCookie": [] }],
+        "paths": paths,
+        "components": {
+            "schemas": schemas,
+            "securitySchemes": {
+                "bearerAuth": { "type": "http", "scheme": "bearer", "bearerFormat": "JWT" },
+                "sessionCookie": { "type": "apiKey", "in": "cookie", "name": "access_token" },
+            },
+        },
+    })
+}
+
+/// `sepa_batches` → `SepaBatches`
+fn schema_name(label: &str) -> String {
+    label
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_uppercase().chain(chars).collect()
+            })
+        })
+        .collect()
+}
+
+/// The searched fields, substring matches first, then the sensitive fields
+/// matched whole.
+fn search_description(info: &EntityInfo) -> String {
+    let mut description = format!("Matches {}", info.search_fields.join(", "));
+    if !info.sensitive_fields.is_empty() {
+        description.push_str(&format!(
+            "; exact value of {}",
+            info.sensitive_fields.join(", ")
+        ));
+    }
+    description
+}
+
+fn field_schema(field_type: FieldType) -> Value {
+    match field_type {
+        FieldType::Text | FieldType::Sensitive => json!({ "type": "string" }),
+        FieldType::Number => json!({ "type": "number" }),
+        FieldType::Date => json!({ "type": "string", "format": "date" }),
+        FieldType::Timestamp => json!({ "type": "string", "format": "date-time" }),
+        FieldType::Bool => json!({ "type": "boolean" }),
+        FieldType::Uuid => json!({ "type": "string", "format": "uuid" }),
+        FieldType::Tags => json!({ "type": "array", "items": { "type": "string" } }),
+    }
+}
+
+/// Schema of a writable column. Only filterable and sensitive columns have
+/// a declared type; the others accept any JSON value.
+fn column_schema(info: &EntityInfo, field: &str) -> Value {
+    if info.sensitive_fields.contains(&field) {
+        return json!({
+            "type": "string",
+            "description": "Encrypted at rest; masked unless the token has the `sensitive` scope",
+        });
+    }
+    info.filter_fields
+        .iter()
+        .find(|(name, _)| *name == field)
+        .map_or_else(|| json!({}), |(_, field_type)| field_schema(*field_type))
+}
+
+fn input_schema(info: &EntityInfo) -> Value {
+    let properties: Map<String, Value> = info
+        .writable_fields
+        .iter()
+        .map(|field| ((*field).to_string(), column_schema(info, field)))
+        .collect();
+    json!({
+        "type": "object",
+        "properties": properties,
+        "additionalProperties": false,
+    })
+}
+
+fn record_schema(info: &EntityInfo) -> Value {
+    let mut properties: Map<String, Value> = info
+        .writable_fields
+        .iter()
+        .map(|field| ((*field).to_string(), column_schema(info, field)))
+        .collect();
+    properties.insert("id".to_string(), field_schema(FieldType::Uuid));
+    properties.insert("revision".to_string(), json!({ "type": "integer" }));
+    for column in ["created_at", "updated_at", "deleted_at"] {
+        properties.insert(column.to_string(), field_schema(FieldType::Timestamp));
+    }
+    properties.insert(
+        "custom".to_string(),
+        json!({ "type": "object", "description": "Custom field values by key" }),
+    );
+    json!({ "type": "object", "properties": properties })
+}
+
+fn error_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": { "error": { "type": "string" } },
+        "required": ["error"],
+    })
+}
+
+fn bulk_schema() -> Value {
+    json!({
+        "type": "object",
+        "description": "Records by `ids`, or every record matching `filter` and `search`",
+        "properties": {
+            "ids": { "type": "array", "items": { "type": "string", "format": "uuid" } },
+            "filter": { "type": "object", "additionalProperties": { "type": "string" } },
+            "search": { "type": "string" },
+            "set": { "type": "object", "description": "Columns to set (PATCH only)" },
+        },
+    })
+}
+
+fn entity_paths(paths: &mut Map<String, Value>, info: &EntityInfo, name: &str) {
+    let base = format!("/{}", info.label.replace('_', "-"));
+    insert_operation(paths, &base, "get", list_operation(info, name));
+    if LIST_ONLY.contains(&info.label) {
+        return;
+    }
+    let tag = info.label;
+    let record = format!("{base}/{{id}}");
+    let input = body(&format!("{name}Input"));
+    insert_operation(
+        paths,
+        &base,
+        "post",
+        json!({
+            "tags": [tag],
+            "summary": format!("Create a record in {tag}"),
+            "requestBody": input,
+            "responses": {
+                "201": json_response("Created", &json!({
+                    "type": "object",
+                    "properties": {
+                        "success": { "type": "boolean" },
+                        "id": field_schema(FieldType::Uuid),
+                    },
+                })),
+                "400": error_response("Invalid field value"),
+            },
+        }),
+    );
+    insert_operation(
+        paths,
+        &base,
+        "patch",
+        json!({
+            "tags": [tag],
+            "summary": format!("Update many records in {tag}"),
+            "requestBody": body("BulkRequest"),
+            "responses": { "200": json_response("Per-record outcome", &json!({ "type": "object" })) },
+        }),
+    );
+    insert_operation(
+        paths,
+        &format!("{base}/bulk-delete"),
+        "post",
+        json!({
+            "tags": [tag],
+            "summary": format!("Delete many records in {tag}"),
+            "requestBody": body("BulkRequest"),
+            "responses": { "200": json_response("Per-record outcome", &json!({ "type": "object" })) },
+        }),
+    );
+    insert_operation(
+        paths,
+        &record,
+        "get",
+        json!({
+            "tags": [tag],
+            "summary": format!("Get a record in {tag}"),
+            "parameters": [
+                query_param("include_deleted", &json!({ "type": "boolean" }), "Also find a record in the trash"),
+            ],
+            "responses": {
+                "200": with_etag(json_response("The record", &schema_ref(name))),
+                "404": error_response("Not found"),
+            },
+        }),
+    );
+    insert_operation(
+        paths,
+        &record,
+        "put",
+        json!({
+            "tags": [tag],
+            "summary": format!("Update a record in {tag}"),
+            "parameters": [if_match()],
+            "requestBody": body(&format!("{name}Input")),
+            "responses": write_responses(name),
+        }),
+    );
+    insert_operation(
+        paths,
+        &record,
+        "delete",
+        json!({
+            "tags": [tag],
+            "summary": format!("Move a record in {tag} to the trash"),
+            "parameters": [if_match()],
+            "responses": write_responses(name),
+        }),
+    );
+}
+
+fn list_operation(info: &EntityInfo, name: &str) -> Value {
+    let mut parameters = vec![
+        query_param(
+            "page",
+            &json!({ "type": "integer", "minimum": 1 }),
+            "Page number",
+        ),
+        query_param(
+            "per_page",
+            &json!({ "type": "integer", "minimum": 1 }),
+            "Page size",
+        ),
+        query_param(
+            "cursor",
+            &json!({ "type": "string" }),
+            "`next_cursor` of the previous page; replaces `page`",
+        ),
+        query_param(
+            "count",
+            &json!({ "type": "string", "enum": ["exact", "estimate", "none"] }),
+            "How to compute `total`",
+        ),
+        query_param(
+            "search",
+            &json!({ "type": "string" }),
+            &search_description(info),
+        ),
+        query_param(
+            "sort",
+            &json!({ "type": "string", "enum": info.sortable_fields, "default": info.default_sort }),
+            "Sort column",
+        ),
+        query_param(
+            "order",
+            &json!({ "type": "string", "enum": ["asc", "desc"] }),
+            "Sort direction",
+        ),
+        query_param(
+            "view",
+            &field_schema(FieldType::Uuid),
+            "Apply a saved view: its parameters and columns",
+        ),
+        query_param(
+            "include_deleted",
+            &json!({ "type": "string", "enum": ["true", "false"] }),
+            "List records in the trash too",
+        ),
+    ];
+    let filters = info.filter_fields.iter().copied().chain([
+        ("created_at", FieldType::Timestamp),
+        ("updated_at", FieldType::Timestamp),
+    ]);
+    for (field, field_type) in filters {
+        parameters.push(query_param(
+            field,
+            &field_schema(field_type),
+            &format!("Filter; also `{field}[op]=value`, see the list filter operators"),
+        ));
+    }
+    json!({
+        "tags": [info.label],
+        "summary": format!("List {}", info.label),
+        "description": "Filters take `?field=value` or `?field[op]=value` with op one of \
+            eq, ne, gt, gte, lt, lte, between, in, nin, like, null, contains, overlaps, \
+            each negatable with a `not_` prefix. Custom fields filter as `custom.<key>`.",
+        "parameters": parameters,
+        "responses": {
+            "200": json_response("A page of records", &json!({
+                "type": "object",
+                "properties": {
+                    "data": { "type": "array", "items": schema_ref(name) },
+                    "total": { "type": "integer" },
+                    "next_cursor": { "type": "string" },
+                },
+                "required": ["data"],
+            })),
+            "400": error_response("Unknown filter field or invalid value"),
+        },
+    })
+}
+
+fn custom_operation(route: &Route) -> Value {
+    let schema = if route.produces == JSON {
+        json!({ "type": "object" })
+    } else {
+        json!({ "type": "string", "format": "binary" })
+    };
+    let mut operation = json!({
+        "tags": [route.tag],
+        "summary": route.summary,
+        "responses": {
+            "200": { "description": "OK", "content": { route.produces: { "schema": schema } } },
+            "default": error_response("Error"),
+        },
+    });
+    if matches!(route.method, "post" | "put") {
+        operation["requestBody"] = json!({
+            "content": { JSON: { "schema": { "type": "object" } } },
+        });
+    }
+    operation
+}
+
+/// Add an operation, with parameters for the `{name}` segments of its path.
+fn insert_operation(
+    paths: &mut Map<String, Value>,
+    path: &str,
+    method: &str,
+    mut operation: Value,
+) {
+    let mut parameters: Vec<Value> = path
+        .split('/')
+        .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+        .map(|name| {
+            let schema = if matches!(name, "id" | "entity_id" | "account_id") {
+                field_schema(FieldType::Uuid)
+            } else {
+                json!({ "type": "string" })
+            };
+            json!({ "name": name, "in": "path", "required": true, "schema": schema })
+        })
+        .collect();
+    let extra = operation
+        .as_object_mut()
+        .and_then(|fields| fields.remove("parameters"));
+    if let Some(Value::Array(extra)) = extra {
+        parameters.extend(extra);
+    }
+    if !parameters.is_empty() {
+        operation["parameters"] = Value::Array(parameters);
+    }
+    let item = paths
+        .entry(path.to_string())
+        .or_insert_with(|| Value::Object(Map::new()));
+    item[method] = operation;
+}
+
+fn schema_ref(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{name}") })
+}
+
+fn body(name: &str) -> Value {
+    json!({ "required": true, "content": { JSON: { "schema": schema_ref(name) } } })
+}
+
+fn json_response(description: &str, schema: &Value) -> Value {
+    json!({ "description": description, "content": { JSON: { "schema": schema } } })
+}
+
+fn error_response(description: &str) -> Value {
+    json_response(description, &schema_ref("Error"))
+}
+
+fn with_etag(mut response: Value) -> Value {
+    response["headers"] = json!({
+        "ETag": { "description": "Record revision, for `If-Match`", "schema": { "type": "string" } },
+    });
+    response
+}
+
+fn query_param(name: &str, schema: &Value, description: &str) -> Value {
+    json!({ "name": name, "in": "query", "schema": schema, "description": description })
+}
+
+fn if_match() -> Value {
+    json!({
+        "name": "If-Match",
+        "in": "header",
+        "required": true,
+        "description": "ETag of the record as last read, or `*`",
+        "schema": { "type": "string" },
+    })
+}
+
+fn write_responses(name: &str) -> Value {
+    json!({
+        "200": with_etag(json_response("Done", &schema_ref("Success"))),
+        "404": error_response("Not found"),
+        "409": json_response("Changed since it was read", &json!({
+            "type": "object",
+            "properties": { "error": { "type": "string" }, "current": schema_ref(name) },
+        })),
+        "428": error_response("`If-Match` is missing"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const METHODS: &[&str] = &["get", "post", "put", "patch", "delete"];
+
+    /// `(method, path)` of every `.route(...)` in the router source. The
+    /// router cannot list its routes at runtime, so its source is read.
+    fn router_routes() -> Vec<(String, String)> {
+        let source = include_str!("../api/mod.rs");
+        let mut routes = Vec::new();
+        for call in source.split(".route(").skip(1) {
+            let path = call.split('"').nth(1).expect("route path literal");
+            for (at, _) in call.match_indices('(') {
+                let before =
+                    call[..at].trim_end_matches(|c: char| c.is_ascii_alphanumeric() || c == '_');
+                let method = &call[before.len()..at];
+                let qualified = before.ends_with("::");
+                if METHODS.contains(&method) && !qualified {
+                    routes.push((method.to_string(), path.to_string()));
+                }
+            }
+        }
+        routes
+    }
+
+    #[test]
+    fn every_router_route_is_documented() {
+        let document = document();
+        let routes = router_routes();
+        assert!(routes.len() > 100, "found only {} routes", routes.len());
+        let missing: Vec<String> = routes
+            .iter()
+            .filter(|(method, path)| document["paths"][path.as_str()][method.as_str()].is_null())
+            .map(|(method, path)| format!("{} {path}", method.to_uppercase()))
+            .collect();
+        assert!(missing.is_empty(), "undocumented routes: {missing:?}");
+    }
+
+    #[test]
+    fn every_documented_route_is_served() {
+        let routes = router_routes();
+        let document = document();
+        let paths = document["paths"].as_object().unwrap();
+        for (path, operations) in paths {
+            for method in operations.as_object().unwrap().keys() {
+                assert!(
+                    routes.iter().any(|(m, p)| m == method && p == path),
+                    "{} {path} is documented but not routed",
+                    method.to_uppercase()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn schema_names_are_pascal_case() {
+        assert_eq!(schema_name("sepa_batches"), "SepaBatches");
+        assert_eq!(schema_name("properties"), "Properties");
+    }
+}
@@ -0,0 +1,267 @@
+//! Owner portal.
+//!
+//! Owners sign in with the `owner` scope and are linked by an admin to one
+//! owner record. Their portfolio is the ` / `-separated `property_name` of
+//! that record, the same list [`PROPERTY_FILTER`] reads; the report figures
+//! reuse the admin reports with the [`ReportFilter`] owner id forced to them.
+
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::api::generic::{AdminEntity, OwnerEntity};
+use crate::error::AdminError;
+use crate::services::pii;
+use crate::services::reporting::{
+    occupancy, profitability, ReportFilter, INVOICE_FILTER, PROPERTY_FILTER,
+};
+
+/// Property names of the owner's portfolio; `$1` is the owner id.
+const OWNER_PROPERTIES: &str = "SELECT unnest(string_to_array(property_name, ' / ')) \
+     FROM admin_owners WHERE id = $1 AND deleted_at IS NULL";
+
+/// Issue statuses that no longer need attention.
+const DONE_ISSUE_STATUSES: &str = "('Resolved', 'Closed')";
+
+/// The owner a portal user acts as.
+#[derive(Debug, Clone)]
+pub struct PortalOwner {
+    pub user_id: String,
+    pub owner_id: Uuid,
+    pub name: String,
+}
+
+/// The owner linked to a signed-in portal user.
+pub async fn portal_owner(pool: &PgPool, user_id: &str) -> Result<PortalOwner, AdminError> {
+    let owner: Option<(Uuid, String)> = sqlx::query_as(
+        "SELECT o.id, o.name FROM admin_portal_accounts a \
+         JOIN admin_owners o ON o.id = a.owner_id \
+         WHERE a.user_id = $1 AND a.role = 'owner' AND o.deleted_at IS NULL",
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+    let (owner_id, name) = owner.ok_or_else(|| {
+        AdminError::Forbidden("This account is not linked to an owner".to_string())
+    })?;
+    Ok(PortalOwner {
+        user_id: user_id.to_string(),
+        owner_id,
+        name,
+    })
+}
+
+/// The caller's report filter narrowed to the owner, over the requested
+/// range (default: the last 12 months). Property and tag filters still
+/// apply, within the portfolio.
+pub fn owner_filter(owner: &PortalOwner, filter: ReportFilter) -> Result<ReportFilter, AdminError> {
+    let mut filter = filter.validate()?;
+    filter.owner = None;
+    filter.owner_id = Some(owner.owner_id);
+    filter.window(12)
+}
+
+/// The owner's details, their own personal data in clear, and the
+/// properties in their portfolio.
+pub async fn owner_overview(pool: &PgPool, owner: &PortalOwner) -> Result<Value, AdminError> {
+    let mut profile: Value = sqlx::query_scalar(
+        "SELECT json_build_object('name', name, 'tax_id', tax_id, 'email', email, \
+            'phone', phone, 'address', address, 'bank_account', bank_account, \
+            'management_fee_pct', management_fee_pct::float8) \
+         FROM admin_owners WHERE id = $1",
+    )
+    .bind(owner.owner_id)
+    .fetch_one(pool)
+    .await?;
+    pii::reveal(&mut profile, OwnerEntity::SENSITIVE_FIELDS, true);
+    let filter = ReportFilter {
+        owner_id: Some(owner.owner_id),
+        ..ReportFilter::default()
+    };
+    let sql = format!(
+        "SELECT json_build_object('id', id, 'property_name', property_name, \
+            'address', address, 'status', status, 'rent', rent::float8, \
+            'contract_ref', contract_ref, 'start_date', start_date, 'end_date', end_date) \
+         FROM admin_properties WHERE {PROPERTY_FILTER} ORDER BY property_name"
+    );
+    let properties: Vec<Value> = filter.scalar(&sql).fetch_all(pool).await?;
+    Ok(json!({ "owner": profile, "properties": properties }))
+}
+
+/// Per-property profitability, as in the admin report, and monthly income
+/// and expenses over the filter's range.
+pub async fn owner_performance(pool: &PgPool, filter: &ReportFilter) -> Result<Value, sqlx::Error> {
+    let properties = profitability(pool, filter).await?;
+    let sql = format!(
+        "SELECT COALESCE(json_agg(json_build_object(\
+            'month', to_char(m.month, 'YYYY-MM'), \
+            'income', COALESCE(t.income, 0), 'expenses', COALESCE(t.expenses, 0), \
+            'collected', COALESCE(t.collected, 0), \
+            'net', COALESCE(t.income, 0) - COALESCE(t.expenses, 0)\
+         ) ORDER BY m.month), '[]') \
+         FROM generate_series(date_trunc('month', $1::date), $2::date, INTERVAL '1 month') m(month) \
+         LEFT JOIN (\
+            SELECT date_trunc('month', invoice_date) AS month, \
+                COALESCE(SUM(amount) FILTER (WHERE type = 'income'), 0)::float8 AS income, \
+                COALESCE(SUM(amount) FILTER (WHERE type = 'expense'), 0)::float8 AS expenses, \
+                COALESCE(SUM(paid) FILTER (WHERE type = 'income'), 0)::float8 AS collected \
+            FROM admin_invoices WHERE {INVOICE_FILTER} \
+            GROUP BY 1\
+         ) t ON t.month = m.month"
+    );
+    let monthly: Value = filter.scalar(&sql).fetch_one(pool).await?;
+    Ok(json!({
+        "from": filter.from,
+        "to": filter.to,
+        "properties": properties,
+        "monthly": monthly,
+    }))
+}
+
+/// Monthly occupancy of the portfolio over the filter's range.
+pub async fn owner_occupancy(pool: &PgPool, filter: &ReportFilter) -> Result<Value, sqlx::Error> {
+    occupancy(pool, filter).await
+}
+
+/// Insurance policies on the owner's properties, latest expiry first.
+pub async fn owner_insurance(
+    pool: &PgPool,
+    owner: &PortalOwner,
+) -> Result<Vec<Value>, sqlx::Error> {
+    sqlx::query_scalar(&format!(
+        "SELECT json_build_object('id', id, 'property_name', property_name, \
+            'insurance_type', insurance_type, 'company', company, \
+            'policy_number', policy_number, 'start_date', start_date, 'end_date', end_date, \
+            'premium', premium::float8, 'status', status) \
+         FROM admin_insurance \
+         WHERE deleted_at IS NULL AND property_name IN ({OWNER_PROPERTIES}) \
+         ORDER BY end_date DESC NULLS LAST, property_name"
+    ))
+    .bind(owner.owner_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Issues on the owner's properties that are not yet resolved or closed.
+pub async fn owner_open_issues(
+    pool: &PgPool,
+    owner: &PortalOwner,
+) -> Result<Vec<Value>, sqlx::Error> {
+    sqlx::query_scalar(&format!(
+        "SELECT json_build_object('id', id, 'property_name', property_name, \
+            'title', title, 'description', description, 'priority', priority, \
+            'status', status, 'cost', cost::float8, 'created_at', created_at, \
+            'updated_at', updated_at) \
+         FROM admin_issues \
+         WHERE deleted_at IS NULL AND status NOT IN {DONE_ISSUE_STATUSES} \
+            AND property_name IN ({OWNER_PROPERTIES}) \
+         ORDER BY CASE priority WHEN 'High' THEN 0 WHEN 'Medium' THEN 1 ELSE 2 END, \
+            created_at DESC"
+    ))
+    .bind(owner.owner_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Documents of contracts on the owner's properties, newest first. Only
+/// documents with a file are listed.
+pub async fn owner_documents(
+    pool: &PgPool,
+    owner: &PortalOwner,
+) -> Result<Vec<Value>, sqlx::Error> {
+    sqlx::query_scalar(&format!(
+        "SELECT json_build_object('id', d.id, 'name', d.name, 'type', d.type, \
+            'file_type', d.file_type, 'size', d.size, 'document_date', d.document_date, \
+            'contract_ref', c.contract_ref, 'property_name', c.property_name, \
+            'tenant_name', c.tenant_name) \
+         FROM admin_contract_documents d \
+         JOIN admin_contracts c ON c.id = d.contract_id \
+         WHERE c.deleted_at IS NULL AND d.content IS NOT NULL \
+            AND c.property_name IN ({OWNER_PROPERTIES}) \
+         ORDER BY d.document_date DESC, d.created_at DESC"
+    ))
+    .bind(owner.owner_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Whether the document belongs to a contract on one of the owner's properties.
+pub async fn owner_owns_document(
+    pool: &PgPool,
+    owner: &PortalOwner,
+    document_id: Uuid,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar(&format!(
+        "SELECT EXISTS (SELECT 1 FROM admin_contract_documents d \
+         JOIN admin_contracts c ON c.id = d.contract_id \
+         WHERE d.id = $2 AND c.deleted_at IS NULL \
+            AND c.property_name IN ({OWNER_PROPERTIES}))"
+    ))
+    .bind(owner.owner_id)
+    .bind(document_id)
+    .fetch_one(pool)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn owner() -> PortalOwner {
+        PortalOwner {
+            user_id: "user-1".to_string(),
+            owner_id: Uuid::new_v4(),
+            name: "Luis Pérez".to_string(),
+        }
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(y, m, d)
+    }
+
+    #[test]
+    fn filter_is_forced_to_the_signed_in_owner() {
+        let owner = owner();
+        let requested = ReportFilter {
+            owner: Some("Someone Else".to_string()),
+            owner_id: Some(Uuid::new_v4()),
+            property: Some("Mayor 3 1A".to_string()),
+            from: date(2026, 1, 1),
+            to: date(2026, 3, 31),
+            ..ReportFilter::default()
+        };
+        let filter = owner_filter(&owner, requested).unwrap();
+        assert_eq!(filter.owner, None);
+        assert_eq!(filter.owner_id, Some(owner.owner_id));
+        assert_eq!(filter.property.as_deref(), Some("Mayor 3 1A"));
+        assert_eq!(
+            (filter.from, filter.to),
+            (date(2026, 1, 1), date(2026, 3, 31))
+        );
+    }
+
+    #[test]
+    fn filter_defaults_to_the_last_twelve_months() {
+        let requested = ReportFilter {
+            to: date(2026, 3, 15),
+            ..ReportFilter::default()
+        };
+        let filter = owner_filter(&owner(), requested).unwrap();
+        assert_eq!(filter.from, date(2025, 4, 1));
+    }
+
+    #[test]
+    fn filter_rejects_an_inverted_range() {
+        let requested = ReportFilter {
+            from: date(2026, 3, 1),
+            to: date(2026, 1, 1),
+            ..ReportFilter::default()
+        };
+        assert!(matches!(
+            owner_filter(&owner(), requested),
+            Err(AdminError::BadRequest(_))
+        ));
+    }
+}
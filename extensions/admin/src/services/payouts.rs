@@ -0,0 +1,370 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::api::actor::Actor;
+use crate::api::generic::{fetch_row_for_update, write_audit};
+use crate::error::AdminError;
+use crate::services::pii;
+use crate::services::sepa::{is_valid_iban, normalize_iban};
+use crate::services::statements::{build_all_owner_statements, OwnerStatement, StatementPeriod};
+
+/// Table and audit label of payout batches.
+pub const BATCH_TABLE: &str = "admin_payout_batches";
+pub const BATCH_LABEL: &str = "payout_batches";
+
+/// Request body for `POST /payout-batches`.
+#[derive(Debug, Deserialize)]
+pub struct PayoutBatchRequest {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub execution_date: NaiveDate,
+    pub debtor_name: String,
+    pub debtor_iban: String,
+    #[serde(default)]
+    pub debtor_bic: String,
+    /// Defaults to the currency of the owner statements
+    pub currency: Option<String>,
+    pub fee_pct: Option<f64>,
+}
+
+/// Owner left out of a batch, with the reason.
+#[derive(Debug, Serialize)]
+pub struct SkippedOwner {
+    pub owner_id: Uuid,
+    pub owner_name: String,
+    pub net_transfer: f64,
+    pub reason: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PayoutBatchCreated {
+    pub id: Uuid,
+    pub batch_ref: String,
+    pub period: StatementPeriod,
+    pub num_transfers: usize,
+    pub total_amount: f64,
+    pub skipped: Vec<SkippedOwner>,
+}
+
+/// Invoice amount still to be paid out to an owner.
+struct Outstanding {
+    invoice_id: Uuid,
+    income: bool,
+    amount: f64,
+}
+
+/// Transfer to one owner with the invoice amounts it settles.
+struct Payout {
+    statement: OwnerStatement,
+    lines: Vec<Outstanding>,
+    amount: f64,
+}
+
+/// Store a `Draft` payout batch with one transfer per owner that is owed
+/// money. Each transfer passes on the rent collected and deducts the expenses
+/// not yet settled by an earlier payout, less the management fee, and records
+/// the amount taken from every invoice so it is never paid out twice.
+///
+/// Invoices dated in the period are included, along with earlier ones that
+/// were partly paid out and have collected more since. They are locked for
+/// the whole transaction, so concurrent batches settle each amount once.
+pub async fn create_payout_batch(
+    pool: &PgPool,
+    actor: &Actor,
+    req: &PayoutBatchRequest,
+) -> Result<PayoutBatchCreated, AdminError> {
+    if req.debtor_name.trim().is_empty() {
+        return Err(AdminError::BadRequest(
+            "debtor_name is required".to_string(),
+        ));
+    }
+    if !is_valid_iban(&req.debtor_iban) {
+        return Err(AdminError::BadRequest(
+            "debtor_iban is not a valid IBAN".to_string(),
+        ));
+    }
+
+    let period = StatementPeriod::resolve(req.from, req.to)?;
+    let statements = build_all_owner_statements(pool, period, req.fee_pct).await?;
+    let currency = req
+        .currency
+        .clone()
+        .or_else(|| statements.first().map(|s| s.currency.clone()))
+        .unwrap_or_else(|| "EUR".to_string());
+
+    let mut tx = pool.begin().await?;
+
+    let (payable, skipped) = partition_owners(&mut tx, statements, &currency, period).await?;
+    if payable.is_empty() {
+        return Err(AdminError::BadRequest(
+            "No owners with a payable net transfer for this period".to_string(),
+        ));
+    }
+
+    let batch_id = Uuid::new_v4();
+    let batch_ref = format!(
+        "PO-{}-{}",
+        period.from.format("%Y%m"),
+        &batch_id.simple().to_string()[..8]
+    )
+    .to_uppercase();
+    let total_amount = round2(payable.iter().map(|p| p.amount).sum());
+    let batch = NewBatch {
+        id: batch_id,
+        batch_ref: &batch_ref,
+        period,
+        currency: &currency,
+        total_amount,
+        num_transfers: payable.len(),
+    };
+    insert_batch(&mut tx, req, &batch).await?;
+
+    let remittance = format!(
+        "Owner statement {} - {}",
+        period.from.format("%d/%m/%Y"),
+        period.to.format("%d/%m/%Y")
+    );
+
+    for (n, payout) in payable.iter().enumerate() {
+        let end_to_end_id = format!("{batch_ref}-{:03}", n + 1);
+        let transfer_id =
+            insert_transfer(&mut tx, batch_id, payout, &end_to_end_id, &remittance).await?;
+        settle(&mut tx, transfer_id, &payout.lines).await?;
+    }
+
+    let new = fetch_row_for_update(&mut tx, BATCH_TABLE, batch_id).await?;
+    write_audit(
+        &mut *tx,
+        actor,
+        BATCH_LABEL,
+        &batch_id.to_string(),
+        "create",
+        None,
+        new.as_ref(),
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(PayoutBatchCreated {
+        id: batch_id,
+        batch_ref,
+        period,
+        num_transfers: payable.len(),
+        total_amount,
+        skipped,
+    })
+}
+
+/// Split the owner statements into payouts and the owners left out. The
+/// invoices of each owner paid stay locked until the transaction ends.
+async fn partition_owners(
+    tx: &mut Transaction<'_, Postgres>,
+    statements: Vec<OwnerStatement>,
+    currency: &str,
+    period: StatementPeriod,
+) -> Result<(Vec<Payout>, Vec<SkippedOwner>), AdminError> {
+    let mut payable = Vec::new();
+    let mut skipped = Vec::new();
+    for statement in statements {
+        let mut reason = if statement.currency != currency {
+            Some("Statement currency differs from batch currency")
+        } else if !is_valid_iban(&statement.owner_bank_account) {
+            Some("Missing or invalid bank account IBAN")
+        } else {
+            None
+        };
+
+        let mut lines = Vec::new();
+        let mut amount = statement.net_transfer;
+        if reason.is_none() {
+            lines = outstanding_lines(tx, &statement.owner_name, period).await?;
+            amount = net_amount(&lines, statement.management_fee_pct);
+            if amount <= 0.0 {
+                reason = Some("Nothing to pay for this period");
+            }
+        }
+
+        match reason {
+            Some(reason) => skipped.push(SkippedOwner {
+                owner_id: statement.owner_id,
+                owner_name: statement.owner_name,
+                net_transfer: amount,
+                reason,
+            }),
+            None => payable.push(Payout {
+                statement,
+                lines,
+                amount,
+            }),
+        }
+    }
+    Ok((payable, skipped))
+}
+
+/// Figures of a batch about to be stored.
+struct NewBatch<'a> {
+    id: Uuid,
+    batch_ref: &'a str,
+    period: StatementPeriod,
+    currency: &'a str,
+    total_amount: f64,
+    num_transfers: usize,
+}
+
+/// Store a draft batch, its debtor IBAN sealed.
+async fn insert_batch(
+    tx: &mut Transaction<'_, Postgres>,
+    req: &PayoutBatchRequest,
+    batch: &NewBatch<'_>,
+) -> Result<(), AdminError> {
+    let (debtor_iban, debtor_iban_index) =
+        pii::protect("debtor_iban", &normalize_iban(&req.debtor_iban))?;
+    sqlx::query(
+        "INSERT INTO admin_payout_batches \
+         (id, batch_ref, period_from, period_to, execution_date, status, debtor_name, \
+          debtor_iban, debtor_bic, currency, total_amount, num_transfers, debtor_iban_bidx) \
+         VALUES ($1, $2, $3, $4, $5, 'Draft', $6, $7, $8, $9, $10::numeric, $11, $12)",
+    )
+    .bind(batch.id)
+    .bind(batch.batch_ref)
+    .bind(batch.period.from)
+    .bind(batch.period.to)
+    .bind(req.execution_date)
+    .bind(req.debtor_name.trim())
+    .bind(debtor_iban)
+    .bind(req.debtor_bic.trim())
+    .bind(batch.currency)
+    .bind(batch.total_amount)
+    .bind(i32::try_from(batch.num_transfers).unwrap_or(i32::MAX))
+    .bind(debtor_iban_index)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+/// Lock the owner's invoices that can take part in a payout for the period
+/// and return the amount each still has to settle.
+async fn outstanding_lines(
+    tx: &mut Transaction<'_, Postgres>,
+    owner_name: &str,
+    period: StatementPeriod,
+) -> Result<Vec<Outstanding>, AdminError> {
+    // Lock in a statement of its own: a batch that settled these invoices
+    // concurrently has committed by the time the locks are granted, and only
+    // a later statement sees its payout items.
+    let ids: Vec<Uuid> = sqlx::query_scalar(
+        "SELECT id FROM admin_invoices \
+         WHERE deleted_at IS NULL AND invoice_date <= $3 \
+         AND ((type = 'income' AND payee = $1) OR (type = 'expense' AND payer = $1)) \
+         AND (invoice_date >= $2 OR id IN (SELECT invoice_id FROM admin_payout_items)) \
+         ORDER BY id FOR UPDATE",
+    )
+    .bind(owner_name)
+    .bind(period.from)
+    .bind(period.to)
+    .fetch_all(&mut **tx)
+    .await?;
+
+    let rows = sqlx::query_as::<_, (Uuid, bool, f64)>(&format!(
+        "SELECT id, income, outstanding::float8 FROM (\
+            SELECT i.id, i.type = 'income' AS income, {OUTSTANDING} AS outstanding \
+            FROM admin_invoices i WHERE i.id = ANY($1)\
+         ) o WHERE outstanding > 0 ORDER BY id"
+    ))
+    .bind(&ids)
+    .fetch_all(&mut **tx)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(invoice_id, income, amount)| Outstanding {
+            invoice_id,
+            income,
+            amount,
+        })
+        .collect())
+}
+
+/// Amount of invoice `i` not yet paid out: rent collected for income, the
+/// invoice amount for expenses, less what earlier payouts settled.
+const OUTSTANDING: &str = "(CASE WHEN i.type = 'income' THEN i.paid ELSE i.amount END \
+     - COALESCE((SELECT SUM(pi.amount) FROM admin_payout_items pi WHERE pi.invoice_id = i.id), 0))";
+
+/// Store the credit transfer of one payout, its IBAN sealed.
+async fn insert_transfer(
+    tx: &mut Transaction<'_, Postgres>,
+    batch_id: Uuid,
+    payout: &Payout,
+    end_to_end_id: &str,
+    remittance: &str,
+) -> Result<Uuid, AdminError> {
+    let statement = &payout.statement;
+    let (creditor_iban, creditor_iban_index) = pii::protect(
+        "creditor_iban",
+        &normalize_iban(&statement.owner_bank_account),
+    )?;
+    let transfer_id = sqlx::query_scalar(
+        "INSERT INTO admin_payout_transfers \
+         (batch_id, owner_id, owner_name, creditor_iban, amount, end_to_end_id, \
+          remittance_info, creditor_iban_bidx) \
+         VALUES ($1, $2, $3, $4, $5::numeric, $6, $7, $8) RETURNING id",
+    )
+    .bind(batch_id)
+    .bind(statement.owner_id)
+    .bind(&statement.owner_name)
+    .bind(creditor_iban)
+    .bind(payout.amount)
+    .bind(end_to_end_id)
+    .bind(remittance)
+    .bind(creditor_iban_index)
+    .fetch_one(&mut **tx)
+    .await?;
+    Ok(transfer_id)
+}
+
+/// Record the invoice amounts a transfer settles. Fails if any amount is no
+/// longer outstanding, rather than paying it out twice.
+async fn settle(
+    tx: &mut Transaction<'_, Postgres>,
+    transfer_id: Uuid,
+    lines: &[Outstanding],
+) -> Result<(), AdminError> {
+    let ids: Vec<Uuid> = lines.iter().map(|l| l.invoice_id).collect();
+    let amounts: Vec<f64> = lines.iter().map(|l| l.amount).collect();
+
+    let inserted = sqlx::query(&format!(
+        "INSERT INTO admin_payout_items (transfer_id, invoice_id, amount) \
+         SELECT $1, l.invoice_id, round(l.amount::numeric, 2) \
+         FROM UNNEST($2::uuid[], $3::float8[]) AS l(invoice_id, amount) \
+         JOIN admin_invoices i ON i.id = l.invoice_id \
+         WHERE round(l.amount::numeric, 2) <= {OUTSTANDING}"
+    ))
+    .bind(transfer_id)
+    .bind(&ids)
+    .bind(&amounts)
+    .execute(&mut **tx)
+    .await?
+    .rows_affected();
+
+    if inserted != lines.len() as u64 {
+        return Err(AdminError::Conflict(
+            "Invoices were settled by another payout; create the batch again".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Net transfer for a set of outstanding amounts: collected rent less
+/// expenses and the management fee on the rent.
+fn net_amount(lines: &[Outstanding], fee_pct: f64) -> f64 {
+    let collected = round2(lines.iter().filter(|l| l.income).map(|l| l.amount).sum());
+    let expenses = round2(lines.iter().filter(|l| !l.income).map(|l| l.amount).sum());
+    let fee = round2(collected * fee_pct / 100.0);
+    round2(collected - expenses - fee)
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
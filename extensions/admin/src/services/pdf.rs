@@ -7,6 +7,14 @@ use printpdf::*;
 
 use crate::error::AdminError;
 
+mod document;
+mod evidence;
+mod inspection;
+mod statement;
+
+pub use document::DocumentBlock;
+pub use evidence::{SignatureEvidence, SignatureMark, SignerEvidence};
+
 // ── Layout constants (A4, millimetres) ──────────────────────────────────────
 const PAGE_W: f32 = 210.0;
 const PAGE_H: f32 = 297.0;
@@ -76,40 +84,38 @@ impl PdfService {
             PdfDocument::new("Invoice", Mm(PAGE_W), Mm(PAGE_H), "Layer 1");
 
         let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
-        let fonts_dir = cwd.join("storage/files/fonts/Nunito");
-
-        let font_regular = load_font(&doc, &fonts_dir.join("Nunito-Regular.ttf"))?;
-        let font_medium = load_font(&doc, &fonts_dir.join("Nunito-Medium.ttf"))?;
-        let font_bold = load_font(&doc, &fonts_dir.join("Nunito-Bold.ttf"))?;
-        let font_extrabold = load_font(&doc, &fonts_dir.join("Nunito-ExtraBold.ttf"))?;
-
-        let fonts = Fonts {
-            regular: &font_regular,
-            medium: &font_medium,
-            bold: &font_bold,
-            extrabold: &font_extrabold,
-        };
+        let loaded = load_fonts(&doc, &cwd)?;
+        let fonts = loaded.as_fonts();
 
         let current_layer = doc.get_page(page).get_layer(layer);
         let mut y = PAGE_H - MT;
 
-        y = draw_header(&current_layer, &fonts, &cwd, y);
+        y = draw_header(&current_layer, &fonts, &cwd, y, "RENTAL INVOICE");
         y = draw_invoice_details(&current_layer, &fonts, entry, y);
         y = draw_parties(&current_layer, &fonts, entry, enrichment, y);
         y = draw_line_items_header(&current_layer, &fonts, y);
         y = draw_line_items(&current_layer, &fonts, entry, y);
-        y = draw_totals(&current_layer, &fonts, entry, y);
-        draw_footer(&current_layer, &fonts, entry, y);
-
-        let mut buf = BufWriter::new(Vec::new());
-        doc.save(&mut buf)
-            .map_err(|e| AdminError::PdfGeneration(format!("PDF save error: {e}")))?;
+        draw_totals(&current_layer, &fonts, entry, y);
+        draw_footer(
+            &current_layer,
+            &fonts,
+            entry.iban.as_deref(),
+            "This document serves as a valid receipt for rental payment.",
+        );
 
-        buf.into_inner()
-            .map_err(|e| AdminError::PdfGeneration(format!("Buffer error: {e}")))
+        save_document(doc)
     }
 }
 
+fn save_document(doc: PdfDocumentReference) -> Result<Vec<u8>, AdminError> {
+    let mut buf = BufWriter::new(Vec::new());
+    doc.save(&mut buf)
+        .map_err(|e| AdminError::PdfGeneration(format!("PDF save error: {e}")))?;
+
+    buf.into_inner()
+        .map_err(|e| AdminError::PdfGeneration(format!("Buffer error: {e}")))
+}
+
 // ── Font bundle ─────────────────────────────────────────────────────────────
 
 struct Fonts<'a> {
@@ -119,6 +125,38 @@ struct Fonts<'a> {
     extrabold: &'a IndirectFontRef,
 }
 
+/// Owned font handles; borrow them as a [`Fonts`] bundle for drawing.
+struct LoadedFonts {
+    regular: IndirectFontRef,
+    medium: IndirectFontRef,
+    bold: IndirectFontRef,
+    extrabold: IndirectFontRef,
+}
+
+impl LoadedFonts {
+    fn as_fonts(&self) -> Fonts<'_> {
+        Fonts {
+            regular: &self.regular,
+            medium: &self.medium,
+            bold: &self.bold,
+            extrabold: &self.extrabold,
+        }
+    }
+}
+
+fn load_fonts(
+    doc: &PdfDocumentReference,
+    cwd: &std::path::Path,
+) -> Result<LoadedFonts, AdminError> {
+    let fonts_dir = cwd.join("storage/files/fonts/Nunito");
+    Ok(LoadedFonts {
+        regular: load_font(doc, &fonts_dir.join("Nunito-Regular.ttf"))?,
+        medium: load_font(doc, &fonts_dir.join("Nunito-Medium.ttf"))?,
+        bold: load_font(doc, &fonts_dir.join("Nunito-Bold.ttf"))?,
+        extrabold: load_font(doc, &fonts_dir.join("Nunito-ExtraBold.ttf"))?,
+    })
+}
+
 fn load_font(
     doc: &PdfDocumentReference,
     path: &std::path::Path,
@@ -130,6 +168,68 @@ fn load_font(
         .map_err(|e| AdminError::PdfGeneration(format!("Font load error: {e}")))
 }
 
+// ── Page flow ───────────────────────────────────────────────────────────────
+
+const BOTTOM_LIMIT: f32 = 40.0; // keep clear of the footer
+
+/// Tracks the current page and vertical position, adding pages as content overflows.
+struct PageCursor<'a> {
+    doc: &'a PdfDocumentReference,
+    fonts: Fonts<'a>,
+    layers: Vec<PdfLayerReference>,
+    y: f32,
+    continuation: String,
+}
+
+impl PageCursor<'_> {
+    fn layer(&self) -> &PdfLayerReference {
+        self.layers
+            .last()
+            .expect("page cursor always holds at least one page")
+    }
+
+    /// Start a new page when fewer than `needed` millimetres remain. Returns true on a page break.
+    fn ensure_space(&mut self, needed: f32) -> bool {
+        if self.y - needed >= BOTTOM_LIMIT {
+            return false;
+        }
+        let (page, layer) = self.doc.add_page(Mm(PAGE_W), Mm(PAGE_H), "Layer 1");
+        self.layers.push(self.doc.get_page(page).get_layer(layer));
+        self.y = PAGE_H - MT;
+
+        txt(
+            self.layer(),
+            &self.continuation,
+            ML,
+            self.y,
+            9.0,
+            self.fonts.bold,
+            LIGHT,
+        );
+        self.y -= 3.0;
+        stroke_line(self.layer(), ML, PAGE_W - MR, self.y, 0.5, ACCENT);
+        self.y -= 8.0;
+        true
+    }
+
+    /// Draw the footer and "Page n of m" on every page.
+    fn draw_footers(&self, iban: Option<&str>, note: &str) {
+        let total = self.layers.len();
+        for (i, layer) in self.layers.iter().enumerate() {
+            draw_footer(layer, &self.fonts, iban, note);
+            txt_right(
+                layer,
+                &format!("Page {} of {total}", i + 1),
+                PAGE_W - MR,
+                12.0,
+                8.0,
+                self.fonts.regular,
+                LIGHT,
+            );
+        }
+    }
+}
+
 // ── Drawing helpers ─────────────────────────────────────────────────────────
 
 fn rgb((r, g, b): (f32, f32, f32)) -> Color {
@@ -205,8 +305,8 @@ fn fmt_date(dt: &NaiveDate) -> String {
     dt.format("%d/%m/%Y").to_string()
 }
 
-fn currency_sym(entry: &InvoiceData) -> &'static str {
-    match entry.currency.as_str() {
+fn currency_sym(currency: &str) -> &'static str {
+    match currency {
         "USD" | "usd" => "$",
         "EUR" | "eur" => "\u{20ac}",
         _ => "\u{20ac}", // EUR euro sign (default)
@@ -304,6 +404,7 @@ fn draw_header(
     fonts: &Fonts<'_>,
     cwd: &std::path::Path,
     top: f32,
+    title: &str,
 ) -> f32 {
     let logo_bytes = LOGO_BYTES.get_or_init(|| {
         let logo_path = cwd.join("storage/files/images/logo.png");
@@ -362,10 +463,10 @@ fn draw_header(
     stroke_line(layer, ML, PAGE_W - MR, y, 0.5, ACCENT);
     y -= 10.0;
 
-    // Title: RENTAL INVOICE
+    // Document title
     txt_center(
         layer,
-        "RENTAL INVOICE",
+        title,
         PAGE_W / 2.0,
         y,
         18.0,
@@ -511,7 +612,7 @@ fn draw_line_items(
     entry: &InvoiceData,
     top: f32,
 ) -> f32 {
-    let sym = currency_sym(entry);
+    let sym = currency_sym(&entry.currency);
     let base = entry.amount - entry.vat;
     let right_x = PAGE_W - MR;
 
@@ -540,7 +641,7 @@ fn draw_totals(
     entry: &InvoiceData,
     top: f32,
 ) -> f32 {
-    let sym = currency_sym(entry);
+    let sym = currency_sym(&entry.currency);
     let base = entry.amount - entry.vat;
     let total_due = entry.amount - entry.discount - entry.retention;
 
@@ -591,26 +692,16 @@ fn draw_totals(
 fn draw_footer(
     layer: &PdfLayerReference,
     fonts: &Fonts<'_>,
-    entry: &InvoiceData,
-    _top: f32,
+    iban: Option<&str>,
+    note: &str,
 ) {
     let mut y = 25.0;
 
     // IBAN line
-    if let Some(ref iban) = entry.iban {
-        if !iban.is_empty() {
-            txt(layer, &format!("IBAN: {iban}"), ML, y, 9.0, fonts.regular, MID);
-            y -= 5.0;
-        }
+    if let Some(iban) = iban.filter(|s| !s.is_empty()) {
+        txt(layer, &format!("IBAN: {iban}"), ML, y, 9.0, fonts.regular, MID);
+        y -= 5.0;
     }
 
-    txt(
-        layer,
-        "This document serves as a valid receipt for rental payment.",
-        ML,
-        y,
-        8.0,
-        fonts.regular,
-        LIGHT,
-    );
+    txt(layer, note, ML, y, 8.0, fonts.regular, LIGHT);
 }
@@ -0,0 +1,177 @@
+use std::path::PathBuf;
+
+use printpdf::{Mm, PdfDocument};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    draw_header, load_fonts, save_document, stroke_line, txt, PageCursor, PdfService, BORDER,
+    CONTENT_W, DARK, ML, MR, MT, PAGE_H, PAGE_W,
+};
+use crate::error::AdminError;
+
+// ── Document layout ─────────────────────────────────────────────────────────
+const BODY_SIZE: f32 = 10.0;
+const LINE_H: f32 = 5.0;
+const BULLET_INDENT: f32 = 6.0;
+
+/// A block of a rendered document template.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "content", rename_all = "snake_case")]
+pub enum DocumentBlock {
+    /// Level 1 to 3
+    Heading(u8, String),
+    Paragraph(String),
+    /// Marker (`•` or `1.`) and text
+    ListItem(String, String),
+    Rule,
+}
+
+impl PdfService {
+    /// Generate a multi-page document (lease, annex) from template blocks.
+    pub fn generate_document_pdf(
+        title: &str,
+        blocks: &[DocumentBlock],
+        note: &str,
+    ) -> Result<Vec<u8>, AdminError> {
+        render_document(title, blocks, note)
+    }
+}
+
+fn render_document(
+    title: &str,
+    blocks: &[DocumentBlock],
+    note: &str,
+) -> Result<Vec<u8>, AdminError> {
+    let (doc, page, layer) = PdfDocument::new(title, Mm(PAGE_W), Mm(PAGE_H), "Layer 1");
+
+    let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    let loaded = load_fonts(&doc, &cwd)?;
+
+    let first_layer = doc.get_page(page).get_layer(layer);
+    let y = draw_header(
+        &first_layer,
+        &loaded.as_fonts(),
+        &cwd,
+        PAGE_H - MT,
+        &title.to_uppercase(),
+    );
+
+    let mut pages = PageCursor {
+        doc: &doc,
+        fonts: loaded.as_fonts(),
+        layers: vec![first_layer],
+        y,
+        continuation: format!("{title} (continued)"),
+    };
+
+    for block in blocks {
+        draw_block(&mut pages, block);
+    }
+    pages.draw_footers(None, note);
+
+    drop(pages);
+    save_document(doc)
+}
+
+fn draw_block(pages: &mut PageCursor<'_>, block: &DocumentBlock) {
+    match block {
+        DocumentBlock::Heading(level, text) => {
+            let size = match level {
+                1 => 14.0,
+                2 => 12.0,
+                _ => 10.5,
+            };
+            let line_h = size * 0.5;
+            // Keep a heading with the first line that follows it
+            pages.ensure_space(line_h * 2.0 + LINE_H + 3.0);
+            pages.y -= 3.0;
+            for line in wrap(text, CONTENT_W, size) {
+                pages.ensure_space(line_h);
+                let fonts = &pages.fonts;
+                txt(pages.layer(), &line, ML, pages.y, size, fonts.bold, DARK);
+                pages.y -= line_h;
+            }
+            pages.y -= 2.0;
+        }
+        DocumentBlock::Paragraph(text) => {
+            draw_lines(pages, text, ML, CONTENT_W);
+            pages.y -= 2.5;
+        }
+        DocumentBlock::ListItem(marker, text) => {
+            pages.ensure_space(LINE_H);
+            let fonts = &pages.fonts;
+            txt(
+                pages.layer(),
+                marker,
+                ML + 1.0,
+                pages.y,
+                BODY_SIZE,
+                fonts.regular,
+                DARK,
+            );
+            draw_lines(pages, text, ML + BULLET_INDENT, CONTENT_W - BULLET_INDENT);
+            pages.y -= 1.0;
+        }
+        DocumentBlock::Rule => {
+            pages.ensure_space(6.0);
+            pages.y -= 1.0;
+            stroke_line(pages.layer(), ML, PAGE_W - MR, pages.y, 0.3, BORDER);
+            pages.y -= 5.0;
+        }
+    }
+}
+
+/// Body text wrapped to `width`, breaking pages between lines.
+fn draw_lines(pages: &mut PageCursor<'_>, text: &str, x: f32, width: f32) {
+    for line in wrap(text, width, BODY_SIZE) {
+        pages.ensure_space(LINE_H);
+        let fonts = &pages.fonts;
+        txt(
+            pages.layer(),
+            &line,
+            x,
+            pages.y,
+            BODY_SIZE,
+            fonts.regular,
+            DARK,
+        );
+        pages.y -= LINE_H;
+    }
+}
+
+/// Greedy word wrap using the same per-character width estimate as
+/// `truncate_to_width`. Words longer than a line are split.
+fn wrap(text: &str, max_mm: f32, font_size: f32) -> Vec<String> {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let max_chars = ((max_mm / (font_size * 0.20)) as usize).max(1);
+
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    let mut line_len = 0;
+    for word in text.split_whitespace() {
+        let mut word: Vec<char> = word.chars().collect();
+        while word.len() > max_chars {
+            if line_len > 0 {
+                lines.push(std::mem::take(&mut line));
+                line_len = 0;
+            }
+            lines.push(word.drain(..max_chars).collect());
+        }
+        let word: String = word.into_iter().collect();
+        let word_len = word.chars().count();
+        if line_len > 0 && line_len + 1 + word_len > max_chars {
+            lines.push(std::mem::take(&mut line));
+            line_len = 0;
+        }
+        if line_len > 0 {
+            line.push(' ');
+            line_len += 1;
+        }
+        line.push_str(&word);
+        line_len += word_len;
+    }
+    if line_len > 0 {
+        lines.push(line);
+    }
+    lines
+}
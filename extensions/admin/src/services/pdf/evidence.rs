@@ -0,0 +1,353 @@
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use printpdf::lopdf::{Document, IncrementalDocument, Object, ObjectId};
+use printpdf::{image_crate, Image, ImageTransform, Mm, PdfDocument};
+
+use super::{
+    auto_crop_white, composite_on_white, draw_header, load_fonts, save_document, stroke_line,
+    truncate_to_width, txt, PageCursor, PdfService, ACCENT, BORDER, CONTENT_W, DARK, GREEN, LIGHT,
+    MID, ML, MR, MT, PAGE_H, PAGE_W, RED,
+};
+use crate::error::AdminError;
+
+// ── Evidence layout ─────────────────────────────────────────────────────────
+const LABEL_X: f32 = ML;
+const VALUE_X: f32 = ML + 32.0;
+const ROW_H: f32 = 5.0;
+const SIGNATURE_W: f32 = 60.0;
+const SIGNATURE_H: f32 = 20.0;
+
+/// What the evidence page of a signed document records.
+pub struct SignatureEvidence {
+    pub request_id: String,
+    pub document_name: String,
+    /// SHA-256 of the PDF sent for signature, hex
+    pub document_hash: String,
+    pub completed_at: DateTime<Utc>,
+    pub signers: Vec<SignerEvidence>,
+}
+
+pub struct SignerEvidence {
+    pub role: String,
+    pub name: String,
+    pub email: String,
+    pub signed_at: DateTime<Utc>,
+    pub ip: String,
+    pub user_agent: String,
+    /// SHA-256 of the PDF the signer was shown, hex
+    pub document_hash: String,
+    pub signature: SignatureMark,
+}
+
+pub enum SignatureMark {
+    /// PNG drawn by the signer
+    Drawn(Vec<u8>),
+    /// Name typed by the signer
+    Typed(String),
+}
+
+impl PdfService {
+    /// The signed copy of a document: the PDF that was signed, byte for byte,
+    /// with the evidence pages appended as an incremental update. Its first
+    /// bytes still hash to the SHA-256 the signers were shown.
+    pub fn stamp_signature_evidence(
+        original: &[u8],
+        evidence: &SignatureEvidence,
+        note: &str,
+    ) -> Result<Vec<u8>, AdminError> {
+        let pages = render_evidence(evidence, note)?;
+        append_pages(original, &pages)
+            .map_err(|e| AdminError::PdfGeneration(format!("Cannot stamp the signed PDF: {e}")))
+    }
+}
+
+fn render_evidence(evidence: &SignatureEvidence, note: &str) -> Result<Vec<u8>, AdminError> {
+    let title = "Signature evidence";
+    let (doc, page, layer) = PdfDocument::new(title, Mm(PAGE_W), Mm(PAGE_H), "Layer 1");
+
+    let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    let loaded = load_fonts(&doc, &cwd)?;
+
+    let first_layer = doc.get_page(page).get_layer(layer);
+    let y = draw_header(
+        &first_layer,
+        &loaded.as_fonts(),
+        &cwd,
+        PAGE_H - MT,
+        &title.to_uppercase(),
+    );
+
+    let mut pages = PageCursor {
+        doc: &doc,
+        fonts: loaded.as_fonts(),
+        layers: vec![first_layer],
+        y,
+        continuation: format!("{title} (continued)"),
+    };
+    draw_evidence(&mut pages, evidence);
+    pages.draw_footers(None, note);
+
+    drop(pages);
+    save_document(doc)
+}
+
+/// Append the pages of `extra` to the PDF `original` as an incremental
+/// update: `original` is copied unchanged and followed by the new objects,
+/// a page tree that lists the new pages after its own, and a new xref.
+fn append_pages(original: &[u8], extra: &[u8]) -> printpdf::lopdf::Result<Vec<u8>> {
+    let mut doc: IncrementalDocument = original.try_into()?;
+    let prev = doc.get_prev_documents();
+    let pages_id = prev.catalog()?.get(b"Pages")?.as_reference()?;
+
+    let mut added = Document::load_mem(extra)?;
+    added.renumber_objects_with(prev.max_id + 1);
+    let added_pages: Vec<ObjectId> = added.get_pages().into_values().collect();
+    // The added document's own catalog and page tree are left out
+    let added_root = added.trailer.get(b"Root")?.as_reference()?;
+    let added_tree = added.catalog()?.get(b"Pages")?.as_reference()?;
+
+    doc.new_document.max_id = added.max_id;
+    for (id, object) in added.objects {
+        if id != added_root && id != added_tree {
+            doc.new_document.objects.insert(id, object);
+        }
+    }
+    for page_id in &added_pages {
+        doc.new_document
+            .get_object_mut(*page_id)?
+            .as_dict_mut()?
+            .set("Parent", pages_id);
+    }
+
+    doc.opt_clone_object_to_new_document(pages_id)?;
+    let tree = doc.new_document.get_object_mut(pages_id)?.as_dict_mut()?;
+    let count = tree.get(b"Count")?.as_i64()?;
+    tree.get_mut(b"Kids")?
+        .as_array_mut()?
+        .extend(added_pages.iter().map(|id| Object::Reference(*id)));
+    tree.set(
+        "Count",
+        count + i64::try_from(added_pages.len()).unwrap_or_default(),
+    );
+
+    let mut bytes = Vec::new();
+    doc.save_to(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Draw the evidence: the document hash, then each signer with their
+/// signature, the time, IP address and browser they signed from.
+fn draw_evidence(pages: &mut PageCursor<'_>, evidence: &SignatureEvidence) {
+    let fonts = &pages.fonts;
+    txt(
+        pages.layer(),
+        &truncate_to_width(&evidence.document_name, CONTENT_W, 12.0),
+        ML,
+        pages.y,
+        12.0,
+        fonts.extrabold,
+        DARK,
+    );
+    pages.y -= 8.0;
+
+    let completed = format_time(&evidence.completed_at);
+    let rows = [
+        ("Request", evidence.request_id.as_str()),
+        ("SHA-256", evidence.document_hash.as_str()),
+        ("Completed", completed.as_str()),
+    ];
+    for (label, value) in rows {
+        row(pages, label, value);
+    }
+    pages.y -= 2.0;
+    stroke_line(pages.layer(), ML, PAGE_W - MR, pages.y, 0.3, BORDER);
+    pages.y -= 7.0;
+
+    for signer in &evidence.signers {
+        draw_signer(pages, signer, &evidence.document_hash);
+    }
+
+    pages.ensure_space(12.0);
+    let fonts = &pages.fonts;
+    for line in [
+        "Each signer opened a one-time link and signed the document identified by the SHA-256",
+        "above. The pages before this one are that document, unchanged.",
+    ] {
+        txt(pages.layer(), line, ML, pages.y, 8.0, fonts.regular, LIGHT);
+        pages.y -= 4.0;
+    }
+}
+
+fn draw_signer(pages: &mut PageCursor<'_>, signer: &SignerEvidence, document_hash: &str) {
+    // Keep a signer's details and signature on one page
+    pages.ensure_space(ROW_H * 6.0 + SIGNATURE_H + 12.0);
+
+    let fonts = &pages.fonts;
+    let heading = format!("{} \u{2014} {}", capitalise(&signer.role), signer.name);
+    txt(
+        pages.layer(),
+        &truncate_to_width(&heading, CONTENT_W, 10.0),
+        ML,
+        pages.y,
+        10.0,
+        fonts.bold,
+        ACCENT,
+    );
+    pages.y -= 6.0;
+
+    let signed_at = format_time(&signer.signed_at);
+    let method = match signer.signature {
+        SignatureMark::Drawn(_) => "Drawn",
+        SignatureMark::Typed(_) => "Typed",
+    };
+    let rows = [
+        ("Email", signer.email.as_str()),
+        ("Signed at", signed_at.as_str()),
+        ("IP address", signer.ip.as_str()),
+        ("Browser", signer.user_agent.as_str()),
+        ("Signature", method),
+    ];
+    for (label, value) in rows {
+        row(pages, label, value);
+    }
+
+    let (label, color) = if signer.document_hash == document_hash {
+        ("Document hash matches", GREEN)
+    } else {
+        ("Document hash differs", RED)
+    };
+    let fonts = &pages.fonts;
+    txt(
+        pages.layer(),
+        "Integrity",
+        LABEL_X,
+        pages.y,
+        8.5,
+        fonts.medium,
+        MID,
+    );
+    txt(
+        pages.layer(),
+        label,
+        VALUE_X,
+        pages.y,
+        8.5,
+        fonts.bold,
+        color,
+    );
+    pages.y -= ROW_H + 2.0;
+
+    match &signer.signature {
+        SignatureMark::Drawn(png) => draw_signature_image(pages, png),
+        SignatureMark::Typed(name) => {
+            let fonts = &pages.fonts;
+            txt(
+                pages.layer(),
+                &truncate_to_width(name, SIGNATURE_W * 2.0, 18.0),
+                VALUE_X,
+                pages.y - 10.0,
+                18.0,
+                fonts.extrabold,
+                DARK,
+            );
+        }
+    }
+    pages.y -= SIGNATURE_H;
+    stroke_line(
+        pages.layer(),
+        VALUE_X,
+        VALUE_X + SIGNATURE_W,
+        pages.y,
+        0.3,
+        BORDER,
+    );
+    pages.y -= 8.0;
+}
+
+/// Place a drawn signature in the signature box, scaled to fit.
+fn draw_signature_image(pages: &PageCursor<'_>, png: &[u8]) {
+    let Ok(img) = image_crate::load_from_memory(png) else {
+        let fonts = &pages.fonts;
+        txt(
+            pages.layer(),
+            "(signature image could not be read)",
+            VALUE_X,
+            pages.y - 10.0,
+            8.5,
+            fonts.regular,
+            RED,
+        );
+        return;
+    };
+    let cropped = image_crate::DynamicImage::ImageRgb8(auto_crop_white(&composite_on_white(&img)));
+
+    #[allow(clippy::cast_precision_loss)]
+    let (w_px, h_px) = (cropped.width() as f32, cropped.height() as f32);
+    let dpi = (w_px / (SIGNATURE_W / 25.4)).max(h_px / (SIGNATURE_H / 25.4));
+    let h_mm = h_px / dpi * 25.4;
+
+    Image::from_dynamic_image(&cropped).add_to_layer(
+        pages.layer().clone(),
+        ImageTransform {
+            translate_x: Some(Mm(VALUE_X)),
+            translate_y: Some(Mm(pages.y - SIGNATURE_H + (SIGNATURE_H - h_mm) / 2.0)),
+            dpi: Some(dpi),
+            ..Default::default()
+        },
+    );
+}
+
+fn row(pages: &mut PageCursor<'_>, label: &str, value: &str) {
+    let fonts = &pages.fonts;
+    txt(
+        pages.layer(),
+        label,
+        LABEL_X,
+        pages.y,
+        8.5,
+        fonts.medium,
+        MID,
+    );
+    txt(
+        pages.layer(),
+        &truncate_to_width(value, CONTENT_W - (VALUE_X - ML), 8.5),
+        VALUE_X,
+        pages.y,
+        8.5,
+        fonts.regular,
+        DARK,
+    );
+    pages.y -= ROW_H;
+}
+
+fn format_time(at: &DateTime<Utc>) -> String {
+    at.format("%d/%m/%Y %H:%M:%S UTC").to_string()
+}
+
+fn capitalise(s: &str) -> String {
+    let mut chars = s.chars();
+    chars.next().map_or_else(String::new, |first| {
+        first.to_uppercase().chain(chars).collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn times_are_shown_in_utc() {
+        let at = Utc.with_ymd_and_hms(2026, 3, 7, 9, 5, 30).unwrap();
+        assert_eq!(format_time(&at), "07/03/2026 09:05:30 UTC");
+    }
+
+    #[test]
+    fn capitalise_upper_cases_the_first_letter() {
+        assert_eq!(capitalise("tenant"), "Tenant");
+        assert_eq!(capitalise("ñame"), "Ñame");
+        assert_eq!(capitalise(""), "");
+    }
+}
@@ -0,0 +1,403 @@
+use std::path::PathBuf;
+
+use printpdf::{image_crate, Image, ImageTransform, Mm, PdfDocument};
+
+use super::{
+    composite_on_white, currency_sym, draw_header, fmt_amount, fmt_date, load_fonts, save_document,
+    stroke_line, truncate_to_width, txt, txt_amount_right, txt_right, PageCursor, PdfService,
+    ACCENT, BORDER, CONTENT_W, DARK, GREEN, LIGHT, MID, ML, MR, MT, PAGE_H, PAGE_W, RED,
+};
+use crate::error::AdminError;
+use crate::services::inspections::{
+    condition_name, ConditionChange, Inspection, InspectionReport, ReportPhoto,
+};
+
+// ── Inspection layout ───────────────────────────────────────────────────────
+const ROW_H: f32 = 5.5;
+const COL_CHECK_IN: f32 = ML + 62.0;
+const COL_CHECK_OUT: f32 = ML + 86.0;
+const COL_CHANGE: f32 = ML + 110.0;
+const COL_DEDUCTION_RIGHT: f32 = PAGE_W - MR;
+const PHOTO_COLUMNS: usize = 3;
+const PHOTO_GAP: f32 = 5.0;
+const PHOTO_H: f32 = 38.0;
+
+impl PdfService {
+    /// Generate the inspection report of a contract: the check-in inventory,
+    /// or both inspections side by side with suggested deductions, then the
+    /// photos.
+    pub fn generate_inspection_pdf(report: &InspectionReport) -> Result<Vec<u8>, AdminError> {
+        let (doc, page, layer) =
+            PdfDocument::new("Inspection Report", Mm(PAGE_W), Mm(PAGE_H), "Layer 1");
+
+        let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        let loaded = load_fonts(&doc, &cwd)?;
+
+        let first_layer = doc.get_page(page).get_layer(layer);
+        let y = draw_header(
+            &first_layer,
+            &loaded.as_fonts(),
+            &cwd,
+            PAGE_H - MT,
+            "INSPECTION REPORT",
+        );
+
+        let contract_ref = report.diff.contract["contract_ref"]
+            .as_str()
+            .unwrap_or_default();
+        let mut pages = PageCursor {
+            doc: &doc,
+            fonts: loaded.as_fonts(),
+            layers: vec![first_layer],
+            y,
+            continuation: format!("INSPECTION REPORT \u{2014} {contract_ref} (continued)"),
+        };
+
+        draw_summary(&mut pages, report);
+        match (&report.diff.check_in, &report.diff.check_out) {
+            (_, Some(_)) => {
+                draw_changes(&mut pages, &report.diff.changes);
+                draw_deductions(&mut pages, report);
+            }
+            (Some(inspection), None) => draw_inventory(&mut pages, inspection),
+            (None, None) => {}
+        }
+        draw_photos(&mut pages, &report.photos);
+        pages.draw_footers(
+            None,
+            "Conditions: 5 Excellent, 4 Good, 3 Fair, 2 Poor, 1 Damaged, 0 Missing.",
+        );
+
+        drop(pages);
+        save_document(doc)
+    }
+}
+
+fn draw_summary(pages: &mut PageCursor<'_>, report: &InspectionReport) {
+    let contract = &report.diff.contract;
+    let field = |name: &str| contract[name].as_str().unwrap_or_default().to_string();
+    let inspection_line = |inspection: Option<&Inspection>| {
+        inspection.map_or_else(
+            || "Not recorded".to_string(),
+            |i| {
+                let by = if i.inspector.is_empty() {
+                    String::new()
+                } else {
+                    format!(" by {}", i.inspector)
+                };
+                format!("{}{by}", fmt_date(&i.inspection_date))
+            },
+        )
+    };
+    let rows = [
+        ("Contract", field("contract_ref")),
+        ("Property", field("property_name")),
+        ("Address", field("address")),
+        ("Tenant", field("tenant_name")),
+        ("Check-in", inspection_line(report.diff.check_in.as_ref())),
+        ("Check-out", inspection_line(report.diff.check_out.as_ref())),
+    ];
+
+    let fonts = &pages.fonts;
+    for (label, value) in rows {
+        txt(pages.layer(), label, ML, pages.y, 8.5, fonts.medium, MID);
+        txt(
+            pages.layer(),
+            &truncate_to_width(&value, CONTENT_W - 28.0, 8.5),
+            ML + 28.0,
+            pages.y,
+            8.5,
+            fonts.regular,
+            DARK,
+        );
+        pages.y -= ROW_H;
+    }
+    pages.y -= 2.0;
+    stroke_line(pages.layer(), ML, PAGE_W - MR, pages.y, 0.3, BORDER);
+    pages.y -= 8.0;
+}
+
+fn section_title(pages: &mut PageCursor<'_>, title: &str) {
+    pages.ensure_space(ROW_H * 4.0);
+    let fonts = &pages.fonts;
+    txt(pages.layer(), title, ML, pages.y, 10.0, fonts.bold, ACCENT);
+    pages.y -= 7.0;
+}
+
+/// Check-in and check-out condition per room, with suggested deductions.
+fn draw_changes(pages: &mut PageCursor<'_>, changes: &[ConditionChange]) {
+    section_title(pages, "CONDITION AT CHECK-IN AND CHECK-OUT");
+    let sym = currency_sym("EUR");
+    let mut room = "";
+    for change in changes {
+        if change.room != room {
+            room = &change.room;
+            room_heading(
+                pages,
+                room,
+                &["Check-in", "Check-out", "Change", "Deduction"],
+            );
+        }
+        pages.ensure_space(ROW_H);
+        let fonts = &pages.fonts;
+        let condition = |c: Option<i16>| c.map_or("\u{2014}", condition_name);
+        let color = match change.change {
+            "worse" | "missing" => RED,
+            "better" => GREEN,
+            _ => DARK,
+        };
+        txt(
+            pages.layer(),
+            &truncate_to_width(&change.item, COL_CHECK_IN - ML - 3.0, 8.5),
+            ML + 2.0,
+            pages.y,
+            8.5,
+            fonts.regular,
+            DARK,
+        );
+        txt(
+            pages.layer(),
+            condition(change.check_in),
+            COL_CHECK_IN,
+            pages.y,
+            8.5,
+            fonts.regular,
+            DARK,
+        );
+        txt(
+            pages.layer(),
+            condition(change.check_out),
+            COL_CHECK_OUT,
+            pages.y,
+            8.5,
+            fonts.regular,
+            DARK,
+        );
+        txt(
+            pages.layer(),
+            change.change,
+            COL_CHANGE,
+            pages.y,
+            8.5,
+            fonts.medium,
+            color,
+        );
+        if change.suggested_deduction > 0.0 {
+            txt_amount_right(
+                pages.layer(),
+                &fmt_amount(change.suggested_deduction),
+                sym,
+                COL_DEDUCTION_RIGHT,
+                pages.y,
+                8.5,
+                fonts.regular,
+                DARK,
+            );
+        }
+        pages.y -= ROW_H;
+        for notes in [&change.check_in_notes, &change.check_out_notes] {
+            item_notes(pages, notes);
+        }
+    }
+    pages.y -= 4.0;
+}
+
+/// The check-in alone, before the tenant has moved out.
+fn draw_inventory(pages: &mut PageCursor<'_>, inspection: &Inspection) {
+    section_title(pages, "INVENTORY AT CHECK-IN");
+    let mut room = "";
+    for item in &inspection.items {
+        if item.room != room {
+            room = &item.room;
+            room_heading(pages, room, &["Condition"]);
+        }
+        pages.ensure_space(ROW_H);
+        let fonts = &pages.fonts;
+        txt(
+            pages.layer(),
+            &truncate_to_width(&item.item, COL_CHECK_IN - ML - 3.0, 8.5),
+            ML + 2.0,
+            pages.y,
+            8.5,
+            fonts.regular,
+            DARK,
+        );
+        txt(
+            pages.layer(),
+            condition_name(item.condition),
+            COL_CHECK_IN,
+            pages.y,
+            8.5,
+            fonts.regular,
+            DARK,
+        );
+        pages.y -= ROW_H;
+        item_notes(pages, &item.notes);
+    }
+    pages.y -= 4.0;
+}
+
+fn room_heading(pages: &mut PageCursor<'_>, room: &str, columns: &[&str]) {
+    // Keep a room's heading with its first item
+    pages.ensure_space(ROW_H * 3.0);
+    let fonts = &pages.fonts;
+    txt(
+        pages.layer(),
+        &truncate_to_width(room, COL_CHECK_IN - ML - 3.0, 9.0),
+        ML,
+        pages.y,
+        9.0,
+        fonts.bold,
+        DARK,
+    );
+    for (column, x) in columns
+        .iter()
+        .zip([COL_CHECK_IN, COL_CHECK_OUT, COL_CHANGE])
+    {
+        txt(pages.layer(), column, x, pages.y, 7.5, fonts.medium, LIGHT);
+    }
+    if columns.len() > 3 {
+        txt_right(
+            pages.layer(),
+            columns[3],
+            COL_DEDUCTION_RIGHT,
+            pages.y,
+            7.5,
+            fonts.medium,
+            LIGHT,
+        );
+    }
+    pages.y -= 2.0;
+    stroke_line(pages.layer(), ML, PAGE_W - MR, pages.y, 0.3, BORDER);
+    pages.y -= 4.5;
+}
+
+fn item_notes(pages: &mut PageCursor<'_>, notes: &str) {
+    if notes.is_empty() {
+        return;
+    }
+    pages.ensure_space(4.0);
+    let fonts = &pages.fonts;
+    txt(
+        pages.layer(),
+        &truncate_to_width(notes, CONTENT_W - 6.0, 7.5),
+        ML + 6.0,
+        pages.y + 0.5,
+        7.5,
+        fonts.regular,
+        LIGHT,
+    );
+    pages.y -= 4.0;
+}
+
+fn draw_deductions(pages: &mut PageCursor<'_>, report: &InspectionReport) {
+    section_title(pages, "DEPOSIT");
+    let sym = currency_sym("EUR");
+    let deposit = report.diff.deposit.as_ref();
+    let amount = |name: &str| deposit.and_then(|d| d[name].as_f64()).unwrap_or_default();
+    let rows = [
+        ("Deposit paid", amount("paid")),
+        ("Suggested deductions", report.diff.suggested_total),
+        ("Deductions applied", amount("deductions")),
+    ];
+    for (label, value) in rows {
+        let fonts = &pages.fonts;
+        txt(pages.layer(), label, ML, pages.y, 8.5, fonts.medium, MID);
+        txt_amount_right(
+            pages.layer(),
+            &fmt_amount(value),
+            sym,
+            COL_DEDUCTION_RIGHT,
+            pages.y,
+            8.5,
+            fonts.regular,
+            DARK,
+        );
+        pages.y -= ROW_H;
+    }
+    let fonts = &pages.fonts;
+    let note = match (deposit, report.diff.capped) {
+        (None, _) => "No deposit is recorded for this contract.",
+        (Some(_), true) => "Suggested deductions are capped at the deposit paid.",
+        (Some(_), false) => {
+            "One grade lost per item is allowed as fair wear and tear before any deduction."
+        }
+    };
+    txt(pages.layer(), note, ML, pages.y, 8.0, fonts.regular, LIGHT);
+    pages.y -= 10.0;
+}
+
+/// Photos in a grid, each captioned with its room, item and inspection.
+fn draw_photos(pages: &mut PageCursor<'_>, photos: &[ReportPhoto]) {
+    if photos.is_empty() {
+        return;
+    }
+    section_title(pages, "PHOTOS");
+    #[allow(clippy::cast_precision_loss)]
+    let cell_w = (CONTENT_W - PHOTO_GAP * (PHOTO_COLUMNS - 1) as f32) / PHOTO_COLUMNS as f32;
+    for row in photos.chunks(PHOTO_COLUMNS) {
+        pages.ensure_space(PHOTO_H + 10.0);
+        for (i, photo) in row.iter().enumerate() {
+            #[allow(clippy::cast_precision_loss)]
+            let x = ML + (cell_w + PHOTO_GAP) * i as f32;
+            draw_photo(pages, photo, x, cell_w);
+        }
+        pages.y -= PHOTO_H + 10.0;
+    }
+}
+
+fn draw_photo(pages: &PageCursor<'_>, photo: &ReportPhoto, x: f32, cell_w: f32) {
+    let fonts = &pages.fonts;
+    let kind = if photo.kind == "check_in" {
+        "check-in"
+    } else {
+        "check-out"
+    };
+    let caption = if photo.caption.is_empty() {
+        format!("{} / {} ({kind})", photo.room, photo.item)
+    } else {
+        format!(
+            "{} / {} ({kind}): {}",
+            photo.room, photo.item, photo.caption
+        )
+    };
+    txt(
+        pages.layer(),
+        &truncate_to_width(&caption, cell_w, 7.0),
+        x,
+        pages.y - PHOTO_H - 4.0,
+        7.0,
+        fonts.regular,
+        MID,
+    );
+
+    let Ok(img) = image_crate::load_from_memory(&photo.content) else {
+        txt(
+            pages.layer(),
+            "(photo could not be read)",
+            x,
+            pages.y - PHOTO_H / 2.0,
+            7.5,
+            fonts.regular,
+            RED,
+        );
+        return;
+    };
+    // Photos are scaled down before embedding to keep the report small
+    let image = image_crate::DynamicImage::ImageRgb8(composite_on_white(&img.thumbnail(900, 900)));
+
+    #[allow(clippy::cast_precision_loss)]
+    let (w_px, h_px) = (image.width() as f32, image.height() as f32);
+    let dpi = (w_px / (cell_w / 25.4)).max(h_px / (PHOTO_H / 25.4));
+    let h_mm = h_px / dpi * 25.4;
+
+    Image::from_dynamic_image(&image).add_to_layer(
+        pages.layer().clone(),
+        ImageTransform {
+            translate_x: Some(Mm(x)),
+            translate_y: Some(Mm(pages.y - PHOTO_H + (PHOTO_H - h_mm) / 2.0)),
+            dpi: Some(dpi),
+            ..Default::default()
+        },
+    );
+}
@@ -0,0 +1,356 @@
+use std::path::PathBuf;
+
+use printpdf::{Mm, PdfDocument};
+
+use super::{
+    currency_sym, draw_header, fmt_amount, fmt_date, load_fonts, save_document, stroke_line,
+    truncate_to_width, txt, txt_amount_right, txt_right, PageCursor, PdfService, ACCENT, BORDER,
+    CONTENT_W, DARK, LIGHT, MID, ML, MR, MT, PAGE_H, PAGE_W,
+};
+use crate::error::AdminError;
+use crate::services::statements::{OwnerStatement, StatementLine};
+
+// ── Statement layout ────────────────────────────────────────────────────────
+const ROW_H: f32 = 5.5;
+const COL_REF: f32 = ML + 20.0;
+const COL_PROPERTY: f32 = ML + 46.0;
+const COL_PARTY: f32 = ML + 94.0;
+const COL_AMOUNT_RIGHT: f32 = PAGE_W - MR - 22.0;
+const COL_PAID_RIGHT: f32 = PAGE_W - MR;
+
+impl PdfService {
+    /// Generate a multi-page owner statement PDF.
+    pub fn generate_owner_statement_pdf(statement: &OwnerStatement) -> Result<Vec<u8>, AdminError> {
+        let (doc, page, layer) =
+            PdfDocument::new("Owner Statement", Mm(PAGE_W), Mm(PAGE_H), "Layer 1");
+
+        let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        let loaded = load_fonts(&doc, &cwd)?;
+
+        let first_layer = doc.get_page(page).get_layer(layer);
+        let y = draw_header(
+            &first_layer,
+            &loaded.as_fonts(),
+            &cwd,
+            PAGE_H - MT,
+            "OWNER STATEMENT",
+        );
+
+        let mut pages = PageCursor {
+            doc: &doc,
+            fonts: loaded.as_fonts(),
+            layers: vec![first_layer],
+            y,
+            continuation: format!(
+                "OWNER STATEMENT \u{2014} {} (continued)",
+                statement.owner_name
+            ),
+        };
+
+        let sym = currency_sym(&statement.currency);
+        draw_owner_block(&mut pages, statement);
+        draw_summary(&mut pages, statement, sym);
+        draw_lines(
+            &mut pages,
+            "RENT COLLECTED",
+            "Tenant",
+            &statement.income,
+            sym,
+            true,
+        );
+        draw_lines(
+            &mut pages,
+            "EXPENSES",
+            "Supplier",
+            &statement.expenses,
+            sym,
+            false,
+        );
+
+        let note = format!(
+            "Statement for {} to {}. Net transfer is paid to the account above.",
+            fmt_date(&statement.period.from),
+            fmt_date(&statement.period.to)
+        );
+        pages.draw_footers(Some(&statement.owner_bank_account), &note);
+
+        drop(pages);
+        save_document(doc)
+    }
+}
+
+// ── Section renderers ───────────────────────────────────────────────────────
+
+fn draw_owner_block(pages: &mut PageCursor<'_>, statement: &OwnerStatement) {
+    let layer = pages.layer().clone();
+    let fonts = &pages.fonts;
+    let lx = ML;
+    let rx = ML + CONTENT_W / 2.0 + 5.0;
+    let top = pages.y;
+
+    // Left: owner
+    let mut yl = top;
+    txt(&layer, "OWNER", lx, yl, 8.0, fonts.bold, LIGHT);
+    yl -= 6.0;
+    txt(
+        &layer,
+        &statement.owner_name,
+        lx,
+        yl,
+        12.0,
+        fonts.bold,
+        DARK,
+    );
+    yl -= 5.5;
+    if !statement.owner_tax_id.is_empty() {
+        let tax_id = format!("Tax ID: {}", statement.owner_tax_id);
+        txt(&layer, &tax_id, lx, yl, 9.0, fonts.regular, MID);
+        yl -= 4.5;
+    }
+    if !statement.owner_address.is_empty() {
+        let address = truncate_to_width(&statement.owner_address, CONTENT_W / 2.0 - 2.0, 9.0);
+        txt(&layer, &address, lx, yl, 9.0, fonts.regular, MID);
+        yl -= 4.5;
+    }
+
+    // Right: period
+    let mut yr = top;
+    txt(&layer, "STATEMENT PERIOD", rx, yr, 8.0, fonts.bold, LIGHT);
+    yr -= 6.0;
+    let period = format!(
+        "{} \u{2013} {}",
+        fmt_date(&statement.period.from),
+        fmt_date(&statement.period.to)
+    );
+    txt(&layer, &period, rx, yr, 12.0, fonts.bold, DARK);
+    yr -= 5.5;
+    let fee = format!("Management fee: {:.2}%", statement.management_fee_pct);
+    txt(&layer, &fee, rx, yr, 9.0, fonts.regular, MID);
+    yr -= 4.5;
+
+    pages.y = yl.min(yr) - 10.0;
+}
+
+fn draw_summary(pages: &mut PageCursor<'_>, statement: &OwnerStatement, sym: &str) {
+    pages.ensure_space(45.0);
+    let layer = pages.layer().clone();
+    let fonts = &pages.fonts;
+    let right_x = PAGE_W - MR;
+    let mut y = pages.y;
+
+    txt(&layer, "SUMMARY", ML, y, 10.0, fonts.bold, DARK);
+    y -= 3.0;
+    stroke_line(&layer, ML, right_x, y, 0.5, ACCENT);
+    y -= 7.0;
+
+    let fee_label = format!("Management fee ({:.2}%)", statement.management_fee_pct);
+    let rows = [
+        ("Rent invoiced", statement.rent_invoiced),
+        ("Rent collected", statement.rent_collected),
+        ("Expenses deducted", -statement.expenses_total),
+        (fee_label.as_str(), -statement.management_fee),
+    ];
+    for (label, value) in rows {
+        txt(&layer, label, ML, y, 10.0, fonts.medium, MID);
+        txt_amount_right(
+            &layer,
+            &fmt_amount(value),
+            sym,
+            right_x,
+            y,
+            10.0,
+            fonts.medium,
+            DARK,
+        );
+        y -= 6.5;
+    }
+
+    stroke_line(&layer, ML, right_x, y + 3.0, 0.3, ACCENT);
+    y -= 5.0;
+    txt(
+        &layer,
+        "Net transfer to owner",
+        ML,
+        y,
+        13.0,
+        fonts.extrabold,
+        DARK,
+    );
+    txt_amount_right(
+        &layer,
+        &fmt_amount(statement.net_transfer),
+        sym,
+        right_x,
+        y,
+        13.0,
+        fonts.extrabold,
+        DARK,
+    );
+
+    pages.y = y - 14.0;
+}
+
+fn draw_lines(
+    pages: &mut PageCursor<'_>,
+    title: &str,
+    party_label: &str,
+    lines: &[StatementLine],
+    sym: &str,
+    show_paid: bool,
+) {
+    pages.ensure_space(25.0);
+    txt(
+        pages.layer(),
+        title,
+        ML,
+        pages.y,
+        10.0,
+        pages.fonts.bold,
+        DARK,
+    );
+    pages.y -= 3.0;
+    stroke_line(pages.layer(), ML, PAGE_W - MR, pages.y, 0.5, ACCENT);
+    pages.y -= 7.0;
+    draw_column_headers(pages, party_label, sym, show_paid);
+
+    if lines.is_empty() {
+        txt(
+            pages.layer(),
+            "No entries in this period.",
+            ML,
+            pages.y,
+            9.0,
+            pages.fonts.regular,
+            LIGHT,
+        );
+        pages.y -= 12.0;
+        return;
+    }
+
+    for line in lines {
+        if pages.ensure_space(ROW_H) {
+            draw_column_headers(pages, party_label, sym, show_paid);
+        }
+        let layer = pages.layer().clone();
+        let fonts = &pages.fonts;
+        let y = pages.y;
+
+        let date = line.invoice_date.as_ref().map(fmt_date).unwrap_or_default();
+        txt(&layer, &date, ML, y, 8.0, fonts.regular, DARK);
+        txt(
+            &layer,
+            &truncate_to_width(&line.reference, 24.0, 8.0),
+            COL_REF,
+            y,
+            8.0,
+            fonts.regular,
+            DARK,
+        );
+        txt(
+            &layer,
+            &truncate_to_width(&line.property_name, 46.0, 8.0),
+            COL_PROPERTY,
+            y,
+            8.0,
+            fonts.regular,
+            DARK,
+        );
+        txt(
+            &layer,
+            &truncate_to_width(&line.counterparty, 26.0, 8.0),
+            COL_PARTY,
+            y,
+            8.0,
+            fonts.regular,
+            DARK,
+        );
+        txt_right(
+            &layer,
+            &fmt_amount(line.amount),
+            COL_AMOUNT_RIGHT,
+            y,
+            8.0,
+            fonts.regular,
+            DARK,
+        );
+        if show_paid {
+            txt_right(
+                &layer,
+                &fmt_amount(line.paid),
+                COL_PAID_RIGHT,
+                y,
+                8.0,
+                fonts.regular,
+                DARK,
+            );
+        }
+        pages.y -= ROW_H;
+    }
+
+    let total: f64 = lines.iter().map(|l| l.amount).sum();
+    stroke_line(pages.layer(), ML, PAGE_W - MR, pages.y + 3.5, 0.15, BORDER);
+    txt(
+        pages.layer(),
+        "Total",
+        ML,
+        pages.y - 1.0,
+        9.0,
+        pages.fonts.bold,
+        DARK,
+    );
+    txt_right(
+        pages.layer(),
+        &fmt_amount(total),
+        COL_AMOUNT_RIGHT,
+        pages.y - 1.0,
+        9.0,
+        pages.fonts.bold,
+        DARK,
+    );
+    if show_paid {
+        let paid: f64 = lines.iter().map(|l| l.paid).sum();
+        txt_right(
+            pages.layer(),
+            &fmt_amount(paid),
+            COL_PAID_RIGHT,
+            pages.y - 1.0,
+            9.0,
+            pages.fonts.bold,
+            DARK,
+        );
+    }
+    pages.y -= 14.0;
+}
+
+fn draw_column_headers(pages: &mut PageCursor<'_>, party_label: &str, sym: &str, show_paid: bool) {
+    let layer = pages.layer().clone();
+    let fonts = &pages.fonts;
+    let y = pages.y;
+
+    txt(&layer, "Date", ML, y, 8.0, fonts.medium, MID);
+    txt(&layer, "Reference", COL_REF, y, 8.0, fonts.medium, MID);
+    txt(&layer, "Property", COL_PROPERTY, y, 8.0, fonts.medium, MID);
+    txt(&layer, party_label, COL_PARTY, y, 8.0, fonts.medium, MID);
+    txt_right(
+        &layer,
+        &format!("Amount ({sym})"),
+        COL_AMOUNT_RIGHT,
+        y,
+        8.0,
+        fonts.medium,
+        MID,
+    );
+    if show_paid {
+        txt_right(
+            &layer,
+            &format!("Paid ({sym})"),
+            COL_PAID_RIGHT,
+            y,
+            8.0,
+            fonts.medium,
+            MID,
+        );
+    }
+    stroke_line(&layer, ML, PAGE_W - MR, y - 2.0, 0.2, DARK);
+    pages.y -= 7.0;
+}
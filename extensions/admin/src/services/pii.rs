@@ -0,0 +1,634 @@
+//! Field-level encryption of personal data: IBANs, tax ids and phone numbers.
+//!
+//! A sensitive column holds `enc:v1:<key id>:<base64 nonce + ciphertext>`,
+//! sealed with ChaCha20-Poly1305 under the active key and with the column
+//! name as associated data. Next to it, `<column>_bidx` holds a blind index:
+//! an HMAC of the normalised value that searches and filters compare with,
+//! so they only ever match whole values. Audit entries copy rows with
+//! their values sealed and webhook payloads mask them; [`scrub_history`]
+//! catches copies written in clear before encryption.
+//!
+//! Keys come from `ADMIN_PII_KEYS`, a comma-separated list of
+//! `<id>:<base64 32-byte key>` with the active key first. A retired key
+//! stays listed until the rotation job has re-sealed its values under the
+//! active one. The blind-index key, `ADMIN_PII_INDEX_KEY` (base64, at least
+//! 32 bytes), is not rotated: changing it would orphan every index. Without
+//! either variable personal data can be neither written nor read, unless
+//! `ADMIN_PII_DEV_KEYS=1` opts into fixed development keys.
+
+use std::sync::OnceLock;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::api::generic::entities;
+use crate::error::AdminError;
+
+/// Start of every sealed value.
+pub const SEALED_PREFIX: &str = "enc:v1:";
+
+/// Shown in place of a value the caller may not see.
+pub const MASK: &str = "••••";
+
+/// Trailing characters a masked value keeps, so records can still be told
+/// apart.
+const VISIBLE_CHARS: usize = 4;
+
+const NONCE_LEN: usize = 12;
+const KEY_LEN: usize = 32;
+
+/// Values re-sealed per transaction by [`reseal_pending`].
+const RESEAL_BATCH: usize = 200;
+
+/// Sensitive columns of tables that are not admin entities, sealed and
+/// rotated like the entities' own.
+const OTHER_COLUMNS: &[(&str, &str)] = &[("admin_payout_transfers", "creditor_iban")];
+
+const DEV_KEY_ID: &str = "dev";
+const DEV_SEED: &str = "systemprompt-admin-pii-development";
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// What a sensitive column holds, which decides how it is normalised
+/// before indexing.
+#[derive(Debug, Clone, Copy)]
+enum Kind {
+    TaxId,
+    Iban,
+    Phone,
+}
+
+impl Kind {
+    fn of(column: &str) -> Self {
+        match column {
+            "bank_account" | "iban" | "debtor_iban" | "creditor_iban" => Self::Iban,
+            "phone" => Self::Phone,
+            _ => Self::TaxId,
+        }
+    }
+
+    /// Separates the indexes of different kinds of value.
+    const fn domain(self) -> &'static str {
+        match self {
+            Self::TaxId => "tax_id",
+            Self::Iban => "iban",
+            Self::Phone => "phone",
+        }
+    }
+
+    /// Spacing, punctuation and case do not matter when matching:
+    /// `es91 2100-0418` finds `ES9121000418`.
+    fn normalize(self, value: &str) -> String {
+        match self {
+            Self::Phone => value.chars().filter(char::is_ascii_digit).collect(),
+            Self::TaxId | Self::Iban => value
+                .chars()
+                .filter(char::is_ascii_alphanumeric)
+                .map(|c| c.to_ascii_uppercase())
+                .collect(),
+        }
+    }
+}
+
+struct Keyring {
+    /// Encryption keys by id, the active key first
+    keys: Vec<(String, ChaCha20Poly1305)>,
+    index_key: Vec<u8>,
+}
+
+static KEYRING: OnceLock<Result<Keyring, String>> = OnceLock::new();
+
+fn keyring() -> Result<&'static Keyring, AdminError> {
+    KEYRING
+        .get_or_init(load_keyring)
+        .as_ref()
+        .map_err(|e| AdminError::Encryption(e.clone()))
+}
+
+fn load_keyring() -> Result<Keyring, String> {
+    let keys = match std::env::var("ADMIN_PII_KEYS") {
+        Ok(spec) if !spec.trim().is_empty() => parse_keys(&spec)?,
+        _ => {
+            dev_keys_allowed("ADMIN_PII_KEYS")?;
+            tracing::warn!(
+                "ADMIN_PII_KEYS is not set; personal data is sealed with the development key"
+            );
+            let cipher = ChaCha20Poly1305::new_from_slice(&dev_key("encryption"))
+                .map_err(|_| "Invalid development key".to_string())?;
+            vec![(DEV_KEY_ID.to_string(), cipher)]
+        }
+    };
+    let index_key = match std::env::var("ADMIN_PII_INDEX_KEY") {
+        Ok(encoded) if !encoded.trim().is_empty() => STANDARD
+            .decode(encoded.trim())
+            .map_err(|_| "ADMIN_PII_INDEX_KEY is not valid base64".to_string())?,
+        _ => {
+            dev_keys_allowed("ADMIN_PII_INDEX_KEY")?;
+            tracing::warn!("ADMIN_PII_INDEX_KEY is not set; blind indexes use the development key");
+            dev_key("index")
+        }
+    };
+    if index_key.len() < KEY_LEN {
+        return Err(format!(
+            "ADMIN_PII_INDEX_KEY must be at least {KEY_LEN} bytes"
+        ));
+    }
+    Ok(Keyring { keys, index_key })
+}
+
+/// `<id>:<base64 key>` entries. Key material never appears in the errors.
+fn parse_keys(spec: &str) -> Result<Vec<(String, ChaCha20Poly1305)>, String> {
+    let mut keys = Vec::new();
+    for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let (id, encoded) = entry
+            .split_once(':')
+            .ok_or_else(|| "ADMIN_PII_KEYS entries must be <id>:<base64 key>".to_string())?;
+        let id = id.trim();
+        if id.is_empty()
+            || !id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(format!("ADMIN_PII_KEYS key id '{id}' is not alphanumeric"));
+        }
+        if keys.iter().any(|(existing, _)| existing == id) {
+            return Err(format!("ADMIN_PII_KEYS lists key '{id}' twice"));
+        }
+        let bytes = STANDARD
+            .decode(encoded.trim())
+            .ok()
+            .filter(|b| b.len() == KEY_LEN)
+            .ok_or_else(|| format!("ADMIN_PII_KEYS key '{id}' is not {KEY_LEN} base64 bytes"))?;
+        let cipher = ChaCha20Poly1305::new_from_slice(&bytes)
+            .map_err(|_| format!("ADMIN_PII_KEYS key '{id}' is invalid"))?;
+        keys.push((id.to_string(), cipher));
+    }
+    if keys.is_empty() {
+        return Err("ADMIN_PII_KEYS lists no keys".to_string());
+    }
+    Ok(keys)
+}
+
+/// Development keys stand in for a missing `variable` only when
+/// `ADMIN_PII_DEV_KEYS` is `1` or `true`; otherwise the keyring fails to load.
+fn dev_keys_allowed(variable: &str) -> Result<(), String> {
+    let allowed =
+        std::env::var("ADMIN_PII_DEV_KEYS").is_ok_and(|flag| matches!(flag.trim(), "1" | "true"));
+    if allowed {
+        Ok(())
+    } else {
+        Err(format!(
+            "{variable} is not set; set it, or ADMIN_PII_DEV_KEYS=1 to use development keys"
+        ))
+    }
+}
+
+fn dev_key(purpose: &str) -> Vec<u8> {
+    Sha256::digest(format!("{DEV_SEED}:{purpose}")).to_vec()
+}
+
+// ── Sealing ─────────────────────────────────────────────────────────────
+
+/// Seal `plaintext` for `column` under the active key. Empty values stay
+/// empty.
+pub fn seal(column: &str, plaintext: &str) -> Result<String, AdminError> {
+    if plaintext.is_empty() {
+        return Ok(String::new());
+    }
+    let (id, cipher) = &keyring()?.keys[0];
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    let ciphertext = cipher
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: plaintext.as_bytes(),
+                aad: column.as_bytes(),
+            },
+        )
+        .map_err(|_| AdminError::Encryption(format!("Could not encrypt {column}")))?;
+
+    let mut sealed = nonce.to_vec();
+    sealed.extend_from_slice(&ciphertext);
+    Ok(format!("{SEALED_PREFIX}{id}:{}", STANDARD.encode(sealed)))
+}
+
+/// The plaintext of a stored value. Values not sealed yet (written before
+/// encryption, and not yet reached by the rotation job) pass through.
+pub fn open(column: &str, stored: &str) -> Result<String, AdminError> {
+    let Some(rest) = stored.strip_prefix(SEALED_PREFIX) else {
+        return Ok(stored.to_string());
+    };
+    let failed = || AdminError::Encryption(format!("Could not decrypt {column}"));
+    let (id, encoded) = rest.split_once(':').ok_or_else(failed)?;
+    let cipher = keyring()?
+        .keys
+        .iter()
+        .find(|(key_id, _)| key_id == id)
+        .map(|(_, cipher)| cipher)
+        .ok_or_else(|| {
+            AdminError::Encryption(format!("{column} is sealed with unknown key '{id}'"))
+        })?;
+
+    let bytes = STANDARD.decode(encoded).map_err(|_| failed())?;
+    if bytes.len() < NONCE_LEN {
+        return Err(failed());
+    }
+    let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+    let plaintext = cipher
+        .decrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: column.as_bytes(),
+            },
+        )
+        .map_err(|_| failed())?;
+    String::from_utf8(plaintext).map_err(|_| failed())
+}
+
+/// Prefix of values sealed under the active key; anything else non-empty
+/// is due for the rotation job.
+pub fn active_prefix() -> Result<String, AdminError> {
+    Ok(format!("{SEALED_PREFIX}{}:", keyring()?.keys[0].0))
+}
+
+/// Blind index of `value` for `column`, or `None` when nothing of it is
+/// left to match once normalised (an empty value, or a name searched
+/// against a phone column).
+pub fn blind_index(column: &str, value: &str) -> Result<Option<String>, AdminError> {
+    let kind = Kind::of(column);
+    let normalized = kind.normalize(value);
+    if normalized.is_empty() {
+        return Ok(None);
+    }
+    let mut mac =
+        HmacSha256::new_from_slice(&keyring()?.index_key).expect("HMAC accepts keys of any length");
+    mac.update(kind.domain().as_bytes());
+    mac.update(b":");
+    mac.update(normalized.as_bytes());
+    Ok(Some(hex::encode(mac.finalize().into_bytes())))
+}
+
+/// The sealed value and blind index to store for `plaintext`.
+pub fn protect(column: &str, plaintext: &str) -> Result<(String, Option<String>), AdminError> {
+    Ok((seal(column, plaintext)?, blind_index(column, plaintext)?))
+}
+
+// ── Masking ─────────────────────────────────────────────────────────────
+
+/// `•••• 4321`: the mask and the last characters of the value.
+pub fn mask(plaintext: &str) -> String {
+    let chars: Vec<char> = plaintext.chars().filter(|c| !c.is_whitespace()).collect();
+    if chars.is_empty() {
+        return String::new();
+    }
+    if chars.len() <= VISIBLE_CHARS {
+        return MASK.to_string();
+    }
+    let tail: String = chars[chars.len() - VISIBLE_CHARS..].iter().collect();
+    format!("{MASK} {tail}")
+}
+
+/// Whether a submitted value is a mask sent back unchanged, which leaves
+/// the stored value as it is.
+pub fn is_masked(value: &str) -> bool {
+    value.starts_with(MASK)
+}
+
+/// Decrypt the `fields` of a row fetched as JSON, masking them unless
+/// `show` is set, and drop their blind indexes. A value that cannot be
+/// decrypted is masked entirely.
+pub fn reveal(row: &mut Value, fields: &[&str], show: bool) {
+    let Some(obj) = row.as_object_mut() else {
+        return;
+    };
+    for field in fields {
+        obj.remove(&format!("{field}_bidx"));
+        let Some(Value::String(stored)) = obj.get_mut(*field) else {
+            continue;
+        };
+        *stored = match open(field, stored) {
+            Ok(plaintext) if show => plaintext,
+            Ok(plaintext) => mask(&plaintext),
+            Err(e) => {
+                tracing::warn!(error = %e, field, "Sensitive value could not be decrypted");
+                MASK.to_string()
+            }
+        };
+    }
+}
+
+// ── Rotation ────────────────────────────────────────────────────────────
+
+/// Seal every sensitive value still in clear (seeded, restored from an old
+/// audit entry) or sealed under a retired key with the active key, filling
+/// in missing blind indexes. Each column is walked once in id order: values
+/// that cannot be decrypted are logged and left as they are, as are values
+/// under the active key with nothing to index. Returns how many values
+/// were re-sealed.
+pub async fn reseal_pending(pool: &PgPool) -> Result<u64, AdminError> {
+    let prefix = active_prefix()?;
+    let columns = entities()
+        .into_iter()
+        .flat_map(|info| {
+            let (table, fields) = (info.table, info.sensitive_fields);
+            fields.iter().map(move |field| (table, *field))
+        })
+        .chain(OTHER_COLUMNS.iter().copied());
+    let mut resealed = 0;
+    for (table, field) in columns {
+        let mut after = None;
+        loop {
+            let batch = reseal_batch(pool, table, field, &prefix, after).await?;
+            resealed += batch.resealed;
+            if batch.scanned < RESEAL_BATCH {
+                break;
+            }
+            after = batch.last_id;
+        }
+    }
+    Ok(resealed)
+}
+
+/// Progress of one [`reseal_batch`].
+struct ResealBatch {
+    scanned: usize,
+    resealed: u64,
+    /// Where the next batch starts
+    last_id: Option<Uuid>,
+}
+
+async fn reseal_batch(
+    pool: &PgPool,
+    table: &str,
+    field: &str,
+    prefix: &str,
+    after: Option<Uuid>,
+) -> Result<ResealBatch, AdminError> {
+    let mut tx = pool.begin().await?;
+    let rows: Vec<(Uuid, String)> = sqlx::query_as(&format!(
+        "SELECT id, {field} FROM {table} \
+         WHERE {field} <> '' AND (NOT starts_with({field}, $1) OR {field}_bidx IS NULL) \
+            AND ($2::uuid IS NULL OR id > $2) \
+         ORDER BY id LIMIT {RESEAL_BATCH} FOR UPDATE SKIP LOCKED"
+    ))
+    .bind(prefix)
+    .bind(after)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut resealed = 0;
+    for (id, stored) in &rows {
+        let plaintext = match open(field, stored) {
+            Ok(plaintext) => plaintext,
+            Err(e) => {
+                tracing::warn!(error = %e, table, field, %id, "Skipping value that cannot be decrypted");
+                continue;
+            }
+        };
+        let index = blind_index(field, &plaintext)?;
+        if stored.starts_with(prefix) && index.is_none() {
+            continue;
+        }
+        sqlx::query(&format!(
+            "UPDATE {table} SET {field} = $1, {field}_bidx = $2 WHERE id = $3"
+        ))
+        .bind(seal(field, &plaintext)?)
+        .bind(index)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+        resealed += 1;
+    }
+    tx.commit().await?;
+    Ok(ResealBatch {
+        scanned: rows.len(),
+        resealed,
+        last_id: rows.last().map(|(id, _)| *id),
+    })
+}
+
+// ── History ─────────────────────────────────────────────────────────────
+
+/// Whether the JSON documents `docs` (`v(doc)`) hold any of the fields `$2`
+/// in clear: neither empty, sealed (`$3`) nor masked (`$4`).
+fn clear_values(docs: &str) -> String {
+    format!(
+        "EXISTS (SELECT 1 FROM unnest($2::text[]) f, (VALUES {docs}) v(doc) \
+            WHERE doc ->> f <> '' AND NOT starts_with(doc ->> f, $3) \
+                AND NOT starts_with(doc ->> f, $4))"
+    )
+}
+
+/// Seal personal data left in clear in audit entries and mask it in webhook
+/// event payloads. Both copy whole rows, and may have been written before
+/// the values were encrypted. Returns how many entries and events changed.
+pub async fn scrub_history(pool: &PgPool) -> Result<u64, AdminError> {
+    let mut scrubbed = 0;
+    for info in entities()
+        .into_iter()
+        .filter(|info| !info.sensitive_fields.is_empty())
+    {
+        loop {
+            let count = seal_audit_batch(pool, info.label, info.sensitive_fields).await?;
+            scrubbed += count as u64;
+            if count < RESEAL_BATCH {
+                break;
+            }
+        }
+        loop {
+            let count = mask_event_batch(pool, info.label, info.sensitive_fields).await?;
+            scrubbed += count as u64;
+            if count < RESEAL_BATCH {
+                break;
+            }
+        }
+    }
+    Ok(scrubbed)
+}
+
+/// Audit entries keep the values sealed, so reverting to them still works.
+async fn seal_audit_batch(
+    pool: &PgPool,
+    entity_type: &str,
+    fields: &[&str],
+) -> Result<usize, AdminError> {
+    let mut tx = pool.begin().await?;
+    let rows: Vec<(Uuid, Option<Value>, Option<Value>)> = sqlx::query_as(&format!(
+        "SELECT id, old_values, new_values FROM admin_audit_log \
+         WHERE entity_type = $1 AND {} \
+         LIMIT {RESEAL_BATCH} FOR UPDATE SKIP LOCKED",
+        clear_values("(old_values), (new_values)")
+    ))
+    .bind(entity_type)
+    .bind(fields)
+    .bind(SEALED_PREFIX)
+    .bind(MASK)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let count = rows.len();
+    for (id, mut old_values, mut new_values) in rows {
+        for row in [&mut old_values, &mut new_values].into_iter().flatten() {
+            seal_fields(row, fields)?;
+        }
+        sqlx::query("UPDATE admin_audit_log SET old_values = $2, new_values = $3 WHERE id = $1")
+            .bind(id)
+            .bind(old_values)
+            .bind(new_values)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+    Ok(count)
+}
+
+/// Event payloads are sent to third parties, so their values are masked
+/// as [`write_audit`] masks new ones.
+///
+/// [`write_audit`]: crate::api::generic::write_audit
+async fn mask_event_batch(
+    pool: &PgPool,
+    entity_type: &str,
+    fields: &[&str],
+) -> Result<usize, AdminError> {
+    let mut tx = pool.begin().await?;
+    let rows: Vec<(Uuid, Value)> = sqlx::query_as(&format!(
+        "SELECT id, payload FROM admin_webhook_events \
+         WHERE entity_type = $1 AND {} \
+         LIMIT {RESEAL_BATCH} FOR UPDATE SKIP LOCKED",
+        clear_values("(payload -> 'data'), (payload -> 'previous')")
+    ))
+    .bind(entity_type)
+    .bind(fields)
+    .bind(SEALED_PREFIX)
+    .bind(MASK)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let count = rows.len();
+    for (id, mut payload) in rows {
+        for key in ["data", "previous"] {
+            if let Some(row) = payload.get_mut(key) {
+                reveal(row, fields, false);
+            }
+        }
+        sqlx::query("UPDATE admin_webhook_events SET payload = $2 WHERE id = $1")
+            .bind(id)
+            .bind(payload)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+    Ok(count)
+}
+
+/// Seal the `fields` of a row that are still in clear.
+fn seal_fields(row: &mut Value, fields: &[&str]) -> Result<(), AdminError> {
+    for field in fields {
+        if let Some(Value::String(value)) = row.get_mut(*field) {
+            if !value.is_empty() && !value.starts_with(SEALED_PREFIX) && !is_masked(value) {
+                *value = seal(field, value)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    /// Tests run with the development keys unless real ones are configured.
+    fn with_dev_keys() {
+        static INIT: std::sync::Once = std::sync::Once::new();
+        INIT.call_once(|| std::env::set_var("ADMIN_PII_DEV_KEYS", "1"));
+    }
+
+    #[test]
+    fn seal_and_open_round_trip() {
+        with_dev_keys();
+        let sealed = seal("tax_id", "B12345678").unwrap();
+        assert!(sealed.starts_with(&active_prefix().unwrap()));
+        assert!(!sealed.contains("B12345678"));
+        assert_ne!(sealed, seal("tax_id", "B12345678").unwrap());
+        assert_eq!(open("tax_id", &sealed).unwrap(), "B12345678");
+        assert_eq!(seal("tax_id", "").unwrap(), "");
+    }
+
+    #[test]
+    fn sealed_values_open_only_for_their_column() {
+        with_dev_keys();
+        let sealed = seal("tax_id", "B12345678").unwrap();
+        assert!(open("bank_account", &sealed).is_err());
+        assert!(open("tax_id", &format!("{SEALED_PREFIX}retired:AAAA")).is_err());
+    }
+
+    #[test]
+    fn values_in_clear_pass_through() {
+        with_dev_keys();
+        assert_eq!(open("phone", "+34 600 000 000").unwrap(), "+34 600 000 000");
+    }
+
+    #[test]
+    fn blind_index_ignores_spacing_and_case() {
+        with_dev_keys();
+        let index = blind_index("iban", "ES9121000418").unwrap();
+        assert!(index.is_some());
+        assert_eq!(blind_index("iban", "es91 2100-0418").unwrap(), index);
+        assert_eq!(blind_index("debtor_iban", "ES9121000418").unwrap(), index);
+        assert_ne!(blind_index("tax_id", "ES9121000418").unwrap(), index);
+        assert_eq!(
+            blind_index("phone", "+34 600-000-000").unwrap(),
+            blind_index("phone", "34600000000").unwrap()
+        );
+        assert_eq!(blind_index("phone", "Jane").unwrap(), None);
+    }
+
+    #[test]
+    fn masks_all_but_the_last_characters() {
+        assert_eq!(mask("ES91 2100 0418 4502 0005 1332"), "•••• 1332");
+        assert_eq!(mask("1234"), MASK);
+        assert_eq!(mask(""), "");
+        assert!(is_masked(&mask("B12345678")));
+        assert!(!is_masked("B12345678"));
+    }
+
+    #[test]
+    fn reveal_masks_unless_shown_and_drops_indexes() {
+        with_dev_keys();
+        let (sealed, index) = protect("tax_id", "B12345678").unwrap();
+        let row = json!({ "name": "Acme", "tax_id": sealed, "tax_id_bidx": index });
+
+        let mut masked = row.clone();
+        reveal(&mut masked, &["tax_id"], false);
+        assert_eq!(masked, json!({ "name": "Acme", "tax_id": "•••• 5678" }));
+
+        let mut shown = row;
+        reveal(&mut shown, &["tax_id"], true);
+        assert_eq!(shown["tax_id"], "B12345678");
+    }
+
+    #[test]
+    fn parse_keys_rejects_bad_entries() {
+        let key = STANDARD.encode([7u8; KEY_LEN]);
+        let keys = parse_keys(&format!("new:{key}, old:{key}")).unwrap();
+        assert_eq!(keys[0].0, "new");
+        assert_eq!(keys.len(), 2);
+        assert!(parse_keys(&format!("a:{key},a:{key}")).is_err());
+        assert!(parse_keys("a:c2hvcnQ=").is_err());
+        assert!(parse_keys(&format!("bad id:{key}")).is_err());
+        assert!(parse_keys(" , ").is_err());
+    }
+}
@@ -0,0 +1,583 @@
+//! Tenant self-service portal and portal access.
+//!
+//! Portal users sign in with the `tenant` scope and are linked by an admin to
+//! one tenant record. Every query here starts from that tenant's id:
+//! contracts linked to the tenant, invoices through those contracts'
+//! references, and issues the tenant reported themselves.
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::api::actor::Actor;
+use crate::api::generic::{write_audit, AdminEntity, TenantEntity};
+use crate::error::AdminError;
+use crate::services::inspections::{decode_photo, PhotoUpload};
+use crate::services::pii;
+
+/// Photos accepted with one issue.
+const MAX_ISSUE_PHOTOS: usize = 6;
+
+/// Issue priorities, most urgent first.
+pub const ISSUE_PRIORITIES: &[&str] = &["High", "Medium", "Low"];
+
+/// References of the tenant's contracts; `$1` is the tenant id.
+const TENANT_CONTRACT_REFS: &str =
+    "SELECT contract_ref FROM admin_contracts WHERE tenant_id = $1 AND deleted_at IS NULL";
+
+const INVOICE_JSON: &str = "json_build_object('id', i.id, 'reference', i.reference, \
+    'description', i.description, 'contract_ref', i.contract_ref, \
+    'property_name', i.property_name, 'status', i.status, 'amount', i.amount::float8, \
+    'paid', i.paid::float8, 'vat', i.vat::float8, 'currency', i.currency, \
+    'invoice_date', i.invoice_date, 'payment_date', i.payment_date)";
+
+const ISSUE_JSON: &str = "json_build_object('id', s.id, 'property_name', s.property_name, \
+    'title', s.title, 'description', s.description, 'priority', s.priority, \
+    'status', s.status, 'created_at', s.created_at, 'updated_at', s.updated_at, \
+    'photo_ids', ARRAY(SELECT p.id FROM admin_issue_photos p \
+                       WHERE p.issue_id = s.id ORDER BY p.created_at))";
+
+/// The tenant a portal user acts as.
+#[derive(Debug, Clone)]
+pub struct PortalTenant {
+    pub user_id: String,
+    pub tenant_id: Uuid,
+    pub name: String,
+}
+
+/// The tenant linked to a signed-in portal user.
+pub async fn portal_tenant(pool: &PgPool, user_id: &str) -> Result<PortalTenant, AdminError> {
+    let tenant: Option<(Uuid, String)> = sqlx::query_as(
+        "SELECT t.id, t.name FROM admin_portal_accounts a \
+         JOIN admin_tenants t ON t.id = a.tenant_id \
+         WHERE a.user_id = $1 AND a.role = 'tenant' AND t.deleted_at IS NULL",
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+    let (tenant_id, name) = tenant.ok_or_else(|| {
+        AdminError::Forbidden("This account is not linked to a tenant".to_string())
+    })?;
+    Ok(PortalTenant {
+        user_id: user_id.to_string(),
+        tenant_id,
+        name,
+    })
+}
+
+// ── Portal access (admin) ───────────────────────────────────────────────
+
+/// Who a portal account signs in as. Each role has its own token scope and
+/// links the account to a record of its own table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortalRole {
+    Tenant,
+    Owner,
+}
+
+impl PortalRole {
+    /// Value of `admin_portal_accounts.role`, also the token scope.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Tenant => "tenant",
+            Self::Owner => "owner",
+        }
+    }
+
+    /// Column of `admin_portal_accounts` pointing at the linked record.
+    const fn column(self) -> &'static str {
+        match self {
+            Self::Tenant => "tenant_id",
+            Self::Owner => "owner_id",
+        }
+    }
+
+    const fn table(self) -> &'static str {
+        match self {
+            Self::Tenant => "admin_tenants",
+            Self::Owner => "admin_owners",
+        }
+    }
+
+    /// Entity label the grants are audited under.
+    const fn entity(self) -> &'static str {
+        match self {
+            Self::Tenant => "tenants",
+            Self::Owner => "owners",
+        }
+    }
+}
+
+/// Body of PUT /tenants/{id}/portal-access and /owners/{id}/portal-access.
+#[derive(Debug, Deserialize)]
+pub struct PortalAccessRequest {
+    /// Subject of the user's access tokens
+    pub user_id: String,
+}
+
+pub async fn portal_accounts(
+    pool: &PgPool,
+    role: PortalRole,
+    record_id: Uuid,
+) -> Result<Vec<Value>, sqlx::Error> {
+    sqlx::query_scalar(&format!(
+        "SELECT json_build_object('id', id, 'user_id', user_id, 'created_by', created_by, \
+            'created_at', created_at) \
+         FROM admin_portal_accounts WHERE {} = $1 AND role = $2 \
+         ORDER BY created_at",
+        role.column()
+    ))
+    .bind(record_id)
+    .bind(role.as_str())
+    .fetch_all(pool)
+    .await
+}
+
+/// Let a user into the portal as the tenant or owner. A user already linked
+/// to another record of the same role is moved to this one.
+pub async fn grant_portal_access(
+    pool: &PgPool,
+    actor: &Actor,
+    role: PortalRole,
+    record_id: Uuid,
+    request: &PortalAccessRequest,
+) -> Result<Value, AdminError> {
+    let user_id = request.user_id.trim();
+    if user_id.is_empty() {
+        return Err(AdminError::BadRequest("user_id is required".to_string()));
+    }
+    let mut tx = pool.begin().await?;
+    let exists: bool = sqlx::query_scalar(&format!(
+        "SELECT EXISTS (SELECT 1 FROM {} WHERE id = $1 AND deleted_at IS NULL)",
+        role.table()
+    ))
+    .bind(record_id)
+    .fetch_one(&mut *tx)
+    .await?;
+    if !exists {
+        return Err(AdminError::NotFound(role.as_str().to_string()));
+    }
+    let column = role.column();
+    let account: Value = sqlx::query_scalar(&format!(
+        "INSERT INTO admin_portal_accounts (user_id, role, {column}, created_by) \
+         VALUES ($1, $2, $3, $4) \
+         ON CONFLICT (user_id, role) DO UPDATE SET {column} = EXCLUDED.{column}, \
+            created_by = EXCLUDED.created_by, created_at = NOW() \
+         RETURNING json_build_object('id', id, 'user_id', user_id, \
+            'created_by', created_by, 'created_at', created_at)"
+    ))
+    .bind(user_id)
+    .bind(role.as_str())
+    .bind(record_id)
+    .bind(actor.user_id.as_deref())
+    .fetch_one(&mut *tx)
+    .await?;
+    write_audit(
+        &mut *tx,
+        actor,
+        role.entity(),
+        &record_id.to_string(),
+        "grant_portal_access",
+        None,
+        Some(&json!({ "user_id": user_id })),
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(account)
+}
+
+pub async fn revoke_portal_access(
+    pool: &PgPool,
+    actor: &Actor,
+    role: PortalRole,
+    record_id: Uuid,
+    account_id: Uuid,
+) -> Result<(), AdminError> {
+    let mut tx = pool.begin().await?;
+    let user_id: Option<String> = sqlx::query_scalar(&format!(
+        "DELETE FROM admin_portal_accounts WHERE id = $1 AND {} = $2 AND role = $3 \
+         RETURNING user_id",
+        role.column()
+    ))
+    .bind(account_id)
+    .bind(record_id)
+    .bind(role.as_str())
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some(user_id) = user_id else {
+        return Err(AdminError::NotFound("portal account".to_string()));
+    };
+    write_audit(
+        &mut *tx,
+        actor,
+        role.entity(),
+        &record_id.to_string(),
+        "revoke_portal_access",
+        Some(&json!({ "user_id": user_id })),
+        None,
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+// ── Tenant views ────────────────────────────────────────────────────────
+
+/// The tenant's own details, their personal data in clear, and contracts.
+pub async fn tenant_overview(pool: &PgPool, tenant: &PortalTenant) -> Result<Value, AdminError> {
+    let mut profile: Value = sqlx::query_scalar(
+        "SELECT json_build_object('name', name, 'email', email, 'phone', phone, \
+            'address', address) \
+         FROM admin_tenants WHERE id = $1",
+    )
+    .bind(tenant.tenant_id)
+    .fetch_one(pool)
+    .await?;
+    pii::reveal(&mut profile, TenantEntity::SENSITIVE_FIELDS, true);
+    let contracts: Vec<Value> = sqlx::query_scalar(
+        "SELECT json_build_object('id', id, 'contract_ref', contract_ref, \
+            'property_name', property_name, 'address', address, 'status', status, \
+            'rent', rent::float8, 'start_date', start_date, 'end_date', end_date) \
+         FROM admin_contracts WHERE tenant_id = $1 AND deleted_at IS NULL \
+         ORDER BY start_date DESC NULLS LAST",
+    )
+    .bind(tenant.tenant_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(json!({ "tenant": profile, "contracts": contracts }))
+}
+
+/// Invoices of the tenant's contracts, newest first.
+pub async fn tenant_invoices(
+    pool: &PgPool,
+    tenant: &PortalTenant,
+) -> Result<Vec<Value>, sqlx::Error> {
+    sqlx::query_scalar(&format!(
+        "SELECT {INVOICE_JSON} FROM admin_invoices i \
+         WHERE i.contract_ref IN ({TENANT_CONTRACT_REFS}) AND i.deleted_at IS NULL \
+         ORDER BY i.invoice_date DESC NULLS LAST, i.created_at DESC"
+    ))
+    .bind(tenant.tenant_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Payments made against the tenant's invoices, with totals.
+pub async fn tenant_payments(pool: &PgPool, tenant: &PortalTenant) -> Result<Value, AdminError> {
+    let payments: Vec<Value> = sqlx::query_scalar(&format!(
+        "SELECT {INVOICE_JSON} FROM admin_invoices i \
+         WHERE i.contract_ref IN ({TENANT_CONTRACT_REFS}) AND i.deleted_at IS NULL \
+            AND i.paid > 0 \
+         ORDER BY i.payment_date DESC NULLS LAST, i.invoice_date DESC NULLS LAST"
+    ))
+    .bind(tenant.tenant_id)
+    .fetch_all(pool)
+    .await?;
+    let (paid, outstanding): (f64, f64) = sqlx::query_as(&format!(
+        "SELECT COALESCE(SUM(i.paid), 0)::float8, \
+            COALESCE(SUM(GREATEST(i.amount - i.paid, 0)), 0)::float8 \
+         FROM admin_invoices i \
+         WHERE i.contract_ref IN ({TENANT_CONTRACT_REFS}) AND i.deleted_at IS NULL"
+    ))
+    .bind(tenant.tenant_id)
+    .fetch_one(pool)
+    .await?;
+    Ok(json!({ "data": payments, "total_paid": paid, "outstanding": outstanding }))
+}
+
+/// Whether the invoice belongs to one of the tenant's contracts.
+pub async fn tenant_owns_invoice(
+    pool: &PgPool,
+    tenant: &PortalTenant,
+    invoice_id: Uuid,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar(&format!(
+        "SELECT EXISTS (SELECT 1 FROM admin_invoices i \
+         WHERE i.id = $2 AND i.contract_ref IN ({TENANT_CONTRACT_REFS}) \
+            AND i.deleted_at IS NULL)"
+    ))
+    .bind(tenant.tenant_id)
+    .bind(invoice_id)
+    .fetch_one(pool)
+    .await
+}
+
+// ── Issues ──────────────────────────────────────────────────────────────
+
+/// Body of POST /portal/tenant/issues.
+#[derive(Debug, Deserialize)]
+pub struct TenantIssueRequest {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    pub priority: Option<String>,
+    /// Needed only when the tenant rents more than one property
+    pub property_name: Option<String>,
+    #[serde(default)]
+    pub photos: Vec<PhotoUpload>,
+}
+
+/// A photo sent with an issue, with its content type and bytes.
+type IssuePhoto<'a> = (&'a PhotoUpload, &'static str, Vec<u8>);
+
+impl TenantIssueRequest {
+    /// The trimmed title, the priority (`Medium` by default) and the photos.
+    fn validate(&self) -> Result<(&str, &str, Vec<IssuePhoto<'_>>), AdminError> {
+        let title = self.title.trim();
+        if title.is_empty() {
+            return Err(AdminError::BadRequest("title is required".to_string()));
+        }
+        let priority = self.priority.as_deref().unwrap_or("Medium");
+        if !ISSUE_PRIORITIES.contains(&priority) {
+            return Err(AdminError::BadRequest(format!(
+                "priority must be one of: {}",
+                ISSUE_PRIORITIES.join(", ")
+            )));
+        }
+        if self.photos.len() > MAX_ISSUE_PHOTOS {
+            return Err(AdminError::BadRequest(format!(
+                "At most {MAX_ISSUE_PHOTOS} photos can be attached"
+            )));
+        }
+        let photos = self
+            .photos
+            .iter()
+            .map(|p| decode_photo(&p.data).map(|(file_type, content)| (p, file_type, content)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((title, priority, photos))
+    }
+}
+
+/// Issues the tenant reported, newest first.
+pub async fn tenant_issues(
+    pool: &PgPool,
+    tenant: &PortalTenant,
+) -> Result<Vec<Value>, sqlx::Error> {
+    sqlx::query_scalar(&format!(
+        "SELECT {ISSUE_JSON} FROM admin_issues s \
+         WHERE s.tenant_id = $1 AND s.deleted_at IS NULL ORDER BY s.created_at DESC"
+    ))
+    .bind(tenant.tenant_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Open a maintenance issue on one of the properties the tenant rents.
+pub async fn create_tenant_issue(
+    pool: &PgPool,
+    actor: &Actor,
+    tenant: &PortalTenant,
+    request: &TenantIssueRequest,
+) -> Result<Value, AdminError> {
+    let (title, priority, photos) = request.validate()?;
+    let property_name = tenant_property(pool, tenant, request.property_name.as_deref()).await?;
+
+    let mut tx = pool.begin().await?;
+    let issue_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO admin_issues (property_name, title, description, priority, status, tenant_id) \
+         VALUES ($1, $2, $3, $4, 'Open', $5) RETURNING id",
+    )
+    .bind(&property_name)
+    .bind(title)
+    .bind(request.description.trim())
+    .bind(priority)
+    .bind(tenant.tenant_id)
+    .fetch_one(&mut *tx)
+    .await?;
+    for (photo, file_type, content) in &photos {
+        sqlx::query(
+            "INSERT INTO admin_issue_photos (issue_id, caption, file_type, size, content) \
+             VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(issue_id)
+        .bind(photo.caption.trim())
+        .bind(file_type)
+        .bind(i32::try_from(content.len()).unwrap_or(i32::MAX))
+        .bind(content)
+        .execute(&mut *tx)
+        .await?;
+    }
+    let issue: Value = sqlx::query_scalar(&format!(
+        "SELECT {ISSUE_JSON} FROM admin_issues s WHERE s.id = $1"
+    ))
+    .bind(issue_id)
+    .fetch_one(&mut *tx)
+    .await?;
+    write_audit(
+        &mut *tx,
+        actor,
+        "issues",
+        &issue_id.to_string(),
+        "create",
+        None,
+        Some(&issue),
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(issue)
+}
+
+/// The property an issue is about: the one named, which must be rented by
+/// the tenant, or the only one they rent.
+async fn tenant_property(
+    pool: &PgPool,
+    tenant: &PortalTenant,
+    requested: Option<&str>,
+) -> Result<String, AdminError> {
+    let properties: Vec<String> = sqlx::query_scalar(
+        "SELECT DISTINCT property_name FROM admin_contracts \
+         WHERE tenant_id = $1 AND deleted_at IS NULL AND property_name <> '' \
+         ORDER BY property_name",
+    )
+    .bind(tenant.tenant_id)
+    .fetch_all(pool)
+    .await?;
+    match (requested.map(str::trim), properties.as_slice()) {
+        (Some(name), _) if properties.iter().any(|p| p == name) => Ok(name.to_string()),
+        (Some(_), _) => Err(AdminError::Forbidden(
+            "You can only report issues on properties you rent".to_string(),
+        )),
+        (None, [only]) => Ok(only.clone()),
+        (None, []) => Err(AdminError::BadRequest(
+            "No rented property found for this account".to_string(),
+        )),
+        (None, _) => Err(AdminError::BadRequest(format!(
+            "property_name is required, one of: {}",
+            properties.join(", ")
+        ))),
+    }
+}
+
+/// Attach another photo to one of the tenant's issues.
+pub async fn add_tenant_issue_photo(
+    pool: &PgPool,
+    tenant: &PortalTenant,
+    issue_id: Uuid,
+    upload: &PhotoUpload,
+) -> Result<Value, AdminError> {
+    let (file_type, content) = decode_photo(&upload.data)?;
+    let photo: Option<Value> = sqlx::query_scalar(
+        "INSERT INTO admin_issue_photos (issue_id, caption, file_type, size, content) \
+         SELECT s.id, $3, $4, $5, $6 FROM admin_issues s \
+         WHERE s.id = $1 AND s.tenant_id = $2 AND s.deleted_at IS NULL \
+            AND (SELECT COUNT(*) FROM admin_issue_photos p WHERE p.issue_id = s.id) < $7 \
+         RETURNING json_build_object('id', id, 'issue_id', issue_id, 'caption', caption, \
+            'file_type', file_type, 'size', size, 'created_at', created_at)",
+    )
+    .bind(issue_id)
+    .bind(tenant.tenant_id)
+    .bind(upload.caption.trim())
+    .bind(file_type)
+    .bind(i32::try_from(content.len()).unwrap_or(i32::MAX))
+    .bind(&content)
+    .bind(i64::try_from(MAX_ISSUE_PHOTOS).unwrap_or(i64::MAX))
+    .fetch_optional(pool)
+    .await?;
+    photo.ok_or_else(|| {
+        AdminError::NotFound(format!(
+            "issue, or it already has {MAX_ISSUE_PHOTOS} photos"
+        ))
+    })
+}
+
+// ── Issue photos ────────────────────────────────────────────────────────
+
+/// Photos of an issue, without their content.
+pub async fn issue_photos(pool: &PgPool, issue_id: Uuid) -> Result<Vec<Value>, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT json_build_object('id', id, 'caption', caption, 'file_type', file_type, \
+            'size', size, 'created_at', created_at) \
+         FROM admin_issue_photos WHERE issue_id = $1 ORDER BY created_at",
+    )
+    .bind(issue_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Content type and bytes of an issue photo. With a tenant, only photos of
+/// their own issues are found.
+pub async fn issue_photo_file(
+    pool: &PgPool,
+    id: Uuid,
+    tenant: Option<&PortalTenant>,
+) -> Result<(String, Vec<u8>), AdminError> {
+    sqlx::query_as(
+        "SELECT p.file_type, p.content FROM admin_issue_photos p \
+         JOIN admin_issues s ON s.id = p.issue_id \
+         WHERE p.id = $1 AND ($2::uuid IS NULL OR s.tenant_id = $2)",
+    )
+    .bind(id)
+    .bind(tenant.map(|t| t.tenant_id))
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AdminError::NotFound("photo".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+
+    use super::*;
+
+    fn issue(title: &str, priority: Option<&str>, photos: usize) -> TenantIssueRequest {
+        TenantIssueRequest {
+            title: title.to_string(),
+            description: String::new(),
+            priority: priority.map(str::to_string),
+            property_name: None,
+            photos: (0..photos)
+                .map(|_| PhotoUpload {
+                    data: format!(
+                        "data:image/jpeg;base64,{}",
+                        STANDARD.encode(b"\xff\xd8\xff")
+                    ),
+                    caption: String::new(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn issue_title_is_trimmed_and_priority_defaults_to_medium() {
+        let request = issue("  Boiler leaking ", None, 1);
+        let (title, priority, photos) = request.validate().unwrap();
+        assert_eq!(title, "Boiler leaking");
+        assert_eq!(priority, "Medium");
+        assert_eq!(photos[0].1, "image/jpeg");
+        assert_eq!(photos[0].2, b"\xff\xd8\xff");
+    }
+
+    #[test]
+    fn issue_needs_a_title_and_a_known_priority() {
+        assert!(issue(" ", None, 0).validate().is_err());
+        assert!(issue("Leak", Some("Urgent"), 0).validate().is_err());
+        assert!(issue("Leak", Some("High"), 0).validate().is_ok());
+    }
+
+    #[test]
+    fn issue_photos_are_limited_and_decoded() {
+        assert!(issue("Leak", None, MAX_ISSUE_PHOTOS).validate().is_ok());
+        assert!(issue("Leak", None, MAX_ISSUE_PHOTOS + 1)
+            .validate()
+            .is_err());
+
+        let mut request = issue("Leak", None, 0);
+        request.photos.push(PhotoUpload {
+            data: "https://example.com/leak.jpg".to_string(),
+            caption: String::new(),
+        });
+        assert!(request.validate().is_err());
+    }
+
+    #[test]
+    fn roles_map_to_their_own_scope_and_table() {
+        assert_eq!(PortalRole::Tenant.as_str(), "tenant");
+        assert_eq!(PortalRole::Owner.as_str(), "owner");
+        assert_eq!(PortalRole::Tenant.column(), "tenant_id");
+        assert_eq!(PortalRole::Owner.table(), "admin_owners");
+        assert_eq!(PortalRole::Owner.entity(), "owners");
+    }
+}
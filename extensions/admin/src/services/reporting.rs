@@ -0,0 +1,487 @@
+use chrono::{Datelike, Days, Local, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::postgres::{PgArguments, PgRow};
+use sqlx::query::{QueryAs, QueryScalar};
+use sqlx::{FromRow, PgPool, Postgres};
+use uuid::Uuid;
+
+use crate::error::AdminError;
+
+/// Invoice conditions for [`ReportFilter`] bind parameters `$1`..`$6`,
+/// written against unqualified `admin_invoices` columns. An owner's invoices
+/// are those on the properties of their portfolio, as in [`PROPERTY_FILTER`].
+/// Deleted invoices and properties are left out.
+pub const INVOICE_FILTER: &str = "deleted_at IS NULL \
+     AND ($1::date IS NULL OR invoice_date >= $1) \
+     AND ($2::date IS NULL OR invoice_date <= $2) \
+     AND ($3::text IS NULL OR property_name = $3) \
+     AND ($4::text IS NULL OR property_name IN (\
+         SELECT unnest(string_to_array(property_name, ' / ')) FROM admin_owners \
+         WHERE deleted_at IS NULL AND name = $4)) \
+     AND ($6::uuid IS NULL OR property_name IN (\
+         SELECT unnest(string_to_array(property_name, ' / ')) FROM admin_owners \
+         WHERE deleted_at IS NULL AND id = $6)) \
+     AND ($5::text IS NULL OR property_name IN (\
+         SELECT property_name FROM admin_properties WHERE deleted_at IS NULL AND $5 = ANY(tags)))";
+
+/// Property conditions for [`ReportFilter`] bind parameters `$3`..`$6`,
+/// written against unqualified `admin_properties` columns. Owners list their
+/// properties as a ` / `-separated `property_name`. Deleted properties and
+/// owners are left out.
+pub const PROPERTY_FILTER: &str = "deleted_at IS NULL \
+     AND ($3::text IS NULL OR property_name = $3) \
+     AND ($4::text IS NULL OR property_name IN (\
+         SELECT unnest(string_to_array(property_name, ' / ')) FROM admin_owners \
+         WHERE deleted_at IS NULL AND name = $4)) \
+     AND ($6::uuid IS NULL OR property_name IN (\
+         SELECT unnest(string_to_array(property_name, ' / ')) FROM admin_owners \
+         WHERE deleted_at IS NULL AND id = $6)) \
+     AND ($5::text IS NULL OR $5 = ANY(tags))";
+
+/// Contracts that count as a let when computing occupancy and vacancies.
+pub const LET_CONTRACT: &str =
+    "c.deleted_at IS NULL AND c.start_date IS NOT NULL \
+     AND c.status NOT IN ('Draft', 'Pending', 'Cancelled')";
+
+/// Audit log conditions for the [`ReportFilter`] date range (`$1`, `$2`).
+pub const AUDIT_FILTER: &str = "($1::date IS NULL OR created_at >= $1) \
+     AND ($2::date IS NULL OR created_at < $2 + 1)";
+
+/// Dashboard and report filters: ?from=2026-01-01&to=2026-03-31&property=...&owner=...&tag=...
+///
+/// Every query built through [`ReportFilter::scalar`] or [`ReportFilter::query_as`]
+/// receives the same six bind parameters, so the SQL fragments above can be
+/// combined freely in any sub-query.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReportFilter {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub property: Option<String>,
+    pub owner: Option<String>,
+    pub tag: Option<String>,
+    /// One owner's portfolio by id, where a name could be shared or renamed.
+    pub owner_id: Option<Uuid>,
+}
+
+impl ReportFilter {
+    /// Reject inverted ranges and treat empty strings as "no filter".
+    pub fn validate(mut self) -> Result<Self, AdminError> {
+        if let (Some(from), Some(to)) = (self.from, self.to) {
+            if from > to {
+                return Err(AdminError::BadRequest(
+                    "'from' must not be after 'to'".to_string(),
+                ));
+            }
+        }
+        for value in [&mut self.property, &mut self.owner, &mut self.tag] {
+            if value.as_deref().is_some_and(|v| v.trim().is_empty()) {
+                *value = None;
+            }
+        }
+        Ok(self)
+    }
+
+    /// Fill in a missing range: `to` defaults to today and `from` to the first
+    /// day of the month `months - 1` months before `to`.
+    pub fn window(&self, months: u32) -> Result<Self, AdminError> {
+        let to = self.to.unwrap_or_else(|| Local::now().date_naive());
+        let from = match self.from {
+            Some(from) => from,
+            None => months_before(month_start(to), months.saturating_sub(1))?,
+        };
+        Ok(self.with_range(from, to))
+    }
+
+    /// Same filter over a different date range.
+    pub fn with_range(&self, from: NaiveDate, to: NaiveDate) -> Self {
+        Self {
+            from: Some(from),
+            to: Some(to),
+            ..self.clone()
+        }
+    }
+
+    pub fn scalar<'q, O>(&'q self, sql: &'q str) -> QueryScalar<'q, Postgres, O, PgArguments>
+    where
+        (O,): for<'r> FromRow<'r, PgRow>,
+    {
+        sqlx::query_scalar(sql)
+            .bind(self.from)
+            .bind(self.to)
+            .bind(self.property.as_deref())
+            .bind(self.owner.as_deref())
+            .bind(self.tag.as_deref())
+            .bind(self.owner_id)
+    }
+
+    pub fn query_as<'q, O>(&'q self, sql: &'q str) -> QueryAs<'q, Postgres, O, PgArguments>
+    where
+        O: for<'r> FromRow<'r, PgRow>,
+    {
+        sqlx::query_as(sql)
+            .bind(self.from)
+            .bind(self.to)
+            .bind(self.property.as_deref())
+            .bind(self.owner.as_deref())
+            .bind(self.tag.as_deref())
+            .bind(self.owner_id)
+    }
+}
+
+/// Unpaid and partly paid rent by payer and property, largest debt first.
+pub async fn arrears(pool: &PgPool, filter: &ReportFilter) -> Result<Value, sqlx::Error> {
+    let sql = format!(
+        "SELECT COALESCE(json_agg(row_to_json(t)), '[]') FROM (\
+            SELECT payer, property_name, \
+                COUNT(*)::int as outstanding_invoices, \
+                SUM(amount)::float8 as total_outstanding, \
+                SUM(paid)::float8 as total_paid, \
+                SUM(amount - paid)::float8 as debt, \
+                MIN(invoice_date) as oldest_date \
+            FROM admin_invoices \
+            WHERE status IN ('Unpaid', 'Partial') AND type = 'income' AND {INVOICE_FILTER} \
+            GROUP BY payer, property_name \
+            ORDER BY debt DESC\
+         ) t"
+    );
+
+    filter.scalar::<Value>(&sql).fetch_one(pool).await
+}
+
+/// Income, expenses, margin and rent collected per property, best margin first.
+pub async fn profitability(pool: &PgPool, filter: &ReportFilter) -> Result<Value, sqlx::Error> {
+    let sql = format!(
+        "SELECT COALESCE(json_agg(row_to_json(t)), '[]') FROM (\
+            SELECT property_name, \
+                COALESCE(SUM(CASE WHEN type = 'income' THEN amount ELSE 0 END), 0)::float8 as income, \
+                COALESCE(SUM(CASE WHEN type = 'expense' THEN amount ELSE 0 END), 0)::float8 as expenses, \
+                COALESCE(SUM(CASE WHEN type = 'income' THEN amount ELSE -amount END), 0)::float8 as margin, \
+                COALESCE(SUM(CASE WHEN type = 'income' THEN paid ELSE 0 END), 0)::float8 as collected \
+            FROM admin_invoices \
+            WHERE property_name != '' AND {INVOICE_FILTER} \
+            GROUP BY property_name \
+            ORDER BY margin DESC\
+         ) t"
+    );
+
+    filter.scalar::<Value>(&sql).fetch_one(pool).await
+}
+
+/// Monthly occupancy over the filter's range: units, units let at month end
+/// and let days against available unit-days. The range must be filled in.
+pub async fn occupancy(pool: &PgPool, filter: &ReportFilter) -> Result<Value, sqlx::Error> {
+    let sql = format!(
+        "WITH props AS (\
+            SELECT property_name FROM admin_properties WHERE {PROPERTY_FILTER}\
+         ), months AS (\
+            SELECT GREATEST(d::date, $1) AS month_start, \
+                LEAST((d + INTERVAL '1 month' - INTERVAL '1 day')::date, $2) AS month_end \
+            FROM generate_series(date_trunc('month', $1::date), $2::date, INTERVAL '1 month') d\
+         ), occupied AS (\
+            SELECT m.month_start, c.property_name, \
+                LEAST(m.month_end - m.month_start + 1, SUM(\
+                    LEAST(COALESCE(c.end_date, m.month_end), m.month_end) \
+                    - GREATEST(c.start_date, m.month_start) + 1)) AS days, \
+                bool_or(COALESCE(c.end_date, m.month_end) >= m.month_end) AS at_end \
+            FROM months m \
+            JOIN admin_contracts c ON c.start_date <= m.month_end \
+                AND (c.end_date IS NULL OR c.end_date >= m.month_start) \
+            WHERE {LET_CONTRACT} AND c.property_name IN (SELECT property_name FROM props) \
+            GROUP BY m.month_start, c.property_name\
+         ), series AS (\
+            SELECT to_char(m.month_start, 'YYYY-MM') AS month, m.month_start, \
+                (SELECT COUNT(*) FROM props)::int AS units, \
+                COUNT(o.property_name) FILTER (WHERE o.at_end)::int AS occupied_units, \
+                COALESCE(SUM(o.days), 0)::int AS occupied_days, \
+                ((m.month_end - m.month_start + 1) * (SELECT COUNT(*) FROM props))::int AS unit_days \
+            FROM months m LEFT JOIN occupied o ON o.month_start = m.month_start \
+            GROUP BY m.month_start, m.month_end\
+         ) \
+         SELECT json_build_object(\
+            'from', $1::date, 'to', $2::date, \
+            'data', COALESCE(json_agg(json_build_object(\
+                'month', month, 'units', units, 'occupied_units', occupied_units, \
+                'occupied_days', occupied_days, 'unit_days', unit_days, \
+                'occupancy_rate', ROUND(occupied_days * 100.0 / NULLIF(unit_days, 0), 1)::float8\
+            ) ORDER BY month_start), '[]'), \
+            'occupancy_rate', ROUND(SUM(occupied_days) * 100.0 / NULLIF(SUM(unit_days), 0), 1)::float8\
+         ) FROM series"
+    );
+
+    filter.scalar::<Value>(&sql).fetch_one(pool).await
+}
+
+/// Headline income and expense figures for one period.
+#[derive(Debug, Clone, Serialize)]
+pub struct PeriodKpis {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub invoiced: f64,
+    pub collected: f64,
+    pub outstanding: f64,
+    pub expenses: f64,
+    pub net: f64,
+    pub collection_rate: f64,
+}
+
+/// Change between two periods; `pct` is `None` when the base is zero.
+#[derive(Debug, Clone, Serialize)]
+pub struct Delta {
+    pub absolute: f64,
+    pub pct: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct KpiDeltas {
+    pub invoiced: Delta,
+    pub collected: Delta,
+    pub outstanding: Delta,
+    pub expenses: Delta,
+    pub net: Delta,
+    pub collection_rate: Delta,
+}
+
+/// Current period against the previous period and the same period a year earlier.
+#[derive(Debug, Clone, Serialize)]
+pub struct PeriodComparison {
+    pub current: PeriodKpis,
+    pub previous: PeriodKpis,
+    pub year_ago: PeriodKpis,
+    pub vs_previous: KpiDeltas,
+    pub vs_year_ago: KpiDeltas,
+}
+
+/// Compare the filter's date range with the preceding period of equal length
+/// and the same period last year. Without a range, month-to-date is compared
+/// with the same days of last month.
+pub async fn compare_periods(
+    pool: &PgPool,
+    filter: &ReportFilter,
+) -> Result<PeriodComparison, AdminError> {
+    let [(from, to), (prev_from, prev_to), (year_from, year_to)] =
+        comparison_ranges(filter, Local::now().date_naive())?;
+
+    let (current, previous, year_ago) = tokio::join!(
+        period_kpis(pool, &filter.with_range(from, to)),
+        period_kpis(pool, &filter.with_range(prev_from, prev_to)),
+        period_kpis(pool, &filter.with_range(year_from, year_to)),
+    );
+    let (current, previous, year_ago) = (current?, previous?, year_ago?);
+
+    Ok(PeriodComparison {
+        vs_previous: deltas(&current, &previous),
+        vs_year_ago: deltas(&current, &year_ago),
+        current,
+        previous,
+        year_ago,
+    })
+}
+
+/// The current, previous and year-ago ranges for [`compare_periods`].
+fn comparison_ranges(
+    filter: &ReportFilter,
+    today: NaiveDate,
+) -> Result<[(NaiveDate, NaiveDate); 3], AdminError> {
+    let to = filter.to.unwrap_or(today);
+    let from = filter.from.unwrap_or_else(|| month_start(to));
+
+    let previous = if filter.from.is_none() && filter.to.is_none() {
+        (months_before(from, 1)?, months_before(to, 1)?)
+    } else {
+        let days = u64::try_from((to - from).num_days()).unwrap_or(0);
+        let prev_to = days_before(from, 1)?;
+        (days_before(prev_to, days)?, prev_to)
+    };
+    let year_ago = (months_before(from, 12)?, months_before(to, 12)?);
+
+    Ok([(from, to), previous, year_ago])
+}
+
+async fn period_kpis(pool: &PgPool, filter: &ReportFilter) -> Result<PeriodKpis, AdminError> {
+    let sql = format!(
+        "SELECT \
+            COALESCE(SUM(amount) FILTER (WHERE type = 'income'), 0)::float8, \
+            COALESCE(SUM(paid) FILTER (WHERE type = 'income'), 0)::float8, \
+            COALESCE(SUM(amount - paid) FILTER (WHERE type = 'income'), 0)::float8, \
+            COALESCE(SUM(amount) FILTER (WHERE type = 'expense'), 0)::float8 \
+         FROM admin_invoices WHERE {INVOICE_FILTER}"
+    );
+    let (invoiced, collected, outstanding, expenses) = filter
+        .query_as::<(f64, f64, f64, f64)>(&sql)
+        .fetch_one(pool)
+        .await?;
+
+    Ok(PeriodKpis {
+        from: filter.from.unwrap_or_default(),
+        to: filter.to.unwrap_or_default(),
+        invoiced,
+        collected,
+        outstanding,
+        expenses,
+        net: round2(collected - expenses),
+        collection_rate: collection_rate(invoiced, collected),
+    })
+}
+
+/// Collected as a percentage of invoiced, to one decimal.
+pub fn collection_rate(invoiced: f64, collected: f64) -> f64 {
+    if invoiced > 0.0 {
+        (collected / invoiced * 1000.0).round() / 10.0
+    } else {
+        0.0
+    }
+}
+
+fn deltas(current: &PeriodKpis, base: &PeriodKpis) -> KpiDeltas {
+    KpiDeltas {
+        invoiced: delta(current.invoiced, base.invoiced),
+        collected: delta(current.collected, base.collected),
+        outstanding: delta(current.outstanding, base.outstanding),
+        expenses: delta(current.expenses, base.expenses),
+        net: delta(current.net, base.net),
+        collection_rate: delta(current.collection_rate, base.collection_rate),
+    }
+}
+
+fn delta(current: f64, base: f64) -> Delta {
+    Delta {
+        absolute: round2(current - base),
+        pct: (base != 0.0).then(|| (((current - base) / base.abs()) * 1000.0).round() / 10.0),
+    }
+}
+
+fn month_start(date: NaiveDate) -> NaiveDate {
+    NaiveDate::from_ymd_opt(date.year(), date.month(), 1)
+        .expect("first day of a month is always valid")
+}
+
+fn months_before(date: NaiveDate, months: u32) -> Result<NaiveDate, AdminError> {
+    date.checked_sub_months(Months::new(months))
+        .ok_or_else(|| out_of_range(date))
+}
+
+fn days_before(date: NaiveDate, days: u64) -> Result<NaiveDate, AdminError> {
+    date.checked_sub_days(Days::new(days))
+        .ok_or_else(|| out_of_range(date))
+}
+
+fn out_of_range(date: NaiveDate) -> AdminError {
+    AdminError::BadRequest(format!("Date {date} is out of range"))
+}
+
+fn round2(v: f64) -> f64 {
+    (v * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn range(from: Option<NaiveDate>, to: Option<NaiveDate>) -> ReportFilter {
+        ReportFilter {
+            from,
+            to,
+            ..ReportFilter::default()
+        }
+    }
+
+    #[test]
+    fn validate_rejects_an_inverted_range() {
+        let filter = range(Some(date(2026, 3, 1)), Some(date(2026, 2, 1)));
+        assert!(matches!(filter.validate(), Err(AdminError::BadRequest(_))));
+    }
+
+    #[test]
+    fn validate_drops_blank_filters() {
+        let filter = ReportFilter {
+            property: Some("  ".to_string()),
+            owner: Some(String::new()),
+            tag: Some("coastal".to_string()),
+            ..ReportFilter::default()
+        }
+        .validate()
+        .unwrap();
+        assert_eq!(filter.property, None);
+        assert_eq!(filter.owner, None);
+        assert_eq!(filter.tag.as_deref(), Some("coastal"));
+    }
+
+    #[test]
+    fn window_starts_on_the_first_of_the_month() {
+        let filter = range(None, Some(date(2026, 3, 17))).window(12).unwrap();
+        assert_eq!(filter.from, Some(date(2025, 4, 1)));
+        assert_eq!(filter.to, Some(date(2026, 3, 17)));
+
+        let filter = range(None, Some(date(2026, 3, 17))).window(1).unwrap();
+        assert_eq!(filter.from, Some(date(2026, 3, 1)));
+    }
+
+    #[test]
+    fn window_keeps_an_explicit_start() {
+        let filter = range(Some(date(2026, 1, 10)), Some(date(2026, 3, 17)))
+            .window(12)
+            .unwrap();
+        assert_eq!(filter.from, Some(date(2026, 1, 10)));
+    }
+
+    #[test]
+    fn month_to_date_compares_with_the_same_days_of_last_month() {
+        let ranges = comparison_ranges(&ReportFilter::default(), date(2026, 3, 31)).unwrap();
+        assert_eq!(
+            ranges,
+            [
+                (date(2026, 3, 1), date(2026, 3, 31)),
+                (date(2026, 2, 1), date(2026, 2, 28)),
+                (date(2025, 3, 1), date(2025, 3, 31)),
+            ]
+        );
+    }
+
+    #[test]
+    fn explicit_range_compares_with_the_preceding_range_of_equal_length() {
+        let filter = range(Some(date(2026, 1, 1)), Some(date(2026, 1, 10)));
+        let ranges = comparison_ranges(&filter, date(2026, 6, 1)).unwrap();
+        assert_eq!(
+            ranges,
+            [
+                (date(2026, 1, 1), date(2026, 1, 10)),
+                (date(2025, 12, 22), date(2025, 12, 31)),
+                (date(2025, 1, 1), date(2025, 1, 10)),
+            ]
+        );
+    }
+
+    #[test]
+    fn out_of_range_dates_are_a_bad_request() {
+        let filter = range(Some(NaiveDate::MIN), Some(NaiveDate::MIN));
+        assert!(matches!(
+            comparison_ranges(&filter, date(2026, 1, 1)),
+            Err(AdminError::BadRequest(_))
+        ));
+    }
+
+    #[test]
+    fn collection_rate_is_a_percentage_to_one_decimal() {
+        assert!((collection_rate(3000.0, 2000.0) - 66.7).abs() < f64::EPSILON);
+        assert!(collection_rate(0.0, 150.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn delta_has_no_percentage_against_zero() {
+        let d = delta(250.0, 0.0);
+        assert!((d.absolute - 250.0).abs() < f64::EPSILON);
+        assert_eq!(d.pct, None);
+    }
+
+    #[test]
+    fn delta_percentage_uses_the_absolute_base() {
+        assert_eq!(delta(150.0, 100.0).pct, Some(50.0));
+        assert_eq!(delta(-50.0, -100.0).pct, Some(50.0));
+        assert_eq!(delta(0.0, 300.0).pct, Some(-100.0));
+    }
+}
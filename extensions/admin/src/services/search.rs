@@ -0,0 +1,264 @@
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+
+use crate::error::AdminError;
+
+/// Hits returned per entity unless `?limit=` says otherwise.
+pub const DEFAULT_LIMIT: i64 = 5;
+pub const MAX_LIMIT: i64 = 50;
+
+/// An entity covered by global search.
+struct SearchTarget {
+    entity: &'static str,
+    table: &'static str,
+    title: &'static str,
+    subtitle: &'static str,
+    /// Searched columns as a `TEXT[]` expression. Must match the index
+    /// expressions in `schema/011_search.sql` (tenants, owners and contacts:
+    /// `schema/022_pii_encryption.sql`). Encrypted columns are left out.
+    fields: &'static str,
+}
+
+const TARGETS: &[SearchTarget] = &[
+    SearchTarget {
+        entity: "properties",
+        table: "admin_properties",
+        title: "property_name",
+        subtitle: "address",
+        fields: "ARRAY[property_name, address, contract_ref] || tags",
+    },
+    SearchTarget {
+        entity: "tenants",
+        table: "admin_tenants",
+        title: "name",
+        subtitle: "property_name",
+        fields: "ARRAY[name, email, property_name]",
+    },
+    SearchTarget {
+        entity: "owners",
+        table: "admin_owners",
+        title: "name",
+        subtitle: "property_name",
+        fields: "ARRAY[name, email, property_name]",
+    },
+    SearchTarget {
+        entity: "contracts",
+        table: "admin_contracts",
+        title: "contract_ref",
+        subtitle: "tenant_name",
+        fields: "ARRAY[contract_ref, property_name, address, tenant_name] || tags",
+    },
+    SearchTarget {
+        entity: "invoices",
+        table: "admin_invoices",
+        title: "reference",
+        subtitle: "property_name",
+        fields: "ARRAY[reference, description, contract_ref, property_name, payer, payee]",
+    },
+    SearchTarget {
+        entity: "contacts",
+        table: "admin_contacts",
+        title: "name",
+        subtitle: "contact_type",
+        fields: "ARRAY[name, email, notes]",
+    },
+];
+
+/// Matched words in snippets are wrapped in `<mark>`; the rest of the
+/// snippet is record text and must be escaped by the client.
+const HEADLINE_OPTIONS: &str = "MaxFragments=2, MaxWords=12, MinWords=4, \
+     FragmentDelimiter=\" … \", StartSel=<mark>, StopSel=</mark>";
+
+/// Query parameters: ?q=garcia&entities=tenants,owners&limit=5
+#[derive(Debug, Deserialize, Default)]
+pub struct SearchQuery {
+    pub q: Option<String>,
+    /// Comma-separated entities to search; all of them when absent
+    pub entities: Option<String>,
+    /// Hits per entity
+    pub limit: Option<i64>,
+}
+
+/// Search the live records of every target entity for `q`.
+///
+/// Words match full-text on their prefix ("gar" finds "García") and, to
+/// tolerate typos, by trigram similarity against the whole record. Hits are
+/// grouped by entity; groups come in order of their best hit.
+pub async fn search(pool: &PgPool, params: &SearchQuery) -> Result<Value, AdminError> {
+    let q = params.q.as_deref().map(str::trim).unwrap_or_default();
+    if q.chars().count() < 2 {
+        return Err(AdminError::BadRequest(
+            "q must be at least 2 characters".to_string(),
+        ));
+    }
+    let tsquery = prefix_tsquery(q)
+        .ok_or_else(|| AdminError::BadRequest("q must contain letters or digits".to_string()))?;
+    let targets = selected_targets(params.entities.as_deref())?;
+    let limit = params.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let hits: Vec<String> = targets.iter().map(|target| hit_sql(target)).collect();
+    let sql = format!(
+        "WITH q AS (SELECT to_tsquery('admin_search', $1) AS tsq, \
+                           lower(admin_unaccent($2)) AS search_key) \
+         SELECT row_to_json(h) FROM ({}) h ORDER BY h.rank DESC",
+        hits.join(" UNION ALL ")
+    );
+    let rows: Vec<Value> = sqlx::query_scalar(&sql)
+        .bind(&tsquery)
+        .bind(q)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+    let total = rows.len();
+    Ok(json!({
+        "query": q,
+        "total": total,
+        "groups": group_by_entity(rows),
+    }))
+}
+
+fn selected_targets(entities: Option<&str>) -> Result<Vec<&'static SearchTarget>, AdminError> {
+    let Some(entities) = entities.filter(|e| !e.trim().is_empty()) else {
+        return Ok(TARGETS.iter().collect());
+    };
+    entities
+        .split(',')
+        .map(str::trim)
+        .filter(|e| !e.is_empty())
+        .map(|entity| {
+            TARGETS
+                .iter()
+                .find(|t| t.entity == entity)
+                .ok_or_else(|| AdminError::BadRequest(format!("Cannot search {entity}")))
+        })
+        .collect()
+}
+
+/// Best `$3` hits of one entity, ranked by full-text relevance plus trigram
+/// similarity.
+fn hit_sql(target: &SearchTarget) -> String {
+    let SearchTarget {
+        entity,
+        table,
+        title,
+        subtitle,
+        fields,
+    } = target;
+    let document = format!("to_tsvector('admin_search', admin_search_text({fields}))");
+    let key = format!("admin_search_key({fields})");
+    format!(
+        "(SELECT '{entity}' AS entity, id, {title} AS title, {subtitle} AS subtitle, \
+             ts_headline('admin_search', admin_search_text({fields}), q.tsq, '{HEADLINE_OPTIONS}') AS snippet, \
+             (ts_rank_cd({document}, q.tsq) + word_similarity(q.search_key, {key}))::float8 AS rank \
+         FROM {table}, q \
+         WHERE deleted_at IS NULL AND ({document} @@ q.tsq OR {key} %> q.search_key) \
+         ORDER BY rank DESC, id LIMIT $3)"
+    )
+}
+
+/// `to_tsquery` input matching every word of `q` by prefix. Punctuation
+/// splits words, so `ana@example.com` becomes `ana:* & example:* & com:*`.
+fn prefix_tsquery(q: &str) -> Option<String> {
+    let words: Vec<String> = q
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| format!("{w}:*"))
+        .collect();
+    (!words.is_empty()).then(|| words.join(" & "))
+}
+
+/// Group rows (already sorted by rank) into `{entity, hits}` groups.
+fn group_by_entity(rows: Vec<Value>) -> Vec<Value> {
+    let mut groups: Vec<(String, Vec<Value>)> = Vec::new();
+    for mut row in rows {
+        let entity = row
+            .as_object_mut()
+            .and_then(|obj| obj.remove("entity"))
+            .and_then(|e| e.as_str().map(str::to_string))
+            .unwrap_or_default();
+        match groups.iter_mut().find(|(name, _)| *name == entity) {
+            Some((_, hits)) => hits.push(row),
+            None => groups.push((entity, vec![row])),
+        }
+    }
+    groups
+        .into_iter()
+        .map(|(entity, hits)| json!({ "entity": entity, "count": hits.len(), "hits": hits }))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tsquery_matches_every_word_by_prefix() {
+        assert_eq!(prefix_tsquery("gar").as_deref(), Some("gar:*"));
+        assert_eq!(
+            prefix_tsquery("  Calle  Mayor ").as_deref(),
+            Some("Calle:* & Mayor:*")
+        );
+    }
+
+    #[test]
+    fn tsquery_splits_on_punctuation_and_keeps_accents() {
+        assert_eq!(
+            prefix_tsquery("ana@example.com").as_deref(),
+            Some("ana:* & example:* & com:*")
+        );
+        assert_eq!(
+            prefix_tsquery("García-Núñez").as_deref(),
+            Some("García:* & Núñez:*")
+        );
+    }
+
+    #[test]
+    fn tsquery_operators_cannot_be_injected() {
+        assert_eq!(
+            prefix_tsquery("a & !b | c:*").as_deref(),
+            Some("a:* & b:* & c:*")
+        );
+        assert_eq!(prefix_tsquery("'); --"), None);
+        assert_eq!(prefix_tsquery(""), None);
+    }
+
+    #[test]
+    fn all_targets_are_searched_by_default() {
+        assert_eq!(selected_targets(None).unwrap().len(), TARGETS.len());
+        assert_eq!(selected_targets(Some(" ")).unwrap().len(), TARGETS.len());
+    }
+
+    #[test]
+    fn targets_are_picked_by_entity_name() {
+        let targets = selected_targets(Some("owners, tenants,")).unwrap();
+        let names: Vec<&str> = targets.iter().map(|t| t.entity).collect();
+        assert_eq!(names, ["owners", "tenants"]);
+    }
+
+    #[test]
+    fn unknown_target_is_rejected() {
+        assert!(matches!(
+            selected_targets(Some("tenants,payments")),
+            Err(AdminError::BadRequest(msg)) if msg == "Cannot search payments"
+        ));
+    }
+
+    #[test]
+    fn hits_are_grouped_in_rank_order() {
+        let rows = vec![
+            json!({"entity": "tenants", "id": 1}),
+            json!({"entity": "owners", "id": 2}),
+            json!({"entity": "tenants", "id": 3}),
+        ];
+        let groups = group_by_entity(rows);
+        assert_eq!(
+            groups,
+            vec![
+                json!({"entity": "tenants", "count": 2, "hits": [{"id": 1}, {"id": 3}]}),
+                json!({"entity": "owners", "count": 1, "hits": [{"id": 2}]}),
+            ]
+        );
+    }
+}
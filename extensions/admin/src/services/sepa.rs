@@ -0,0 +1,311 @@
+use std::fmt::Write;
+
+use chrono::{NaiveDate, NaiveDateTime};
+
+/// ISO 20022 namespace for SEPA credit transfer initiation.
+const PAIN_001_NS: &str = "urn:iso:std:iso:20022:tech:xsd:pain.001.001.03";
+
+// SEPA field length limits
+const MAX_ID_LEN: usize = 35;
+const MAX_NAME_LEN: usize = 70;
+const MAX_REMITTANCE_LEN: usize = 140;
+
+/// Ordering account and header data for a credit transfer file.
+#[derive(Debug)]
+pub struct CreditTransferBatch {
+    pub message_id: String,
+    pub created_at: NaiveDateTime,
+    pub execution_date: NaiveDate,
+    pub debtor_name: String,
+    pub debtor_iban: String,
+    pub debtor_bic: String,
+    pub currency: String,
+    pub transfers: Vec<CreditTransfer>,
+}
+
+/// A single transfer to a creditor (owner).
+#[derive(Debug)]
+pub struct CreditTransfer {
+    pub end_to_end_id: String,
+    pub creditor_name: String,
+    pub creditor_iban: String,
+    pub amount: f64,
+    pub remittance_info: String,
+}
+
+/// Strip spaces and upper-case an IBAN as written in the admin tables.
+pub fn normalize_iban(iban: &str) -> String {
+    iban.chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_ascii_uppercase()
+}
+
+/// Validate an IBAN's structure and ISO 7064 mod-97 check digits.
+pub fn is_valid_iban(iban: &str) -> bool {
+    let iban = normalize_iban(iban);
+    if !(15..=34).contains(&iban.len()) || !iban.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return false;
+    }
+
+    let (head, tail) = iban.split_at(4);
+    let remainder = tail.chars().chain(head.chars()).try_fold(0u32, |acc, c| {
+        let digit = c.to_digit(36)?;
+        Some(if digit < 10 {
+            (acc * 10 + digit) % 97
+        } else {
+            (acc * 100 + digit) % 97
+        })
+    });
+    remainder == Some(1)
+}
+
+/// Render a `pain.001.001.03` customer credit transfer initiation document.
+pub fn build_pain_001(batch: &CreditTransferBatch) -> String {
+    let count = batch.transfers.len();
+    let control_sum: f64 = batch.transfers.iter().map(|t| t.amount).sum();
+    let message_id = clip(&batch.message_id, MAX_ID_LEN);
+
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<Document xmlns=\"{PAIN_001_NS}\" xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\">"
+    );
+    xml.push_str("  <CstmrCdtTrfInitn>\n");
+
+    // Group header
+    xml.push_str("    <GrpHdr>\n");
+    let _ = writeln!(xml, "      <MsgId>{}</MsgId>", escape(&message_id));
+    let _ = writeln!(
+        xml,
+        "      <CreDtTm>{}</CreDtTm>",
+        batch.created_at.format("%Y-%m-%dT%H:%M:%S")
+    );
+    let _ = writeln!(xml, "      <NbOfTxs>{count}</NbOfTxs>");
+    let _ = writeln!(xml, "      <CtrlSum>{control_sum:.2}</CtrlSum>");
+    let _ = writeln!(
+        xml,
+        "      <InitgPty><Nm>{}</Nm></InitgPty>",
+        escape(&clip(&batch.debtor_name, MAX_NAME_LEN))
+    );
+    xml.push_str("    </GrpHdr>\n");
+
+    // Payment information (one debtor account, one execution date)
+    xml.push_str("    <PmtInf>\n");
+    let _ = writeln!(xml, "      <PmtInfId>{}</PmtInfId>", escape(&message_id));
+    xml.push_str("      <PmtMtd>TRF</PmtMtd>\n");
+    xml.push_str("      <BtchBookg>true</BtchBookg>\n");
+    let _ = writeln!(xml, "      <NbOfTxs>{count}</NbOfTxs>");
+    let _ = writeln!(xml, "      <CtrlSum>{control_sum:.2}</CtrlSum>");
+    if batch.currency == "EUR" {
+        xml.push_str("      <PmtTpInf><SvcLvl><Cd>SEPA</Cd></SvcLvl></PmtTpInf>\n");
+    }
+    let _ = writeln!(
+        xml,
+        "      <ReqdExctnDt>{}</ReqdExctnDt>",
+        batch.execution_date.format("%Y-%m-%d")
+    );
+    let _ = writeln!(
+        xml,
+        "      <Dbtr><Nm>{}</Nm></Dbtr>",
+        escape(&clip(&batch.debtor_name, MAX_NAME_LEN))
+    );
+    let _ = writeln!(
+        xml,
+        "      <DbtrAcct><Id><IBAN>{}</IBAN></Id></DbtrAcct>",
+        normalize_iban(&batch.debtor_iban)
+    );
+    if batch.debtor_bic.trim().is_empty() {
+        xml.push_str(
+            "      <DbtrAgt><FinInstnId><Othr><Id>NOTPROVIDED</Id></Othr></FinInstnId></DbtrAgt>\n",
+        );
+    } else {
+        let _ = writeln!(
+            xml,
+            "      <DbtrAgt><FinInstnId><BIC>{}</BIC></FinInstnId></DbtrAgt>",
+            escape(batch.debtor_bic.trim())
+        );
+    }
+    xml.push_str("      <ChrgBr>SLEV</ChrgBr>\n");
+
+    for transfer in &batch.transfers {
+        write_transfer(&mut xml, transfer, &batch.currency);
+    }
+
+    xml.push_str("    </PmtInf>\n");
+    xml.push_str("  </CstmrCdtTrfInitn>\n");
+    xml.push_str("</Document>\n");
+    xml
+}
+
+fn write_transfer(xml: &mut String, transfer: &CreditTransfer, currency: &str) {
+    xml.push_str("      <CdtTrfTxInf>\n");
+    let _ = writeln!(
+        xml,
+        "        <PmtId><EndToEndId>{}</EndToEndId></PmtId>",
+        escape(&clip(&transfer.end_to_end_id, MAX_ID_LEN))
+    );
+    let _ = writeln!(
+        xml,
+        "        <Amt><InstdAmt Ccy=\"{}\">{:.2}</InstdAmt></Amt>",
+        escape(currency),
+        transfer.amount
+    );
+    let _ = writeln!(
+        xml,
+        "        <Cdtr><Nm>{}</Nm></Cdtr>",
+        escape(&clip(&transfer.creditor_name, MAX_NAME_LEN))
+    );
+    let _ = writeln!(
+        xml,
+        "        <CdtrAcct><Id><IBAN>{}</IBAN></Id></CdtrAcct>",
+        normalize_iban(&transfer.creditor_iban)
+    );
+    let _ = writeln!(
+        xml,
+        "        <RmtInf><Ustrd>{}</Ustrd></RmtInf>",
+        escape(&clip(&transfer.remittance_info, MAX_REMITTANCE_LEN))
+    );
+    xml.push_str("      </CdtTrfTxInf>\n");
+}
+
+fn clip(s: &str, max_chars: usize) -> String {
+    s.chars().take(max_chars).collect()
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_valid_ibans() {
+        assert!(is_valid_iban("GB82WEST12345698765432"));
+        assert!(is_valid_iban("DE89370400440532013000"));
+        assert!(is_valid_iban("ES9121000418450200051332"));
+        assert!(is_valid_iban("NL91ABNA0417164300"));
+    }
+
+    #[test]
+    fn ignores_spacing_and_case() {
+        assert!(is_valid_iban("gb82 west 1234 5698 7654 32"));
+        assert_eq!(
+            normalize_iban(" es91 2100 0418 4502 0005 1332 "),
+            "ES9121000418450200051332"
+        );
+    }
+
+    #[test]
+    fn rejects_wrong_check_digits() {
+        assert!(!is_valid_iban("GB82WEST12345698765431"));
+        assert!(!is_valid_iban("GB28WEST12345698765432"));
+    }
+
+    #[test]
+    fn rejects_bad_length_and_characters() {
+        assert!(!is_valid_iban(""));
+        assert!(!is_valid_iban("GB82WEST1234"));
+        assert!(!is_valid_iban("GB82-WEST-1234-5698-7654-32"));
+        assert!(!is_valid_iban(&format!("GB82{}", "1".repeat(31))));
+    }
+
+    fn transfer(end_to_end_id: &str, name: &str, amount: f64, remittance: &str) -> CreditTransfer {
+        CreditTransfer {
+            end_to_end_id: end_to_end_id.to_string(),
+            creditor_name: name.to_string(),
+            creditor_iban: "es91 2100 0418 4502 0005 1332".to_string(),
+            amount,
+            remittance_info: remittance.to_string(),
+        }
+    }
+
+    fn batch(currency: &str, transfers: Vec<CreditTransfer>) -> CreditTransferBatch {
+        CreditTransferBatch {
+            message_id: "PO-202501-ABCDEF12".to_string(),
+            created_at: NaiveDate::from_ymd_opt(2025, 2, 3)
+                .and_then(|d| d.and_hms_opt(10, 15, 0))
+                .unwrap(),
+            execution_date: NaiveDate::from_ymd_opt(2025, 2, 5).unwrap(),
+            debtor_name: "Gestión & Alquileres S.L.".to_string(),
+            debtor_iban: "DE89 3704 0044 0532 0130 00".to_string(),
+            debtor_bic: String::new(),
+            currency: currency.to_string(),
+            transfers,
+        }
+    }
+
+    #[test]
+    fn pain_001_counts_and_sums_the_transfers() {
+        let xml = build_pain_001(&batch(
+            "EUR",
+            vec![
+                transfer("PO-1-001", "Ana", 0.1, "Rent"),
+                transfer("PO-1-002", "Luis", 0.2, "Rent"),
+                transfer("PO-1-003", "Marta", 1200.0, "Rent"),
+            ],
+        ));
+        assert_eq!(xml.matches("<NbOfTxs>3</NbOfTxs>").count(), 2);
+        assert_eq!(xml.matches("<CtrlSum>1200.30</CtrlSum>").count(), 2);
+        assert_eq!(xml.matches("<CdtTrfTxInf>").count(), 3);
+        assert!(xml.contains("<InstdAmt Ccy=\"EUR\">0.10</InstdAmt>"));
+        assert!(xml.contains("<CreDtTm>2025-02-03T10:15:00</CreDtTm>"));
+        assert!(xml.contains("<ReqdExctnDt>2025-02-05</ReqdExctnDt>"));
+        assert!(xml.contains("<IBAN>DE89370400440532013000</IBAN>"));
+        assert!(xml.contains("<IBAN>ES9121000418450200051332</IBAN>"));
+        assert!(xml.contains("<Othr><Id>NOTPROVIDED</Id></Othr>"));
+    }
+
+    #[test]
+    fn pain_001_escapes_names_and_remittance() {
+        let xml = build_pain_001(&batch(
+            "EUR",
+            vec![transfer(
+                "PO-1-001",
+                "O'Brien <Holdings>",
+                10.0,
+                "Rent \"May\" & fees",
+            )],
+        ));
+        assert!(xml.contains("<Nm>Gestión &amp; Alquileres S.L.</Nm>"));
+        assert!(xml.contains("<Nm>O&apos;Brien &lt;Holdings&gt;</Nm>"));
+        assert!(xml.contains("<Ustrd>Rent &quot;May&quot; &amp; fees</Ustrd>"));
+        assert!(!xml.contains("<Holdings>"));
+    }
+
+    #[test]
+    fn pain_001_clips_fields_to_sepa_limits() {
+        let long = "x".repeat(200);
+        let xml = build_pain_001(&batch("EUR", vec![transfer(&long, &long, 1.0, &long)]));
+        assert!(xml.contains(&format!("<EndToEndId>{}</EndToEndId>", "x".repeat(35))));
+        assert!(xml.contains(&format!("<Cdtr><Nm>{}</Nm></Cdtr>", "x".repeat(70))));
+        assert!(xml.contains(&format!("<Ustrd>{}</Ustrd>", "x".repeat(140))));
+    }
+
+    #[test]
+    fn pain_001_sets_the_sepa_service_level_only_for_euros() {
+        let sepa = "<SvcLvl><Cd>SEPA</Cd></SvcLvl>";
+        let eur = build_pain_001(&batch("EUR", vec![transfer("1", "Ana", 1.0, "Rent")]));
+        assert!(eur.contains(sepa));
+        let gbp = build_pain_001(&batch("GBP", vec![transfer("1", "Ana", 1.0, "Rent")]));
+        assert!(!gbp.contains(sepa));
+        assert!(gbp.contains("<InstdAmt Ccy=\"GBP\">1.00</InstdAmt>"));
+    }
+
+    #[test]
+    fn pain_001_names_the_debtor_bic_when_known() {
+        let mut with_bic = batch("EUR", Vec::new());
+        with_bic.debtor_bic = " COBADEFFXXX ".to_string();
+        let xml = build_pain_001(&with_bic);
+        assert!(xml.contains("<FinInstnId><BIC>COBADEFFXXX</BIC></FinInstnId>"));
+        assert!(xml.contains("<NbOfTxs>0</NbOfTxs>"));
+        assert!(xml.contains("<CtrlSum>0.00</CtrlSum>"));
+    }
+}
@@ -0,0 +1,804 @@
+//! Self-hosted electronic signatures for contract documents.
+//!
+//! A signature request sends one generated document to the tenant and the
+//! owner. Each signer gets a one-time link (`/admin/sign/?token=..`) to a page
+//! where they read the PDF and draw or type their signature. Only a hash of
+//! the token is stored, so links cannot be recovered from the database. When
+//! the last signer signs, an evidence page recording each signature,
+//! timestamp, IP address and the document hash is appended to the signed PDF
+//! as an incremental update, the copy is attached to the contract, and the
+//! contract becomes `Active`.
+
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use rand::RngCore;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::api::actor::Actor;
+use crate::api::generic::write_audit;
+use crate::error::AdminError;
+use crate::services::contract_documents::{attach_document, file_stem, NewDocument};
+use crate::services::pdf::{PdfService, SignatureEvidence, SignatureMark, SignerEvidence};
+
+/// Days a signing link stays valid unless the request says otherwise.
+pub const DEFAULT_EXPIRY_DAYS: i64 = 14;
+pub const MAX_EXPIRY_DAYS: i64 = 90;
+
+/// Largest drawn signature accepted, in bytes of PNG.
+const MAX_SIGNATURE_BYTES: usize = 512 * 1024;
+
+const SIGNER_ROLES: &[&str] = &["tenant", "owner"];
+const PNG_DATA_URL: &str = "data:image/png;base64,";
+const PNG_MAGIC: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// SHA-256 of `bytes`, hex.
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+/// Body of POST /contract-documents/{id}/signature-requests. Without
+/// `signers`, the contract's tenant and the property's owner are asked.
+#[derive(Debug, Deserialize, Default)]
+pub struct SignatureRequestBody {
+    pub signers: Option<Vec<SignerInput>>,
+    pub expires_in_days: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SignerInput {
+    pub role: String,
+    pub name: String,
+    #[serde(default)]
+    pub email: String,
+}
+
+/// Body of POST /sign/{token}.
+#[derive(Debug, Deserialize)]
+pub struct SignBody {
+    /// drawn or typed
+    pub method: String,
+    /// The typed name, or a PNG data URL of the drawn signature
+    pub signature: String,
+    /// SHA-256 of the document the signer was shown, as returned by
+    /// GET /sign/{token}
+    pub document_hash: String,
+}
+
+// ── Requests ────────────────────────────────────────────────────────────
+
+/// Send a generated document for signature. Returns the request with each
+/// signer's one-time link; the links are not shown again.
+pub async fn create_signature_request(
+    pool: &PgPool,
+    actor: &Actor,
+    document_id: Uuid,
+    body: &SignatureRequestBody,
+) -> Result<Value, AdminError> {
+    let days = body.expires_in_days.unwrap_or(DEFAULT_EXPIRY_DAYS);
+    if !(1..=MAX_EXPIRY_DAYS).contains(&days) {
+        return Err(AdminError::BadRequest(format!(
+            "expires_in_days must be between 1 and {MAX_EXPIRY_DAYS}"
+        )));
+    }
+
+    let mut tx = pool.begin().await?;
+    let document: Value = sqlx::query_scalar(
+        "SELECT json_build_object('id', d.id, 'contract_id', d.contract_id, 'type', d.type, \
+            'sha256', d.sha256, 'generated', d.blocks IS NOT NULL, \
+            'tenant_name', c.tenant_name, 'property_name', c.property_name) \
+         FROM admin_contract_documents d JOIN admin_contracts c ON c.id = d.contract_id \
+         WHERE d.id = $1 AND c.deleted_at IS NULL FOR UPDATE OF d",
+    )
+    .bind(document_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AdminError::NotFound("document".to_string()))?;
+    if document["generated"] != true || document["type"] == "signed" {
+        return Err(AdminError::BadRequest(
+            "Only documents generated from a template can be sent for signature".to_string(),
+        ));
+    }
+    let pending: bool = sqlx::query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM admin_signature_requests \
+         WHERE document_id = $1 AND status = 'pending')",
+    )
+    .bind(document_id)
+    .fetch_one(&mut *tx)
+    .await?;
+    if pending {
+        return Err(AdminError::Conflict(
+            "The document already has a signature request in progress".to_string(),
+        ));
+    }
+
+    let signers = match &body.signers {
+        Some(signers) => validate_signers(signers)?,
+        None => contract_parties(&mut tx, &document).await?,
+    };
+    let contract_id = document["contract_id"].as_str().unwrap_or_default();
+
+    let request: Value = sqlx::query_scalar(
+        "INSERT INTO admin_signature_requests \
+         (contract_id, document_id, document_hash, created_by, expires_at) \
+         VALUES ($1::uuid, $2, $3, $4, NOW() + make_interval(days => $5::int)) \
+         RETURNING row_to_json(admin_signature_requests.*)",
+    )
+    .bind(contract_id)
+    .bind(document_id)
+    .bind(document["sha256"].as_str().unwrap_or_default())
+    .bind(actor.user_id.as_deref())
+    .bind(days)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let request_id = request["id"].as_str().unwrap_or_default();
+    let links = insert_signers(&mut tx, request_id, &signers).await?;
+
+    write_audit(
+        &mut *tx,
+        actor,
+        "contracts",
+        contract_id,
+        "request_signature",
+        None,
+        Some(&json!({
+            "request_id": request["id"],
+            "document_id": document_id,
+            "signers": signers.iter().map(|s| &s.name).collect::<Vec<_>>(),
+        })),
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(json!({ "request": request, "signers": links }))
+}
+
+/// Signature requests of one contract, or of every contract, newest first,
+/// each with its signers' progress.
+pub async fn list_signature_requests(
+    pool: &PgPool,
+    contract_id: Option<Uuid>,
+) -> Result<Vec<Value>, sqlx::Error> {
+    sqlx::query_scalar(&format!(
+        "SELECT {REQUEST_JSON} FROM admin_signature_requests r \
+         WHERE $1::uuid IS NULL OR r.contract_id = $1 ORDER BY r.created_at DESC"
+    ))
+    .bind(contract_id)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_signature_request(pool: &PgPool, id: Uuid) -> Result<Value, AdminError> {
+    sqlx::query_scalar(&format!(
+        "SELECT {REQUEST_JSON} FROM admin_signature_requests r WHERE r.id = $1"
+    ))
+    .bind(id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AdminError::NotFound("signature request".to_string()))
+}
+
+/// Withdraw a pending request; its links stop working.
+pub async fn cancel_signature_request(
+    pool: &PgPool,
+    actor: &Actor,
+    id: Uuid,
+) -> Result<Value, AdminError> {
+    let mut tx = pool.begin().await?;
+    let cancelled: Option<Uuid> = sqlx::query_scalar(
+        "UPDATE admin_signature_requests SET status = 'cancelled' \
+         WHERE id = $1 AND status = 'pending' RETURNING contract_id",
+    )
+    .bind(id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some(contract_id) = cancelled else {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM admin_signature_requests WHERE id = $1)",
+        )
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+        return Err(if exists {
+            AdminError::Conflict("Only a pending request can be cancelled".to_string())
+        } else {
+            AdminError::NotFound("signature request".to_string())
+        });
+    };
+    write_audit(
+        &mut *tx,
+        actor,
+        "contracts",
+        &contract_id.to_string(),
+        "cancel_signature",
+        None,
+        Some(&json!({ "request_id": id })),
+    )
+    .await?;
+    tx.commit().await?;
+    get_signature_request(pool, id).await
+}
+
+/// A request with its signers, without tokens or signature images.
+const REQUEST_JSON: &str = "json_build_object(\
+    'id', r.id, 'contract_id', r.contract_id, 'document_id', r.document_id, \
+    'document_hash', r.document_hash, 'status', r.status, \
+    'signed_document_id', r.signed_document_id, 'signed_hash', r.signed_hash, \
+    'created_by', r.created_by, \
+    'expires_at', r.expires_at, 'created_at', r.created_at, 'completed_at', r.completed_at, \
+    'signers', (SELECT COALESCE(json_agg(json_build_object(\
+        'id', s.id, 'role', s.role, 'name', s.name, 'email', s.email, 'status', s.status, \
+        'method', s.method, 'signed_at', s.signed_at, 'ip', s.ip) ORDER BY s.role DESC), '[]') \
+        FROM admin_signers s WHERE s.request_id = r.id))";
+
+/// Store the signers with a hash of their tokens, returning each with the
+/// link built from the token itself.
+async fn insert_signers(
+    tx: &mut Transaction<'_, Postgres>,
+    request_id: &str,
+    signers: &[SignerInput],
+) -> Result<Vec<Value>, sqlx::Error> {
+    let mut links = Vec::with_capacity(signers.len());
+    for signer in signers {
+        let token = new_token();
+        let mut stored: Value = sqlx::query_scalar(
+            "INSERT INTO admin_signers (request_id, role, name, email, token_hash) \
+             VALUES ($1::uuid, $2, $3, $4, $5) \
+             RETURNING json_build_object('id', id, 'role', role, 'name', name, 'email', email, \
+                'status', status)",
+        )
+        .bind(request_id)
+        .bind(&signer.role)
+        .bind(&signer.name)
+        .bind(&signer.email)
+        .bind(sha256_hex(token.as_bytes()))
+        .fetch_one(&mut **tx)
+        .await?;
+        stored["link"] = json!(format!("/admin/sign/?token={token}"));
+        links.push(stored);
+    }
+    Ok(links)
+}
+
+fn validate_signers(signers: &[SignerInput]) -> Result<Vec<SignerInput>, AdminError> {
+    if signers.is_empty() {
+        return Err(AdminError::BadRequest(
+            "At least one signer is required".to_string(),
+        ));
+    }
+    signers
+        .iter()
+        .map(|s| {
+            if !SIGNER_ROLES.contains(&s.role.as_str()) {
+                return Err(AdminError::BadRequest(format!(
+                    "Signer role must be one of: {}",
+                    SIGNER_ROLES.join(", ")
+                )));
+            }
+            if s.name.trim().is_empty() {
+                return Err(AdminError::BadRequest(
+                    "Every signer needs a name".to_string(),
+                ));
+            }
+            Ok(SignerInput {
+                role: s.role.clone(),
+                name: s.name.trim().to_string(),
+                email: s.email.trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// The contract's tenant (by name) and the owner of its property.
+async fn contract_parties(
+    tx: &mut Transaction<'_, Postgres>,
+    document: &Value,
+) -> Result<Vec<SignerInput>, AdminError> {
+    let tenant_name = document["tenant_name"].as_str().unwrap_or_default();
+    let property_name = document["property_name"].as_str().unwrap_or_default();
+    let tenant: Option<(String, String)> = sqlx::query_as(
+        "SELECT name, email FROM admin_tenants WHERE name = $1 AND deleted_at IS NULL \
+         ORDER BY created_at DESC LIMIT 1",
+    )
+    .bind(tenant_name)
+    .fetch_optional(&mut **tx)
+    .await?;
+    let owner: Option<(String, String)> = sqlx::query_as(
+        "SELECT name, email FROM admin_owners WHERE property_name = $1 AND deleted_at IS NULL \
+         ORDER BY created_at DESC LIMIT 1",
+    )
+    .bind(property_name)
+    .fetch_optional(&mut **tx)
+    .await?;
+
+    let (Some((tenant_name, tenant_email)), Some((owner_name, owner_email))) = (tenant, owner)
+    else {
+        return Err(AdminError::BadRequest(
+            "The contract's tenant or the property's owner could not be found; list the signers"
+                .to_string(),
+        ));
+    };
+    Ok(vec![
+        SignerInput {
+            role: "tenant".to_string(),
+            name: tenant_name,
+            email: tenant_email,
+        },
+        SignerInput {
+            role: "owner".to_string(),
+            name: owner_name,
+            email: owner_email,
+        },
+    ])
+}
+
+fn new_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+// ── Signing (public, by token) ──────────────────────────────────────────
+
+/// What the signing page shows: who is signing what, and the hash of the
+/// document to echo back when signing.
+pub async fn signing_view(pool: &PgPool, token: &str) -> Result<Value, AdminError> {
+    sqlx::query_scalar(
+        "SELECT json_build_object(\
+            'signer', json_build_object('name', s.name, 'role', s.role, 'status', s.status, \
+                'signed_at', s.signed_at), \
+            'request', json_build_object('status', r.status, 'expires_at', r.expires_at, \
+                'expired', r.expires_at < NOW()), \
+            'document', json_build_object('name', d.name, 'size', d.size, \
+                'sha256', r.document_hash), \
+            'contract', json_build_object('contract_ref', c.contract_ref, \
+                'property_name', c.property_name)) \
+         FROM admin_signers s \
+         JOIN admin_signature_requests r ON r.id = s.request_id \
+         JOIN admin_contract_documents d ON d.id = r.document_id \
+         JOIN admin_contracts c ON c.id = r.contract_id \
+         WHERE s.token_hash = $1",
+    )
+    .bind(sha256_hex(token.as_bytes()))
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AdminError::NotFound("signing link".to_string()))
+}
+
+/// The PDF a signer is asked to sign, while the request is open.
+pub async fn signing_document(pool: &PgPool, token: &str) -> Result<(String, Vec<u8>), AdminError> {
+    let row: Option<(String, Option<Vec<u8>>, String, bool)> = sqlx::query_as(
+        "SELECT d.original_file, d.content, r.status, r.expires_at < NOW() \
+         FROM admin_signers s \
+         JOIN admin_signature_requests r ON r.id = s.request_id \
+         JOIN admin_contract_documents d ON d.id = r.document_id \
+         WHERE s.token_hash = $1",
+    )
+    .bind(sha256_hex(token.as_bytes()))
+    .fetch_optional(pool)
+    .await?;
+    match row {
+        None | Some((_, None, _, _)) => Err(AdminError::NotFound("signing link".to_string())),
+        Some((_, _, status, expired))
+            if status == "cancelled" || (status == "pending" && expired) =>
+        {
+            Err(AdminError::Forbidden(
+                "This signing link is no longer valid".to_string(),
+            ))
+        }
+        Some((name, Some(content), _, _)) => Ok((name, content)),
+    }
+}
+
+/// Record a signature. The link works once: a signer who has signed cannot
+/// sign again. When this is the last signature, the signed document is built
+/// and attached and the contract becomes `Active`. The signer's IP is the
+/// trusted client address of [`Actor`].
+pub async fn sign(
+    pool: &PgPool,
+    actor: &Actor,
+    token: &str,
+    body: &SignBody,
+) -> Result<Value, AdminError> {
+    let mut tx = pool.begin().await?;
+    let signer: Option<(Uuid, Uuid, String, String, String, bool)> = sqlx::query_as(
+        "SELECT s.id, r.id, s.status, r.status, r.document_hash, r.expires_at < NOW() \
+         FROM admin_signers s JOIN admin_signature_requests r ON r.id = s.request_id \
+         WHERE s.token_hash = $1 FOR UPDATE OF s, r",
+    )
+    .bind(sha256_hex(token.as_bytes()))
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some((signer_id, request_id, signer_status, request_status, document_hash, expired)) =
+        signer
+    else {
+        return Err(AdminError::NotFound("signing link".to_string()));
+    };
+    if signer_status == "signed" {
+        return Err(AdminError::Conflict(
+            "This document has already been signed with this link".to_string(),
+        ));
+    }
+    if request_status != "pending" || expired {
+        return Err(AdminError::Forbidden(
+            "This signing link is no longer valid".to_string(),
+        ));
+    }
+    if body.document_hash.trim() != document_hash {
+        return Err(AdminError::Conflict(
+            "The document has changed since it was opened; reload it before signing".to_string(),
+        ));
+    }
+    let signature = validate_signature(body)?;
+
+    sqlx::query(
+        "UPDATE admin_signers SET status = 'signed', method = $2, signature = $3, \
+         document_hash = $4, signed_at = NOW(), ip = $5, user_agent = $6 WHERE id = $1",
+    )
+    .bind(signer_id)
+    .bind(&body.method)
+    .bind(&signature)
+    .bind(&document_hash)
+    .bind(actor.ip.as_deref())
+    .bind(actor.user_agent.as_deref())
+    .execute(&mut *tx)
+    .await?;
+
+    let remaining: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM admin_signers WHERE request_id = $1 AND status = 'pending'",
+    )
+    .bind(request_id)
+    .fetch_one(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    // The signature stands even if the signed copy cannot be built now;
+    // POST /signature-requests/{id}/complete builds it later
+    let signed_document_id = if remaining == 0 {
+        complete_signature_request(pool, actor, request_id)
+            .await
+            .inspect_err(|e| tracing::error!(error = %e, %request_id, "Signed copy not built"))
+            .ok()
+    } else {
+        None
+    };
+
+    Ok(json!({
+        "status": "signed",
+        "completed": signed_document_id.is_some(),
+        "remaining": remaining,
+        "signed_document_id": signed_document_id,
+    }))
+}
+
+/// The stored form of a signature: the trimmed typed name, or the data URL
+/// of a drawn signature once it is known to hold a PNG of sensible size.
+fn validate_signature(body: &SignBody) -> Result<String, AdminError> {
+    let signature = body.signature.trim();
+    match body.method.as_str() {
+        "typed" => {
+            let length = signature.chars().count();
+            if length == 0 || length > 200 {
+                return Err(AdminError::BadRequest(
+                    "A typed signature must be between 1 and 200 characters".to_string(),
+                ));
+            }
+        }
+        "drawn" => {
+            decode_png(signature).ok_or_else(|| {
+                AdminError::BadRequest(format!(
+                    "A drawn signature must be a PNG data URL of at most {} KB",
+                    MAX_SIGNATURE_BYTES / 1024
+                ))
+            })?;
+        }
+        _ => {
+            return Err(AdminError::BadRequest(
+                "method must be drawn or typed".to_string(),
+            ))
+        }
+    }
+    Ok(signature.to_string())
+}
+
+fn decode_png(data_url: &str) -> Option<Vec<u8>> {
+    let png = STANDARD.decode(data_url.strip_prefix(PNG_DATA_URL)?).ok()?;
+    (png.len() <= MAX_SIGNATURE_BYTES && png.starts_with(PNG_MAGIC)).then_some(png)
+}
+
+type CompletionRow = (
+    Uuid,            // contract_id
+    String,          // document name
+    String,          // document_hash
+    Option<Vec<u8>>, // document content
+    String,          // status
+    Option<Uuid>,    // signed_document_id
+    i64,             // signers still pending
+);
+
+/// Build the signed copy of a request every signer has signed, attach it to
+/// the contract, close the request and make the contract `Active`. Returns
+/// the id of the signed document, or of the one already built.
+///
+/// The PDF is built before any row is locked; the request is then locked and
+/// completed only if no one else completed it meanwhile.
+pub async fn complete_signature_request(
+    pool: &PgPool,
+    actor: &Actor,
+    request_id: Uuid,
+) -> Result<Uuid, AdminError> {
+    let (contract_id, document_name, document_hash, original, status, signed_id, pending): CompletionRow =
+        sqlx::query_as(
+            "SELECT r.contract_id, d.name, r.document_hash, d.content, r.status, \
+                r.signed_document_id, \
+                (SELECT COUNT(*) FROM admin_signers s \
+                 WHERE s.request_id = r.id AND s.status = 'pending') \
+             FROM admin_signature_requests r \
+             JOIN admin_contract_documents d ON d.id = r.document_id WHERE r.id = $1",
+        )
+        .bind(request_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AdminError::NotFound("signature request".to_string()))?;
+    if let Some(signed_id) = signed_id {
+        return Ok(signed_id);
+    }
+    if status != "pending" || pending > 0 {
+        return Err(AdminError::Conflict(
+            "Only a pending request every signer has signed can be completed".to_string(),
+        ));
+    }
+    let original = original
+        .filter(|o| sha256_hex(o) == document_hash)
+        .ok_or_else(|| {
+            AdminError::Conflict("The document sent for signature is no longer stored".to_string())
+        })?;
+
+    let evidence = SignatureEvidence {
+        request_id: request_id.to_string(),
+        document_name: document_name.clone(),
+        document_hash,
+        completed_at: Utc::now(),
+        signers: signer_evidence(pool, request_id).await?,
+    };
+    let note = format!("{document_name} \u{2022} signed");
+    // Run PDF generation on blocking thread pool to avoid stalling async runtime
+    let pdf = tokio::task::spawn_blocking(move || {
+        PdfService::stamp_signature_evidence(&original, &evidence, &note)
+    })
+    .await
+    .map_err(|e| AdminError::PdfGeneration(format!("PDF task failed: {e}")))??;
+
+    let mut tx = pool.begin().await?;
+    let (status, signed_id): (String, Option<Uuid>) = sqlx::query_as(
+        "SELECT status, signed_document_id FROM admin_signature_requests \
+         WHERE id = $1 FOR UPDATE",
+    )
+    .bind(request_id)
+    .fetch_one(&mut *tx)
+    .await?;
+    if let Some(signed_id) = signed_id {
+        return Ok(signed_id);
+    }
+    if status != "pending" {
+        return Err(AdminError::Conflict(
+            "The request was cancelled before it could be completed".to_string(),
+        ));
+    }
+
+    let name = format!("{document_name} (signed)");
+    let signed = attach_document(
+        &mut tx,
+        contract_id,
+        &NewDocument {
+            name: &name,
+            doc_type: "signed",
+            file_name: &format!("{}.pdf", file_stem(&name)),
+            content: &pdf,
+            template_id: None,
+            blocks: None,
+        },
+    )
+    .await?;
+    sqlx::query(
+        "UPDATE admin_signature_requests SET status = 'completed', completed_at = NOW(), \
+         signed_document_id = $2, signed_hash = $3 WHERE id = $1",
+    )
+    .bind(request_id)
+    .bind(signed.id)
+    .bind(sha256_hex(&pdf))
+    .execute(&mut *tx)
+    .await?;
+
+    contract_signed(&mut tx, actor, contract_id, request_id, signed.id).await?;
+    tx.commit().await?;
+    Ok(signed.id)
+}
+
+/// Audit the signature and make the contract `Active`.
+async fn contract_signed(
+    tx: &mut Transaction<'_, Postgres>,
+    actor: &Actor,
+    contract_id: Uuid,
+    request_id: Uuid,
+    document_id: Uuid,
+) -> Result<(), AdminError> {
+    write_audit(
+        &mut **tx,
+        actor,
+        "contracts",
+        &contract_id.to_string(),
+        "sign",
+        None,
+        Some(&json!({ "request_id": request_id, "document_id": document_id })),
+    )
+    .await?;
+
+    let previous: String =
+        sqlx::query_scalar("SELECT status FROM admin_contracts WHERE id = $1 FOR UPDATE")
+            .bind(contract_id)
+            .fetch_one(&mut **tx)
+            .await?;
+    if previous == "Active" {
+        return Ok(());
+    }
+    sqlx::query("UPDATE admin_contracts SET status = 'Active', updated_at = NOW() WHERE id = $1")
+        .bind(contract_id)
+        .execute(&mut **tx)
+        .await?;
+    write_audit(
+        &mut **tx,
+        actor,
+        "contracts",
+        &contract_id.to_string(),
+        "update",
+        Some(&json!({ "status": previous })),
+        Some(&json!({ "status": "Active" })),
+    )
+    .await?;
+    Ok(())
+}
+
+async fn signer_evidence(
+    pool: &PgPool,
+    request_id: Uuid,
+) -> Result<Vec<SignerEvidence>, sqlx::Error> {
+    type Row = (
+        String,
+        String,
+        String,
+        String,
+        String,
+        String,
+        DateTime<Utc>,
+        Option<String>,
+        Option<String>,
+    );
+    let rows: Vec<Row> = sqlx::query_as(
+        "SELECT role, name, email, method, signature, document_hash, signed_at, ip, user_agent \
+         FROM admin_signers WHERE request_id = $1 ORDER BY role DESC, signed_at",
+    )
+    .bind(request_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(
+            |(role, name, email, method, signature, document_hash, signed_at, ip, user_agent)| {
+                let signature = if method == "drawn" {
+                    SignatureMark::Drawn(decode_png(&signature).unwrap_or_default())
+                } else {
+                    SignatureMark::Typed(signature)
+                };
+                SignerEvidence {
+                    role,
+                    name,
+                    email,
+                    signed_at,
+                    ip: ip.unwrap_or_else(|| "unknown".to_string()),
+                    user_agent: user_agent.unwrap_or_else(|| "unknown".to_string()),
+                    document_hash,
+                    signature,
+                }
+            },
+        )
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signer(role: &str, name: &str, email: &str) -> SignerInput {
+        SignerInput {
+            role: role.to_string(),
+            name: name.to_string(),
+            email: email.to_string(),
+        }
+    }
+
+    fn sign_body(method: &str, signature: &str) -> SignBody {
+        SignBody {
+            method: method.to_string(),
+            signature: signature.to_string(),
+            document_hash: String::new(),
+        }
+    }
+
+    fn png_data_url(len: usize) -> String {
+        let mut png = PNG_MAGIC.to_vec();
+        png.resize(len, 0);
+        format!("{PNG_DATA_URL}{}", STANDARD.encode(png))
+    }
+
+    #[test]
+    fn sha256_hex_is_lowercase_hex() {
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn tokens_are_random_url_safe_and_256_bits() {
+        let (a, b) = (new_token(), new_token());
+        assert_ne!(a, b);
+        assert_eq!(URL_SAFE_NO_PAD.decode(&a).unwrap().len(), 32);
+        assert!(a
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+    }
+
+    #[test]
+    fn signers_are_trimmed() {
+        let signers =
+            validate_signers(&[signer("tenant", "  Ana García ", " ana@example.com ")]).unwrap();
+        assert_eq!(signers[0].name, "Ana García");
+        assert_eq!(signers[0].email, "ana@example.com");
+    }
+
+    #[test]
+    fn signers_need_a_known_role_and_a_name() {
+        assert!(validate_signers(&[]).is_err());
+        assert!(validate_signers(&[signer("witness", "Ana", "")]).is_err());
+        assert!(
+            validate_signers(&[signer("owner", "Luis", ""), signer("tenant", " ", "")]).is_err()
+        );
+    }
+
+    #[test]
+    fn typed_signature_is_the_trimmed_name() {
+        assert_eq!(
+            validate_signature(&sign_body("typed", " Ana García ")).unwrap(),
+            "Ana García"
+        );
+        assert!(validate_signature(&sign_body("typed", "   ")).is_err());
+        assert!(validate_signature(&sign_body("typed", &"x".repeat(201))).is_err());
+        assert!(validate_signature(&sign_body("typed", &"ñ".repeat(200))).is_ok());
+    }
+
+    #[test]
+    fn drawn_signature_must_be_a_png_data_url() {
+        assert!(validate_signature(&sign_body("drawn", &png_data_url(64))).is_ok());
+        assert!(validate_signature(&sign_body("drawn", "data:image/jpeg;base64,AAAA")).is_err());
+        let not_png = format!("{PNG_DATA_URL}{}", STANDARD.encode(b"GIF89a"));
+        assert!(validate_signature(&sign_body("drawn", &not_png)).is_err());
+        assert!(validate_signature(&sign_body("drawn", "data:image/png;base64,%%")).is_err());
+    }
+
+    #[test]
+    fn drawn_signature_size_is_capped() {
+        assert!(decode_png(&png_data_url(MAX_SIGNATURE_BYTES)).is_some());
+        assert!(decode_png(&png_data_url(MAX_SIGNATURE_BYTES + 1)).is_none());
+    }
+
+    #[test]
+    fn unknown_method_is_rejected() {
+        assert!(validate_signature(&sign_body("stamped", "Ana")).is_err());
+    }
+}
@@ -0,0 +1,349 @@
+use chrono::{Datelike, Local, NaiveDate};
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AdminError;
+use crate::services::pii;
+
+/// Inclusive date range an owner statement covers.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct StatementPeriod {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+impl StatementPeriod {
+    /// Resolve an optional `from`/`to` pair, defaulting to the previous calendar month.
+    pub fn resolve(from: Option<NaiveDate>, to: Option<NaiveDate>) -> Result<Self, AdminError> {
+        let today = Local::now().date_naive();
+        let month_start = NaiveDate::from_ymd_opt(today.year(), today.month(), 1)
+            .expect("first day of the current month is always valid");
+        let prev_end = month_start
+            .pred_opt()
+            .expect("the day before a month start is always valid");
+        let prev_start = NaiveDate::from_ymd_opt(prev_end.year(), prev_end.month(), 1)
+            .expect("first day of the previous month is always valid");
+
+        let period = Self {
+            from: from.unwrap_or(prev_start),
+            to: to.unwrap_or(prev_end),
+        };
+        if period.from > period.to {
+            return Err(AdminError::BadRequest(
+                "'from' must not be after 'to'".to_string(),
+            ));
+        }
+        Ok(period)
+    }
+}
+
+/// One invoice row on an owner statement.
+#[derive(Debug, Clone, Serialize)]
+pub struct StatementLine {
+    pub invoice_id: Uuid,
+    pub reference: String,
+    pub description: String,
+    pub property_name: String,
+    /// Tenant for income lines, supplier for expense lines
+    pub counterparty: String,
+    pub status: String,
+    pub invoice_date: Option<NaiveDate>,
+    pub amount: f64,
+    pub paid: f64,
+    /// Amount already settled through owner payouts
+    pub paid_out: f64,
+}
+
+/// Owner settlement (liquidación) for a period: rent collected, expenses,
+/// management fee and the net amount to transfer to the owner.
+#[derive(Debug, Clone, Serialize)]
+pub struct OwnerStatement {
+    pub owner_id: Uuid,
+    pub owner_name: String,
+    pub owner_tax_id: String,
+    pub owner_address: String,
+    pub owner_bank_account: String,
+    pub period: StatementPeriod,
+    pub currency: String,
+    pub management_fee_pct: f64,
+    pub income: Vec<StatementLine>,
+    pub expenses: Vec<StatementLine>,
+    pub rent_invoiced: f64,
+    pub rent_collected: f64,
+    pub expenses_total: f64,
+    pub management_fee: f64,
+    pub net_transfer: f64,
+}
+
+impl OwnerStatement {
+    /// The statement with the owner's tax id and IBAN masked, for callers
+    /// without the `sensitive` scope.
+    #[must_use]
+    pub fn masked(mut self) -> Self {
+        self.owner_tax_id = pii::mask(&self.owner_tax_id);
+        self.owner_bank_account = pii::mask(&self.owner_bank_account);
+        self
+    }
+}
+
+type OwnerRow = (Uuid, String, String, String, String, f64);
+
+type InvoiceRow = (
+    Uuid,              // id
+    String,            // reference
+    String,            // description
+    String,            // property_name
+    String,            // payer
+    String,            // payee
+    String,            // status
+    String,            // type
+    String,            // currency
+    f64,               // amount
+    f64,               // paid
+    Option<NaiveDate>, // invoice_date
+    f64,               // paid_out
+);
+
+const OWNER_SELECT: &str = "SELECT id, name, tax_id, address, bank_account, \
+     management_fee_pct::float8 FROM admin_owners WHERE deleted_at IS NULL";
+
+/// Build the statement for a single owner. Returns `None` when the owner does not exist.
+///
+/// Income lines are rent invoices whose payee is the owner; expense lines are
+/// invoices the owner pays (payer). `fee_pct` overrides the owner's own
+/// `management_fee_pct` when given.
+pub async fn build_owner_statement(
+    pool: &PgPool,
+    owner_id: Uuid,
+    period: StatementPeriod,
+    fee_pct: Option<f64>,
+) -> Result<Option<OwnerStatement>, AdminError> {
+    let owner = sqlx::query_as::<_, OwnerRow>(&format!("{OWNER_SELECT} AND id = $1"))
+        .bind(owner_id)
+        .fetch_optional(pool)
+        .await?;
+
+    match owner {
+        Some(owner) => Ok(Some(statement_for(pool, owner, period, fee_pct).await?)),
+        None => Ok(None),
+    }
+}
+
+/// Build statements for every owner, ordered by name.
+pub async fn build_all_owner_statements(
+    pool: &PgPool,
+    period: StatementPeriod,
+    fee_pct: Option<f64>,
+) -> Result<Vec<OwnerStatement>, AdminError> {
+    let owners = sqlx::query_as::<_, OwnerRow>(&format!("{OWNER_SELECT} ORDER BY name"))
+        .fetch_all(pool)
+        .await?;
+
+    let mut statements = Vec::with_capacity(owners.len());
+    for owner in owners {
+        statements.push(statement_for(pool, owner, period, fee_pct).await?);
+    }
+    Ok(statements)
+}
+
+async fn statement_for(
+    pool: &PgPool,
+    owner: OwnerRow,
+    period: StatementPeriod,
+    fee_pct: Option<f64>,
+) -> Result<OwnerStatement, AdminError> {
+    let (owner_id, owner_name, owner_tax_id, owner_address, owner_bank_account, owner_fee) = owner;
+
+    let rows = sqlx::query_as::<_, InvoiceRow>(
+        "SELECT id, reference, description, property_name, payer, payee, status, type, \
+         currency, amount::float8, paid::float8, invoice_date, \
+         (SELECT COALESCE(SUM(pi.amount), 0)::float8 FROM admin_payout_items pi \
+          WHERE pi.invoice_id = admin_invoices.id) \
+         FROM admin_invoices \
+         WHERE deleted_at IS NULL AND invoice_date BETWEEN $2 AND $3 \
+         AND ((type = 'income' AND payee = $1) OR (type = 'expense' AND payer = $1)) \
+         ORDER BY invoice_date, reference",
+    )
+    .bind(&owner_name)
+    .bind(period.from)
+    .bind(period.to)
+    .fetch_all(pool)
+    .await?;
+
+    let currency = rows
+        .first()
+        .map_or_else(|| "GBP".to_string(), |r| r.8.clone());
+
+    let mut income = Vec::new();
+    let mut expenses = Vec::new();
+    for row in rows {
+        let is_income = row.7 == "income";
+        let line = StatementLine {
+            invoice_id: row.0,
+            reference: row.1,
+            description: row.2,
+            property_name: row.3,
+            counterparty: if is_income { row.4 } else { row.5 },
+            status: row.6,
+            invoice_date: row.11,
+            amount: row.9,
+            paid: row.10,
+            paid_out: row.12,
+        };
+        if is_income {
+            income.push(line);
+        } else {
+            expenses.push(line);
+        }
+    }
+
+    let management_fee_pct = fee_pct.unwrap_or(owner_fee);
+    let totals = Totals::settle(&income, &expenses, management_fee_pct);
+
+    Ok(OwnerStatement {
+        owner_id,
+        owner_name,
+        owner_tax_id: pii::open("tax_id", &owner_tax_id)?,
+        owner_address,
+        owner_bank_account: pii::open("bank_account", &owner_bank_account)?,
+        period,
+        currency,
+        management_fee_pct,
+        income,
+        expenses,
+        rent_invoiced: totals.rent_invoiced,
+        rent_collected: totals.rent_collected,
+        expenses_total: totals.expenses_total,
+        management_fee: totals.management_fee,
+        net_transfer: totals.net_transfer,
+    })
+}
+
+/// Statement totals, each rounded to cents.
+#[derive(Debug, PartialEq)]
+struct Totals {
+    rent_invoiced: f64,
+    rent_collected: f64,
+    expenses_total: f64,
+    management_fee: f64,
+    net_transfer: f64,
+}
+
+impl Totals {
+    /// The management fee is charged on rent actually collected, not on rent
+    /// invoiced; the owner receives what is left after expenses and the fee.
+    fn settle(income: &[StatementLine], expenses: &[StatementLine], fee_pct: f64) -> Self {
+        let rent_invoiced = round2(income.iter().map(|l| l.amount).sum());
+        let rent_collected = round2(income.iter().map(|l| l.paid).sum());
+        let expenses_total = round2(expenses.iter().map(|l| l.amount).sum());
+        let management_fee = round2(rent_collected * fee_pct / 100.0);
+        Self {
+            rent_invoiced,
+            rent_collected,
+            expenses_total,
+            management_fee,
+            net_transfer: round2(rent_collected - expenses_total - management_fee),
+        }
+    }
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(amount: f64, paid: f64) -> StatementLine {
+        StatementLine {
+            invoice_id: Uuid::nil(),
+            reference: "INV-1".to_string(),
+            description: "Rent".to_string(),
+            property_name: "Flat 1".to_string(),
+            counterparty: "Tenant".to_string(),
+            status: "paid".to_string(),
+            invoice_date: NaiveDate::from_ymd_opt(2025, 3, 1),
+            amount,
+            paid,
+            paid_out: 0.0,
+        }
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn round2_rounds_to_cents() {
+        assert!((round2(10.006) - 10.01).abs() < f64::EPSILON);
+        assert!((round2(10.004) - 10.0).abs() < f64::EPSILON);
+        assert!((round2(-3.456) - -3.46).abs() < f64::EPSILON);
+        assert!((round2(0.1 + 0.2) - 0.3).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn fee_is_charged_on_rent_collected() {
+        let income = [line(1000.0, 1000.0), line(850.0, 400.0)];
+        let expenses = [line(120.5, 0.0), line(79.5, 79.5)];
+        let totals = Totals::settle(&income, &expenses, 8.0);
+        assert_eq!(
+            totals,
+            Totals {
+                rent_invoiced: 1850.0,
+                rent_collected: 1400.0,
+                expenses_total: 200.0,
+                management_fee: 112.0,
+                net_transfer: 1088.0,
+            }
+        );
+    }
+
+    #[test]
+    fn fee_and_net_are_rounded_to_cents() {
+        let income = [line(733.33, 733.33)];
+        let totals = Totals::settle(&income, &[], 7.5);
+        assert!((totals.management_fee - 55.0).abs() < f64::EPSILON);
+        assert!((totals.net_transfer - 678.33).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn net_transfer_goes_negative_when_expenses_exceed_rent() {
+        let totals = Totals::settle(&[line(500.0, 500.0)], &[line(700.0, 0.0)], 10.0);
+        assert!((totals.management_fee - 50.0).abs() < f64::EPSILON);
+        assert!((totals.net_transfer - -250.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn empty_statement_is_all_zero() {
+        let totals = Totals::settle(&[], &[], 10.0);
+        assert!(totals.rent_invoiced.abs() < f64::EPSILON);
+        assert!(totals.management_fee.abs() < f64::EPSILON);
+        assert!(totals.net_transfer.abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn resolve_keeps_explicit_bounds() {
+        let period =
+            StatementPeriod::resolve(Some(date(2025, 1, 1)), Some(date(2025, 3, 31))).unwrap();
+        assert_eq!(period.from, date(2025, 1, 1));
+        assert_eq!(period.to, date(2025, 3, 31));
+    }
+
+    #[test]
+    fn resolve_defaults_to_the_previous_month() {
+        let period = StatementPeriod::resolve(None, None).unwrap();
+        let today = Local::now().date_naive();
+        assert_eq!(period.from.day(), 1);
+        assert_eq!(period.to.succ_opt().unwrap().day(), 1);
+        assert_eq!(period.to.succ_opt().unwrap().month(), today.month());
+        assert_eq!(period.from.month(), period.to.month());
+    }
+
+    #[test]
+    fn resolve_rejects_an_inverted_range() {
+        let err = StatementPeriod::resolve(Some(date(2025, 4, 1)), Some(date(2025, 3, 31)));
+        assert!(matches!(err, Err(AdminError::BadRequest(_))));
+    }
+}
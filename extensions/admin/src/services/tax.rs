@@ -0,0 +1,426 @@
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, Local, NaiveDate};
+use serde::Serialize;
+use sqlx::PgPool;
+
+use crate::error::AdminError;
+use crate::services::pii;
+
+/// Default IRPF withholding rate on urban property rent.
+pub const DEFAULT_WITHHOLDING_RATE: f64 = 19.0;
+
+/// Modelo 347 only lists counterparties whose annual total exceeds this amount.
+pub const MODELO_347_THRESHOLD: f64 = 3005.06;
+
+/// Fiscal year, optionally narrowed to a quarter (1-4).
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct TaxPeriod {
+    pub year: i32,
+    pub quarter: Option<u32>,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+impl TaxPeriod {
+    /// A full fiscal year, defaulting to the previous one.
+    pub fn year(year: Option<i32>) -> Result<Self, AdminError> {
+        let year = year.unwrap_or_else(|| Local::now().year() - 1);
+        Self::build(year, None)
+    }
+
+    /// A quarter, defaulting to the previous calendar quarter.
+    pub fn quarter(year: Option<i32>, quarter: Option<u32>) -> Result<Self, AdminError> {
+        let today = Local::now().date_naive();
+        let current = (today.month() - 1) / 3 + 1;
+        let (default_year, default_quarter) = if current == 1 {
+            (today.year() - 1, 4)
+        } else {
+            (today.year(), current - 1)
+        };
+        Self::build(
+            year.unwrap_or(default_year),
+            Some(quarter.unwrap_or(default_quarter)),
+        )
+    }
+
+    fn build(year: i32, quarter: Option<u32>) -> Result<Self, AdminError> {
+        if !(2000..=2100).contains(&year) {
+            return Err(AdminError::BadRequest(
+                "year must be between 2000 and 2100".to_string(),
+            ));
+        }
+        let (first_month, last_month) = match quarter {
+            None => (1, 12),
+            Some(q @ 1..=4) => (q * 3 - 2, q * 3),
+            Some(_) => {
+                return Err(AdminError::BadRequest(
+                    "quarter must be between 1 and 4".to_string(),
+                ))
+            }
+        };
+        let from = NaiveDate::from_ymd_opt(year, first_month, 1)
+            .expect("first day of a quarter is always valid");
+        let to = if last_month == 12 {
+            NaiveDate::from_ymd_opt(year, 12, 31)
+        } else {
+            NaiveDate::from_ymd_opt(year, last_month + 1, 1).and_then(|d| d.pred_opt())
+        }
+        .expect("last day of a quarter is always valid");
+
+        Ok(Self {
+            year,
+            quarter,
+            from,
+            to,
+        })
+    }
+
+    /// AEAT period code: `1T`..`4T` for quarters, `0A` for the year.
+    pub fn code(&self) -> String {
+        self.quarter
+            .map_or_else(|| "0A".to_string(), |q| format!("{q}T"))
+    }
+}
+
+/// Party on a tax return, identified by its tax ID (NIF/NIE/CIF).
+#[derive(Debug, Clone, Serialize)]
+pub struct TaxParty {
+    pub tax_id: String,
+    pub name: String,
+    pub phone: String,
+}
+
+/// Rent paid by a withholding tenant to one owner for one property.
+#[derive(Debug, Clone, Serialize)]
+pub struct WithholdingLine {
+    pub perceptor_tax_id: String,
+    pub perceptor_name: String,
+    /// Two-digit province code, from the owner's postal code
+    pub province_code: String,
+    pub property_name: String,
+    pub property_address: String,
+    pub base: f64,
+    pub rate: f64,
+    pub withholding: f64,
+}
+
+/// Withholding return (Modelo 115 for a quarter, Modelo 180 for the year)
+/// of one tenant acting as withholding agent.
+#[derive(Debug, Clone, Serialize)]
+pub struct WithholdingReturn {
+    pub declarant: TaxParty,
+    pub period: TaxPeriod,
+    pub num_perceptors: usize,
+    pub total_base: f64,
+    pub total_withholding: f64,
+    pub lines: Vec<WithholdingLine>,
+}
+
+/// Counterparty on an owner's Modelo 347.
+#[derive(Debug, Clone, Serialize)]
+pub struct ThirdPartyLine {
+    pub tax_id: String,
+    pub name: String,
+    pub province_code: String,
+    /// `A` purchases (suppliers), `B` sales (tenants)
+    pub operation_key: char,
+    pub annual_amount: f64,
+    pub quarterly_amounts: [f64; 4],
+}
+
+/// Modelo 347 return of one owner.
+#[derive(Debug, Clone, Serialize)]
+pub struct ThirdPartyReturn {
+    pub declarant: TaxParty,
+    pub period: TaxPeriod,
+    pub num_counterparties: usize,
+    pub total_amount: f64,
+    pub lines: Vec<ThirdPartyLine>,
+}
+
+impl TaxParty {
+    fn masked(self) -> Self {
+        Self {
+            tax_id: pii::mask(&self.tax_id),
+            phone: pii::mask(&self.phone),
+            ..self
+        }
+    }
+}
+
+impl WithholdingReturn {
+    /// The return with tax ids and phone numbers masked, for callers
+    /// without the `sensitive` scope.
+    #[must_use]
+    pub fn masked(mut self) -> Self {
+        self.declarant = self.declarant.masked();
+        for line in &mut self.lines {
+            line.perceptor_tax_id = pii::mask(&line.perceptor_tax_id);
+        }
+        self
+    }
+}
+
+impl ThirdPartyReturn {
+    /// The return with tax ids and phone numbers masked, for callers
+    /// without the `sensitive` scope.
+    #[must_use]
+    pub fn masked(mut self) -> Self {
+        self.declarant = self.declarant.masked();
+        for line in &mut self.lines {
+            line.tax_id = pii::mask(&line.tax_id);
+        }
+        self
+    }
+}
+
+type WithholdingRow = (
+    String, // tenant tax_id
+    String, // tenant name
+    String, // tenant phone
+    String, // owner tax_id
+    String, // owner name
+    String, // owner address
+    String, // property_name
+    String, // property address
+    f64,    // base
+);
+
+/// Rent invoices from withholding tenants in the period, aggregated per
+/// tenant, owner and property. Returns one return per tenant tax ID,
+/// optionally limited to a single declarant.
+///
+/// Tax ids are encrypted, so parties are grouped and filtered on their blind
+/// indexes (or the value itself while it awaits encryption) and decrypted
+/// here.
+pub async fn build_withholding_returns(
+    pool: &PgPool,
+    period: TaxPeriod,
+    rate: f64,
+    declarant: Option<&str>,
+) -> Result<Vec<WithholdingReturn>, AdminError> {
+    let rows = sqlx::query_as::<_, WithholdingRow>(&format!(
+        "SELECT MIN(t.tax_id), MIN(t.name), MIN(t.phone), MIN(o.tax_id), MIN(o.name), \
+             MIN(o.address), i.property_name, COALESCE(MIN(p.address), ''), \
+             SUM(i.amount - i.vat)::float8 \
+         FROM admin_invoices i \
+         JOIN admin_tenants t ON t.name = i.payer AND t.irpf_withholding \
+         JOIN admin_owners o ON o.name = i.payee \
+         LEFT JOIN admin_properties p ON p.property_name = i.property_name \
+         WHERE i.type = 'income' AND i.deleted_at IS NULL \
+             AND i.invoice_date BETWEEN $1 AND $2 \
+             AND {} \
+         GROUP BY {TENANT_KEY}, {OWNER_KEY}, i.property_name \
+         ORDER BY {TENANT_KEY}, MIN(o.name), i.property_name",
+        declarant_filter("t")
+    ))
+    .bind(period.from)
+    .bind(period.to)
+    .bind(declarant_index(declarant)?)
+    .bind(declarant)
+    .fetch_all(pool)
+    .await?;
+
+    let mut returns: Vec<WithholdingReturn> = Vec::new();
+    for mut row in rows {
+        row.0 = pii::open("tax_id", &row.0)?;
+        row.2 = pii::open("phone", &row.2)?;
+        row.3 = pii::open("tax_id", &row.3)?;
+        let line = WithholdingLine {
+            perceptor_tax_id: row.3,
+            perceptor_name: row.4,
+            province_code: province_code(&row.5),
+            property_name: row.6,
+            property_address: row.7,
+            base: round2(row.8),
+            rate,
+            withholding: round2(row.8 * rate / 100.0),
+        };
+        match returns.last_mut() {
+            Some(r) if r.declarant.tax_id == row.0 => r.lines.push(line),
+            _ => returns.push(WithholdingReturn {
+                declarant: TaxParty {
+                    tax_id: row.0,
+                    name: row.1,
+                    phone: row.2,
+                },
+                period,
+                num_perceptors: 0,
+                total_base: 0.0,
+                total_withholding: 0.0,
+                lines: vec![line],
+            }),
+        }
+    }
+
+    for r in &mut returns {
+        let mut perceptors: Vec<&str> = r
+            .lines
+            .iter()
+            .map(|l| l.perceptor_tax_id.as_str())
+            .collect();
+        perceptors.sort_unstable();
+        perceptors.dedup();
+        r.num_perceptors = perceptors.len();
+        r.total_base = round2(r.lines.iter().map(|l| l.base).sum());
+        r.total_withholding = round2(r.lines.iter().map(|l| l.withholding).sum());
+    }
+    returns.sort_by(|a, b| a.declarant.tax_id.cmp(&b.declarant.tax_id));
+    Ok(returns)
+}
+
+/// What groups a tenant or owner in the returns: the blind index of their
+/// tax id, or the tax id itself while it awaits encryption.
+const TENANT_KEY: &str = "COALESCE(t.tax_id_bidx, t.tax_id)";
+const OWNER_KEY: &str = "COALESCE(o.tax_id_bidx, o.tax_id)";
+
+/// Condition limiting `alias` to the declarant, with its blind index bound
+/// as `$3` and the tax id as given as `$4` (both NULL for every declarant).
+fn declarant_filter(alias: &str) -> String {
+    format!("($4::text IS NULL OR {alias}.tax_id_bidx = $3 OR {alias}.tax_id = $4)")
+}
+
+fn declarant_index(declarant: Option<&str>) -> Result<Option<String>, AdminError> {
+    Ok(match declarant {
+        Some(tax_id) => pii::blind_index("tax_id", tax_id)?,
+        None => None,
+    })
+}
+
+type ThirdPartyRow = (
+    String, // owner tax_id
+    String, // owner name
+    String, // owner phone
+    String, // counterparty tax_id
+    String, // counterparty name
+    String, // counterparty address
+    String, // operation key
+    i32,    // quarter
+    f64,    // amount
+);
+
+/// Owners' operations with tenants (rent without withholding, which is
+/// declared on Modelo 180 instead) and suppliers for the year, keeping the
+/// counterparties above the Modelo 347 threshold.
+pub async fn build_third_party_returns(
+    pool: &PgPool,
+    period: TaxPeriod,
+    declarant: Option<&str>,
+) -> Result<Vec<ThirdPartyReturn>, AdminError> {
+    let owner_filter = declarant_filter("o");
+    let rows = sqlx::query_as::<_, ThirdPartyRow>(&format!(
+        "SELECT * FROM (\
+            SELECT MIN(o.tax_id), MIN(o.name) AS owner_name, MIN(o.phone), MIN(t.tax_id), \
+                MIN(t.name) AS party_name, MIN(t.address), 'B' AS op, \
+                EXTRACT(QUARTER FROM i.invoice_date)::int, SUM(i.amount)::float8 \
+            FROM admin_invoices i \
+            JOIN admin_owners o ON o.name = i.payee \
+            JOIN admin_tenants t ON t.name = i.payer AND NOT t.irpf_withholding \
+            WHERE i.type = 'income' AND i.deleted_at IS NULL AND i.invoice_date BETWEEN $1 AND $2 \
+                AND {owner_filter} \
+            GROUP BY {OWNER_KEY}, {TENANT_KEY}, 8 \
+            UNION ALL \
+            SELECT MIN(o.tax_id), MIN(o.name), MIN(o.phone), MIN(c.tax_id), MIN(c.name), \
+                MIN(c.address), 'A', \
+                EXTRACT(QUARTER FROM i.invoice_date)::int, SUM(i.amount)::float8 \
+            FROM admin_invoices i \
+            JOIN admin_owners o ON o.name = i.payer \
+            JOIN admin_contacts c ON c.name = i.payee \
+            WHERE i.type = 'expense' AND i.deleted_at IS NULL AND i.invoice_date BETWEEN $1 AND $2 \
+                AND {owner_filter} \
+            GROUP BY {OWNER_KEY}, COALESCE(c.tax_id_bidx, c.tax_id), 8\
+         ) ops \
+         ORDER BY owner_name, party_name, op"
+    ))
+    .bind(period.from)
+    .bind(period.to)
+    .bind(declarant_index(declarant)?)
+    .bind(declarant)
+    .fetch_all(pool)
+    .await?;
+
+    // (owner tax_id) -> (owner, (party tax_id, key) -> line)
+    let mut owners: BTreeMap<String, (TaxParty, BTreeMap<(String, char), ThirdPartyLine>)> =
+        BTreeMap::new();
+    for mut row in rows {
+        row.0 = pii::open("tax_id", &row.0)?;
+        row.2 = pii::open("phone", &row.2)?;
+        row.3 = pii::open("tax_id", &row.3)?;
+        let key = row.6.chars().next().unwrap_or('B');
+        let (_, parties) = owners.entry(row.0.clone()).or_insert_with(|| {
+            (
+                TaxParty {
+                    tax_id: row.0.clone(),
+                    name: row.1.clone(),
+                    phone: row.2.clone(),
+                },
+                BTreeMap::new(),
+            )
+        });
+        let line = parties
+            .entry((row.3.clone(), key))
+            .or_insert_with(|| ThirdPartyLine {
+                tax_id: row.3.clone(),
+                name: row.4.clone(),
+                province_code: province_code(&row.5),
+                operation_key: key,
+                annual_amount: 0.0,
+                quarterly_amounts: [0.0; 4],
+            });
+        let quarter = usize::try_from(row.7 - 1).unwrap_or(0).min(3);
+        line.quarterly_amounts[quarter] += row.8;
+        line.annual_amount += row.8;
+    }
+
+    let mut returns: Vec<ThirdPartyReturn> = owners
+        .into_values()
+        .map(|(declarant, parties)| {
+            let lines: Vec<ThirdPartyLine> = parties
+                .into_values()
+                .map(|mut l| {
+                    l.annual_amount = round2(l.annual_amount);
+                    l.quarterly_amounts = l.quarterly_amounts.map(round2);
+                    l
+                })
+                .filter(|l| l.annual_amount > MODELO_347_THRESHOLD)
+                .collect();
+            ThirdPartyReturn {
+                declarant,
+                period,
+                num_counterparties: lines.len(),
+                total_amount: round2(lines.iter().map(|l| l.annual_amount).sum()),
+                lines,
+            }
+        })
+        .filter(|r| !r.lines.is_empty())
+        .collect();
+    returns.sort_by(|a, b| a.declarant.name.cmp(&b.declarant.name));
+    Ok(returns)
+}
+
+/// Five-digit Spanish postal code found in an address.
+pub fn postal_code(address: &str) -> Option<String> {
+    let bytes = address.as_bytes();
+    bytes
+        .windows(5)
+        .enumerate()
+        .find(|(i, w)| {
+            w.iter().all(u8::is_ascii_digit)
+                && (*i == 0 || !bytes[i - 1].is_ascii_digit())
+                && bytes.get(i + 5).is_none_or(|b| !b.is_ascii_digit())
+        })
+        .map(|(_, w)| String::from_utf8_lossy(w).into_owned())
+        .filter(|code| ("01000"..="52999").contains(&code.as_str()))
+}
+
+/// Province code (first two digits of the postal code) found in an address.
+pub fn province_code(address: &str) -> String {
+    postal_code(address)
+        .map(|code| code[..2].to_string())
+        .unwrap_or_default()
+}
+
+fn round2(v: f64) -> f64 {
+    (v * 100.0).round() / 100.0
+}
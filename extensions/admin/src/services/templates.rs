@@ -0,0 +1,527 @@
+//! Lease and annex templates with `{{root.field}}` merge fields.
+//!
+//! A template body is Markdown (`#` headings, `-` and `1.` lists, `---`
+//! rules, paragraphs) or simple HTML (`h1`–`h3`, `p`, `ul`/`ol`/`li`, `br`,
+//! `hr`). Merge fields name a column of the contract being rendered or of
+//! its tenant, owner or property (`{{tenant.name}}`, `{{contract.rent}}`),
+//! or `{{today}}`. They are filled in after the body is split into blocks,
+//! so merged values never change the document structure.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AdminError;
+use crate::services::pdf::DocumentBlock;
+
+pub const TEMPLATE_KINDS: &[&str] = &["lease", "annex"];
+pub const TEMPLATE_FORMATS: &[&str] = &["markdown", "html"];
+
+/// Objects a merge field can start with.
+const MERGE_ROOTS: &[&str] = &["contract", "tenant", "owner", "property", "today"];
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct DocumentTemplate {
+    pub id: Uuid,
+    pub name: String,
+    /// lease or annex
+    pub kind: String,
+    /// markdown or html
+    pub format: String,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Body of POST /document-templates and PUT /document-templates/{id}.
+#[derive(Debug, Deserialize)]
+pub struct DocumentTemplateRequest {
+    pub name: String,
+    #[serde(default = "default_kind")]
+    pub kind: String,
+    #[serde(default = "default_format")]
+    pub format: String,
+    pub body: String,
+}
+
+fn default_kind() -> String {
+    "lease".to_string()
+}
+
+fn default_format() -> String {
+    "markdown".to_string()
+}
+
+impl DocumentTemplateRequest {
+    fn validate(&self) -> Result<(), AdminError> {
+        if self.name.trim().is_empty() {
+            return Err(AdminError::BadRequest("name is required".to_string()));
+        }
+        if !TEMPLATE_KINDS.contains(&self.kind.as_str()) {
+            return Err(AdminError::BadRequest(format!(
+                "kind must be one of: {}",
+                TEMPLATE_KINDS.join(", ")
+            )));
+        }
+        if !TEMPLATE_FORMATS.contains(&self.format.as_str()) {
+            return Err(AdminError::BadRequest(format!(
+                "format must be one of: {}",
+                TEMPLATE_FORMATS.join(", ")
+            )));
+        }
+        if self.body.trim().is_empty() {
+            return Err(AdminError::BadRequest("body is required".to_string()));
+        }
+        for field in merge_fields(&self.body)? {
+            let root = field.split('.').next().unwrap_or_default();
+            if !MERGE_ROOTS.contains(&root) {
+                return Err(AdminError::BadRequest(format!(
+                    "Unknown merge field {{{{{field}}}}}: fields start with {}",
+                    MERGE_ROOTS.join(", ")
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+const TEMPLATE_COLUMNS: &str = "id, name, kind, format, body, created_at, updated_at";
+
+/// Every template, or those of one kind, by name.
+pub async fn list_templates(
+    pool: &PgPool,
+    kind: Option<&str>,
+) -> Result<Vec<DocumentTemplate>, sqlx::Error> {
+    sqlx::query_as::<_, DocumentTemplate>(&format!(
+        "SELECT {TEMPLATE_COLUMNS} FROM admin_document_templates \
+         WHERE $1::text IS NULL OR kind = $1 ORDER BY name"
+    ))
+    .bind(kind)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_template(pool: &PgPool, id: Uuid) -> Result<DocumentTemplate, AdminError> {
+    sqlx::query_as::<_, DocumentTemplate>(&format!(
+        "SELECT {TEMPLATE_COLUMNS} FROM admin_document_templates WHERE id = $1"
+    ))
+    .bind(id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AdminError::NotFound("document template".to_string()))
+}
+
+pub async fn create_template(
+    pool: &PgPool,
+    request: &DocumentTemplateRequest,
+) -> Result<DocumentTemplate, AdminError> {
+    request.validate()?;
+    sqlx::query_as::<_, DocumentTemplate>(&format!(
+        "INSERT INTO admin_document_templates (name, kind, format, body) \
+         VALUES ($1, $2, $3, $4) RETURNING {TEMPLATE_COLUMNS}"
+    ))
+    .bind(request.name.trim())
+    .bind(&request.kind)
+    .bind(&request.format)
+    .bind(&request.body)
+    .fetch_one(pool)
+    .await
+    .map_err(duplicate_name)
+}
+
+pub async fn update_template(
+    pool: &PgPool,
+    id: Uuid,
+    request: &DocumentTemplateRequest,
+) -> Result<DocumentTemplate, AdminError> {
+    request.validate()?;
+    sqlx::query_as::<_, DocumentTemplate>(&format!(
+        "UPDATE admin_document_templates \
+         SET name = $2, kind = $3, format = $4, body = $5, updated_at = NOW() \
+         WHERE id = $1 RETURNING {TEMPLATE_COLUMNS}"
+    ))
+    .bind(id)
+    .bind(request.name.trim())
+    .bind(&request.kind)
+    .bind(&request.format)
+    .bind(&request.body)
+    .fetch_optional(pool)
+    .await
+    .map_err(duplicate_name)?
+    .ok_or_else(|| AdminError::NotFound("document template".to_string()))
+}
+
+/// Delete a template. Documents generated from it are kept.
+pub async fn delete_template(pool: &PgPool, id: Uuid) -> Result<(), AdminError> {
+    let result = sqlx::query("DELETE FROM admin_document_templates WHERE id = $1")
+        .bind(id)
+        .execute(pool)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(AdminError::NotFound("document template".to_string()));
+    }
+    Ok(())
+}
+
+fn duplicate_name(e: sqlx::Error) -> AdminError {
+    match &e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => {
+            AdminError::Conflict("A template with this name already exists".to_string())
+        }
+        _ => AdminError::Database(e),
+    }
+}
+
+// ── Rendering ───────────────────────────────────────────────────────────
+
+/// The template split into blocks with every merge field filled in from
+/// `context` (`{"contract": {..}, "tenant": {..}, ...}`). Fields without a
+/// value are left blank and returned as `unresolved`.
+pub fn render(template: &DocumentTemplate, context: &Value) -> (Vec<DocumentBlock>, Vec<String>) {
+    let blocks = if template.format == "html" {
+        html_blocks(&template.body)
+    } else {
+        markdown_blocks(&template.body)
+    };
+
+    let mut unresolved = Vec::new();
+    let mut fill = |text: &str| merge(text, context, &mut unresolved);
+    let blocks = blocks
+        .into_iter()
+        .map(|block| match block {
+            DocumentBlock::Heading(level, text) => DocumentBlock::Heading(level, fill(&text)),
+            DocumentBlock::Paragraph(text) => DocumentBlock::Paragraph(fill(&text)),
+            DocumentBlock::ListItem(marker, text) => DocumentBlock::ListItem(marker, fill(&text)),
+            DocumentBlock::Rule => DocumentBlock::Rule,
+        })
+        .collect();
+    unresolved.sort();
+    unresolved.dedup();
+    (blocks, unresolved)
+}
+
+/// The merge fields of a template body, trimmed: `{{ tenant.name }}` gives
+/// `tenant.name`.
+fn merge_fields(body: &str) -> Result<Vec<&str>, AdminError> {
+    let mut fields = Vec::new();
+    let mut rest = body;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let end = after.find("}}").ok_or_else(|| {
+            AdminError::BadRequest("Unclosed merge field: missing }}".to_string())
+        })?;
+        fields.push(after[..end].trim());
+        rest = &after[end + 2..];
+    }
+    Ok(fields)
+}
+
+fn merge(text: &str, context: &Value, unresolved: &mut Vec<String>) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            // Rejected when the template is saved; keep the text as written
+            out.push_str(&rest[start..]);
+            return out;
+        };
+        let field = after[..end].trim();
+        let value = field
+            .split('.')
+            .try_fold(context, |value, key| value.get(key))
+            .and_then(merge_value);
+        match value {
+            Some(value) => out.push_str(&value),
+            None => unresolved.push(field.to_string()),
+        }
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Text for a merged value: amounts with two decimals, dates as
+/// `dd/mm/yyyy`, lists comma-separated. `None` for null and objects.
+fn merge_value(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(
+            NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                .map_or_else(|_| s.clone(), |d| d.format("%d/%m/%Y").to_string()),
+        ),
+        Value::Number(n) if n.is_f64() => n.as_f64().map(|f| format!("{f:.2}")),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(if *b { "Yes" } else { "No" }.to_string()),
+        Value::Array(items) => Some(
+            items
+                .iter()
+                .filter_map(merge_value)
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+        Value::Null | Value::Object(_) => None,
+    }
+}
+
+fn markdown_blocks(body: &str) -> Vec<DocumentBlock> {
+    let mut blocks = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let flush = |paragraph: &mut Vec<&str>, blocks: &mut Vec<DocumentBlock>| {
+        if !paragraph.is_empty() {
+            blocks.push(DocumentBlock::Paragraph(plain(&paragraph.join(" "))));
+            paragraph.clear();
+        }
+    };
+
+    for line in body.lines().map(str::trim) {
+        let hashes = line.chars().take_while(|c| *c == '#').count();
+        let numbered = line
+            .split_once(". ")
+            .filter(|(n, _)| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()));
+        let block = if line.is_empty() {
+            None
+        } else if hashes > 0 && line[hashes..].starts_with(' ') {
+            let level = u8::try_from(hashes.min(3)).unwrap_or(3);
+            Some(DocumentBlock::Heading(level, plain(&line[hashes..])))
+        } else if matches!(line, "---" | "***" | "___") {
+            Some(DocumentBlock::Rule)
+        } else if let Some(text) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) {
+            Some(DocumentBlock::ListItem("\u{2022}".to_string(), plain(text)))
+        } else if let Some((number, text)) = numbered {
+            Some(DocumentBlock::ListItem(format!("{number}."), plain(text)))
+        } else {
+            paragraph.push(line);
+            continue;
+        };
+        flush(&mut paragraph, &mut blocks);
+        blocks.extend(block);
+    }
+    flush(&mut paragraph, &mut blocks);
+    blocks
+}
+
+/// Inline text without emphasis markers.
+fn plain(text: &str) -> String {
+    text.replace("**", "").replace("__", "").trim().to_string()
+}
+
+fn html_blocks(body: &str) -> Vec<DocumentBlock> {
+    let mut blocks = Vec::new();
+    let mut text = String::new();
+    // What the text being collected becomes
+    let mut current = DocumentBlock::Paragraph(String::new());
+    // One entry per open list: the next number of an `ol`, None for a `ul`
+    let mut lists: Vec<Option<u32>> = Vec::new();
+    let mut skip_depth = 0u32;
+
+    let mut rest = body;
+    while let Some(start) = rest.find('<') {
+        if skip_depth == 0 {
+            text.push_str(&rest[..start]);
+        }
+        let Some(end) = rest[start..].find('>') else {
+            rest = &rest[start..];
+            break;
+        };
+        let tag = &rest[start + 1..start + end];
+        rest = &rest[start + end + 1..];
+
+        let closing = tag.starts_with('/');
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match name.as_str() {
+            "script" | "style" | "head" | "title" => {
+                skip_depth = if closing {
+                    skip_depth.saturating_sub(1)
+                } else {
+                    skip_depth + 1
+                };
+            }
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "p" | "div" | "li" | "br" | "tr" => {
+                push_html_block(&mut blocks, &current, &mut text);
+                current = if closing {
+                    DocumentBlock::Paragraph(String::new())
+                } else {
+                    html_block_kind(&name, &mut lists)
+                };
+            }
+            "ul" | "ol" => {
+                push_html_block(&mut blocks, &current, &mut text);
+                if closing {
+                    lists.pop();
+                } else {
+                    lists.push((name == "ol").then_some(1));
+                }
+                current = DocumentBlock::Paragraph(String::new());
+            }
+            "hr" => {
+                push_html_block(&mut blocks, &current, &mut text);
+                blocks.push(DocumentBlock::Rule);
+            }
+            _ => {}
+        }
+    }
+    if skip_depth == 0 {
+        text.push_str(rest);
+    }
+    push_html_block(&mut blocks, &current, &mut text);
+    blocks
+}
+
+/// The block an opening tag starts.
+fn html_block_kind(name: &str, lists: &mut [Option<u32>]) -> DocumentBlock {
+    match name {
+        "h1" => DocumentBlock::Heading(1, String::new()),
+        "h2" => DocumentBlock::Heading(2, String::new()),
+        "h3" | "h4" | "h5" | "h6" => DocumentBlock::Heading(3, String::new()),
+        "li" => {
+            let marker = match lists.last_mut() {
+                Some(Some(next)) => {
+                    let marker = format!("{next}.");
+                    *next += 1;
+                    marker
+                }
+                _ => "\u{2022}".to_string(),
+            };
+            DocumentBlock::ListItem(marker, String::new())
+        }
+        _ => DocumentBlock::Paragraph(String::new()),
+    }
+}
+
+/// Add the collected text as a block of the current kind, if there is any.
+fn push_html_block(blocks: &mut Vec<DocumentBlock>, current: &DocumentBlock, text: &mut String) {
+    let collected = decode_entities(&std::mem::take(text));
+    let collected = collected.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collected.is_empty() {
+        return;
+    }
+    blocks.push(match current {
+        DocumentBlock::Heading(level, _) => DocumentBlock::Heading(*level, collected),
+        DocumentBlock::ListItem(marker, _) => DocumentBlock::ListItem(marker.clone(), collected),
+        DocumentBlock::Paragraph(_) | DocumentBlock::Rule => DocumentBlock::Paragraph(collected),
+    });
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&euro;", "\u{20ac}")
+        .replace("&pound;", "\u{a3}")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn template(format: &str, body: &str) -> DocumentTemplate {
+        DocumentTemplate {
+            id: Uuid::nil(),
+            name: "Lease".to_string(),
+            kind: "lease".to_string(),
+            format: format.to_string(),
+            body: body.to_string(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn context() -> Value {
+        json!({
+            "tenant": { "name": "Sarah Johnson", "email": null },
+            "contract": { "rent": 1250.0, "start_date": "2026-03-01", "months": 12 },
+            "property": { "tags": ["garden", "sea view"], "furnished": true },
+        })
+    }
+
+    #[test]
+    fn markdown_splits_into_blocks() {
+        let body = "# Lease\n\nThe tenant is\n**{{ tenant.name }}**.\n\n\
+                    - Rent: {{contract.rent}}\n2. Term: {{contract.months}} months\n\n---";
+        let (blocks, unresolved) = render(&template("markdown", body), &context());
+        assert_eq!(
+            blocks,
+            vec![
+                DocumentBlock::Heading(1, "Lease".to_string()),
+                DocumentBlock::Paragraph("The tenant is Sarah Johnson.".to_string()),
+                DocumentBlock::ListItem("\u{2022}".to_string(), "Rent: 1250.00".to_string()),
+                DocumentBlock::ListItem("2.".to_string(), "Term: 12 months".to_string()),
+                DocumentBlock::Rule,
+            ]
+        );
+        assert!(unresolved.is_empty());
+    }
+
+    #[test]
+    fn html_splits_into_blocks() {
+        let body = "<h2>Terms</h2><p>Starts&nbsp;{{contract.start_date}}</p>\
+                    <ol><li>One</li><li>Two</li></ol><script>alert(1)</script><hr>";
+        let (blocks, _) = render(&template("html", body), &context());
+        assert_eq!(
+            blocks,
+            vec![
+                DocumentBlock::Heading(2, "Terms".to_string()),
+                DocumentBlock::Paragraph("Starts 01/03/2026".to_string()),
+                DocumentBlock::ListItem("1.".to_string(), "One".to_string()),
+                DocumentBlock::ListItem("2.".to_string(), "Two".to_string()),
+                DocumentBlock::Rule,
+            ]
+        );
+    }
+
+    #[test]
+    fn formats_merged_values() {
+        let body = "{{property.tags}} / {{property.furnished}}";
+        let (blocks, _) = render(&template("markdown", body), &context());
+        assert_eq!(
+            blocks,
+            vec![DocumentBlock::Paragraph(
+                "garden, sea view / Yes".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn missing_fields_are_blank_and_reported_once() {
+        let body = "To {{tenant.email}} and {{ tenant.email }}, {{owner.name}}";
+        let (blocks, unresolved) = render(&template("markdown", body), &context());
+        assert_eq!(
+            blocks,
+            vec![DocumentBlock::Paragraph("To  and , ".to_string())]
+        );
+        assert_eq!(unresolved, vec!["owner.name", "tenant.email"]);
+    }
+
+    #[test]
+    fn merged_values_do_not_change_the_structure() {
+        let context = json!({ "tenant": { "name": "# Not a heading" } });
+        let (blocks, _) = render(&template("markdown", "Dear {{tenant.name}}"), &context);
+        assert_eq!(
+            blocks,
+            vec![DocumentBlock::Paragraph("Dear # Not a heading".to_string())]
+        );
+    }
+
+    #[test]
+    fn merge_fields_must_be_closed() {
+        assert_eq!(
+            merge_fields("{{ tenant.name }} {{today}}").unwrap(),
+            vec!["tenant.name", "today"]
+        );
+        assert!(merge_fields("Dear {{tenant.name").is_err());
+    }
+}
@@ -0,0 +1,91 @@
+use sqlx::PgPool;
+
+use crate::api::actor::Actor;
+use crate::api::generic::{entities, write_audit, EntityInfo};
+
+/// Days a deleted record stays in the trash before it is purged.
+pub const DEFAULT_RETENTION_DAYS: i32 = 30;
+
+/// Retention period from `ADMIN_TRASH_RETENTION_DAYS`, or the default when
+/// unset or not a positive number.
+pub fn retention_days() -> i32 {
+    std::env::var("ADMIN_TRASH_RETENTION_DAYS")
+        .ok()
+        .and_then(|v| v.trim().parse::<i32>().ok())
+        .filter(|days| *days > 0)
+        .unwrap_or(DEFAULT_RETENTION_DAYS)
+}
+
+/// Permanently delete every record that has been in the trash for longer
+/// than `days`, one table at a time. Records other rows still refer to are
+/// kept until those are gone, and a table that fails is logged and skipped
+/// so the others are still purged. Each removal is audited as a `purge`
+/// without the row data. Returns the number of records removed.
+pub async fn purge_expired(pool: &PgPool, days: i32) -> u64 {
+    let mut purged = 0u64;
+    for info in entities().into_iter().filter(|info| info.trashable) {
+        match purge_table(pool, &info, days).await {
+            Ok(count) => purged += count,
+            Err(e) => tracing::error!(error = %e, table = info.table, "Trash purge failed"),
+        }
+    }
+    purged
+}
+
+async fn purge_table(pool: &PgPool, info: &EntityInfo, days: i32) -> Result<u64, sqlx::Error> {
+    let table = info.table;
+    let unreferenced = unreferenced(pool, table).await?;
+    let expired = "t.deleted_at < NOW() - make_interval(days => $1)";
+
+    let mut tx = pool.begin().await?;
+    let ids: Vec<String> = sqlx::query_scalar(&format!(
+        "DELETE FROM {table} t WHERE {expired} AND {unreferenced} RETURNING t.id::text"
+    ))
+    .bind(days)
+    .fetch_all(&mut *tx)
+    .await?;
+    let kept: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {table} t WHERE {expired}"))
+        .bind(days)
+        .fetch_one(&mut *tx)
+        .await?;
+    if kept > 0 {
+        tracing::warn!(
+            table,
+            kept,
+            "Expired trash records are still referenced and were kept"
+        );
+    }
+
+    let actor = Actor::default();
+    for id in &ids {
+        write_audit(&mut *tx, &actor, info.label, id, "purge", None, None).await?;
+    }
+    tx.commit().await?;
+    Ok(ids.len() as u64)
+}
+
+/// SQL condition, over rows of `table` aliased `t`, that holds when no row
+/// refers to `t` through a foreign key. Purges check it rather than rely on
+/// `ON DELETE CASCADE`, which would silently take documents, photos or
+/// payout items with the record.
+pub async fn unreferenced(pool: &PgPool, table: &str) -> Result<String, sqlx::Error> {
+    let references: Vec<(String, String)> = sqlx::query_as(
+        "SELECT c.conrelid::regclass::text, quote_ident(a.attname) \
+         FROM pg_constraint c \
+         JOIN pg_attribute a ON a.attrelid = c.conrelid AND a.attnum = c.conkey[1] \
+         WHERE c.contype = 'f' AND c.confrelid = $1::regclass AND cardinality(c.conkey) = 1 \
+         ORDER BY 1, 2",
+    )
+    .bind(table)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(references
+        .iter()
+        .map(|(referencing, column)| {
+            format!("NOT EXISTS (SELECT 1 FROM {referencing} r WHERE r.{column} = t.id)")
+        })
+        .chain(std::iter::once("TRUE".to_string()))
+        .collect::<Vec<_>>()
+        .join(" AND "))
+}
@@ -0,0 +1,426 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::api::actor::Actor;
+use crate::api::generic::{build_filters, entity_info, EntityInfo};
+use crate::api::pagination::MAX_PER_PAGE;
+use crate::api::types::PaginationQuery;
+use crate::error::AdminError;
+use crate::services::custom_fields::{self, definitions, CustomField, CUSTOM_PREFIX};
+
+/// List parameters stored with a view. Everything but paging and count mode.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ViewQuery {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub search: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub per_page: Option<i64>,
+    /// Filter grammar keys and values, e.g. `{"status": "Unpaid"}`
+    #[serde(default)]
+    pub filters: HashMap<String, String>,
+}
+
+impl ViewQuery {
+    /// Use the view as defaults for a request: parameters sent explicitly
+    /// win, filters are merged with the request's filters taking precedence.
+    pub fn apply_to(self, params: PaginationQuery) -> PaginationQuery {
+        let mut filters = self.filters;
+        filters.extend(params.filters);
+        PaginationQuery {
+            page: params.page,
+            per_page: params.per_page.or(self.per_page),
+            search: params.search.or(self.search),
+            sort: params.sort.or(self.sort),
+            order: params.order.or(self.order),
+            cursor: params.cursor,
+            count: params.count,
+            filters,
+        }
+    }
+
+    fn as_params(&self) -> PaginationQuery {
+        self.clone().apply_to(PaginationQuery::default())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct SavedView {
+    pub id: Uuid,
+    pub entity: String,
+    pub name: String,
+    pub owner_user_id: String,
+    pub shared: bool,
+    pub query: Json<ViewQuery>,
+    pub columns: Vec<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Body of POST /views and PUT /views/{id}.
+#[derive(Debug, Deserialize)]
+pub struct SavedViewRequest {
+    pub entity: String,
+    pub name: String,
+    #[serde(default)]
+    pub shared: bool,
+    #[serde(default)]
+    pub query: ViewQuery,
+    /// Columns the list returns, besides `id`; custom fields as
+    /// `custom.<key>`. Empty returns every column.
+    #[serde(default)]
+    pub columns: Vec<String>,
+}
+
+impl SavedViewRequest {
+    /// Reject unknown entities, blank names, and a sort, page size, filters
+    /// or columns the entity's list endpoint would refuse or ignore, so a
+    /// saved view always loads as saved.
+    async fn validate(&self, pool: &PgPool) -> Result<(), AdminError> {
+        let info = entity_info(&self.entity)
+            .ok_or_else(|| AdminError::BadRequest(format!("Unknown entity: {}", self.entity)))?;
+        if self.name.trim().is_empty() {
+            return Err(AdminError::BadRequest("View name is required".to_string()));
+        }
+        let custom = definitions(pool, info.label).await?;
+        build_filters(&info, &custom, &self.query.as_params())?;
+        validate_query(&info, &custom, &self.query)?;
+
+        let table_columns = table_columns(pool, &info).await?;
+        if let Some(unknown) = self
+            .columns
+            .iter()
+            .find(|c| !table_columns.contains(c) && custom_fields::find(&custom, c).is_none())
+        {
+            return Err(AdminError::BadRequest(format!(
+                "Unknown column for {}: {unknown}",
+                info.label
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Check the stored sort, order and page size the way the list applies them,
+/// where an unknown sort would silently fall back to the default.
+fn validate_query(
+    info: &EntityInfo,
+    custom: &[CustomField],
+    query: &ViewQuery,
+) -> Result<(), AdminError> {
+    if let Some(sort) = query.sort.as_deref() {
+        if !info.sortable_fields.contains(&sort) && custom_fields::find(custom, sort).is_none() {
+            return Err(AdminError::BadRequest(format!(
+                "Cannot sort {} by {sort}",
+                info.label
+            )));
+        }
+    }
+    if let Some(order) = query.order.as_deref() {
+        if order != "asc" && order != "desc" {
+            return Err(AdminError::BadRequest(format!(
+                "order must be asc or desc, not '{order}'"
+            )));
+        }
+    }
+    if let Some(per_page) = query.per_page {
+        if !(1..=MAX_PER_PAGE).contains(&per_page) {
+            return Err(AdminError::BadRequest(format!(
+                "per_page must be between 1 and {MAX_PER_PAGE}"
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// The entity's own columns, without the blind indexes of sensitive fields.
+async fn table_columns(pool: &PgPool, info: &EntityInfo) -> Result<Vec<String>, AdminError> {
+    let columns: Vec<String> = sqlx::query_scalar(
+        "SELECT column_name::text FROM information_schema.columns \
+         WHERE table_name = $1 ORDER BY ordinal_position",
+    )
+    .bind(info.table)
+    .fetch_all(pool)
+    .await?;
+    Ok(columns
+        .into_iter()
+        .filter(|c| {
+            !c.strip_suffix("_bidx")
+                .is_some_and(|field| info.sensitive_fields.contains(&field))
+        })
+        .collect())
+}
+
+const VIEW_COLUMNS: &str =
+    "id, entity, name, owner_user_id, shared, query, columns, created_at, updated_at";
+
+fn owner(actor: &Actor) -> Result<&str, AdminError> {
+    actor
+        .user_id
+        .as_deref()
+        .ok_or_else(|| AdminError::Forbidden("Saved views need a signed-in user".to_string()))
+}
+
+/// Views the actor owns or that are shared, optionally for one entity.
+pub async fn list_views(
+    pool: &PgPool,
+    actor: &Actor,
+    entity: Option<&str>,
+) -> Result<Vec<SavedView>, AdminError> {
+    let views = sqlx::query_as::<_, SavedView>(&format!(
+        "SELECT {VIEW_COLUMNS} FROM admin_saved_views \
+         WHERE (owner_user_id = $1 OR shared) AND ($2::text IS NULL OR entity = $2) \
+         ORDER BY entity, name"
+    ))
+    .bind(actor.user_id.as_deref())
+    .bind(entity)
+    .fetch_all(pool)
+    .await?;
+    Ok(views)
+}
+
+/// A view the actor can see: their own or a shared one.
+pub async fn get_view(pool: &PgPool, actor: &Actor, id: Uuid) -> Result<SavedView, AdminError> {
+    sqlx::query_as::<_, SavedView>(&format!(
+        "SELECT {VIEW_COLUMNS} FROM admin_saved_views \
+         WHERE id = $1 AND (owner_user_id = $2 OR shared)"
+    ))
+    .bind(id)
+    .bind(actor.user_id.as_deref())
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AdminError::NotFound("view".to_string()))
+}
+
+pub async fn create_view(
+    pool: &PgPool,
+    actor: &Actor,
+    request: &SavedViewRequest,
+) -> Result<SavedView, AdminError> {
+    request.validate(pool).await?;
+    let owner = owner(actor)?;
+    sqlx::query_as::<_, SavedView>(&format!(
+        "INSERT INTO admin_saved_views (entity, name, owner_user_id, shared, query, columns) \
+         VALUES ($1, $2, $3, $4, $5, $6) RETURNING {VIEW_COLUMNS}"
+    ))
+    .bind(&request.entity)
+    .bind(request.name.trim())
+    .bind(owner)
+    .bind(request.shared)
+    .bind(Json(&request.query))
+    .bind(&request.columns)
+    .fetch_one(pool)
+    .await
+    .map_err(duplicate_name)
+}
+
+/// Replace a view. Only its owner may change it.
+pub async fn update_view(
+    pool: &PgPool,
+    actor: &Actor,
+    id: Uuid,
+    request: &SavedViewRequest,
+) -> Result<SavedView, AdminError> {
+    request.validate(pool).await?;
+    let view = get_view(pool, actor, id).await?;
+    let owner = owner(actor)?;
+    if view.owner_user_id != owner {
+        return Err(AdminError::Forbidden(
+            "Only the owner can change a shared view".to_string(),
+        ));
+    }
+    sqlx::query_as::<_, SavedView>(&format!(
+        "UPDATE admin_saved_views \
+         SET entity = $2, name = $3, shared = $4, query = $5, columns = $6, updated_at = NOW() \
+         WHERE id = $1 RETURNING {VIEW_COLUMNS}"
+    ))
+    .bind(id)
+    .bind(&request.entity)
+    .bind(request.name.trim())
+    .bind(request.shared)
+    .bind(Json(&request.query))
+    .bind(&request.columns)
+    .fetch_one(pool)
+    .await
+    .map_err(duplicate_name)
+}
+
+/// Delete a view. Only its owner may delete it.
+pub async fn delete_view(pool: &PgPool, actor: &Actor, id: Uuid) -> Result<(), AdminError> {
+    let view = get_view(pool, actor, id).await?;
+    if view.owner_user_id != owner(actor)? {
+        return Err(AdminError::Forbidden(
+            "Only the owner can delete a shared view".to_string(),
+        ));
+    }
+    sqlx::query("DELETE FROM admin_saved_views WHERE id = $1")
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Apply `?view=<id>` to list parameters for `entity`, returning them with
+/// the view's columns. Requests without a view are returned unchanged, with
+/// no columns (every column).
+pub async fn with_saved_view(
+    pool: &PgPool,
+    actor: &Actor,
+    entity: &str,
+    mut params: PaginationQuery,
+) -> Result<(PaginationQuery, Vec<String>), AdminError> {
+    let Some(id) = params.filters.remove("view").filter(|v| !v.is_empty()) else {
+        return Ok((params, Vec::new()));
+    };
+    let id = Uuid::parse_str(&id)
+        .map_err(|_| AdminError::BadRequest(format!("Invalid view id: {id}")))?;
+    let view = get_view(pool, actor, id).await?;
+    if view.entity != entity {
+        return Err(AdminError::BadRequest(format!(
+            "View '{}' is for {}, not {entity}",
+            view.name, view.entity
+        )));
+    }
+    Ok((view.query.0.apply_to(params), view.columns))
+}
+
+/// Narrow a listed row to `id` and the view's `columns`. Custom field
+/// columns keep their keys in `custom_fields`. No columns keeps the row whole.
+pub fn select_columns(row: &mut serde_json::Value, columns: &[String]) {
+    let Some(obj) = row.as_object_mut().filter(|_| !columns.is_empty()) else {
+        return;
+    };
+    let custom_keys: Vec<&str> = columns
+        .iter()
+        .filter_map(|c| c.strip_prefix(CUSTOM_PREFIX))
+        .collect();
+    obj.retain(|key, _| {
+        key == "id"
+            || columns.iter().any(|c| c == key)
+            || (key == "custom_fields" && !custom_keys.is_empty())
+    });
+    if let Some(custom) = obj
+        .get_mut("custom_fields")
+        .and_then(serde_json::Value::as_object_mut)
+    {
+        custom.retain(|key, _| custom_keys.contains(&key.as_str()));
+    }
+}
+
+fn duplicate_name(e: sqlx::Error) -> AdminError {
+    match &e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => AdminError::Conflict(
+            "You already have a view with this name for this entity".to_string(),
+        ),
+        _ => AdminError::Database(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn invoices() -> EntityInfo {
+        entity_info("invoices").expect("invoices entity")
+    }
+
+    fn query(sort: Option<&str>, order: Option<&str>, per_page: Option<i64>) -> ViewQuery {
+        ViewQuery {
+            sort: sort.map(str::to_string),
+            order: order.map(str::to_string),
+            per_page,
+            ..ViewQuery::default()
+        }
+    }
+
+    #[test]
+    fn valid_query_is_accepted() {
+        let view = query(Some("amount"), Some("desc"), Some(25));
+        assert!(validate_query(&invoices(), &[], &view).is_ok());
+        assert!(validate_query(&invoices(), &[], &ViewQuery::default()).is_ok());
+    }
+
+    #[test]
+    fn unknown_sort_is_rejected() {
+        let view = query(Some("tenant_phone"), None, None);
+        assert!(matches!(
+            validate_query(&invoices(), &[], &view),
+            Err(AdminError::BadRequest(msg)) if msg == "Cannot sort invoices by tenant_phone"
+        ));
+    }
+
+    #[test]
+    fn order_and_page_size_are_checked() {
+        for view in [
+            query(None, Some("up"), None),
+            query(None, None, Some(0)),
+            query(None, None, Some(MAX_PER_PAGE + 1)),
+        ] {
+            assert!(matches!(
+                validate_query(&invoices(), &[], &view),
+                Err(AdminError::BadRequest(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn request_parameters_win_over_the_view() {
+        let view = ViewQuery {
+            sort: Some("amount".to_string()),
+            per_page: Some(50),
+            filters: HashMap::from([
+                ("status".to_string(), "Unpaid".to_string()),
+                ("type".to_string(), "income".to_string()),
+            ]),
+            ..ViewQuery::default()
+        };
+        let params = PaginationQuery {
+            sort: Some("invoice_date".to_string()),
+            filters: HashMap::from([("status".to_string(), "Partial".to_string())]),
+            ..PaginationQuery::default()
+        };
+        let merged = view.apply_to(params);
+        assert_eq!(merged.sort.as_deref(), Some("invoice_date"));
+        assert_eq!(merged.per_page, Some(50));
+        assert_eq!(merged.filters["status"], "Partial");
+        assert_eq!(merged.filters["type"], "income");
+    }
+
+    #[test]
+    fn select_columns_keeps_id_and_the_chosen_columns() {
+        let mut row = json!({
+            "id": 1,
+            "reference": "INV-1",
+            "amount": 900,
+            "custom_fields": {"floor": 2, "keys": 3},
+        });
+        select_columns(
+            &mut row,
+            &["reference".to_string(), "custom.floor".to_string()],
+        );
+        assert_eq!(
+            row,
+            json!({"id": 1, "reference": "INV-1", "custom_fields": {"floor": 2}})
+        );
+    }
+
+    #[test]
+    fn select_columns_without_columns_keeps_the_row() {
+        let mut row = json!({"id": 1, "reference": "INV-1"});
+        select_columns(&mut row, &[]);
+        assert_eq!(row, json!({"id": 1, "reference": "INV-1"}));
+
+        let mut row = json!({"id": 1, "custom_fields": {"floor": 2}});
+        select_columns(&mut row, &["reference".to_string()]);
+        assert_eq!(row, json!({"id": 1}));
+    }
+}
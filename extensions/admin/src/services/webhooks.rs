@@ -0,0 +1,901 @@
+//! Outbound webhooks.
+//!
+//! Domain events go into `admin_webhook_events`, an outbox written in the
+//! same transaction as the change: [`write_audit`] adds the events of every
+//! audited change (see [`audit_events`]) and the scheduled webhook job adds
+//! `contract.expiring`. An insert trigger queues one delivery per subscribed
+//! webhook; [`deliver_pending`] POSTs them signed with HMAC-SHA256 and
+//! retries failures with exponential backoff, logging every attempt.
+//!
+//! Webhook URLs must point at public hosts: loopback, private and link-local
+//! addresses are refused when a webhook is saved and again, after DNS
+//! resolution, every time a delivery is sent. Redirects are not followed.
+//!
+//! [`write_audit`]: crate::api::generic::write_audit
+
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha2::Sha256;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::api::actor::Actor;
+use crate::api::generic::write_audit;
+use crate::error::AdminError;
+use crate::services::reporting::LET_CONTRACT;
+
+/// Attempts before a delivery is given up as `failed`.
+pub const MAX_ATTEMPTS: i32 = 8;
+
+/// Deliveries sent per job run.
+const BATCH_SIZE: i64 = 50;
+
+/// First retry delay; doubled on each further attempt up to [`MAX_BACKOFF_SECS`].
+const BASE_BACKOFF_SECS: i64 = 60;
+const MAX_BACKOFF_SECS: i64 = 6 * 60 * 60;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long a claimed delivery is held before another run may pick it up.
+const CLAIM_LEASE: &str = "5 minutes";
+
+/// Response body kept in the attempt log, in bytes. Only this much of the
+/// body is read.
+const RESPONSE_EXCERPT: usize = 1000;
+
+const DEFAULT_EXPIRING_DAYS: i32 = 60;
+const DEFAULT_EVENT_RETENTION_DAYS: i32 = 30;
+
+/// Audit actions that raise no event unless they changed the row: exporting
+/// a payout batch for the first time moves it to `Exported`.
+const SILENT_ACTIONS: &[&str] = &["export"];
+
+type HmacSha256 = Hmac<Sha256>;
+
+// ── Events ──────────────────────────────────────────────────────────────
+
+/// Event types raised by one audited change: `<entity>.<action>` (e.g.
+/// `invoice.created`), plus `<entity>.status_changed` when the status moved
+/// and `invoice.paid` when an invoice became paid.
+pub fn audit_events(
+    entity_type: &str,
+    action: &str,
+    old_values: Option<&Value>,
+    new_values: Option<&Value>,
+) -> Vec<String> {
+    if SILENT_ACTIONS.contains(&action) && old_values == new_values {
+        return Vec::new();
+    }
+    let entity = entity_name(entity_type);
+    let verb = match action {
+        "create" => "created",
+        "update" => "updated",
+        "delete" => "deleted",
+        "restore" => "restored",
+        "purge" => "purged",
+        "export" => "exported",
+        other => other,
+    };
+    let mut events = vec![format!("{entity}.{verb}")];
+
+    let old_status = status(old_values);
+    let new_status = status(new_values);
+    if old_values.is_some() && new_status.is_some() && old_status != new_status {
+        events.push(format!("{entity}.status_changed"));
+    }
+    if entity == "invoice" && new_status == Some("Paid") && old_status != Some("Paid") {
+        events.push("invoice.paid".to_string());
+    }
+    events
+}
+
+/// Singular event prefix for an audit entity label: `properties` → `property`.
+fn entity_name(label: &str) -> String {
+    if let Some(stem) = label.strip_suffix("ies") {
+        format!("{stem}y")
+    } else if let Some(stem) = label.strip_suffix("ches") {
+        format!("{stem}ch")
+    } else {
+        label.strip_suffix('s').unwrap_or(label).to_string()
+    }
+}
+
+fn status(values: Option<&Value>) -> Option<&str> {
+    values.and_then(|v| v.get("status")).and_then(Value::as_str)
+}
+
+/// Days ahead of a contract's end date that `contract.expiring` is raised,
+/// from `ADMIN_CONTRACT_EXPIRING_DAYS` (default 60).
+pub fn expiring_days() -> i32 {
+    std::env::var("ADMIN_CONTRACT_EXPIRING_DAYS")
+        .ok()
+        .and_then(|v| v.trim().parse::<i32>().ok())
+        .filter(|days| *days > 0)
+        .unwrap_or(DEFAULT_EXPIRING_DAYS)
+}
+
+/// Days events are kept once none of their deliveries is pending, from
+/// `ADMIN_WEBHOOK_EVENT_RETENTION_DAYS` (default 30).
+pub fn event_retention_days() -> i32 {
+    std::env::var("ADMIN_WEBHOOK_EVENT_RETENTION_DAYS")
+        .ok()
+        .and_then(|v| v.trim().parse::<i32>().ok())
+        .filter(|days| *days > 0)
+        .unwrap_or(DEFAULT_EVENT_RETENTION_DAYS)
+}
+
+/// Raise `contract.expiring` for let contracts ending within `days`, once per
+/// contract and end date. Returns the number of events queued.
+pub async fn queue_expiring_contracts(pool: &PgPool, days: i32) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(&format!(
+        "INSERT INTO admin_webhook_events (event_type, entity_type, entity_id, payload, dedupe_key) \
+         SELECT 'contract.expiring', 'contracts', c.id, \
+            jsonb_build_object('entity_type', 'contracts', 'entity_id', c.id, \
+                'action', 'expiring', 'days_remaining', c.end_date - CURRENT_DATE, \
+                'data', to_jsonb(c), 'occurred_at', NOW()), \
+            'contract.expiring:' || c.id || ':' || c.end_date \
+         FROM admin_contracts c \
+         WHERE {LET_CONTRACT} AND c.end_date BETWEEN CURRENT_DATE AND CURRENT_DATE + $1 \
+         ON CONFLICT (dedupe_key) DO NOTHING"
+    ))
+    .bind(days)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Remove events older than `days` that have nothing left to deliver.
+pub async fn prune_events(pool: &PgPool, days: i32) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        "DELETE FROM admin_webhook_events e \
+         WHERE e.created_at < NOW() - make_interval(days => $1) \
+            AND NOT EXISTS (SELECT 1 FROM admin_webhook_deliveries d \
+                            WHERE d.event_id = e.id AND d.status = 'pending')",
+    )
+    .bind(days)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+// ── Webhooks (admin) ────────────────────────────────────────────────────
+
+const WEBHOOK_JSON: &str = "json_build_object('id', id, 'name', name, 'url', url, \
+    'events', events, 'is_active', is_active, 'created_at', created_at, \
+    'updated_at', updated_at)";
+
+/// Body of POST /webhooks and PUT /webhooks/{id}.
+#[derive(Debug, Deserialize)]
+pub struct WebhookRequest {
+    pub name: String,
+    pub url: String,
+    /// Event types (`invoice.paid`) or entity wildcards (`invoice.*`); empty for all
+    #[serde(default)]
+    pub events: Vec<String>,
+    pub is_active: Option<bool>,
+    /// On update, replace the signing secret with a new one
+    #[serde(default)]
+    pub rotate_secret: bool,
+}
+
+impl WebhookRequest {
+    fn validate(&self) -> Result<(&str, &str, Vec<String>), AdminError> {
+        let name = self.name.trim();
+        if name.is_empty() {
+            return Err(AdminError::BadRequest("name is required".to_string()));
+        }
+        let url = self.url.trim();
+        let parsed = reqwest::Url::parse(url)
+            .map_err(|_| AdminError::BadRequest(format!("'{url}' is not a valid URL")))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(AdminError::BadRequest(
+                "url must use http or https".to_string(),
+            ));
+        }
+        check_host(&parsed).map_err(AdminError::BadRequest)?;
+        let mut events = Vec::with_capacity(self.events.len());
+        for event in &self.events {
+            let event = event.trim();
+            let valid = event.split_once('.').is_some_and(|(entity, name)| {
+                !entity.is_empty()
+                    && !name.is_empty()
+                    && event
+                        .chars()
+                        .all(|c| c.is_ascii_lowercase() || matches!(c, '.' | '_' | '*'))
+            });
+            if !valid {
+                return Err(AdminError::BadRequest(format!(
+                    "'{event}' is not an event type such as invoice.paid or invoice.*"
+                )));
+            }
+            events.push(event.to_string());
+        }
+        Ok((name, url, events))
+    }
+}
+
+/// Refuse URLs without a host, `localhost` names and IP literals that are
+/// not publicly routable. Names are checked again once resolved, by
+/// [`PublicResolver`].
+fn check_host(url: &reqwest::Url) -> Result<(), String> {
+    let host = url
+        .host_str()
+        .ok_or_else(|| "url must have a host".to_string())?
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .trim_end_matches('.')
+        .to_ascii_lowercase();
+    if host == "localhost" || host.ends_with(".localhost") {
+        return Err(format!("url host {host} is not a public address"));
+    }
+    match host.parse::<IpAddr>() {
+        Ok(ip) if !is_public(ip) => Err(format!("url host {ip} is not a public address")),
+        _ => Ok(()),
+    }
+}
+
+/// Whether `ip` is routable on the internet: not loopback, private,
+/// link-local, shared (carrier-grade NAT), unspecified, broadcast or
+/// documentation space, including IPv4 addresses mapped into IPv6.
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            !(v4.is_private()
+                || v4.is_loopback()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_documentation()
+                || a == 0
+                || (a == 100 && (64..128).contains(&b)))
+        }
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_public(IpAddr::V4(v4));
+            }
+            let first = v6.segments()[0];
+            !(v6.is_loopback()
+                || v6.is_unspecified()
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80)
+        }
+    }
+}
+
+/// System DNS resolution that fails when a name resolves to any address
+/// that is not public, so a host saved as public cannot later be pointed
+/// at the internal network.
+struct PublicResolver;
+
+impl reqwest::dns::Resolve for PublicResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        Box::pin(resolve_public(name.as_str().to_string()))
+    }
+}
+
+async fn resolve_public(
+    host: String,
+) -> Result<reqwest::dns::Addrs, Box<dyn std::error::Error + Send + Sync>> {
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0)).await?.collect();
+    if let Some(addr) = addrs.iter().find(|addr| !is_public(addr.ip())) {
+        return Err(format!("{host} resolves to {}, not a public address", addr.ip()).into());
+    }
+    Ok(Box::new(addrs.into_iter()))
+}
+
+/// HTTP client for deliveries: public hosts only, no proxy, no redirects.
+pub fn delivery_client() -> reqwest::Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .no_proxy()
+        .dns_resolver(Arc::new(PublicResolver))
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+}
+
+fn new_secret() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    format!("whsec_{}", hex::encode(bytes))
+}
+
+/// Registered webhooks, without their secrets.
+pub async fn list_webhooks(pool: &PgPool) -> Result<Vec<Value>, sqlx::Error> {
+    sqlx::query_scalar(&format!(
+        "SELECT {WEBHOOK_JSON} FROM admin_webhooks ORDER BY created_at"
+    ))
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_webhook(pool: &PgPool, id: Uuid) -> Result<Value, AdminError> {
+    sqlx::query_scalar(&format!(
+        "SELECT {WEBHOOK_JSON} FROM admin_webhooks WHERE id = $1"
+    ))
+    .bind(id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AdminError::NotFound("webhook".to_string()))
+}
+
+/// Register a webhook. The response is the only time the secret is shown.
+pub async fn create_webhook(
+    pool: &PgPool,
+    actor: &Actor,
+    request: &WebhookRequest,
+) -> Result<Value, AdminError> {
+    let (name, url, events) = request.validate()?;
+    let secret = new_secret();
+    let mut tx = pool.begin().await?;
+    let (id, webhook): (Uuid, Value) = sqlx::query_as(&format!(
+        "INSERT INTO admin_webhooks (name, url, secret, events, is_active) \
+         VALUES ($1, $2, $3, $4, $5) RETURNING id, {WEBHOOK_JSON}"
+    ))
+    .bind(name)
+    .bind(url)
+    .bind(&secret)
+    .bind(&events)
+    .bind(request.is_active.unwrap_or(true))
+    .fetch_one(&mut *tx)
+    .await?;
+    write_audit(
+        &mut *tx,
+        actor,
+        "webhooks",
+        &id.to_string(),
+        "create",
+        None,
+        Some(&webhook),
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(with_secret(webhook, &secret))
+}
+
+/// Update a webhook; with `rotate_secret` the new secret is returned.
+pub async fn update_webhook(
+    pool: &PgPool,
+    actor: &Actor,
+    id: Uuid,
+    request: &WebhookRequest,
+) -> Result<Value, AdminError> {
+    let (name, url, events) = request.validate()?;
+    let secret = request.rotate_secret.then(new_secret);
+    let mut tx = pool.begin().await?;
+    let old: Option<Value> = sqlx::query_scalar(&format!(
+        "SELECT {WEBHOOK_JSON} FROM admin_webhooks WHERE id = $1 FOR UPDATE"
+    ))
+    .bind(id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some(old) = old else {
+        return Err(AdminError::NotFound("webhook".to_string()));
+    };
+    let webhook: Value = sqlx::query_scalar(&format!(
+        "UPDATE admin_webhooks SET name = $2, url = $3, events = $4, \
+            is_active = COALESCE($5, is_active), secret = COALESCE($6, secret), \
+            updated_at = NOW() \
+         WHERE id = $1 RETURNING {WEBHOOK_JSON}"
+    ))
+    .bind(id)
+    .bind(name)
+    .bind(url)
+    .bind(&events)
+    .bind(request.is_active)
+    .bind(secret.as_deref())
+    .fetch_one(&mut *tx)
+    .await?;
+    write_audit(
+        &mut *tx,
+        actor,
+        "webhooks",
+        &id.to_string(),
+        "update",
+        Some(&old),
+        Some(&webhook),
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(match secret {
+        Some(secret) => with_secret(webhook, &secret),
+        None => webhook,
+    })
+}
+
+pub async fn delete_webhook(pool: &PgPool, actor: &Actor, id: Uuid) -> Result<(), AdminError> {
+    let mut tx = pool.begin().await?;
+    let old: Option<Value> = sqlx::query_scalar(&format!(
+        "DELETE FROM admin_webhooks WHERE id = $1 RETURNING {WEBHOOK_JSON}"
+    ))
+    .bind(id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some(old) = old else {
+        return Err(AdminError::NotFound("webhook".to_string()));
+    };
+    write_audit(
+        &mut *tx,
+        actor,
+        "webhooks",
+        &id.to_string(),
+        "delete",
+        Some(&old),
+        None,
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+fn with_secret(mut webhook: Value, secret: &str) -> Value {
+    if let Some(object) = webhook.as_object_mut() {
+        object.insert("secret".to_string(), json!(secret));
+    }
+    webhook
+}
+
+// ── Delivery log ────────────────────────────────────────────────────────
+
+/// Query parameters for the delivery log: ?status=failed&limit=100
+#[derive(Debug, Deserialize, Default)]
+pub struct DeliveryQuery {
+    pub status: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// Deliveries of a webhook, newest first.
+pub async fn webhook_deliveries(
+    pool: &PgPool,
+    webhook_id: Uuid,
+    query: &DeliveryQuery,
+) -> Result<Vec<Value>, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT json_build_object('id', d.id, 'event_id', e.id, 'event_type', e.event_type, \
+            'entity_type', e.entity_type, 'entity_id', e.entity_id, 'status', d.status, \
+            'attempts', d.attempts, 'response_status', d.response_status, \
+            'last_error', d.last_error, 'next_attempt_at', d.next_attempt_at, \
+            'delivered_at', d.delivered_at, 'created_at', d.created_at) \
+         FROM admin_webhook_deliveries d \
+         JOIN admin_webhook_events e ON e.id = d.event_id \
+         WHERE d.webhook_id = $1 AND ($2::text IS NULL OR d.status = $2) \
+         ORDER BY d.created_at DESC LIMIT $3",
+    )
+    .bind(webhook_id)
+    .bind(query.status.as_deref().filter(|s| !s.is_empty()))
+    .bind(query.limit.unwrap_or(100).clamp(1, 500))
+    .fetch_all(pool)
+    .await
+}
+
+/// One delivery with the event payload and every attempt made.
+pub async fn delivery_detail(pool: &PgPool, id: Uuid) -> Result<Value, AdminError> {
+    sqlx::query_scalar(
+        "SELECT json_build_object('id', d.id, 'webhook_id', d.webhook_id, \
+            'status', d.status, 'attempts', d.attempts, 'next_attempt_at', d.next_attempt_at, \
+            'delivered_at', d.delivered_at, 'created_at', d.created_at, \
+            'event', json_build_object('id', e.id, 'event_type', e.event_type, \
+                'payload', e.payload, 'created_at', e.created_at), \
+            'log', COALESCE((SELECT json_agg(json_build_object('attempt', a.attempt, \
+                    'response_status', a.response_status, 'response_body', a.response_body, \
+                    'error', a.error, 'duration_ms', a.duration_ms, 'created_at', a.created_at) \
+                ORDER BY a.attempt) FROM admin_webhook_attempts a WHERE a.delivery_id = d.id), '[]')) \
+         FROM admin_webhook_deliveries d \
+         JOIN admin_webhook_events e ON e.id = d.event_id \
+         WHERE d.id = $1",
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AdminError::NotFound("delivery".to_string()))
+}
+
+/// Send a delivery again on the next run, with a fresh set of attempts.
+pub async fn retry_delivery(pool: &PgPool, id: Uuid) -> Result<Value, AdminError> {
+    let updated = sqlx::query(
+        "UPDATE admin_webhook_deliveries SET status = 'pending', attempts = 0, \
+            next_attempt_at = NOW(), updated_at = NOW() \
+         WHERE id = $1 AND status <> 'pending'",
+    )
+    .bind(id)
+    .execute(pool)
+    .await?;
+    if updated.rows_affected() == 0 {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM admin_webhook_deliveries WHERE id = $1)",
+        )
+        .bind(id)
+        .fetch_one(pool)
+        .await?;
+        if !exists {
+            return Err(AdminError::NotFound("delivery".to_string()));
+        }
+    }
+    delivery_detail(pool, id).await
+}
+
+// ── Delivery ────────────────────────────────────────────────────────────
+
+#[derive(Debug, sqlx::FromRow)]
+struct DueDelivery {
+    id: Uuid,
+    attempts: i32,
+    url: String,
+    secret: String,
+    event_id: Uuid,
+    event_type: String,
+    payload: Value,
+    created_at: DateTime<Utc>,
+}
+
+/// Outcome of one delivery run.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DeliveryStats {
+    pub delivered: u64,
+    pub retrying: u64,
+    pub failed: u64,
+}
+
+/// Send every due delivery, up to one batch, with a client from
+/// [`delivery_client`]. Deliveries are claimed with a lease so concurrent
+/// runs never send the same one twice. A delivery whose attempt cannot be
+/// recorded is logged and skipped; it is sent again once its lease expires.
+pub async fn deliver_pending(
+    pool: &PgPool,
+    client: &reqwest::Client,
+) -> Result<DeliveryStats, sqlx::Error> {
+    let due: Vec<DueDelivery> = sqlx::query_as(&format!(
+        "WITH claimed AS (\
+            UPDATE admin_webhook_deliveries SET next_attempt_at = NOW() + INTERVAL '{CLAIM_LEASE}' \
+            WHERE id IN (\
+                SELECT d.id FROM admin_webhook_deliveries d \
+                JOIN admin_webhooks w ON w.id = d.webhook_id AND w.is_active \
+                WHERE d.status = 'pending' AND d.next_attempt_at <= NOW() \
+                ORDER BY d.next_attempt_at LIMIT $1 \
+                FOR UPDATE OF d SKIP LOCKED) \
+            RETURNING id, webhook_id, event_id, attempts\
+         ) \
+         SELECT c.id, c.attempts, w.url, w.secret, e.id AS event_id, e.event_type, \
+            e.payload, e.created_at \
+         FROM claimed c \
+         JOIN admin_webhooks w ON w.id = c.webhook_id \
+         JOIN admin_webhook_events e ON e.id = c.event_id"
+    ))
+    .bind(BATCH_SIZE)
+    .fetch_all(pool)
+    .await?;
+
+    let mut stats = DeliveryStats::default();
+    if due.is_empty() {
+        return Ok(stats);
+    }
+    for delivery in &due {
+        match send(pool, client, delivery).await {
+            Ok(DeliveryState::Delivered) => stats.delivered += 1,
+            Ok(DeliveryState::Retrying) => stats.retrying += 1,
+            Ok(DeliveryState::Failed) => stats.failed += 1,
+            Err(e) => tracing::error!(
+                delivery_id = %delivery.id,
+                event = %delivery.event_type,
+                error = %e,
+                "Could not record webhook delivery attempt"
+            ),
+        }
+    }
+    Ok(stats)
+}
+
+enum DeliveryState {
+    Delivered,
+    Retrying,
+    Failed,
+}
+
+/// POST one delivery and record the attempt.
+async fn send(
+    pool: &PgPool,
+    client: &reqwest::Client,
+    delivery: &DueDelivery,
+) -> Result<DeliveryState, sqlx::Error> {
+    let started = Instant::now();
+    let result = post(client, delivery).await;
+    let (status_code, response_body, error) = match result {
+        Ok(response) => {
+            let status = response.status();
+            let text = read_excerpt(response).await;
+            let error = (!status.is_success()).then(|| format!("HTTP {status}"));
+            (Some(i32::from(status.as_u16())), text, error)
+        }
+        Err(e) => (None, String::new(), Some(e)),
+    };
+    let duration_ms = i32::try_from(started.elapsed().as_millis()).unwrap_or(i32::MAX);
+    let attempt = delivery.attempts + 1;
+
+    let state = match &error {
+        None => DeliveryState::Delivered,
+        Some(_) if attempt >= MAX_ATTEMPTS => DeliveryState::Failed,
+        Some(_) => DeliveryState::Retrying,
+    };
+    let (status, delivered) = match state {
+        DeliveryState::Delivered => ("delivered", true),
+        DeliveryState::Retrying => ("pending", false),
+        DeliveryState::Failed => ("failed", false),
+    };
+
+    let mut tx = pool.begin().await?;
+    sqlx::query(
+        "INSERT INTO admin_webhook_attempts \
+            (delivery_id, attempt, response_status, response_body, error, duration_ms) \
+         VALUES ($1, $2, $3, $4, $5, $6)",
+    )
+    .bind(delivery.id)
+    .bind(attempt)
+    .bind(status_code)
+    .bind(&response_body)
+    .bind(error.as_deref())
+    .bind(duration_ms)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query(
+        "UPDATE admin_webhook_deliveries SET status = $2, attempts = $3, \
+            response_status = $4, last_error = $5, \
+            next_attempt_at = NOW() + make_interval(secs => $6), \
+            delivered_at = CASE WHEN $7 THEN NOW() ELSE delivered_at END, \
+            updated_at = NOW() \
+         WHERE id = $1",
+    )
+    .bind(delivery.id)
+    .bind(status)
+    .bind(attempt)
+    .bind(status_code)
+    .bind(error.as_deref())
+    .bind(backoff_secs(attempt))
+    .bind(delivered)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    if let Some(error) = &error {
+        tracing::warn!(
+            delivery_id = %delivery.id,
+            event = %delivery.event_type,
+            attempt,
+            error = %error,
+            "Webhook delivery failed"
+        );
+    }
+    Ok(state)
+}
+
+/// The start of a response body, reading no more than [`RESPONSE_EXCERPT`]
+/// bytes of it. A body that fails midway keeps what arrived.
+async fn read_excerpt(mut response: reqwest::Response) -> String {
+    let mut body = Vec::new();
+    while body.len() < RESPONSE_EXCERPT {
+        match response.chunk().await {
+            Ok(Some(chunk)) => {
+                let wanted = chunk.len().min(RESPONSE_EXCERPT - body.len());
+                body.extend_from_slice(&chunk[..wanted]);
+            }
+            Ok(None) | Err(_) => break,
+        }
+    }
+    excerpt(body)
+}
+
+/// Up to [`RESPONSE_EXCERPT`] bytes of `body` as text, cut at a character
+/// boundary.
+fn excerpt(mut body: Vec<u8>) -> String {
+    body.truncate(RESPONSE_EXCERPT);
+    if let Err(e) = std::str::from_utf8(&body) {
+        // A character split by the cut is dropped; invalid bytes elsewhere
+        // are replaced
+        if e.error_len().is_none() {
+            body.truncate(e.valid_up_to());
+        }
+    }
+    String::from_utf8_lossy(&body).into_owned()
+}
+
+/// An error followed by its sources, so a refused address or a TLS failure
+/// shows in the attempt log rather than a generic "error sending request".
+fn error_chain(e: &dyn std::error::Error) -> String {
+    let mut message = e.to_string();
+    let mut source = e.source();
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    message
+}
+
+/// POST a delivery to its webhook, refusing URLs that are no longer public.
+///
+/// The body is `{id, event, created_at, data}`. Receivers verify
+/// `X-Webhook-Signature: sha256=<hex>`, the HMAC-SHA256 of
+/// `<X-Webhook-Timestamp>.<body>` keyed with the webhook secret.
+async fn post(
+    client: &reqwest::Client,
+    delivery: &DueDelivery,
+) -> Result<reqwest::Response, String> {
+    let url = reqwest::Url::parse(&delivery.url).map_err(|e| e.to_string())?;
+    check_host(&url)?;
+    let body = json!({
+        "id": delivery.event_id,
+        "event": delivery.event_type,
+        "created_at": delivery.created_at,
+        "data": delivery.payload,
+    })
+    .to_string();
+    let timestamp = Utc::now().timestamp().to_string();
+    let signature = sign(&delivery.secret, &timestamp, &body);
+    client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header("X-Webhook-Id", delivery.event_id.to_string())
+        .header("X-Webhook-Event", &delivery.event_type)
+        .header("X-Webhook-Timestamp", &timestamp)
+        .header("X-Webhook-Signature", format!("sha256={signature}"))
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| error_chain(&e))
+}
+
+/// Hex HMAC-SHA256 of `<timestamp>.<body>`.
+fn sign(secret: &str, timestamp: &str, body: &str) -> String {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Delay before the attempt after `attempt`: 1, 2, 4 … minutes, capped at 6 hours.
+#[allow(clippy::cast_precision_loss)]
+fn backoff_secs(attempt: i32) -> f64 {
+    let exponent = u32::try_from(attempt.saturating_sub(1))
+        .unwrap_or(0)
+        .min(16);
+    (BASE_BACKOFF_SECS.saturating_mul(1 << exponent)).min(MAX_BACKOFF_SECS) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn excerpt_keeps_short_bodies_whole() {
+        assert_eq!(excerpt(b"{\"ok\":true}".to_vec()), "{\"ok\":true}");
+        assert_eq!(excerpt(Vec::new()), "");
+    }
+
+    #[test]
+    fn excerpt_is_capped_in_bytes_at_a_character_boundary() {
+        let long = "a".repeat(RESPONSE_EXCERPT + 50);
+        assert_eq!(excerpt(long.into_bytes()).len(), RESPONSE_EXCERPT);
+
+        // "ñ" is two bytes; the one straddling the cut is dropped
+        let mut body = "a".repeat(RESPONSE_EXCERPT - 1).into_bytes();
+        body.extend_from_slice("ñ".as_bytes());
+        let text = excerpt(body);
+        assert_eq!(text.len(), RESPONSE_EXCERPT - 1);
+        assert!(text.chars().all(|c| c == 'a'));
+    }
+
+    #[test]
+    fn excerpt_replaces_invalid_bytes() {
+        assert_eq!(excerpt(b"ok \xff end".to_vec()), "ok \u{fffd} end");
+    }
+
+    #[test]
+    fn signs_timestamp_and_body() {
+        assert_eq!(
+            sign("secret", "1700000000", r#"{"event":"invoice.paid"}"#),
+            "76b976c071e576b6ea563a824c1df8340f9d3358de82cc5aa7f38997e8392e8a"
+        );
+    }
+
+    #[test]
+    fn audit_events_name_the_entity_and_action() {
+        assert_eq!(
+            audit_events("properties", "create", None, Some(&json!({}))),
+            ["property.created"]
+        );
+        assert_eq!(
+            audit_events("payout_batches", "delete", None, None),
+            ["payout_batch.deleted"]
+        );
+        assert_eq!(
+            audit_events("contracts", "sign", None, None),
+            ["contract.sign"]
+        );
+        assert!(audit_events("invoices", "export", None, None).is_empty());
+        assert_eq!(
+            audit_events(
+                "payout_batches",
+                "export",
+                Some(&json!({ "status": "Approved" })),
+                Some(&json!({ "status": "Exported" })),
+            ),
+            ["payout_batch.exported", "payout_batch.status_changed"]
+        );
+    }
+
+    #[test]
+    fn audit_events_report_status_changes_and_payment() {
+        let draft = json!({ "status": "Draft" });
+        let sent = json!({ "status": "Sent" });
+        let paid = json!({ "status": "Paid" });
+        assert_eq!(
+            audit_events("contracts", "update", Some(&draft), Some(&sent)),
+            ["contract.updated", "contract.status_changed"]
+        );
+        assert_eq!(
+            audit_events("invoices", "update", Some(&sent), Some(&paid)),
+            ["invoice.updated", "invoice.status_changed", "invoice.paid"]
+        );
+        assert_eq!(
+            audit_events("invoices", "update", Some(&paid), Some(&paid)),
+            ["invoice.updated"]
+        );
+        // A created invoice has no previous status to change from
+        assert_eq!(
+            audit_events("invoices", "create", None, Some(&paid)),
+            ["invoice.created", "invoice.paid"]
+        );
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        assert!((backoff_secs(1) - 60.0).abs() < f64::EPSILON);
+        assert!((backoff_secs(2) - 120.0).abs() < f64::EPSILON);
+        assert!((backoff_secs(4) - 480.0).abs() < f64::EPSILON);
+        assert!((backoff_secs(0) - 60.0).abs() < f64::EPSILON);
+        assert!((backoff_secs(30) - 21_600.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn only_public_addresses_are_public() {
+        for ip in ["8.8.8.8", "2606:4700::1111", "100.128.0.1"] {
+            assert!(is_public(ip.parse().unwrap()), "{ip}");
+        }
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "192.0.2.10",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public(ip.parse().unwrap()), "{ip}");
+        }
+    }
+
+    #[test]
+    fn check_host_refuses_local_hosts() {
+        let check = |url: &str| check_host(&reqwest::Url::parse(url).unwrap());
+        assert!(check("https://hooks.example.com/in").is_ok());
+        assert!(check("https://93.184.216.34/in").is_ok());
+        assert!(check("http://localhost:8080/").is_err());
+        assert!(check("http://api.localhost/").is_err());
+        assert!(check("http://127.0.0.1/").is_err());
+        assert!(check("http://[::1]/").is_err());
+        assert!(check("http://169.254.169.254/latest/meta-data").is_err());
+    }
+}
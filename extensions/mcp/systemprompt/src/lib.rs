@@ -1,4 +1,5 @@
 mod cli;
+mod property;
 pub mod server;
 pub mod tools;
 
@@ -0,0 +1,80 @@
+use crate::tools::{CREATE_ISSUE, DRAFT_INVOICE, GET_CONTRACT, LIST_ARREARS, SEARCH_PROPERTIES};
+use rmcp::model::CallToolResult;
+use rmcp::ErrorData as McpError;
+use serde::de::DeserializeOwned;
+use systemprompt::database::DbPool;
+use systemprompt_admin_extension::api::actor::Actor;
+use systemprompt_admin_extension::error::AdminError;
+use systemprompt_admin_extension::services::agent;
+use systemprompt_admin_extension::services::reporting::ReportFilter;
+
+/// Run one of the property-management tools as the token's user.
+///
+/// Invalid input, unknown records and rejected writes come back as tool
+/// errors the model can correct; database failures are protocol errors.
+pub async fn execute(
+    db_pool: &DbPool,
+    tool_name: &str,
+    arguments: serde_json::Map<String, serde_json::Value>,
+    auth_token: &str,
+) -> Result<CallToolResult, McpError> {
+    let pool = db_pool
+        .pool()
+        .ok_or_else(|| McpError::internal_error("PgPool not available from database", None))?;
+    let actor = Actor::from_token(auth_token);
+
+    let result = match tool_name {
+        SEARCH_PROPERTIES => {
+            let input: agent::PropertySearch = parse(arguments)?;
+            agent::search_properties(&pool, &input)
+                .await
+                .map(|properties| {
+                    serde_json::json!({ "count": properties.len(), "properties": properties })
+                })
+                .map_err(AdminError::from)
+        }
+        GET_CONTRACT => {
+            let input: agent::ContractLookup = parse(arguments)?;
+            agent::contract_detail(&pool, &input).await
+        }
+        LIST_ARREARS => {
+            let input: ReportFilter = parse(arguments)?;
+            agent::arrears_summary(&pool, input).await
+        }
+        CREATE_ISSUE => {
+            let input: agent::IssueRequest = parse(arguments)?;
+            agent::create_issue(&pool, &actor, &input).await
+        }
+        DRAFT_INVOICE => {
+            let input: agent::InvoiceDraftRequest = parse(arguments)?;
+            agent::draft_invoice(&pool, &actor, &input).await
+        }
+        _ => {
+            return Err(McpError::invalid_params(
+                format!("Unknown property tool: '{tool_name}'"),
+                None,
+            ))
+        }
+    };
+
+    match result {
+        Ok(output) => Ok(CallToolResult::structured(output)),
+        Err(AdminError::Database(e)) => {
+            tracing::error!(error = %e, tool = %tool_name, "Property tool failed");
+            Err(McpError::internal_error(
+                format!("Database error: {e}"),
+                None,
+            ))
+        }
+        Err(e) => Ok(CallToolResult::structured_error(
+            serde_json::json!({ "error": e.to_string() }),
+        )),
+    }
+}
+
+fn parse<T: DeserializeOwned>(
+    arguments: serde_json::Map<String, serde_json::Value>,
+) -> Result<T, McpError> {
+    serde_json::from_value(serde_json::Value::Object(arguments))
+        .map_err(|e| McpError::invalid_params(format!("Invalid input parameters: {e}"), None))
+}
@@ -1,5 +1,6 @@
 use crate::cli;
-use crate::tools::{self, CliInput, SERVER_NAME};
+use crate::property;
+use crate::tools::{self, CliInput, PROPERTY_TOOLS, SERVER_NAME};
 use anyhow::Result;
 use rmcp::model::{
     CallToolRequestParams, CallToolResult, Icon, Implementation, InitializeRequestParams,
@@ -67,7 +68,9 @@ impl ServerHandler for SystempromptServer {
             instructions: Some(
                 "Execute SystemPrompt CLI commands. MANDATORY FIRST STEP: Run 'core playbooks show guide_start' \
                  before any task. Playbooks: 'core playbooks show <id>' or 'core playbooks list'. \
-                 Discord: 'plugins run discord send \"message\"'. Full documentation: https://systemprompt.io/playbooks"
+                 Discord: 'plugins run discord send \"message\"'. Property management: search_properties, \
+                 get_contract, list_arrears, create_issue and draft_invoice (save a draft only once the \
+                 user confirms it). Full documentation: https://systemprompt.io/playbooks"
                     .to_string(),
             ),
         }
@@ -121,6 +124,9 @@ impl ServerHandler for SystempromptServer {
                 )
                 .await
             }
+            name if PROPERTY_TOOLS.contains(&name) => {
+                property::execute(&self.db_pool, name, arguments, auth_result.token()).await
+            }
             _ => Err(McpError::invalid_params(
                 format!(
                     "Unknown tool: '{tool_name}'\n\nMANDATORY FIRST STEP: Run 'core playbooks show \
@@ -1,4 +1,4 @@
-use rmcp::model::{Meta, Tool};
+use rmcp::model::{Meta, Tool, ToolAnnotations};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use systemprompt::mcp::{default_tool_visibility, tool_ui_meta};
@@ -6,6 +6,21 @@ use systemprompt::models::artifacts::{CliArtifact, ToolResponse};
 
 pub const SERVER_NAME: &str = "systemprompt";
 
+pub const SEARCH_PROPERTIES: &str = "search_properties";
+pub const GET_CONTRACT: &str = "get_contract";
+pub const LIST_ARREARS: &str = "list_arrears";
+pub const CREATE_ISSUE: &str = "create_issue";
+pub const DRAFT_INVOICE: &str = "draft_invoice";
+
+/// Tools backed by the admin property-management services.
+pub const PROPERTY_TOOLS: &[&str] = &[
+    SEARCH_PROPERTIES,
+    GET_CONTRACT,
+    LIST_ARREARS,
+    CREATE_ISSUE,
+    DRAFT_INVOICE,
+];
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CliInput {
     pub command: String,
@@ -44,7 +59,14 @@ pub fn output_schema() -> serde_json::Value {
 
 #[must_use]
 pub fn list_tools() -> Vec<Tool> {
-    vec![create_cli_tool()]
+    vec![
+        create_cli_tool(),
+        create_search_properties_tool(),
+        create_get_contract_tool(),
+        create_list_arrears_tool(),
+        create_issue_tool(),
+        create_draft_invoice_tool(),
+    ]
 }
 
 fn create_cli_tool() -> Tool {
@@ -85,3 +107,333 @@ fn create_cli_tool() -> Tool {
         meta: Some(create_ui_meta()),
     }
 }
+
+fn property_tool(
+    name: &str,
+    title: &str,
+    description: &str,
+    input: &serde_json::Value,
+    output: &serde_json::Value,
+    annotations: ToolAnnotations,
+) -> Tool {
+    let input_obj = input
+        .as_object()
+        .cloned()
+        .expect("input schema must be a JSON object");
+    let output_obj = output
+        .as_object()
+        .cloned()
+        .expect("output schema must be a JSON object");
+
+    Tool {
+        name: name.to_string().into(),
+        title: Some(title.to_string()),
+        description: Some(description.to_string().into()),
+        input_schema: Arc::new(input_obj),
+        output_schema: Some(Arc::new(output_obj)),
+        annotations: Some(annotations.with_title(title)),
+        icons: None,
+        meta: None,
+    }
+}
+
+fn read_only() -> ToolAnnotations {
+    ToolAnnotations::new()
+        .read_only(true)
+        .destructive(false)
+        .open_world(false)
+}
+
+fn contract_lookup_properties() -> serde_json::Value {
+    serde_json::json!({
+        "contract_id": {
+            "type": "string",
+            "format": "uuid",
+            "description": "Contract id"
+        },
+        "contract_ref": {
+            "type": "string",
+            "description": "Contract reference, e.g. 'KING ST 15 - JOHNSON' (case-insensitive)"
+        }
+    })
+}
+
+#[must_use]
+pub fn search_properties_input_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "query": {
+                "type": "string",
+                "description": "Words in the property name, address or contract reference, e.g. 'Flat 3B' or 'Deansgate'. Accents and case are ignored."
+            },
+            "status": {
+                "type": "string",
+                "description": "Only properties with this status, e.g. 'Available' or 'Let'"
+            },
+            "limit": {
+                "type": "integer",
+                "minimum": 1,
+                "maximum": 50,
+                "default": 10
+            }
+        }
+    })
+}
+
+#[must_use]
+pub fn search_properties_output_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "count": { "type": "integer" },
+            "properties": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "id": { "type": "string", "format": "uuid" },
+                        "property_name": { "type": "string" },
+                        "address": { "type": "string" },
+                        "status": { "type": "string" },
+                        "rent": { "type": "number" },
+                        "bedrooms": { "type": ["integer", "null"] },
+                        "bathrooms": { "type": ["integer", "null"] },
+                        "sqm": { "type": ["number", "null"] },
+                        "contract_ref": { "type": "string" },
+                        "tenant_name": { "type": ["string", "null"] },
+                        "tags": { "type": ["array", "null"], "items": { "type": "string" } }
+                    }
+                }
+            }
+        },
+        "required": ["count", "properties"]
+    })
+}
+
+fn create_search_properties_tool() -> Tool {
+    property_tool(
+        SEARCH_PROPERTIES,
+        "Search properties",
+        "Find properties by name, address or contract reference, optionally by status. \
+         Returns rent, size and the current tenant of each match.",
+        &search_properties_input_schema(),
+        &search_properties_output_schema(),
+        read_only(),
+    )
+}
+
+#[must_use]
+pub fn get_contract_input_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": contract_lookup_properties(),
+        "description": "Give contract_id or contract_ref"
+    })
+}
+
+#[must_use]
+pub fn get_contract_output_schema() -> serde_json::Value {
+    let list = serde_json::json!({ "type": "array", "items": { "type": "object" } });
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "contract": {
+                "type": "object",
+                "description": "The contract record: contract_ref, property_name, tenant_name, status, rent, start_date, end_date, ..."
+            },
+            "documents": list,
+            "deposits": list,
+            "invoices": { "type": "array", "items": { "type": "object" }, "description": "Latest 12 invoices" },
+            "open_issues": { "type": "array", "items": { "type": "object" }, "description": "Unresolved issues on the contract's property" }
+        },
+        "required": ["contract", "documents", "deposits", "invoices", "open_issues"]
+    })
+}
+
+fn create_get_contract_tool() -> Tool {
+    property_tool(
+        GET_CONTRACT,
+        "Get contract",
+        "Contract detail by id or reference: terms, documents, deposits, latest invoices \
+         and open issues on the property.",
+        &get_contract_input_schema(),
+        &get_contract_output_schema(),
+        read_only(),
+    )
+}
+
+#[must_use]
+pub fn list_arrears_input_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "from": { "type": "string", "format": "date", "description": "Invoices dated from (YYYY-MM-DD)" },
+            "to": { "type": "string", "format": "date", "description": "Invoices dated until (YYYY-MM-DD)" },
+            "property": { "type": "string", "description": "Exact property name" },
+            "owner": { "type": "string", "description": "Exact owner name" },
+            "tag": { "type": "string", "description": "Property tag" }
+        }
+    })
+}
+
+#[must_use]
+pub fn list_arrears_output_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "data": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "payer": { "type": "string", "description": "Tenant" },
+                        "property_name": { "type": "string" },
+                        "outstanding_invoices": { "type": "integer" },
+                        "total_outstanding": { "type": "number" },
+                        "total_paid": { "type": "number" },
+                        "debt": { "type": "number" },
+                        "oldest_date": { "type": ["string", "null"], "format": "date" }
+                    }
+                }
+            },
+            "total_debt": { "type": "number" },
+            "filters": { "type": "object" }
+        },
+        "required": ["data", "total_debt"]
+    })
+}
+
+fn create_list_arrears_tool() -> Tool {
+    property_tool(
+        LIST_ARREARS,
+        "List arrears",
+        "Tenants with unpaid or partly paid rent, by property, largest debt first, \
+         with the total owed.",
+        &list_arrears_input_schema(),
+        &list_arrears_output_schema(),
+        read_only(),
+    )
+}
+
+#[must_use]
+pub fn create_issue_input_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "property": {
+                "type": "string",
+                "description": "Property name or a unique part of it, e.g. 'Flat 3B'"
+            },
+            "title": { "type": "string", "description": "Short summary, e.g. 'Leak under the kitchen sink'" },
+            "description": { "type": "string" },
+            "priority": { "type": "string", "enum": ["High", "Medium", "Low"], "default": "Medium" }
+        },
+        "required": ["property", "title"]
+    })
+}
+
+#[must_use]
+pub fn create_issue_output_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "id": { "type": "string", "format": "uuid" },
+            "property_name": { "type": "string" },
+            "title": { "type": "string" },
+            "description": { "type": "string" },
+            "priority": { "type": "string" },
+            "status": { "type": "string" },
+            "created_at": { "type": "string", "format": "date-time" }
+        },
+        "required": ["id", "property_name", "title", "status"]
+    })
+}
+
+fn create_issue_tool() -> Tool {
+    property_tool(
+        CREATE_ISSUE,
+        "Create issue",
+        "Log a maintenance issue on a property. The change is recorded in the audit log.",
+        &create_issue_input_schema(),
+        &create_issue_output_schema(),
+        ToolAnnotations::new()
+            .read_only(false)
+            .destructive(false)
+            .idempotent(false)
+            .open_world(false),
+    )
+}
+
+#[must_use]
+pub fn draft_invoice_input_schema() -> serde_json::Value {
+    let mut properties = contract_lookup_properties();
+    properties["invoice_date"] = serde_json::json!({
+        "type": "string",
+        "format": "date",
+        "description": "Defaults to today"
+    });
+    properties["amount"] = serde_json::json!({
+        "type": "number",
+        "exclusiveMinimum": 0,
+        "description": "Defaults to the contract rent"
+    });
+    properties["description"] = serde_json::json!({
+        "type": "string",
+        "description": "Defaults to 'Monthly rent - <property>'"
+    });
+    properties["save"] = serde_json::json!({
+        "type": "boolean",
+        "default": false,
+        "description": "Record the invoice. Leave false to get the draft for the user to review first."
+    });
+    serde_json::json!({
+        "type": "object",
+        "properties": properties,
+        "description": "Give contract_id or contract_ref"
+    })
+}
+
+#[must_use]
+pub fn draft_invoice_output_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "saved": { "type": "boolean" },
+            "invoice": {
+                "type": "object",
+                "properties": {
+                    "id": { "type": "string", "format": "uuid", "description": "Only once saved" },
+                    "reference": { "type": "string" },
+                    "description": { "type": "string" },
+                    "contract_ref": { "type": "string" },
+                    "property_name": { "type": "string" },
+                    "payer": { "type": "string" },
+                    "payee": { "type": "string" },
+                    "status": { "type": "string" },
+                    "amount": { "type": "number" },
+                    "invoice_date": { "type": "string", "format": "date" },
+                    "type": { "type": "string" }
+                }
+            }
+        },
+        "required": ["saved", "invoice"]
+    })
+}
+
+fn create_draft_invoice_tool() -> Tool {
+    property_tool(
+        DRAFT_INVOICE,
+        "Draft invoice",
+        "Prepare a rent invoice for a contract: the tenant pays the property's owner, \
+         for the contract rent unless an amount is given. Nothing is recorded unless \
+         save is true; show the draft to the user and save once they confirm.",
+        &draft_invoice_input_schema(),
+        &draft_invoice_output_schema(),
+        ToolAnnotations::new()
+            .read_only(false)
+            .destructive(false)
+            .idempotent(false)
+            .open_world(false),
+    )
+}
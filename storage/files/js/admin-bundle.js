@@ -42,18 +42,111 @@ function confirmAction(title, message) {
     });
 }
 
+/**
+ * Merge dialog for a save rejected with 409: lists the fields where the
+ * values being saved differ from the record as now stored and lets the user
+ * pick one of each. Resolves to the merged values, or null when cancelled.
+ */
+const ConflictDialog = {
+    _same(a, b) {
+        return JSON.stringify(a ?? '') === JSON.stringify(b ?? '');
+    },
+    _text(value) {
+        if (value == null || value === '') return '<em>empty</em>';
+        return window.AdminApp.escapeHtml(typeof value === 'object' ? JSON.stringify(value) : String(value));
+    },
+    resolve(mine, current) {
+        const fields = Object.keys(mine).filter(key => key in current && !this._same(mine[key], current[key]));
+        if (!fields.length) return Promise.resolve({ ...mine });
+        return new Promise(resolve => {
+            const overlay = document.createElement('div');
+            overlay.className = 'confirm-overlay';
+            overlay.innerHTML = `
+                <div class="confirm-dialog conflict-dialog">
+                    <h3>Record changed by someone else</h3>
+                    <p>Choose which value to keep for each field that differs.</p>
+                    <table class="conflict-fields">
+                        <thead><tr><th>Field</th><th>Yours</th><th>Current</th></tr></thead>
+                        <tbody>${fields.map(key => `
+                            <tr>
+                                <td>${this._text(key)}</td>
+                                <td><label><input type="radio" name="conflict-${this._text(key)}" data-field="${this._text(key)}" value="mine" checked> ${this._text(mine[key])}</label></td>
+                                <td><label><input type="radio" name="conflict-${this._text(key)}" data-field="${this._text(key)}" value="current"> ${this._text(current[key])}</label></td>
+                            </tr>`).join('')}
+                        </tbody>
+                    </table>
+                    <div class="actions">
+                        <button class="btn btn-secondary" data-action="cancel">Cancel</button>
+                        <button class="btn btn-primary" data-action="save">Save merged</button>
+                    </div>
+                </div>`;
+            document.body.appendChild(overlay);
+            ScrollLock.lock();
+            const close = result => { overlay.remove(); ScrollLock.unlock(); resolve(result); };
+            overlay.querySelector('[data-action="cancel"]').onclick = () => close(null);
+            overlay.querySelector('[data-action="save"]').onclick = () => {
+                const merged = { ...mine };
+                overlay.querySelectorAll('input[type="radio"]:checked').forEach(input => {
+                    if (input.value === 'current') merged[input.dataset.field] = current[input.dataset.field];
+                });
+                close(merged);
+            };
+        });
+    }
+};
+
 const api = {
+    // Record revisions seen in responses, sent back as If-Match on PUT/DELETE.
+    // Records never read through the API are sent without a precondition, so
+    // the server rejects the write (428) rather than overwrite a newer version.
+    _versions: new Map(),
+    _track(path, body) {
+        const base = path.split('?')[0];
+        if (Array.isArray(body?.data)) {
+            for (const row of body.data) {
+                if (row && row.id && row.revision != null) this._versions.set(`${base}/${row.id}`, row.revision);
+            }
+            return;
+        }
+        // The record itself, or wrapped by a detail endpoint ({ contract: {...}, documents: [...] })
+        const id = base.split('/').pop();
+        for (const row of [body, ...Object.values(body || {})]) {
+            if (row && typeof row === 'object' && row.id === id && row.revision != null) {
+                this._versions.set(base, row.revision);
+                return;
+            }
+        }
+    },
+    _ifMatch(path) {
+        const revision = this._versions.get(path.split('?')[0]);
+        return revision != null ? { 'If-Match': `"${revision}"` } : {};
+    },
+    // A 409 carries the current record: remember its revision for the retry
+    _conflict(path, err) {
+        if (err.status !== 409 || !err.current || err.current.revision == null) return false;
+        this._versions.set(path.split('?')[0], err.current.revision);
+        return true;
+    },
     _handleResponse(res) {
         if (res.status === 401) {
             window.location.href = AdminApp.loginUrl(window.location.pathname);
             throw new Error('Unauthorized');
         }
-        if (!res.ok) { return res.json().catch(() => ({})).then(e => { throw new Error(e.error || res.statusText); }); }
+        if (!res.ok) {
+            return res.json().catch(() => ({})).then(e => {
+                const err = new Error(e.error || res.statusText);
+                err.status = res.status;
+                err.current = e.current;
+                throw err;
+            });
+        }
         return res.json();
     },
     async get(path) {
         const res = await fetch(`${API_BASE}${path}`);
-        return this._handleResponse(res);
+        const body = await this._handleResponse(res);
+        this._track(path, body);
+        return body;
     },
     async post(path, data) {
         const res = await fetch(`${API_BASE}${path}`, {
@@ -61,16 +154,43 @@ const api = {
         });
         return this._handleResponse(res);
     },
-    async put(path, data) {
+    async _put(path, data) {
         const res = await fetch(`${API_BASE}${path}`, {
-            method: 'PUT', headers: { 'Content-Type': 'application/json' }, body: JSON.stringify(data)
+            method: 'PUT',
+            headers: { 'Content-Type': 'application/json', ...this._ifMatch(path) },
+            body: JSON.stringify(data)
         });
+        const etag = res.ok && res.headers.get('ETag');
+        if (etag) this._versions.set(path.split('?')[0], Number(etag.replace(/\D/g, '')));
         return this._handleResponse(res);
     },
-    async del(path) {
-        const res = await fetch(`${API_BASE}${path}`, { method: 'DELETE' });
+    async put(path, data) {
+        try {
+            return await this._put(path, data);
+        } catch (err) {
+            if (!this._conflict(path, err)) throw err;
+            const merged = await ConflictDialog.resolve(data, err.current);
+            if (!merged) throw err;
+            return this._put(path, merged);
+        }
+    },
+    async _del(path) {
+        const res = await fetch(`${API_BASE}${path}`, { method: 'DELETE', headers: this._ifMatch(path) });
         return this._handleResponse(res);
     },
+    async del(path) {
+        try {
+            return await this._del(path);
+        } catch (err) {
+            if (!this._conflict(path, err)) throw err;
+            const confirmed = await confirmAction(
+                'Record changed by someone else',
+                'It was edited after you opened it. Delete the current version anyway?'
+            );
+            if (!confirmed) throw err;
+            return this._del(path);
+        }
+    },
     async uploadFile(path, file) {
         const formData = new FormData();
         formData.append('file', file);
@@ -158,7 +278,7 @@ const ScrollLock = {
 };
 
 const user = getUserInfo();
-window.AdminApp = { API_BASE, Toast, api, confirmAction, user, getUserInitials, initSidebar, ScrollLock };
+window.AdminApp = { API_BASE, Toast, api, confirmAction, ConflictDialog, user, getUserInitials, initSidebar, ScrollLock };
 
 (function(AdminApp) {
 
@@ -2522,7 +2642,7 @@ Object.assign(AdminApp, { DetailPanel, openDocumentModal });
 
 (function(AdminApp) {
 
-const { api, Toast, escapeHtml, formatDate, timeAgo, API_BASE } = AdminApp;
+const { api, Toast, escapeHtml, formatDate, timeAgo, API_BASE, confirmAction } = AdminApp;
 
 function getIdFromUrl() {
     return new URLSearchParams(window.location.search).get('id');
@@ -2619,6 +2739,53 @@ async function uploadCertificate(depositId, file) {
     }
 }
 
+/**
+ * Portal users linked to a tenant or owner record. `recordPath` is the
+ * record's API path, e.g. `/tenants/<id>`; `scope` says what the portal shows.
+ */
+async function renderPortalAccess(container, recordPath, scope) {
+    try {
+        const accounts = (await api.get(`${recordPath}/portal-access`)).data || [];
+        container.innerHTML = `<p class="text-sm mb-2">Users listed here sign in to the portal at <a href="/admin/portal/" target="_blank" class="text-accent">/admin/portal/</a> and see only ${escapeHtml(scope)}.</p>
+            ${accounts.length === 0 ? '<p class="text-tertiary text-sm mb-2">No portal users</p>' : `<table class="data-table mb-4"><thead><tr><th>User</th><th>Granted by</th><th>Granted</th><th></th></tr></thead><tbody>
+                ${accounts.map(a => `<tr>
+                    <td>${escapeHtml(a.user_id)}</td>
+                    <td>${escapeHtml(a.created_by || '-')}</td>
+                    <td>${formatDate(a.created_at)}</td>
+                    <td><button class="btn btn-secondary btn-sm portal-revoke" data-account-id="${a.id}">Revoke</button></td>
+                </tr>`).join('')}
+            </tbody></table>`}
+            <div class="flex gap-2">
+                <input id="portal-user-id" class="field-input" placeholder="User id">
+                <button class="btn btn-primary btn-sm" id="portal-grant">Grant access</button>
+            </div>`;
+
+        container.querySelector('#portal-grant').addEventListener('click', async () => {
+            const userId = container.querySelector('#portal-user-id').value.trim();
+            if (!userId) return;
+            try {
+                await api.put(`${recordPath}/portal-access`, { user_id: userId });
+                Toast.show('Portal access granted');
+            } catch (err) {
+                Toast.show(`Error: ${err.message}`, 'error');
+            }
+            renderPortalAccess(container, recordPath, scope);
+        });
+
+        container.querySelectorAll('.portal-revoke').forEach(btn => {
+            btn.addEventListener('click', async () => {
+                const ok = await confirmAction('Revoke portal access', 'The user will no longer be able to sign in to the portal as this record.');
+                if (!ok) return;
+                await api.del(`${recordPath}/portal-access/${btn.dataset.accountId}`);
+                Toast.show('Portal access revoked');
+                renderPortalAccess(container, recordPath, scope);
+            });
+        });
+    } catch (err) {
+        container.innerHTML = `<p class="text-danger">Error loading portal access: ${escapeHtml(err.message)}</p>`;
+    }
+}
+
 Object.assign(AdminApp, {
     getIdFromUrl,
     initTabs,
@@ -2626,6 +2793,7 @@ Object.assign(AdminApp, {
     formatFileSize,
     renderCertificateCard,
     uploadCertificate,
+    renderPortalAccess,
     entityDetailUrl: AdminApp.detailUrl,
 });
 
@@ -2848,6 +3016,7 @@ async function renderTenantDetail(container) {
             <button class="tab" data-tab="contracts">Contracts</button>
             <button class="tab" data-tab="invoices">Invoices</button>
             <button class="tab" data-tab="deposits">Deposits</button>
+            <button class="tab" data-tab="portal">Portal</button>
         </div>`;
 
         html += '<div class="tab-content">';
@@ -2887,6 +3056,7 @@ async function renderTenantDetail(container) {
         html += '<div class="tab-panel" data-panel="contracts"><div class="tab-table-root"></div></div>';
         html += '<div class="tab-panel" data-panel="invoices"><div class="tab-table-root"></div></div>';
         html += '<div class="tab-panel" data-panel="deposits"><div class="tab-table-root"></div></div>';
+        html += '<div class="tab-panel" data-panel="portal"><div class="tab-table-root"></div></div>';
         html += '</div>';
 
         el.innerHTML = html;
@@ -2935,6 +3105,9 @@ async function renderTenantDetail(container) {
                         ],
                         onRowClick: (row) => { AdminApp.navigateTo('deposit', row.id); }
                     });
+                case 'portal':
+                    AdminApp.renderPortalAccess(tabContainer, `/tenants/${id}`, "this tenant's contracts, invoices and issues");
+                    return null;
             }
         }, t);
 
@@ -3038,6 +3211,7 @@ async function renderOwnerDetail(container) {
             <button class="tab" data-tab="properties">Properties</button>
             <button class="tab" data-tab="income">Income</button>
             <button class="tab" data-tab="expenses">Expenses</button>
+            <button class="tab" data-tab="portal">Portal</button>
         </div>`;
 
         html += '<div class="tab-content">';
@@ -3110,6 +3284,7 @@ async function renderOwnerDetail(container) {
         html += '<div class="tab-panel" data-panel="properties"><div class="tab-table-root"></div></div>';
         html += '<div class="tab-panel" data-panel="income"><div class="tab-table-root"></div></div>';
         html += '<div class="tab-panel" data-panel="expenses"><div class="tab-table-root"></div></div>';
+        html += '<div class="tab-panel" data-panel="portal"><div class="tab-table-root"></div></div>';
         html += '</div>';
 
         el.innerHTML = html;
@@ -3158,6 +3333,9 @@ async function renderOwnerDetail(container) {
                         ],
                         onRowClick: (row) => { AdminApp.navigateTo('invoice', row.id); }
                     });
+                case 'portal':
+                    AdminApp.renderPortalAccess(tabContainer, `/owners/${p.id}`, "this owner's properties, statements, documents and open issues");
+                    return null;
             }
         }, p);
 
@@ -3351,7 +3529,7 @@ AdminApp.renderDepositDetail = renderDepositDetail;
 const {
     api, Toast, FormPanel,
     formatCurrency, formatDate,
-    escapeHtml, statusBadge,
+    escapeHtml, statusBadge, API_BASE,
 } = AdminApp;
 
 async function renderIssueDetail(container) {
@@ -3360,7 +3538,11 @@ async function renderIssueDetail(container) {
     if (!id) { el.innerHTML = '<div class="empty-state">No issue specified.</div>'; return; }
 
     try {
-        const d = await api.get(`/issues/${id}`);
+        const [d, photoData] = await Promise.all([
+            api.get(`/issues/${id}`),
+            api.get(`/issues/${id}/photos`),
+        ]);
+        const photos = photoData.data || [];
 
         let html = '';
 
@@ -3391,6 +3573,12 @@ async function renderIssueDetail(container) {
                 <h3>Description</h3>
                 <p class="note-text">${escapeHtml(d.descripcion)}</p>
             </div>` : ''}
+            ${photos.length ? `<div class="detail-info-section">
+                <h3>Photos${d.tenant_id ? ' (reported by the tenant)' : ''}</h3>
+                <div class="flex gap-2">
+                    ${photos.map(p => `<a href="${API_BASE}/issue-photos/${p.id}" target="_blank"><img src="${API_BASE}/issue-photos/${p.id}" alt="${escapeHtml(p.caption)}" class="cert-thumb"></a>`).join('')}
+                </div>
+            </div>` : ''}
         </div>`;
 
         el.innerHTML = html;
@@ -5688,6 +5876,7 @@ async function renderContractDetail(container) {
             <button class="tab" data-tab="deposits">Deposits</button>
             <button class="tab" data-tab="incidencias">Issues</button>
             <button class="tab" data-tab="documentos">Documents</button>
+            <button class="tab" data-tab="inspections">Inspections</button>
         </div>`;
 
         html += '<div class="tab-content">';
@@ -5698,6 +5887,7 @@ async function renderContractDetail(container) {
         html += '<div class="tab-panel" data-panel="deposits"><div class="tab-table-root"></div></div>';
         html += '<div class="tab-panel" data-panel="incidencias"><div class="tab-table-root"></div></div>';
         html += '<div class="tab-panel" data-panel="documentos"><div class="tab-table-root"></div></div>';
+        html += '<div class="tab-panel" data-panel="inspections"><div class="tab-table-root"></div></div>';
         html += '</div>';
 
         el.innerHTML = html;
@@ -6068,7 +6258,7 @@ const {
 function renderDocumentCard(doc) {
     const isImage = doc.file_type && doc.file_type.startsWith('image/');
     const hasFile = doc.original_file;
-    const downloadUrl = hasFile ? `${API_BASE}/contract_documents/${doc.id}/download` : null;
+    const downloadUrl = hasFile ? `${API_BASE}/contract-documents/${doc.id}/download` : null;
     const tipoLabel = doc.type || 'contract';
 
     let preview;
@@ -6100,21 +6290,41 @@ function renderDocumentCard(doc) {
             ${nameLink}
             <span class="cert-meta">${meta}</span>
         </div>
+        ${doc.template_id ? `<button class="btn btn-secondary btn-sm doc-sign" data-doc-id="${doc.id}">Send for signature</button>` : ''}
         <button class="btn-icon doc-delete" data-doc-id="${doc.id}" title="Delete">&times;</button>
     </div>`;
 }
 
+function renderSigningLinks(signers) {
+    const origin = window.location.origin;
+    return `<div class="cert-list mb-4">
+        <p class="text-sm mb-2">Send each signer their one-time link. The links are not shown again.</p>
+        ${signers.map(s => `<div class="mb-2">
+            <span class="text-sm">${escapeHtml(s.name)} (${escapeHtml(s.role)})${s.email ? ` &middot; ${escapeHtml(s.email)}` : ''}</span>
+            <input class="field-input" readonly value="${escapeHtml(origin + s.link)}" onclick="this.select()">
+        </div>`).join('')}
+    </div>`;
+}
+
 async function renderDocumentsTab(container, contract, renderContractDetail) {
     const el = typeof container === 'string' ? document.querySelector(container) : container;
     const contractId = contract.id;
 
     try {
-        const resp = await api.get(`/contract_documents?contract_id=${contractId}&per_page=100`);
+        const [resp, templatesResp] = await Promise.all([
+            api.get(`/contract-documents?contract_id=${contractId}`),
+            api.get('/document-templates'),
+        ]);
         const docs = resp.items || resp.data || [];
+        const templates = templatesResp.data || [];
 
         let html = `<div class="flex justify-between items-center mb-4">
             <h3>Contract documents</h3>
             <div class="flex gap-2">
+                ${templates.length ? `<select id="doc-template-select" class="field-input">
+                    ${templates.map(t => `<option value="${t.id}">${escapeHtml(t.name)} (${escapeHtml(t.kind)})</option>`).join('')}
+                </select>
+                <button class="btn btn-primary btn-sm" id="doc-generate-btn">Generate</button>` : ''}
                 <label class="btn btn-secondary btn-sm clickable">
                     <input type="file" id="doc-file-input" accept=".pdf,.jpg,.jpeg,.png,.webp,.doc,.docx" multiple class="hidden">
                     Upload file
@@ -6131,6 +6341,23 @@ async function renderDocumentsTab(container, contract, renderContractDetail) {
 
         el.innerHTML = html;
 
+        // Generate from template
+        el.querySelector('#doc-generate-btn')?.addEventListener('click', async () => {
+            const templateId = el.querySelector('#doc-template-select').value;
+            try {
+                const result = await api.post(`/contracts/${contractId}/documents/generate`, { template_id: templateId });
+                const missing = result.unresolved || [];
+                if (missing.length) {
+                    Toast.show(`Document generated; no value for ${missing.join(', ')}`);
+                } else {
+                    Toast.show('Document generated');
+                }
+            } catch (err) {
+                Toast.show(`Error: ${err.message}`, 'error');
+            }
+            renderDocumentsTab(container, contract, renderContractDetail);
+        });
+
         // File input upload
         el.querySelector('#doc-file-input')?.addEventListener('change', async (e) => {
             for (const file of e.target.files) {
@@ -6164,6 +6391,20 @@ async function renderDocumentsTab(container, contract, renderContractDetail) {
             });
         }
 
+        // Send for signature
+        el.querySelectorAll('.doc-sign').forEach(btn => {
+            btn.addEventListener('click', async (e) => {
+                e.stopPropagation();
+                try {
+                    const result = await api.post(`/contract-documents/${btn.dataset.docId}/signature-requests`, {});
+                    Toast.show('Signature request created');
+                    el.querySelector('#doc-list').insertAdjacentHTML('beforebegin', renderSigningLinks(result.signers || []));
+                } catch (err) {
+                    Toast.show(`Error: ${err.message}`, 'error');
+                }
+            });
+        });
+
         // Delete buttons
         el.querySelectorAll('.doc-delete').forEach(btn => {
             btn.addEventListener('click', async (e) => {
@@ -6171,7 +6412,7 @@ async function renderDocumentsTab(container, contract, renderContractDetail) {
                 const docId = btn.dataset.docId;
                 const ok = await confirmAction('Delete document', 'This action cannot be undone.');
                 if (ok) {
-                    await api.del(`/contract_documents/${docId}`);
+                    await api.del(`/contract-documents/${docId}`);
                     Toast.show('Document deleted');
                     renderDocumentsTab(container, contract, renderContractDetail);
                 }
@@ -6182,6 +6423,151 @@ async function renderDocumentsTab(container, contract, renderContractDetail) {
     }
 }
 
+const CONDITIONS = ['Missing', 'Damaged', 'Poor', 'Fair', 'Good', 'Excellent'];
+
+function inspectionLines(inspection) {
+    return (inspection?.items || []).map(i =>
+        [i.room, i.item, i.condition, i.replacement_cost ?? '', i.notes].join(' | ')
+    ).join('\n');
+}
+
+function parseInspectionLines(text) {
+    return text.split('\n').map(l => l.trim()).filter(Boolean).map(line => {
+        const [room, item, condition, cost, ...notes] = line.split('|').map(p => p.trim());
+        return {
+            room,
+            item,
+            condition: Number(condition),
+            replacement_cost: cost ? Number(cost) : null,
+            notes: notes.join(' | '),
+        };
+    });
+}
+
+function renderInspectionItems(inspection) {
+    if (!inspection) return '<p class="text-tertiary text-sm">Not recorded</p>';
+    return `<table class="data-table"><thead><tr><th>Room</th><th>Item</th><th>Condition</th><th>Notes</th><th>Photos</th></tr></thead><tbody>
+        ${inspection.items.map(i => `<tr>
+            <td>${escapeHtml(i.room)}</td>
+            <td>${escapeHtml(i.item)}</td>
+            <td>${CONDITIONS[i.condition] || i.condition}</td>
+            <td>${escapeHtml(i.notes)}</td>
+            <td>
+                ${i.photo_ids.map(p => `<a href="${API_BASE}/inspection-photos/${p}" target="_blank">photo</a>`).join(' ')}
+                <label class="btn btn-secondary btn-sm clickable">
+                    <input type="file" class="hidden inspection-photo" data-item-id="${i.id}" accept="image/jpeg,image/png,image/webp">
+                    Add
+                </label>
+            </td>
+        </tr>`).join('')}
+    </tbody></table>`;
+}
+
+async function renderInspectionsTab(container, contract) {
+    const el = typeof container === 'string' ? document.querySelector(container) : container;
+    const contractId = contract.id;
+
+    try {
+        const diff = await api.get(`/contracts/${contractId}/inspections/diff`);
+
+        let html = `<div class="flex justify-between items-center mb-4">
+            <h3>Inspections</h3>
+            <div class="flex gap-2">
+                <button class="btn btn-secondary btn-sm inspection-edit" data-kind="check_in">Edit check-in</button>
+                <button class="btn btn-secondary btn-sm inspection-edit" data-kind="check_out">Edit check-out</button>
+                ${diff.check_in || diff.check_out ? `<a class="btn btn-secondary btn-sm" href="${API_BASE}/contracts/${contractId}/inspections/pdf" target="_blank">Report PDF</a>` : ''}
+            </div>
+        </div>`;
+
+        if (diff.check_in && diff.check_out) {
+            html += `<h4 class="mb-2">Comparison</h4>
+            <table class="data-table mb-4"><thead><tr><th>Room</th><th>Item</th><th>Check-in</th><th>Check-out</th><th>Change</th><th>Deduction</th></tr></thead><tbody>
+                ${diff.changes.map(c => `<tr>
+                    <td>${escapeHtml(c.room)}</td>
+                    <td>${escapeHtml(c.item)}</td>
+                    <td>${c.check_in === null ? '&mdash;' : CONDITIONS[c.check_in]}</td>
+                    <td>${c.check_out === null ? '&mdash;' : CONDITIONS[c.check_out]}</td>
+                    <td>${escapeHtml(c.change)}</td>
+                    <td>${c.suggested_deduction ? c.suggested_deduction.toFixed(2) : ''}</td>
+                </tr>`).join('')}
+            </tbody></table>
+            <div class="flex justify-between items-center mb-4">
+                <span class="text-sm">Suggested deductions: ${diff.suggested_total.toFixed(2)}${diff.capped ? ' (capped at the deposit paid)' : ''}${diff.deposit ? ` &middot; deposit paid ${Number(diff.deposit.paid).toFixed(2)}` : ' &middot; no deposit recorded'}</span>
+                ${diff.deposit ? '<button class="btn btn-primary btn-sm" id="inspection-apply">Apply to deposit</button>' : ''}
+            </div>`;
+        }
+
+        html += `<h4 class="mb-2">Check-in</h4>${renderInspectionItems(diff.check_in)}
+            <h4 class="mb-2 mt-4">Check-out</h4>${renderInspectionItems(diff.check_out)}`;
+        el.innerHTML = html;
+
+        el.querySelectorAll('.inspection-edit').forEach(btn => {
+            btn.addEventListener('click', () => {
+                const kind = btn.dataset.kind;
+                // A new check-out starts from the check-in inventory
+                const current = diff[kind] || (kind === 'check_out' ? diff.check_in : null);
+                const fp = new FormPanel({
+                    title: kind === 'check_in' ? 'Check-in' : 'Check-out',
+                    fields: [
+                        { key: 'inspection_date', label: 'Date', type: 'date' },
+                        { key: 'inspector', label: 'Inspector' },
+                        { key: 'notes', label: 'Notes', type: 'textarea' },
+                        { key: 'items', label: 'Items: room | item | condition 0-5 | replacement cost | notes', type: 'textarea' },
+                    ],
+                    onSubmit: async (data) => {
+                        await api.put(`/contracts/${contractId}/inspections/${kind}`, {
+                            inspection_date: data.inspection_date || null,
+                            inspector: data.inspector || '',
+                            notes: data.notes || '',
+                            items: parseInspectionLines(data.items || ''),
+                        });
+                        Toast.show('Inspection saved');
+                        renderInspectionsTab(container, contract);
+                    }
+                });
+                fp.open({
+                    inspection_date: diff[kind]?.inspection_date || '',
+                    inspector: diff[kind]?.inspector || '',
+                    notes: diff[kind]?.notes || '',
+                    items: inspectionLines(current),
+                });
+            });
+        });
+
+        el.querySelectorAll('.inspection-photo').forEach(input => {
+            input.addEventListener('change', () => {
+                const file = input.files[0];
+                if (!file) return;
+                const reader = new FileReader();
+                reader.onload = async () => {
+                    try {
+                        await api.post(`/inspection-items/${input.dataset.itemId}/photos`, { data: reader.result });
+                        Toast.show('Photo added');
+                    } catch (err) {
+                        Toast.show(`Error: ${err.message}`, 'error');
+                    }
+                    renderInspectionsTab(container, contract);
+                };
+                reader.readAsDataURL(file);
+            });
+        });
+
+        el.querySelector('#inspection-apply')?.addEventListener('click', async () => {
+            const ok = await confirmAction('Apply deductions', 'The suggested deductions replace any already applied to the deposit.');
+            if (!ok) return;
+            try {
+                const result = await api.post(`/contracts/${contractId}/inspections/deductions`, {});
+                Toast.show(`Deductions of ${result.deductions.toFixed(2)} applied`);
+            } catch (err) {
+                Toast.show(`Error: ${err.message}`, 'error');
+            }
+            renderInspectionsTab(container, contract);
+        });
+    } catch (err) {
+        el.innerHTML = `<p class="text-danger">Error loading inspections: ${escapeHtml(err.message)}</p>`;
+    }
+}
+
 function initContractTabTable(tabName, container, contract, renderContractDetail) {
     const contractName = contract.contract_ref;
     const activoName = contract.property_name;
@@ -6336,6 +6722,9 @@ function initContractTabTable(tabName, container, contract, renderContractDetail
         case 'documentos':
             renderDocumentsTab(container, contract, renderContractDetail);
             return null;
+        case 'inspections':
+            renderInspectionsTab(container, contract);
+            return null;
     }
 }
 
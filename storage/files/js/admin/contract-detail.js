@@ -54,6 +54,7 @@ async function renderContractDetail(container) {
             <button class="tab" data-tab="deposits">Deposits</button>
             <button class="tab" data-tab="incidencias">Issues</button>
             <button class="tab" data-tab="documentos">Documents</button>
+            <button class="tab" data-tab="inspections">Inspections</button>
         </div>`;
 
         html += '<div class="tab-content">';
@@ -64,6 +65,7 @@ async function renderContractDetail(container) {
         html += '<div class="tab-panel" data-panel="deposits"><div class="tab-table-root"></div></div>';
         html += '<div class="tab-panel" data-panel="incidencias"><div class="tab-table-root"></div></div>';
         html += '<div class="tab-panel" data-panel="documentos"><div class="tab-table-root"></div></div>';
+        html += '<div class="tab-panel" data-panel="inspections"><div class="tab-table-root"></div></div>';
         html += '</div>';
 
         el.innerHTML = html;
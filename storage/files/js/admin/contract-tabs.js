@@ -8,7 +8,7 @@ const {
 function renderDocumentCard(doc) {
     const isImage = doc.file_type && doc.file_type.startsWith('image/');
     const hasFile = doc.original_file;
-    const downloadUrl = hasFile ? `${API_BASE}/contract_documents/${doc.id}/download` : null;
+    const downloadUrl = hasFile ? `${API_BASE}/contract-documents/${doc.id}/download` : null;
     const tipoLabel = doc.type || 'contract';
 
     let preview;
@@ -40,21 +40,41 @@ function renderDocumentCard(doc) {
             ${nameLink}
             <span class="cert-meta">${meta}</span>
         </div>
+        ${doc.template_id ? `<button class="btn btn-secondary btn-sm doc-sign" data-doc-id="${doc.id}">Send for signature</button>` : ''}
         <button class="btn-icon doc-delete" data-doc-id="${doc.id}" title="Delete">&times;</button>
     </div>`;
 }
 
+function renderSigningLinks(signers) {
+    const origin = window.location.origin;
+    return `<div class="cert-list mb-4">
+        <p class="text-sm mb-2">Send each signer their one-time link. The links are not shown again.</p>
+        ${signers.map(s => `<div class="mb-2">
+            <span class="text-sm">${escapeHtml(s.name)} (${escapeHtml(s.role)})${s.email ? ` &middot; ${escapeHtml(s.email)}` : ''}</span>
+            <input class="field-input" readonly value="${escapeHtml(origin + s.link)}" onclick="this.select()">
+        </div>`).join('')}
+    </div>`;
+}
+
 async function renderDocumentsTab(container, contract, renderContractDetail) {
     const el = typeof container === 'string' ? document.querySelector(container) : container;
     const contractId = contract.id;
 
     try {
-        const resp = await api.get(`/contract_documents?contract_id=${contractId}&per_page=100`);
+        const [resp, templatesResp] = await Promise.all([
+            api.get(`/contract-documents?contract_id=${contractId}`),
+            api.get('/document-templates'),
+        ]);
         const docs = resp.items || resp.data || [];
+        const templates = templatesResp.data || [];
 
         let html = `<div class="flex justify-between items-center mb-4">
             <h3>Contract documents</h3>
             <div class="flex gap-2">
+                ${templates.length ? `<select id="doc-template-select" class="field-input">
+                    ${templates.map(t => `<option value="${t.id}">${escapeHtml(t.name)} (${escapeHtml(t.kind)})</option>`).join('')}
+                </select>
+                <button class="btn btn-primary btn-sm" id="doc-generate-btn">Generate</button>` : ''}
                 <label class="btn btn-secondary btn-sm clickable">
                     <input type="file" id="doc-file-input" accept=".pdf,.jpg,.jpeg,.png,.webp,.doc,.docx" multiple class="hidden">
                     Upload file
@@ -71,6 +91,23 @@ async function renderDocumentsTab(container, contract, renderContractDetail) {
 
         el.innerHTML = html;
 
+        // Generate from template
+        el.querySelector('#doc-generate-btn')?.addEventListener('click', async () => {
+            const templateId = el.querySelector('#doc-template-select').value;
+            try {
+                const result = await api.post(`/contracts/${contractId}/documents/generate`, { template_id: templateId });
+                const missing = result.unresolved || [];
+                if (missing.length) {
+                    Toast.show(`Document generated; no value for ${missing.join(', ')}`);
+                } else {
+                    Toast.show('Document generated');
+                }
+            } catch (err) {
+                Toast.show(`Error: ${err.message}`, 'error');
+            }
+            renderDocumentsTab(container, contract, renderContractDetail);
+        });
+
         // File input upload
         el.querySelector('#doc-file-input')?.addEventListener('change', async (e) => {
             for (const file of e.target.files) {
@@ -104,6 +141,20 @@ async function renderDocumentsTab(container, contract, renderContractDetail) {
             });
         }
 
+        // Send for signature
+        el.querySelectorAll('.doc-sign').forEach(btn => {
+            btn.addEventListener('click', async (e) => {
+                e.stopPropagation();
+                try {
+                    const result = await api.post(`/contract-documents/${btn.dataset.docId}/signature-requests`, {});
+                    Toast.show('Signature request created');
+                    el.querySelector('#doc-list').insertAdjacentHTML('beforebegin', renderSigningLinks(result.signers || []));
+                } catch (err) {
+                    Toast.show(`Error: ${err.message}`, 'error');
+                }
+            });
+        });
+
         // Delete buttons
         el.querySelectorAll('.doc-delete').forEach(btn => {
             btn.addEventListener('click', async (e) => {
@@ -111,7 +162,7 @@ async function renderDocumentsTab(container, contract, renderContractDetail) {
                 const docId = btn.dataset.docId;
                 const ok = await confirmAction('Delete document', 'This action cannot be undone.');
                 if (ok) {
-                    await api.del(`/contract_documents/${docId}`);
+                    await api.del(`/contract-documents/${docId}`);
                     Toast.show('Document deleted');
                     renderDocumentsTab(container, contract, renderContractDetail);
                 }
@@ -122,6 +173,151 @@ async function renderDocumentsTab(container, contract, renderContractDetail) {
     }
 }
 
+const CONDITIONS = ['Missing', 'Damaged', 'Poor', 'Fair', 'Good', 'Excellent'];
+
+function inspectionLines(inspection) {
+    return (inspection?.items || []).map(i =>
+        [i.room, i.item, i.condition, i.replacement_cost ?? '', i.notes].join(' | ')
+    ).join('\n');
+}
+
+function parseInspectionLines(text) {
+    return text.split('\n').map(l => l.trim()).filter(Boolean).map(line => {
+        const [room, item, condition, cost, ...notes] = line.split('|').map(p => p.trim());
+        return {
+            room,
+            item,
+            condition: Number(condition),
+            replacement_cost: cost ? Number(cost) : null,
+            notes: notes.join(' | '),
+        };
+    });
+}
+
+function renderInspectionItems(inspection) {
+    if (!inspection) return '<p class="text-tertiary text-sm">Not recorded</p>';
+    return `<table class="data-table"><thead><tr><th>Room</th><th>Item</th><th>Condition</th><th>Notes</th><th>Photos</th></tr></thead><tbody>
+        ${inspection.items.map(i => `<tr>
+            <td>${escapeHtml(i.room)}</td>
+            <td>${escapeHtml(i.item)}</td>
+            <td>${CONDITIONS[i.condition] || i.condition}</td>
+            <td>${escapeHtml(i.notes)}</td>
+            <td>
+                ${i.photo_ids.map(p => `<a href="${API_BASE}/inspection-photos/${p}" target="_blank">photo</a>`).join(' ')}
+                <label class="btn btn-secondary btn-sm clickable">
+                    <input type="file" class="hidden inspection-photo" data-item-id="${i.id}" accept="image/jpeg,image/png,image/webp">
+                    Add
+                </label>
+            </td>
+        </tr>`).join('')}
+    </tbody></table>`;
+}
+
+async function renderInspectionsTab(container, contract) {
+    const el = typeof container === 'string' ? document.querySelector(container) : container;
+    const contractId = contract.id;
+
+    try {
+        const diff = await api.get(`/contracts/${contractId}/inspections/diff`);
+
+        let html = `<div class="flex justify-between items-center mb-4">
+            <h3>Inspections</h3>
+            <div class="flex gap-2">
+                <button class="btn btn-secondary btn-sm inspection-edit" data-kind="check_in">Edit check-in</button>
+                <button class="btn btn-secondary btn-sm inspection-edit" data-kind="check_out">Edit check-out</button>
+                ${diff.check_in || diff.check_out ? `<a class="btn btn-secondary btn-sm" href="${API_BASE}/contracts/${contractId}/inspections/pdf" target="_blank">Report PDF</a>` : ''}
+            </div>
+        </div>`;
+
+        if (diff.check_in && diff.check_out) {
+            html += `<h4 class="mb-2">Comparison</h4>
+            <table class="data-table mb-4"><thead><tr><th>Room</th><th>Item</th><th>Check-in</th><th>Check-out</th><th>Change</th><th>Deduction</th></tr></thead><tbody>
+                ${diff.changes.map(c => `<tr>
+                    <td>${escapeHtml(c.room)}</td>
+                    <td>${escapeHtml(c.item)}</td>
+                    <td>${c.check_in === null ? '&mdash;' : CONDITIONS[c.check_in]}</td>
+                    <td>${c.check_out === null ? '&mdash;' : CONDITIONS[c.check_out]}</td>
+                    <td>${escapeHtml(c.change)}</td>
+                    <td>${c.suggested_deduction ? c.suggested_deduction.toFixed(2) : ''}</td>
+                </tr>`).join('')}
+            </tbody></table>
+            <div class="flex justify-between items-center mb-4">
+                <span class="text-sm">Suggested deductions: ${diff.suggested_total.toFixed(2)}${diff.capped ? ' (capped at the deposit paid)' : ''}${diff.deposit ? ` &middot; deposit paid ${Number(diff.deposit.paid).toFixed(2)}` : ' &middot; no deposit recorded'}</span>
+                ${diff.deposit ? '<button class="btn btn-primary btn-sm" id="inspection-apply">Apply to deposit</button>' : ''}
+            </div>`;
+        }
+
+        html += `<h4 class="mb-2">Check-in</h4>${renderInspectionItems(diff.check_in)}
+            <h4 class="mb-2 mt-4">Check-out</h4>${renderInspectionItems(diff.check_out)}`;
+        el.innerHTML = html;
+
+        el.querySelectorAll('.inspection-edit').forEach(btn => {
+            btn.addEventListener('click', () => {
+                const kind = btn.dataset.kind;
+                // A new check-out starts from the check-in inventory
+                const current = diff[kind] || (kind === 'check_out' ? diff.check_in : null);
+                const fp = new FormPanel({
+                    title: kind === 'check_in' ? 'Check-in' : 'Check-out',
+                    fields: [
+                        { key: 'inspection_date', label: 'Date', type: 'date' },
+                        { key: 'inspector', label: 'Inspector' },
+                        { key: 'notes', label: 'Notes', type: 'textarea' },
+                        { key: 'items', label: 'Items: room | item | condition 0-5 | replacement cost | notes', type: 'textarea' },
+                    ],
+                    onSubmit: async (data) => {
+                        await api.put(`/contracts/${contractId}/inspections/${kind}`, {
+                            inspection_date: data.inspection_date || null,
+                            inspector: data.inspector || '',
+                            notes: data.notes || '',
+                            items: parseInspectionLines(data.items || ''),
+                        });
+                        Toast.show('Inspection saved');
+                        renderInspectionsTab(container, contract);
+                    }
+                });
+                fp.open({
+                    inspection_date: diff[kind]?.inspection_date || '',
+                    inspector: diff[kind]?.inspector || '',
+                    notes: diff[kind]?.notes || '',
+                    items: inspectionLines(current),
+                });
+            });
+        });
+
+        el.querySelectorAll('.inspection-photo').forEach(input => {
+            input.addEventListener('change', () => {
+                const file = input.files[0];
+                if (!file) return;
+                const reader = new FileReader();
+                reader.onload = async () => {
+                    try {
+                        await api.post(`/inspection-items/${input.dataset.itemId}/photos`, { data: reader.result });
+                        Toast.show('Photo added');
+                    } catch (err) {
+                        Toast.show(`Error: ${err.message}`, 'error');
+                    }
+                    renderInspectionsTab(container, contract);
+                };
+                reader.readAsDataURL(file);
+            });
+        });
+
+        el.querySelector('#inspection-apply')?.addEventListener('click', async () => {
+            const ok = await confirmAction('Apply deductions', 'The suggested deductions replace any already applied to the deposit.');
+            if (!ok) return;
+            try {
+                const result = await api.post(`/contracts/${contractId}/inspections/deductions`, {});
+                Toast.show(`Deductions of ${result.deductions.toFixed(2)} applied`);
+            } catch (err) {
+                Toast.show(`Error: ${err.message}`, 'error');
+            }
+            renderInspectionsTab(container, contract);
+        });
+    } catch (err) {
+        el.innerHTML = `<p class="text-danger">Error loading inspections: ${escapeHtml(err.message)}</p>`;
+    }
+}
+
 function initContractTabTable(tabName, container, contract, renderContractDetail) {
     const contractName = contract.contract_ref;
     const activoName = contract.property_name;
@@ -276,6 +472,9 @@ function initContractTabTable(tabName, container, contract, renderContractDetail
         case 'documentos':
             renderDocumentsTab(container, contract, renderContractDetail);
             return null;
+        case 'inspections':
+            renderInspectionsTab(container, contract);
+            return null;
     }
 }
 
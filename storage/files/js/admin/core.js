@@ -42,18 +42,111 @@ function confirmAction(title, message) {
     });
 }
 
+/**
+ * Merge dialog for a save rejected with 409: lists the fields where the
+ * values being saved differ from the record as now stored and lets the user
+ * pick one of each. Resolves to the merged values, or null when cancelled.
+ */
+const ConflictDialog = {
+    _same(a, b) {
+        return JSON.stringify(a ?? '') === JSON.stringify(b ?? '');
+    },
+    _text(value) {
+        if (value == null || value === '') return '<em>empty</em>';
+        return window.AdminApp.escapeHtml(typeof value === 'object' ? JSON.stringify(value) : String(value));
+    },
+    resolve(mine, current) {
+        const fields = Object.keys(mine).filter(key => key in current && !this._same(mine[key], current[key]));
+        if (!fields.length) return Promise.resolve({ ...mine });
+        return new Promise(resolve => {
+            const overlay = document.createElement('div');
+            overlay.className = 'confirm-overlay';
+            overlay.innerHTML = `
+                <div class="confirm-dialog conflict-dialog">
+                    <h3>Record changed by someone else</h3>
+                    <p>Choose which value to keep for each field that differs.</p>
+                    <table class="conflict-fields">
+                        <thead><tr><th>Field</th><th>Yours</th><th>Current</th></tr></thead>
+                        <tbody>${fields.map(key => `
+                            <tr>
+                                <td>${this._text(key)}</td>
+                                <td><label><input type="radio" name="conflict-${this._text(key)}" data-field="${this._text(key)}" value="mine" checked> ${this._text(mine[key])}</label></td>
+                                <td><label><input type="radio" name="conflict-${this._text(key)}" data-field="${this._text(key)}" value="current"> ${this._text(current[key])}</label></td>
+                            </tr>`).join('')}
+                        </tbody>
+                    </table>
+                    <div class="actions">
+                        <button class="btn btn-secondary" data-action="cancel">Cancel</button>
+                        <button class="btn btn-primary" data-action="save">Save merged</button>
+                    </div>
+                </div>`;
+            document.body.appendChild(overlay);
+            ScrollLock.lock();
+            const close = result => { overlay.remove(); ScrollLock.unlock(); resolve(result); };
+            overlay.querySelector('[data-action="cancel"]').onclick = () => close(null);
+            overlay.querySelector('[data-action="save"]').onclick = () => {
+                const merged = { ...mine };
+                overlay.querySelectorAll('input[type="radio"]:checked').forEach(input => {
+                    if (input.value === 'current') merged[input.dataset.field] = current[input.dataset.field];
+                });
+                close(merged);
+            };
+        });
+    }
+};
+
 const api = {
+    // Record revisions seen in responses, sent back as If-Match on PUT/DELETE.
+    // Records never read through the API are sent without a precondition, so
+    // the server rejects the write (428) rather than overwrite a newer version.
+    _versions: new Map(),
+    _track(path, body) {
+        const base = path.split('?')[0];
+        if (Array.isArray(body?.data)) {
+            for (const row of body.data) {
+                if (row && row.id && row.revision != null) this._versions.set(`${base}/${row.id}`, row.revision);
+            }
+            return;
+        }
+        // The record itself, or wrapped by a detail endpoint ({ contract: {...}, documents: [...] })
+        const id = base.split('/').pop();
+        for (const row of [body, ...Object.values(body || {})]) {
+            if (row && typeof row === 'object' && row.id === id && row.revision != null) {
+                this._versions.set(base, row.revision);
+                return;
+            }
+        }
+    },
+    _ifMatch(path) {
+        const revision = this._versions.get(path.split('?')[0]);
+        return revision != null ? { 'If-Match': `"${revision}"` } : {};
+    },
+    // A 409 carries the current record: remember its revision for the retry
+    _conflict(path, err) {
+        if (err.status !== 409 || !err.current || err.current.revision == null) return false;
+        this._versions.set(path.split('?')[0], err.current.revision);
+        return true;
+    },
     _handleResponse(res) {
         if (res.status === 401) {
             window.location.href = AdminApp.loginUrl(window.location.pathname);
             throw new Error('Unauthorized');
         }
-        if (!res.ok) { return res.json().catch(() => ({})).then(e => { throw new Error(e.error || res.statusText); }); }
+        if (!res.ok) {
+            return res.json().catch(() => ({})).then(e => {
+                const err = new Error(e.error || res.statusText);
+                err.status = res.status;
+                err.current = e.current;
+                throw err;
+            });
+        }
         return res.json();
     },
     async get(path) {
         const res = await fetch(`${API_BASE}${path}`);
-        return this._handleResponse(res);
+        const body = await this._handleResponse(res);
+        this._track(path, body);
+        return body;
     },
     async post(path, data) {
         const res = await fetch(`${API_BASE}${path}`, {
@@ -61,16 +154,43 @@ const api = {
         });
         return this._handleResponse(res);
     },
-    async put(path, data) {
+    async _put(path, data) {
         const res = await fetch(`${API_BASE}${path}`, {
-            method: 'PUT', headers: { 'Content-Type': 'application/json' }, body: JSON.stringify(data)
+            method: 'PUT',
+            headers: { 'Content-Type': 'application/json', ...this._ifMatch(path) },
+            body: JSON.stringify(data)
         });
+        const etag = res.ok && res.headers.get('ETag');
+        if (etag) this._versions.set(path.split('?')[0], Number(etag.replace(/\D/g, '')));
         return this._handleResponse(res);
     },
-    async del(path) {
-        const res = await fetch(`${API_BASE}${path}`, { method: 'DELETE' });
+    async put(path, data) {
+        try {
+            return await this._put(path, data);
+        } catch (err) {
+            if (!this._conflict(path, err)) throw err;
+            const merged = await ConflictDialog.resolve(data, err.current);
+            if (!merged) throw err;
+            return this._put(path, merged);
+        }
+    },
+    async _del(path) {
+        const res = await fetch(`${API_BASE}${path}`, { method: 'DELETE', headers: this._ifMatch(path) });
         return this._handleResponse(res);
     },
+    async del(path) {
+        try {
+            return await this._del(path);
+        } catch (err) {
+            if (!this._conflict(path, err)) throw err;
+            const confirmed = await confirmAction(
+                'Record changed by someone else',
+                'It was edited after you opened it. Delete the current version anyway?'
+            );
+            if (!confirmed) throw err;
+            return this._del(path);
+        }
+    },
     async uploadFile(path, file) {
         const formData = new FormData();
         formData.append('file', file);
@@ -158,4 +278,4 @@ const ScrollLock = {
 };
 
 const user = getUserInfo();
-window.AdminApp = { API_BASE, Toast, api, confirmAction, user, getUserInitials, initSidebar, ScrollLock };
+window.AdminApp = { API_BASE, Toast, api, confirmAction, ConflictDialog, user, getUserInitials, initSidebar, ScrollLock };
@@ -3,7 +3,7 @@
 const {
     api, Toast, FormPanel,
     formatCurrency, formatDate,
-    escapeHtml, statusBadge,
+    escapeHtml, statusBadge, API_BASE,
 } = AdminApp;
 
 async function renderIssueDetail(container) {
@@ -12,7 +12,11 @@ async function renderIssueDetail(container) {
     if (!id) { el.innerHTML = '<div class="empty-state">No issue specified.</div>'; return; }
 
     try {
-        const d = await api.get(`/issues/${id}`);
+        const [d, photoData] = await Promise.all([
+            api.get(`/issues/${id}`),
+            api.get(`/issues/${id}/photos`),
+        ]);
+        const photos = photoData.data || [];
 
         let html = '';
 
@@ -43,6 +47,12 @@ async function renderIssueDetail(container) {
                 <h3>Description</h3>
                 <p class="note-text">${escapeHtml(d.descripcion)}</p>
             </div>` : ''}
+            ${photos.length ? `<div class="detail-info-section">
+                <h3>Photos${d.tenant_id ? ' (reported by the tenant)' : ''}</h3>
+                <div class="flex gap-2">
+                    ${photos.map(p => `<a href="${API_BASE}/issue-photos/${p.id}" target="_blank"><img src="${API_BASE}/issue-photos/${p.id}" alt="${escapeHtml(p.caption)}" class="cert-thumb"></a>`).join('')}
+                </div>
+            </div>` : ''}
         </div>`;
 
         el.innerHTML = html;
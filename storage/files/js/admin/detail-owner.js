@@ -63,6 +63,7 @@ async function renderOwnerDetail(container) {
             <button class="tab" data-tab="properties">Properties</button>
             <button class="tab" data-tab="income">Income</button>
             <button class="tab" data-tab="expenses">Expenses</button>
+            <button class="tab" data-tab="portal">Portal</button>
         </div>`;
 
         html += '<div class="tab-content">';
@@ -135,6 +136,7 @@ async function renderOwnerDetail(container) {
         html += '<div class="tab-panel" data-panel="properties"><div class="tab-table-root"></div></div>';
         html += '<div class="tab-panel" data-panel="income"><div class="tab-table-root"></div></div>';
         html += '<div class="tab-panel" data-panel="expenses"><div class="tab-table-root"></div></div>';
+        html += '<div class="tab-panel" data-panel="portal"><div class="tab-table-root"></div></div>';
         html += '</div>';
 
         el.innerHTML = html;
@@ -183,6 +185,9 @@ async function renderOwnerDetail(container) {
                         ],
                         onRowClick: (row) => { AdminApp.navigateTo('invoice', row.id); }
                     });
+                case 'portal':
+                    AdminApp.renderPortalAccess(tabContainer, `/owners/${p.id}`, "this owner's properties, statements, documents and open issues");
+                    return null;
             }
         }, p);
 
@@ -1,6 +1,6 @@
 (function(AdminApp) {
 
-const { api, Toast, escapeHtml, formatDate, timeAgo, API_BASE } = AdminApp;
+const { api, Toast, escapeHtml, formatDate, timeAgo, API_BASE, confirmAction } = AdminApp;
 
 function getIdFromUrl() {
     return new URLSearchParams(window.location.search).get('id');
@@ -97,6 +97,53 @@ async function uploadCertificate(depositId, file) {
     }
 }
 
+/**
+ * Portal users linked to a tenant or owner record. `recordPath` is the
+ * record's API path, e.g. `/tenants/<id>`; `scope` says what the portal shows.
+ */
+async function renderPortalAccess(container, recordPath, scope) {
+    try {
+        const accounts = (await api.get(`${recordPath}/portal-access`)).data || [];
+        container.innerHTML = `<p class="text-sm mb-2">Users listed here sign in to the portal at <a href="/admin/portal/" target="_blank" class="text-accent">/admin/portal/</a> and see only ${escapeHtml(scope)}.</p>
+            ${accounts.length === 0 ? '<p class="text-tertiary text-sm mb-2">No portal users</p>' : `<table class="data-table mb-4"><thead><tr><th>User</th><th>Granted by</th><th>Granted</th><th></th></tr></thead><tbody>
+                ${accounts.map(a => `<tr>
+                    <td>${escapeHtml(a.user_id)}</td>
+                    <td>${escapeHtml(a.created_by || '-')}</td>
+                    <td>${formatDate(a.created_at)}</td>
+                    <td><button class="btn btn-secondary btn-sm portal-revoke" data-account-id="${a.id}">Revoke</button></td>
+                </tr>`).join('')}
+            </tbody></table>`}
+            <div class="flex gap-2">
+                <input id="portal-user-id" class="field-input" placeholder="User id">
+                <button class="btn btn-primary btn-sm" id="portal-grant">Grant access</button>
+            </div>`;
+
+        container.querySelector('#portal-grant').addEventListener('click', async () => {
+            const userId = container.querySelector('#portal-user-id').value.trim();
+            if (!userId) return;
+            try {
+                await api.put(`${recordPath}/portal-access`, { user_id: userId });
+                Toast.show('Portal access granted');
+            } catch (err) {
+                Toast.show(`Error: ${err.message}`, 'error');
+            }
+            renderPortalAccess(container, recordPath, scope);
+        });
+
+        container.querySelectorAll('.portal-revoke').forEach(btn => {
+            btn.addEventListener('click', async () => {
+                const ok = await confirmAction('Revoke portal access', 'The user will no longer be able to sign in to the portal as this record.');
+                if (!ok) return;
+                await api.del(`${recordPath}/portal-access/${btn.dataset.accountId}`);
+                Toast.show('Portal access revoked');
+                renderPortalAccess(container, recordPath, scope);
+            });
+        });
+    } catch (err) {
+        container.innerHTML = `<p class="text-danger">Error loading portal access: ${escapeHtml(err.message)}</p>`;
+    }
+}
+
 Object.assign(AdminApp, {
     getIdFromUrl,
     initTabs,
@@ -104,6 +151,7 @@ Object.assign(AdminApp, {
     formatFileSize,
     renderCertificateCard,
     uploadCertificate,
+    renderPortalAccess,
     entityDetailUrl: AdminApp.detailUrl,
 });
 
@@ -59,6 +59,7 @@ async function renderTenantDetail(container) {
             <button class="tab" data-tab="contracts">Contracts</button>
             <button class="tab" data-tab="invoices">Invoices</button>
             <button class="tab" data-tab="deposits">Deposits</button>
+            <button class="tab" data-tab="portal">Portal</button>
         </div>`;
 
         html += '<div class="tab-content">';
@@ -98,6 +99,7 @@ async function renderTenantDetail(container) {
         html += '<div class="tab-panel" data-panel="contracts"><div class="tab-table-root"></div></div>';
         html += '<div class="tab-panel" data-panel="invoices"><div class="tab-table-root"></div></div>';
         html += '<div class="tab-panel" data-panel="deposits"><div class="tab-table-root"></div></div>';
+        html += '<div class="tab-panel" data-panel="portal"><div class="tab-table-root"></div></div>';
         html += '</div>';
 
         el.innerHTML = html;
@@ -146,6 +148,9 @@ async function renderTenantDetail(container) {
                         ],
                         onRowClick: (row) => { AdminApp.navigateTo('deposit', row.id); }
                     });
+                case 'portal':
+                    AdminApp.renderPortalAccess(tabContainer, `/tenants/${id}`, "this tenant's contracts, invoices and issues");
+                    return null;
             }
         }, t);
 